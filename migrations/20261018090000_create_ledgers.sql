@@ -0,0 +1,30 @@
+-- Migration to create shared ledgers, their members and invitations
+CREATE TABLE ledgers (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE ledger_members (
+    ledger_id TEXT NOT NULL REFERENCES ledgers (id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (ledger_id, user_id)
+);
+
+CREATE INDEX ledger_members_user_id_idx ON ledger_members (user_id);
+
+CREATE TABLE ledger_invitations (
+    token TEXT PRIMARY KEY,
+    ledger_id TEXT NOT NULL REFERENCES ledgers (id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('editor', 'viewer')),
+    invited_by TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_by TEXT,
+    accepted_at TIMESTAMPTZ
+);
+
+ALTER TABLE expenses ADD COLUMN ledger_id TEXT REFERENCES ledgers (id) ON DELETE CASCADE;
+
+CREATE INDEX expenses_ledger_id_idx ON expenses (ledger_id);
@@ -0,0 +1,13 @@
+-- Migration to add amounts to expenses and split them between ledger members
+ALTER TABLE expenses ADD COLUMN amount BIGINT NOT NULL DEFAULT 0 CHECK (amount >= 0);
+ALTER TABLE expenses ADD COLUMN paid_by TEXT;
+ALTER TABLE expenses ADD COLUMN split_method TEXT
+    CHECK (split_method IN ('equal', 'shares', 'percentage', 'exact'));
+
+CREATE TABLE expense_shares (
+    expense_id TEXT NOT NULL REFERENCES expenses (id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (expense_id, user_id)
+);
@@ -0,0 +1,13 @@
+-- Migration to record settlement payments between ledger members
+CREATE TABLE settlements (
+    id TEXT PRIMARY KEY,
+    ledger_id TEXT NOT NULL REFERENCES ledgers (id) ON DELETE CASCADE,
+    from_user_id TEXT NOT NULL,
+    to_user_id TEXT NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    recorded_by TEXT NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (from_user_id <> to_user_id)
+);
+
+CREATE INDEX settlements_ledger_id_idx ON settlements (ledger_id);
@@ -0,0 +1,12 @@
+-- Migration to create expense categories, personal or shared in a ledger
+CREATE TABLE categories (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    ledger_id TEXT REFERENCES ledgers (id) ON DELETE CASCADE,
+    owner_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Names are unique within a ledger, or among a user's personal categories.
+CREATE UNIQUE INDEX categories_scope_name_idx
+    ON categories (COALESCE(ledger_id, owner_id), lower(name));
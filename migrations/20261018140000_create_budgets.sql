@@ -0,0 +1,16 @@
+-- Migration to categorise and date expenses, and to budget spending per category
+ALTER TABLE expenses ADD COLUMN category_id TEXT REFERENCES categories (id) ON DELETE SET NULL;
+ALTER TABLE expenses ADD COLUMN spent_on DATE NOT NULL DEFAULT CURRENT_DATE;
+
+CREATE INDEX expenses_category_spent_on_idx ON expenses (category_id, spent_on);
+
+CREATE TABLE budgets (
+    id TEXT PRIMARY KEY,
+    category_id TEXT NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    ledger_id TEXT REFERENCES ledgers (id) ON DELETE CASCADE,
+    owner_id TEXT NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    period TEXT NOT NULL CHECK (period IN ('weekly', 'monthly')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (category_id, period)
+);
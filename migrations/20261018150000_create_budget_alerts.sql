@@ -0,0 +1,8 @@
+-- Migration to remember which budget thresholds were already notified in each period
+CREATE TABLE budget_alerts (
+    budget_id TEXT NOT NULL REFERENCES budgets (id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    threshold SMALLINT NOT NULL,
+    notified_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (budget_id, period_start, threshold)
+);
@@ -0,0 +1,23 @@
+-- Migration to repeat expenses on a schedule
+CREATE TABLE recurring_expenses (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    ledger_id TEXT REFERENCES ledgers (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount >= 0),
+    category_id TEXT REFERENCES categories (id) ON DELETE SET NULL,
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekly', 'monthly', 'yearly')),
+    interval_count INTEGER NOT NULL DEFAULT 1 CHECK (interval_count > 0),
+    day_of_month SMALLINT CHECK (day_of_month BETWEEN 1 AND 31),
+    starts_on DATE NOT NULL,
+    ends_on DATE CHECK (ends_on >= starts_on),
+    materialized_through DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX recurring_expenses_due_idx ON recurring_expenses (materialized_through);
+
+-- Each occurrence of a recurring expense is created at most once
+ALTER TABLE expenses ADD COLUMN recurring_expense_id TEXT
+    REFERENCES recurring_expenses (id) ON DELETE SET NULL;
+CREATE UNIQUE INDEX expenses_recurring_occurrence_idx ON expenses (recurring_expense_id, spent_on);
@@ -0,0 +1,7 @@
+-- Migration to record income and transfers alongside expenses
+ALTER TABLE expenses ADD COLUMN kind TEXT NOT NULL DEFAULT 'expense'
+    CHECK (kind IN ('expense', 'income', 'transfer'));
+ALTER TABLE expenses ADD CONSTRAINT expenses_split_kind_check
+    CHECK (paid_by IS NULL OR kind = 'expense');
+
+CREATE INDEX expenses_ledger_kind_idx ON expenses (ledger_id, kind);
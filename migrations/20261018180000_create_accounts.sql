@@ -0,0 +1,23 @@
+-- Migration to record transactions against the accounts money is paid from
+CREATE TABLE accounts (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('wallet', 'bank', 'card')),
+    currency CHAR(3) NOT NULL,
+    opening_balance BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX accounts_owner_idx ON accounts (owner_id);
+
+-- Expenses recorded before accounts existed keep a NULL account
+ALTER TABLE expenses ADD COLUMN account_id TEXT REFERENCES accounts (id);
+ALTER TABLE expenses ADD COLUMN to_account_id TEXT REFERENCES accounts (id);
+ALTER TABLE expenses ADD CONSTRAINT expenses_to_account_kind_check
+    CHECK (to_account_id IS NULL OR kind = 'transfer');
+
+CREATE INDEX expenses_account_idx ON expenses (account_id, spent_on);
+CREATE INDEX expenses_to_account_idx ON expenses (to_account_id, spent_on);
+
+ALTER TABLE recurring_expenses ADD COLUMN account_id TEXT REFERENCES accounts (id);
@@ -0,0 +1,50 @@
+-- Migration to convert amounts between currencies
+-- Rates are quoted as units of currency per euro, as in the ECB reference rates
+CREATE TABLE exchange_rates (
+    currency CHAR(3) NOT NULL,
+    rate_on DATE NOT NULL,
+    rate NUMERIC(20, 8) NOT NULL CHECK (rate > 0),
+    PRIMARY KEY (currency, rate_on)
+);
+
+-- Number of decimal places of the minor unit of an ISO 4217 currency
+CREATE FUNCTION currency_exponent(currency TEXT) RETURNS INTEGER
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN currency IN ('BIF', 'CLP', 'DJF', 'GNF', 'ISK', 'JPY', 'KMF', 'KRW', 'PYG', 'RWF',
+            'UGX', 'VND', 'VUV', 'XAF', 'XOF', 'XPF') THEN 0
+        WHEN currency IN ('BHD', 'IQD', 'JOD', 'KWD', 'LYD', 'OMR', 'TND') THEN 3
+        ELSE 2
+    END
+$$;
+
+-- Rate of a currency on a day, falling back on the latest rate of the previous week since
+-- none are published on weekends and holidays. NULL if unknown.
+CREATE FUNCTION exchange_rate(currency TEXT, on_day DATE) RETURNS NUMERIC
+LANGUAGE sql STABLE AS $$
+    SELECT CASE
+        WHEN currency = 'EUR' THEN 1
+        ELSE (
+            SELECT r.rate
+            FROM exchange_rates r
+            WHERE r.currency = exchange_rate.currency
+                AND r.rate_on BETWEEN on_day - 7 AND on_day
+            ORDER BY r.rate_on DESC
+            LIMIT 1
+        )
+    END
+$$;
+
+-- Converts an amount in minor units between currencies at the rates of a day. NULL if a rate
+-- is unknown.
+CREATE FUNCTION convert_amount(amount BIGINT, from_currency TEXT, to_currency TEXT, on_day DATE)
+RETURNS BIGINT
+LANGUAGE sql STABLE AS $$
+    SELECT CASE
+        WHEN from_currency = to_currency THEN amount
+        ELSE ROUND(
+            amount * exchange_rate(to_currency, on_day) / exchange_rate(from_currency, on_day)
+                * power(10::NUMERIC, currency_exponent(to_currency) - currency_exponent(from_currency))
+        )::BIGINT
+    END
+$$;
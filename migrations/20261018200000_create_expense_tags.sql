@@ -0,0 +1,9 @@
+-- Migration to label expenses with free-form tags and report on them
+CREATE TABLE expense_tags (
+    expense_id TEXT NOT NULL REFERENCES expenses (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (expense_id, tag)
+);
+
+CREATE INDEX expense_tags_tag_idx ON expense_tags (tag);
+CREATE INDEX expenses_ledger_spent_on_idx ON expenses (ledger_id, spent_on);
@@ -0,0 +1,6 @@
+-- Migration to remember the identifiers banks give imported transactions, so that importing an
+-- overlapping statement does not record them twice
+ALTER TABLE expenses ADD COLUMN import_id TEXT;
+
+CREATE UNIQUE INDEX expenses_account_import_idx ON expenses (account_id, import_id)
+    WHERE import_id IS NOT NULL;
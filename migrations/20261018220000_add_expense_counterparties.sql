@@ -0,0 +1,3 @@
+-- Migration to keep the counterparty and reference bank statements give transactions
+ALTER TABLE expenses ADD COLUMN counterparty TEXT;
+ALTER TABLE expenses ADD COLUMN reference TEXT;
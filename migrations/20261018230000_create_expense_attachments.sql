@@ -0,0 +1,12 @@
+-- Migration to attach files, such as receipts, to expenses. Their content lives in the
+-- attachment storage, under a key derived from both ids.
+CREATE TABLE expense_attachments (
+    id TEXT PRIMARY KEY,
+    expense_id TEXT NOT NULL REFERENCES expenses (id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX expense_attachments_expense_id_idx ON expense_attachments (expense_id);
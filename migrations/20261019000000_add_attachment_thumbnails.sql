@@ -0,0 +1,11 @@
+-- Migration to track the thumbnail generated in the background for each attachment. Pictures
+-- and PDF documents start out pending; other kinds of files never get one.
+ALTER TABLE expense_attachments
+    ADD COLUMN thumbnail_status TEXT NOT NULL DEFAULT 'pending';
+
+UPDATE expense_attachments
+SET thumbnail_status = 'unavailable'
+WHERE content_type NOT IN ('image/jpeg', 'image/png', 'image/webp', 'application/pdf');
+
+CREATE INDEX expense_attachments_pending_thumbnail_idx ON expense_attachments (created_at)
+    WHERE thumbnail_status = 'pending';
@@ -0,0 +1,19 @@
+-- Migration to file personal expenses automatically with user-defined rules
+CREATE TABLE rules (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    name_pattern TEXT,
+    min_amount BIGINT,
+    max_amount BIGINT,
+    account_id TEXT REFERENCES accounts (id) ON DELETE CASCADE,
+    counterparty TEXT,
+    kind TEXT CHECK (kind IN ('expense', 'income', 'transfer')),
+    category_id TEXT REFERENCES categories (id) ON DELETE CASCADE,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    rename TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX rules_owner_priority_idx ON rules (owner_id, priority, created_at);
@@ -0,0 +1,8 @@
+-- Migration to let users tune how suspected duplicate expenses are detected
+CREATE TABLE duplicate_settings (
+    owner_id TEXT PRIMARY KEY,
+    mode TEXT NOT NULL CHECK (mode IN ('off', 'warn', 'block')),
+    date_window_days INTEGER NOT NULL,
+    min_similarity SMALLINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
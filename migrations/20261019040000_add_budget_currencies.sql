@@ -0,0 +1,4 @@
+-- Migration to set budgets in a currency, into which the spending in their category is converted
+-- Budgets set before accounts had currencies are taken to be in euros, the reference currency
+ALTER TABLE budgets ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'EUR';
+ALTER TABLE budgets ALTER COLUMN currency DROP DEFAULT;
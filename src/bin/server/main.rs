@@ -1,9 +1,17 @@
 use api_lib::{
-    config::Config,
-    domain::finance::service::Service,
+    config::{AttachmentStorageConfig, Config},
+    domain::{auth, finance},
+    inbound::ecb::ExchangeRateImporter,
     inbound::http::{HttpServer, HttpServerConfig},
+    inbound::scheduler::{
+        IdempotencyKeyScheduler, RecurringExpenseScheduler, SchedulerConfig, ThumbnailScheduler,
+    },
+    outbound::storage::{Storage, local::LocalStorage, s3::S3Storage},
+    outbound::thumbnail::Thumbnailer,
     outbound::{email_client::EmailClient, postgres::Postgres, prometheus::Prometheus},
 };
+use std::path::PathBuf;
+use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
@@ -19,12 +27,52 @@ async fn main() -> anyhow::Result<()> {
     let postgres = Postgres::new(&config.database_url).await?;
     let prometheus = Prometheus::new();
     let email_client = EmailClient::new();
-    let finance_service = Service::new(postgres, prometheus, email_client);
+    let storage = match config.attachment_storage {
+        AttachmentStorageConfig::Local { dir } => Storage::Local(LocalStorage::new(dir)),
+        AttachmentStorageConfig::S3(s3_config) => Storage::S3(S3Storage::new(s3_config)?),
+    };
+    let finance_service = finance::service::Service::new(
+        postgres.clone(),
+        prometheus,
+        email_client,
+        storage,
+        Thumbnailer::new(),
+    );
+    let auth_service = auth::service::Service::new(postgres);
+
+    // `api import-rates <file>` loads an ECB reference rates file instead of serving
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("import-rates") {
+        let path = args
+            .next()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow::anyhow!("usage: api import-rates <file>"))?;
+        let imported = ExchangeRateImporter::new(finance_service)
+            .import_file(&path)
+            .await?;
+        tracing::info!(
+            "Imported {} exchange rates from {}",
+            imported,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let scheduler_config = SchedulerConfig {
+        interval: Duration::from_secs(config.scheduler_interval_secs),
+    };
+    RecurringExpenseScheduler::new(finance_service.clone(), scheduler_config).spawn();
+    IdempotencyKeyScheduler::new(finance_service.clone(), scheduler_config).spawn();
+    let thumbnail_config = SchedulerConfig {
+        interval: Duration::from_secs(config.thumbnail_interval_secs),
+    };
+    ThumbnailScheduler::new(finance_service.clone(), thumbnail_config).spawn();
 
     let server_config = HttpServerConfig {
         port: &config.server_port,
+        trusted_proxy_secret: config.trusted_proxy_secret.clone(),
     };
     tracing::info!("Starting server with server config: {:?}", server_config);
-    let http_server = HttpServer::new(finance_service, server_config).await?;
+    let http_server = HttpServer::new(finance_service, auth_service, server_config).await?;
     http_server.run().await
 }
@@ -1,25 +1,119 @@
 use std::env;
+use std::path::PathBuf;
 
 use anyhow::Context;
 
+use crate::inbound::http::TrustedProxySecret;
+use crate::outbound::storage::s3::S3Config;
+
 const DATABASE_URL_KEY: &str = "DATABASE_URL";
 
 const SERVER_PORT_KEY: &str = "SERVER_PORT";
 
+const TRUSTED_PROXY_SECRET_KEY: &str = "TRUSTED_PROXY_SECRET";
+
+const SCHEDULER_INTERVAL_SECS_KEY: &str = "SCHEDULER_INTERVAL_SECS";
+
+const THUMBNAIL_INTERVAL_SECS_KEY: &str = "THUMBNAIL_INTERVAL_SECS";
+
+const ATTACHMENT_STORAGE_KEY: &str = "ATTACHMENT_STORAGE";
+
+const ATTACHMENT_DIR_KEY: &str = "ATTACHMENT_DIR";
+
+const S3_ENDPOINT_KEY: &str = "S3_ENDPOINT";
+
+const S3_BUCKET_KEY: &str = "S3_BUCKET";
+
+const S3_REGION_KEY: &str = "S3_REGION";
+
+const S3_ACCESS_KEY_ID_KEY: &str = "S3_ACCESS_KEY_ID";
+
+const S3_SECRET_ACCESS_KEY_KEY: &str = "S3_SECRET_ACCESS_KEY";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub server_port: String,
     pub database_url: String,
+    /// The secret the upstream gateway sends to assert the identity of callers. Unset, callers
+    /// can only authenticate with API keys.
+    pub trusted_proxy_secret: Option<TrustedProxySecret>,
+    /// How often recurring expenses are checked for due occurrences.
+    pub scheduler_interval_secs: u64,
+    /// How often attachments are checked for pending thumbnails.
+    pub thumbnail_interval_secs: u64,
+    /// Where the content of attachments is kept.
+    pub attachment_storage: AttachmentStorageConfig,
+}
+
+/// Where the content of attachments is kept, chosen by `ATTACHMENT_STORAGE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentStorageConfig {
+    /// `local`, the default: files under `ATTACHMENT_DIR`, `attachments` by default.
+    Local { dir: PathBuf },
+    /// `s3`: objects of an S3-compatible store, configured by the `S3_*` variables.
+    S3(S3Config),
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Config> {
         let server_port = load_env(SERVER_PORT_KEY).unwrap_or("3000".to_string());
         let database_url = load_env(DATABASE_URL_KEY).unwrap_or("sqlite://dev.db".to_string());
+        let trusted_proxy_secret = load_env(TRUSTED_PROXY_SECRET_KEY)
+            .ok()
+            .map(TrustedProxySecret::new)
+            .transpose()
+            .with_context(|| format!("invalid {}", TRUSTED_PROXY_SECRET_KEY))?;
+        let scheduler_interval_secs = match load_env(SCHEDULER_INTERVAL_SECS_KEY) {
+            Ok(secs) => secs
+                .parse()
+                .with_context(|| format!("invalid {} {:?}", SCHEDULER_INTERVAL_SECS_KEY, secs))?,
+            Err(_) => 3600,
+        };
+        anyhow::ensure!(
+            scheduler_interval_secs > 0,
+            "{} must be positive",
+            SCHEDULER_INTERVAL_SECS_KEY
+        );
+
+        let thumbnail_interval_secs = match load_env(THUMBNAIL_INTERVAL_SECS_KEY) {
+            Ok(secs) => secs
+                .parse()
+                .with_context(|| format!("invalid {} {:?}", THUMBNAIL_INTERVAL_SECS_KEY, secs))?,
+            Err(_) => 10,
+        };
+        anyhow::ensure!(
+            thumbnail_interval_secs > 0,
+            "{} must be positive",
+            THUMBNAIL_INTERVAL_SECS_KEY
+        );
+
+        let attachment_storage = match load_env(ATTACHMENT_STORAGE_KEY).as_deref() {
+            Ok("local") | Err(_) => AttachmentStorageConfig::Local {
+                dir: load_env(ATTACHMENT_DIR_KEY)
+                    .unwrap_or("attachments".to_string())
+                    .into(),
+            },
+            Ok("s3") => AttachmentStorageConfig::S3(S3Config {
+                endpoint: load_env(S3_ENDPOINT_KEY)?,
+                bucket: load_env(S3_BUCKET_KEY)?,
+                region: load_env(S3_REGION_KEY).unwrap_or("us-east-1".to_string()),
+                access_key_id: load_env(S3_ACCESS_KEY_ID_KEY)?,
+                secret_access_key: load_env(S3_SECRET_ACCESS_KEY_KEY)?,
+            }),
+            Ok(other) => anyhow::bail!(
+                "invalid {} {:?}, expected local or s3",
+                ATTACHMENT_STORAGE_KEY,
+                other
+            ),
+        };
 
         Ok(Config {
             server_port,
             database_url,
+            trusted_proxy_secret,
+            scheduler_interval_secs,
+            thumbnail_interval_secs,
+            attachment_storage,
         })
     }
 }
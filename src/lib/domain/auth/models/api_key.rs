@@ -0,0 +1,286 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::principal::Scope;
+
+/// Every API key starts with this marker, which makes leaked keys easy to spot and tells the auth
+/// layer apart from other bearer tokens.
+pub const API_KEY_MARKER: &str = "dlk";
+
+/// An API key a user issued for scripts and CI jobs. The secret itself is never stored: only its
+/// [ApiKeySecret::hash] is.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiKey {
+    id: Uuid,
+    user_id: Uuid,
+    name: ApiKeyName,
+    prefix: String,
+    scope: Scope,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    pub fn new(
+        id: Uuid,
+        user_id: Uuid,
+        name: ApiKeyName,
+        prefix: String,
+        scope: Scope,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            prefix,
+            scope,
+            created_at,
+            last_used_at: None,
+            revoked_at: None,
+        }
+    }
+
+    /// Records when the key was last used to authenticate.
+    pub fn with_last_used_at(mut self, last_used_at: Option<DateTime<Utc>>) -> Self {
+        self.last_used_at = last_used_at;
+        self
+    }
+
+    /// Records when the key was revoked.
+    pub fn with_revoked_at(mut self, revoked_at: Option<DateTime<Utc>>) -> Self {
+        self.revoked_at = revoked_at;
+        self
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    /// The user the key acts on behalf of.
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn name(&self) -> &ApiKeyName {
+        &self.name
+    }
+
+    /// The public part of the key, which identifies it without revealing the secret.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn last_used_at(&self) -> Option<&DateTime<Utc>> {
+        self.last_used_at.as_ref()
+    }
+
+    pub fn revoked_at(&self) -> Option<&DateTime<Utc>> {
+        self.revoked_at.as_ref()
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+/// A validated and formatted API key name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiKeyName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("api key name cannot be empty")]
+pub struct ApiKeyNameEmptyError;
+
+impl ApiKeyName {
+    pub fn new(raw: &str) -> Result<Self, ApiKeyNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(ApiKeyNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for ApiKeyName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The plaintext form of an API key, `dlk_<prefix>_<secret>`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKeySecret {
+    prefix: String,
+    secret: String,
+}
+
+impl ApiKeySecret {
+    /// Generate a fresh key with a random prefix and secret.
+    pub fn generate() -> Self {
+        let prefix = Uuid::new_v4().simple().to_string()[..8].to_string();
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        Self { prefix, secret }
+    }
+
+    /// Parse a key presented by a client.
+    pub fn parse(raw: &str) -> Result<Self, ApiKeyError> {
+        let mut parts = raw.trim().splitn(3, '_');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(API_KEY_MARKER), Some(prefix), Some(secret))
+                if !prefix.is_empty() && !secret.is_empty() =>
+            {
+                Ok(Self {
+                    prefix: prefix.to_string(),
+                    secret: secret.to_string(),
+                })
+            }
+            _ => Err(ApiKeyError::Invalid),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The SHA-256 digest of the whole key, hex encoded, as stored at rest.
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.expose().as_bytes()))
+    }
+
+    /// The plaintext key. Only ever shown once, when the key is issued.
+    pub fn expose(&self) -> String {
+        format!("{}_{}_{}", API_KEY_MARKER, self.prefix, self.secret)
+    }
+
+    /// Compare against a stored hash without leaking how many leading characters matched.
+    pub fn matches(&self, hash: &str) -> bool {
+        let own = self.hash();
+        own.len() == hash.len()
+            && own
+                .bytes()
+                .zip(hash.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl std::fmt::Debug for ApiKeySecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeySecret")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A newly issued [ApiKey] together with its plaintext secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuedApiKey {
+    api_key: ApiKey,
+    secret: ApiKeySecret,
+}
+
+impl IssuedApiKey {
+    pub fn new(api_key: ApiKey, secret: ApiKeySecret) -> Self {
+        Self { api_key, secret }
+    }
+
+    pub fn api_key(&self) -> &ApiKey {
+        &self.api_key
+    }
+
+    pub fn secret(&self) -> &ApiKeySecret {
+        &self.secret
+    }
+}
+
+/// The fields required by the domain to issue an [ApiKey].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateApiKeyRequest {
+    user_id: Uuid,
+    name: ApiKeyName,
+    scope: Scope,
+}
+
+impl CreateApiKeyRequest {
+    pub fn new(user_id: Uuid, name: &str, scope: Scope) -> Result<Self, ApiKeyNameEmptyError> {
+        let name = ApiKeyName::new(name)?;
+        Ok(Self {
+            user_id,
+            name,
+            scope,
+        })
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn name(&self) -> &ApiKeyName {
+        &self.name
+    }
+
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+    #[error("invalid api key")]
+    Invalid,
+    #[error("api key {id} has been revoked")]
+    Revoked { id: Uuid },
+    #[error("api key {id} not found")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_key_round_trips() {
+        let secret = ApiKeySecret::generate();
+        let parsed = ApiKeySecret::parse(&secret.expose()).unwrap();
+
+        assert_eq!(parsed.prefix(), secret.prefix());
+        assert!(parsed.matches(&secret.hash()));
+    }
+
+    #[test]
+    fn test_tampered_key_does_not_match() {
+        let secret = ApiKeySecret::generate();
+        let tampered = ApiKeySecret::parse(&format!("{}0", secret.expose())).unwrap();
+
+        assert!(!tampered.matches(&secret.hash()));
+    }
+
+    #[test]
+    fn test_foreign_token_is_invalid() {
+        assert!(matches!(
+            ApiKeySecret::parse("ghp_abc"),
+            Err(ApiKeyError::Invalid)
+        ));
+        assert!(matches!(
+            ApiKeySecret::parse("dlk_prefix"),
+            Err(ApiKeyError::Invalid)
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+/// What a [Principal] is allowed to do, regardless of which resources it may reach.
+///
+/// Scopes are ordered by privilege, so `scope >= Scope::ReadWrite` reads as "may write".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    /// May only read.
+    Read,
+    /// May read and write.
+    ReadWrite,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::ReadWrite => "read_write",
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown scope {0}")]
+pub struct UnknownScopeError(pub String);
+
+impl FromStr for Scope {
+    type Err = UnknownScopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" => Ok(Scope::Read),
+            "read_write" => Ok(Scope::ReadWrite),
+            _ => Err(UnknownScopeError(s.to_string())),
+        }
+    }
+}
+
+/// The authenticated caller on whose behalf a domain operation is performed.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Principal {
+    user_id: Uuid,
+    scope: Scope,
+    api_key_id: Option<Uuid>,
+}
+
+impl Principal {
+    /// A user acting interactively, with full [Scope::ReadWrite] access.
+    pub fn new(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            scope: Scope::ReadWrite,
+            api_key_id: None,
+        }
+    }
+
+    /// A machine acting on behalf of `user_id` through the API key `api_key_id`, limited to
+    /// the key's `scope`.
+    pub fn from_api_key(user_id: Uuid, api_key_id: Uuid, scope: Scope) -> Self {
+        Self {
+            user_id,
+            scope,
+            api_key_id: Some(api_key_id),
+        }
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// The API key the caller authenticated with, or `None` for an interactive user.
+    pub fn api_key_id(&self) -> Option<&Uuid> {
+        self.api_key_id.as_ref()
+    }
+}
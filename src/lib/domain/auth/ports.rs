@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::models::api_key::{
+    ApiKey, ApiKeyError, ApiKeySecret, CreateApiKeyRequest, IssuedApiKey,
+};
+use super::models::principal::Principal;
+
+/// `AuthService` is the public API for the auth domain.
+///
+/// It issues and verifies the credentials that machines use in place of an interactive login.
+pub trait AuthService: Clone + Send + Sync + 'static {
+    /// Issue a new [ApiKey]. The plaintext secret is only available in the returned
+    /// [IssuedApiKey].
+    fn create_api_key(
+        &self,
+        req: &CreateApiKeyRequest,
+    ) -> impl Future<Output = Result<IssuedApiKey, ApiKeyError>> + Send;
+
+    /// List the [ApiKey]s issued by a user, including revoked ones.
+    fn list_api_keys(
+        &self,
+        user_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<ApiKey>, ApiKeyError>> + Send;
+
+    /// Revoke one of a user's [ApiKey]s. Revoking an already revoked key is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// - [ApiKeyError::NotFound] if the user has no such key.
+    fn revoke_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<ApiKey, ApiKeyError>> + Send;
+
+    /// Resolve the [Principal] behind a raw API key and record that the key was used.
+    ///
+    /// # Errors
+    ///
+    /// - [ApiKeyError::Invalid] if the key is malformed, unknown or does not match.
+    /// - [ApiKeyError::Revoked] if the key has been revoked.
+    fn authenticate_api_key(
+        &self,
+        raw: &str,
+    ) -> impl Future<Output = Result<Principal, ApiKeyError>> + Send;
+}
+
+/// `ApiKeyRepository` represents a store of API keys.
+pub trait ApiKeyRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [ApiKey], storing only the hash of `secret`.
+    fn create_api_key(
+        &self,
+        req: &CreateApiKeyRequest,
+        secret: &ApiKeySecret,
+    ) -> impl Future<Output = Result<ApiKey, ApiKeyError>> + Send;
+
+    /// Retrieve an [ApiKey] and its stored hash by the key's public prefix.
+    fn find_api_key_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> impl Future<Output = Result<Option<(ApiKey, String)>, ApiKeyError>> + Send;
+
+    /// Retrieve the [ApiKey]s issued by a user.
+    fn list_api_keys_for_user(
+        &self,
+        user_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<ApiKey>, ApiKeyError>> + Send;
+
+    /// Mark one of a user's [ApiKey]s as revoked, returning `None` if the user has no such key.
+    fn revoke_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<ApiKey>, ApiKeyError>> + Send;
+
+    /// Record that an [ApiKey] was used at `at`.
+    fn touch_api_key(
+        &self,
+        id: &Uuid,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), ApiKeyError>> + Send;
+}
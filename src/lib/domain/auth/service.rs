@@ -0,0 +1,87 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    models::{
+        api_key::{ApiKey, ApiKeyError, ApiKeySecret, CreateApiKeyRequest, IssuedApiKey},
+        principal::Principal,
+    },
+    ports::{ApiKeyRepository, AuthService},
+};
+
+/// Canonical implementation of the [AuthService] port, through which the auth domain API is
+/// consumed.
+#[derive(Debug, Clone)]
+pub struct Service<R>
+where
+    R: ApiKeyRepository,
+{
+    repo: R,
+}
+
+impl<R> Service<R>
+where
+    R: ApiKeyRepository,
+{
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+}
+
+impl<R> AuthService for Service<R>
+where
+    R: ApiKeyRepository,
+{
+    /// Generate a fresh secret and persist the [ApiKey] it belongs to.
+    async fn create_api_key(&self, req: &CreateApiKeyRequest) -> Result<IssuedApiKey, ApiKeyError> {
+        let secret = ApiKeySecret::generate();
+        let api_key = self.repo.create_api_key(req, &secret).await?;
+        Ok(IssuedApiKey::new(api_key, secret))
+    }
+
+    async fn list_api_keys(&self, user_id: &Uuid) -> Result<Vec<ApiKey>, ApiKeyError> {
+        self.repo.list_api_keys_for_user(user_id).await
+    }
+
+    /// Revoke an [ApiKey] owned by `user_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [ApiKeyError::NotFound] if the [ApiKeyRepository] has no such key for the user.
+    async fn revoke_api_key(&self, user_id: &Uuid, id: &Uuid) -> Result<ApiKey, ApiKeyError> {
+        self.repo
+            .revoke_api_key(user_id, id)
+            .await?
+            .ok_or(ApiKeyError::NotFound { id: *id })
+    }
+
+    /// Verify `raw` against the stored hash of the key sharing its prefix.
+    ///
+    /// # Errors
+    ///
+    /// - [ApiKeyError::Invalid] if the key is malformed, unknown or its secret does not match.
+    /// - [ApiKeyError::Revoked] if the key has been revoked.
+    async fn authenticate_api_key(&self, raw: &str) -> Result<Principal, ApiKeyError> {
+        let secret = ApiKeySecret::parse(raw)?;
+        let (api_key, hash) = self
+            .repo
+            .find_api_key_by_prefix(secret.prefix())
+            .await?
+            .ok_or(ApiKeyError::Invalid)?;
+        if !secret.matches(&hash) {
+            return Err(ApiKeyError::Invalid);
+        }
+        if api_key.is_revoked() {
+            return Err(ApiKeyError::Revoked { id: *api_key.id() });
+        }
+
+        if let Err(e) = self.repo.touch_api_key(api_key.id(), Utc::now()).await {
+            tracing::warn!("failed to record use of api key {}: {}", api_key.id(), e);
+        }
+        Ok(Principal::from_api_key(
+            *api_key.user_id(),
+            *api_key.id(),
+            api_key.scope(),
+        ))
+    }
+}
@@ -1,3 +1,6 @@
 pub mod models;
+pub mod policy;
 pub mod ports;
 pub mod service;
+#[cfg(test)]
+pub(crate) mod testing;
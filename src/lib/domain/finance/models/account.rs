@@ -0,0 +1,331 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::exchange::MissingExchangeRate;
+
+/// Where the money of an [Account] is kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccountKind {
+    /// Cash or a digital wallet.
+    Wallet,
+    Bank,
+    /// A credit or debit card.
+    Card,
+}
+
+impl AccountKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountKind::Wallet => "wallet",
+            AccountKind::Bank => "bank",
+            AccountKind::Card => "card",
+        }
+    }
+}
+
+impl Display for AccountKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown account kind {0}")]
+pub struct UnknownAccountKindError(pub String);
+
+impl FromStr for AccountKind {
+    type Err = UnknownAccountKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "wallet" => Ok(AccountKind::Wallet),
+            "bank" => Ok(AccountKind::Bank),
+            "card" => Ok(AccountKind::Card),
+            _ => Err(UnknownAccountKindError(s.to_string())),
+        }
+    }
+}
+
+/// A validated ISO 4217 currency code, such as `EUR`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("invalid currency code {0}, expected three letters")]
+pub struct InvalidCurrencyError(pub String);
+
+impl Currency {
+    pub fn new(raw: &str) -> Result<Self, InvalidCurrencyError> {
+        let trimmed = raw.trim();
+        if trimmed.len() == 3 && trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+            Ok(Self(trimmed.to_ascii_uppercase()))
+        } else {
+            Err(InvalidCurrencyError(raw.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// How many decimal places the minor unit of the currency has, matching the
+    /// `currency_exponent` database function.
+    pub fn exponent(&self) -> u32 {
+        match self.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A validated and formatted account name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccountName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("account name cannot be empty")]
+pub struct AccountNameEmptyError;
+
+impl AccountName {
+    pub fn new(raw: &str) -> Result<Self, AccountNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(AccountNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for AccountName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A wallet, bank account or card some money is paid from or into. Accounts are personal: every
+/// transaction is recorded against one of its author's accounts.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Account {
+    id: Uuid,
+    owner_id: Uuid,
+    name: AccountName,
+    kind: AccountKind,
+    currency: Currency,
+    opening_balance: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl Account {
+    pub fn new(
+        id: Uuid,
+        owner_id: Uuid,
+        name: AccountName,
+        kind: AccountKind,
+        currency: Currency,
+        opening_balance: i64,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            owner_id,
+            name,
+            kind,
+            currency,
+            opening_balance,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn name(&self) -> &AccountName {
+        &self.name
+    }
+
+    pub fn kind(&self) -> AccountKind {
+        self.kind
+    }
+
+    /// The currency of the account, and of every transaction recorded against it.
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// The balance before any transaction was recorded, in minor units.
+    pub fn opening_balance(&self) -> i64 {
+        self.opening_balance
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// The balance of an [Account] at the end of a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BalancePoint {
+    day: NaiveDate,
+    balance: i64,
+}
+
+impl BalancePoint {
+    pub fn new(day: NaiveDate, balance: i64) -> Self {
+        Self { day, balance }
+    }
+
+    pub fn day(&self) -> &NaiveDate {
+        &self.day
+    }
+
+    /// In minor units of the account's currency.
+    pub fn balance(&self) -> i64 {
+        self.balance
+    }
+}
+
+/// The current balance of an [Account], with its history over a range of days, in the currency
+/// of the account or converted to another one.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccountBalance {
+    account: Account,
+    currency: Currency,
+    balance: i64,
+    history: Vec<BalancePoint>,
+}
+
+impl AccountBalance {
+    pub fn new(
+        account: Account,
+        currency: Currency,
+        balance: i64,
+        history: Vec<BalancePoint>,
+    ) -> Self {
+        Self {
+            account,
+            currency,
+            balance,
+            history,
+        }
+    }
+
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    /// The currency the balances are given in.
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// The balance including every transaction up to today, in minor units.
+    pub fn balance(&self) -> i64 {
+        self.balance
+    }
+
+    /// The end-of-day balances over the requested range, one per day.
+    pub fn history(&self) -> &[BalancePoint] {
+        &self.history
+    }
+}
+
+/// The fields required by the domain to create an [Account].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateAccountRequest {
+    owner_id: Uuid,
+    name: AccountName,
+    kind: AccountKind,
+    currency: Currency,
+    opening_balance: i64,
+}
+
+impl CreateAccountRequest {
+    pub fn new(
+        owner_id: Uuid,
+        name: &str,
+        kind: AccountKind,
+        currency: Currency,
+    ) -> Result<Self, AccountNameEmptyError> {
+        let name = AccountName::new(name)?;
+        Ok(Self {
+            owner_id,
+            name,
+            kind,
+            currency,
+            opening_balance: 0,
+        })
+    }
+
+    /// Sets the balance before any transaction, in minor units. May be negative, e.g. for a
+    /// card.
+    pub fn with_opening_balance(mut self, opening_balance: i64) -> Self {
+        self.opening_balance = opening_balance;
+        self
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn name(&self) -> &AccountName {
+        &self.name
+    }
+
+    pub fn kind(&self) -> AccountKind {
+        self.kind
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn opening_balance(&self) -> i64 {
+        self.opening_balance
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AccountError {
+    #[error("account {id} not found")]
+    NotFound { id: Uuid },
+    #[error("cannot report more than {max} days of balance history, got {days}")]
+    RangeTooLong { days: i64, max: i64 },
+    #[error(transparent)]
+    MissingExchangeRate(#[from] MissingExchangeRate),
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_is_normalized() {
+        assert_eq!(Currency::new(" eur ").unwrap().as_str(), "EUR");
+        assert!(Currency::new("EURO").is_err());
+        assert!(Currency::new("E1R").is_err());
+    }
+}
@@ -0,0 +1,483 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+/// The largest file that can be attached to an [Expense](super::expense::Expense), in bytes.
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// The longest file name kept for an [Attachment], in characters.
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// The largest width or height of a thumbnail, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// A file, such as the photo of a receipt, attached to an [Expense](super::expense::Expense).
+///
+/// Only what describes the file is kept here: its content lives in the attachment storage, under
+/// its [storage key](Attachment::storage_key), next to its thumbnail once generated.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Attachment {
+    id: Uuid,
+    expense_id: Uuid,
+    file_name: String,
+    content_type: AttachmentContentType,
+    size: u64,
+    created_at: DateTime<Utc>,
+    thumbnail_status: ThumbnailStatus,
+}
+
+impl Attachment {
+    /// A new attachment's thumbnail is pending if one can be generated for its content type.
+    pub fn new(
+        id: Uuid,
+        expense_id: Uuid,
+        file_name: &str,
+        content_type: AttachmentContentType,
+        size: u64,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            expense_id,
+            file_name: file_name.to_string(),
+            content_type,
+            size,
+            created_at,
+            thumbnail_status: if content_type.has_thumbnail() {
+                ThumbnailStatus::Pending
+            } else {
+                ThumbnailStatus::Unavailable
+            },
+        }
+    }
+
+    pub fn with_thumbnail_status(mut self, thumbnail_status: ThumbnailStatus) -> Self {
+        self.thumbnail_status = thumbnail_status;
+        self
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn expense_id(&self) -> &Uuid {
+        &self.expense_id
+    }
+
+    /// The name of the file as uploaded, without any directory.
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// The type of the content, as read from the content itself rather than declared by the
+    /// uploader.
+    pub fn content_type(&self) -> AttachmentContentType {
+        self.content_type
+    }
+
+    /// The size of the content, in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn thumbnail_status(&self) -> ThumbnailStatus {
+        self.thumbnail_status
+    }
+
+    /// The key the content is stored under, grouping the attachments of an expense together.
+    pub fn storage_key(&self) -> String {
+        self.variant_key(AttachmentVariant::Original)
+    }
+
+    /// The key `variant` of the content is stored under, next to the original.
+    pub fn variant_key(&self, variant: AttachmentVariant) -> String {
+        match variant {
+            AttachmentVariant::Original => format!("expenses/{}/{}", self.expense_id, self.id),
+            AttachmentVariant::Thumb => format!("expenses/{}/{}-thumb", self.expense_id, self.id),
+        }
+    }
+
+    /// The type of the content of `variant`: thumbnails are always JPEG pictures.
+    pub fn variant_content_type(&self, variant: AttachmentVariant) -> AttachmentContentType {
+        match variant {
+            AttachmentVariant::Original => self.content_type,
+            AttachmentVariant::Thumb => AttachmentContentType::Jpeg,
+        }
+    }
+}
+
+/// Where the generation of the thumbnail of an [Attachment] stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ThumbnailStatus {
+    /// Waiting for the background job to generate it.
+    Pending,
+    /// Generated and stored next to the original.
+    Ready,
+    /// Never to be generated: the content cannot be decoded, or is of a kind without thumbnails.
+    Unavailable,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown thumbnail status {0:?}")]
+pub struct UnknownThumbnailStatusError(pub String);
+
+impl ThumbnailStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Ready => "ready",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+impl FromStr for ThumbnailStatus {
+    type Err = UnknownThumbnailStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "ready" => Ok(Self::Ready),
+            "unavailable" => Ok(Self::Unavailable),
+            _ => Err(UnknownThumbnailStatusError(s.to_string())),
+        }
+    }
+}
+
+impl Display for ThumbnailStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The versions of the content of an [Attachment] that can be downloaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttachmentVariant {
+    /// The file as uploaded.
+    #[default]
+    Original,
+    /// A JPEG picture of at most [THUMBNAIL_SIZE] pixels a side: the picture resized, or the
+    /// first page of a PDF document.
+    Thumb,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown attachment variant {0:?}, expected original or thumb")]
+pub struct UnknownAttachmentVariantError(pub String);
+
+impl FromStr for AttachmentVariant {
+    type Err = UnknownAttachmentVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "original" => Ok(Self::Original),
+            "thumb" => Ok(Self::Thumb),
+            _ => Err(UnknownAttachmentVariantError(s.to_string())),
+        }
+    }
+}
+
+/// The kinds of files accepted as attachments: pictures of receipts, and PDF invoices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttachmentContentType {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Heic,
+    Pdf,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown attachment content type {0:?}")]
+pub struct UnknownAttachmentContentTypeError(pub String);
+
+impl AttachmentContentType {
+    /// Recognizes the type of `content` from its first bytes, or `None` if it is not one of the
+    /// accepted types.
+    pub fn sniff(content: &[u8]) -> Option<Self> {
+        const HEIC_BRANDS: [&[u8]; 6] = [b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis"];
+        if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if content.starts_with(b"\x89PNG\r\n\x1A\n") {
+            Some(Self::Png)
+        } else if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if content.len() >= 12 && &content[..4] == b"RIFF" && &content[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else if content.len() >= 12
+            && &content[4..8] == b"ftyp"
+            && HEIC_BRANDS.contains(&&content[8..12])
+        {
+            Some(Self::Heic)
+        } else if content.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else {
+            None
+        }
+    }
+
+    /// The media type, as sent in a `Content-Type` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Heic => "image/heic",
+            Self::Pdf => "application/pdf",
+        }
+    }
+
+    /// Whether a thumbnail can be generated for this type. GIF animations and HEIC pictures are
+    /// left without one.
+    pub fn has_thumbnail(&self) -> bool {
+        matches!(self, Self::Jpeg | Self::Png | Self::Webp | Self::Pdf)
+    }
+
+    /// The usual file extension, for files uploaded without a name.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Heic => "heic",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+impl FromStr for AttachmentContentType {
+    type Err = UnknownAttachmentContentTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "image/jpeg" => Ok(Self::Jpeg),
+            "image/png" => Ok(Self::Png),
+            "image/gif" => Ok(Self::Gif),
+            "image/webp" => Ok(Self::Webp),
+            "image/heic" => Ok(Self::Heic),
+            "application/pdf" => Ok(Self::Pdf),
+            _ => Err(UnknownAttachmentContentTypeError(s.to_string())),
+        }
+    }
+}
+
+impl Display for AttachmentContentType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The fields required by the domain to attach a file to an
+/// [Expense](super::expense::Expense).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateAttachmentRequest {
+    expense_id: Uuid,
+    file_name: String,
+    content_type: AttachmentContentType,
+    content: Vec<u8>,
+}
+
+impl CreateAttachmentRequest {
+    /// Attaches `content`, uploaded as `file_name`, to the expense `expense_id`.
+    ///
+    /// Only the last component of `file_name` is kept, without control characters, so that it
+    /// can safely be sent back in a `Content-Disposition` header.
+    ///
+    /// # Errors
+    ///
+    /// - [AttachmentError::TooLarge] if `content` is larger than [MAX_ATTACHMENT_SIZE].
+    /// - [AttachmentError::UnsupportedContentType] if `content` is not a picture or a PDF,
+    ///   whatever its name says.
+    pub fn new(
+        expense_id: Uuid,
+        file_name: &str,
+        content: Vec<u8>,
+    ) -> Result<Self, AttachmentError> {
+        if content.len() > MAX_ATTACHMENT_SIZE {
+            return Err(AttachmentError::TooLarge {
+                size: content.len(),
+                max: MAX_ATTACHMENT_SIZE,
+            });
+        }
+        let content_type = AttachmentContentType::sniff(&content)
+            .ok_or(AttachmentError::UnsupportedContentType)?;
+        let base_name = file_name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_control() && *c != '"')
+            .take(MAX_FILE_NAME_LENGTH)
+            .collect::<String>();
+        let file_name = match base_name.trim() {
+            "" | "." | ".." => format!("attachment.{}", content_type.extension()),
+            trimmed => trimmed.to_string(),
+        };
+        Ok(Self {
+            expense_id,
+            file_name,
+            content_type,
+            content,
+        })
+    }
+
+    pub fn expense_id(&self) -> &Uuid {
+        &self.expense_id
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn content_type(&self) -> AttachmentContentType {
+        self.content_type
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+/// An [Attachment] together with its content, as downloaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttachmentFile {
+    attachment: Attachment,
+    content: Vec<u8>,
+}
+
+impl AttachmentFile {
+    pub fn new(attachment: Attachment, content: Vec<u8>) -> Self {
+        Self {
+            attachment,
+            content,
+        }
+    }
+
+    pub fn attachment(&self) -> &Attachment {
+        &self.attachment
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("expense {id} not found")]
+    ExpenseNotFound { id: Uuid },
+    #[error("attachment {id} not found")]
+    NotFound { id: Uuid },
+    #[error("attachment of {size} bytes is larger than the {max} bytes allowed")]
+    TooLarge { size: usize, max: usize },
+    #[error("attachments must be JPEG, PNG, GIF, WebP or HEIC pictures, or PDF documents")]
+    UnsupportedContentType,
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_is_sniffed() {
+        assert_eq!(
+            AttachmentContentType::sniff(b"\xFF\xD8\xFF\xE0\x00\x10JFIF"),
+            Some(AttachmentContentType::Jpeg)
+        );
+        assert_eq!(
+            AttachmentContentType::sniff(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some(AttachmentContentType::Webp)
+        );
+        assert_eq!(
+            AttachmentContentType::sniff(b"\x00\x00\x00\x18ftypheic\x00\x00\x00\x00"),
+            Some(AttachmentContentType::Heic)
+        );
+        assert_eq!(
+            AttachmentContentType::sniff(b"%PDF-1.7\n"),
+            Some(AttachmentContentType::Pdf)
+        );
+        assert_eq!(AttachmentContentType::sniff(b"<html>"), None);
+        assert_eq!(
+            "image/png".parse::<AttachmentContentType>().unwrap(),
+            AttachmentContentType::Png
+        );
+    }
+
+    #[test]
+    fn test_thumbnail_is_stored_next_to_the_original() {
+        let attachment = Attachment::new(
+            Uuid::from_u128(2),
+            Uuid::from_u128(1),
+            "receipt.png",
+            AttachmentContentType::Png,
+            42,
+            Utc::now(),
+        );
+        assert_eq!(attachment.thumbnail_status(), ThumbnailStatus::Pending);
+        assert_eq!(
+            attachment.variant_key(AttachmentVariant::Thumb),
+            format!("{}-thumb", attachment.storage_key())
+        );
+        assert_eq!(
+            attachment.variant_content_type(AttachmentVariant::Thumb),
+            AttachmentContentType::Jpeg
+        );
+
+        let attachment = Attachment::new(
+            Uuid::from_u128(3),
+            Uuid::from_u128(1),
+            "receipt.heic",
+            AttachmentContentType::Heic,
+            42,
+            Utc::now(),
+        );
+        assert_eq!(attachment.thumbnail_status(), ThumbnailStatus::Unavailable);
+        assert!("large".parse::<AttachmentVariant>().is_err());
+    }
+
+    #[test]
+    fn test_request_is_validated() {
+        let expense_id = Uuid::from_u128(1);
+        let req = CreateAttachmentRequest::new(
+            expense_id,
+            "C:\\Receipts\\bakery.pdf",
+            b"%PDF-1.4".to_vec(),
+        )
+        .unwrap();
+        assert_eq!(req.file_name(), "bakery.pdf");
+        assert_eq!(req.content_type(), AttachmentContentType::Pdf);
+        let req = CreateAttachmentRequest::new(expense_id, "../", b"GIF89a".to_vec()).unwrap();
+        assert_eq!(req.file_name(), "attachment.gif");
+
+        assert!(matches!(
+            CreateAttachmentRequest::new(expense_id, "notes.txt", b"receipt".to_vec()),
+            Err(AttachmentError::UnsupportedContentType)
+        ));
+        let mut content = b"%PDF-".to_vec();
+        content.resize(MAX_ATTACHMENT_SIZE + 1, 0);
+        assert!(matches!(
+            CreateAttachmentRequest::new(expense_id, "big.pdf", content),
+            Err(AttachmentError::TooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+/// An amount, in minor units, that one member owes another.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Debt {
+    from: Uuid,
+    to: Uuid,
+    amount: i64,
+}
+
+impl Debt {
+    pub fn new(from: Uuid, to: Uuid, amount: i64) -> Self {
+        Self { from, to, amount }
+    }
+
+    /// The member who owes.
+    pub fn from(&self) -> &Uuid {
+        &self.from
+    }
+
+    /// The member who is owed.
+    pub fn to(&self) -> &Uuid {
+        &self.to
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+}
+
+/// The net position of a member: positive when the group owes them, negative when they owe the
+/// group.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemberBalance {
+    user_id: Uuid,
+    net: i64,
+}
+
+impl MemberBalance {
+    pub fn new(user_id: Uuid, net: i64) -> Self {
+        Self { user_id, net }
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn net(&self) -> i64 {
+        self.net
+    }
+}
+
+/// Who owes whom within a ledger.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedgerBalances {
+    members: Vec<MemberBalance>,
+    debts: Vec<Debt>,
+}
+
+impl LedgerBalances {
+    /// Net the raw `debts` between each pair of members and derive every member's balance.
+    ///
+    /// `members` lists everyone who should appear in the result even without any activity. Debts
+    /// in both directions between the same pair cancel out, so at most one [Debt] remains per
+    /// pair.
+    pub fn from_debts(members: &[Uuid], debts: &[Debt]) -> Self {
+        let mut pairs: BTreeMap<(Uuid, Uuid), i64> = BTreeMap::new();
+        for debt in debts.iter().filter(|d| d.from != d.to) {
+            if debt.from < debt.to {
+                *pairs.entry((debt.from, debt.to)).or_default() += debt.amount;
+            } else {
+                *pairs.entry((debt.to, debt.from)).or_default() -= debt.amount;
+            }
+        }
+
+        let mut nets: BTreeMap<Uuid, i64> = members.iter().map(|m| (*m, 0)).collect();
+        let mut netted = Vec::new();
+        for ((a, b), amount) in pairs {
+            let debt = match amount {
+                0 => continue,
+                amount if amount > 0 => Debt::new(a, b, amount),
+                amount => Debt::new(b, a, -amount),
+            };
+            *nets.entry(debt.from).or_default() -= debt.amount;
+            *nets.entry(debt.to).or_default() += debt.amount;
+            netted.push(debt);
+        }
+
+        Self {
+            members: nets
+                .into_iter()
+                .map(|(user_id, net)| MemberBalance::new(user_id, net))
+                .collect(),
+            debts: netted,
+        }
+    }
+
+    pub fn members(&self) -> &[MemberBalance] {
+        &self.members
+    }
+
+    pub fn debts(&self) -> &[Debt] {
+        &self.debts
+    }
+
+    /// A small set of transfers that brings every member's balance back to zero.
+    ///
+    /// Finding the true minimum is NP-hard, so the largest debtor repeatedly pays the largest
+    /// creditor. This needs at most one transfer fewer than the number of members with a non-zero
+    /// balance and never routes money through someone who is already settled. Ties are broken by
+    /// user id, keeping the result deterministic.
+    pub fn simplified_transfers(&self) -> Vec<Debt> {
+        let mut debtors: Vec<(Uuid, i64)> = Vec::new();
+        let mut creditors: Vec<(Uuid, i64)> = Vec::new();
+        for member in &self.members {
+            match member.net {
+                net if net < 0 => debtors.push((member.user_id, -net)),
+                net if net > 0 => creditors.push((member.user_id, net)),
+                _ => {}
+            }
+        }
+
+        let mut transfers = Vec::new();
+        loop {
+            debtors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            creditors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let (Some(debtor), Some(creditor)) = (debtors.first_mut(), creditors.first_mut())
+            else {
+                break;
+            };
+
+            let amount = debtor.1.min(creditor.1);
+            transfers.push(Debt::new(debtor.0, creditor.0, amount));
+            debtor.1 -= amount;
+            creditor.1 -= amount;
+            debtors.retain(|(_, owed)| *owed > 0);
+            creditors.retain(|(_, owed)| *owed > 0);
+        }
+        transfers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opposite_debts_are_netted() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let balances =
+            LedgerBalances::from_debts(&[a, b], &[Debt::new(a, b, 1000), Debt::new(b, a, 300)]);
+
+        assert_eq!(balances.debts(), &[Debt::new(a, b, 700)]);
+        assert!(balances.members().contains(&MemberBalance::new(a, -700)));
+        assert!(balances.members().contains(&MemberBalance::new(b, 700)));
+    }
+
+    #[test]
+    fn test_simplified_transfers_skip_intermediaries() {
+        // a owes b, b owes c: a can pay c directly.
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let balances =
+            LedgerBalances::from_debts(&[a, b, c], &[Debt::new(a, b, 500), Debt::new(b, c, 500)]);
+
+        assert_eq!(balances.simplified_transfers(), vec![Debt::new(a, c, 500)]);
+    }
+
+    #[test]
+    fn test_simplified_transfers_settle_everyone() {
+        let members: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let debts = vec![
+            Debt::new(members[0], members[1], 1200),
+            Debt::new(members[1], members[2], 700),
+            Debt::new(members[2], members[3], 300),
+            Debt::new(members[3], members[0], 900),
+            Debt::new(members[4], members[2], 450),
+        ];
+        let balances = LedgerBalances::from_debts(&members, &debts);
+        let transfers = balances.simplified_transfers();
+
+        let non_zero = balances.members().iter().filter(|m| m.net() != 0).count();
+        assert!(transfers.len() < non_zero);
+        for member in balances.members() {
+            let paid: i64 = transfers
+                .iter()
+                .filter(|t| t.from() == member.user_id())
+                .map(Debt::amount)
+                .sum();
+            let received: i64 = transfers
+                .iter()
+                .filter(|t| t.to() == member.user_id())
+                .map(Debt::amount)
+                .sum();
+            assert_eq!(member.net() + paid - received, 0);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::account::Currency;
+use super::exchange::{MissingExchangeRate, REFERENCE_CURRENCY};
+
+/// How often a [Budget] resets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BudgetPeriod {
+    /// Monday to Sunday.
+    Weekly,
+    /// First to last day of the calendar month.
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Weekly => "weekly",
+            BudgetPeriod::Monthly => "monthly",
+        }
+    }
+
+    /// The period containing `day`, as a half-open range of dates `[start, end)`.
+    pub fn window(&self, day: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = match self {
+            BudgetPeriod::Weekly => {
+                day - Days::new(u64::from(day.weekday().num_days_from_monday()))
+            }
+            BudgetPeriod::Monthly => day.with_day(1).expect("every month has a first day"),
+        };
+        let end = match self {
+            BudgetPeriod::Weekly => start + Days::new(7),
+            BudgetPeriod::Monthly => start + Months::new(1),
+        };
+        (start, end)
+    }
+}
+
+impl Display for BudgetPeriod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown budget period {0}")]
+pub struct UnknownBudgetPeriodError(pub String);
+
+impl FromStr for BudgetPeriod {
+    type Err = UnknownBudgetPeriodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "weekly" => Ok(BudgetPeriod::Weekly),
+            "monthly" => Ok(BudgetPeriod::Monthly),
+            _ => Err(UnknownBudgetPeriodError(s.to_string())),
+        }
+    }
+}
+
+/// A spending limit on a category, renewed every [BudgetPeriod].
+///
+/// A budget lives wherever its category does: in the category's ledger, or among its owner's
+/// personal data. Its amount is in its own currency, into which the spending in the category is
+/// converted.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Budget {
+    id: Uuid,
+    category_id: Uuid,
+    ledger_id: Option<Uuid>,
+    owner_id: Uuid,
+    amount: i64,
+    currency: Currency,
+    period: BudgetPeriod,
+    created_at: DateTime<Utc>,
+}
+
+impl Budget {
+    pub fn new(
+        id: Uuid,
+        category_id: Uuid,
+        ledger_id: Option<Uuid>,
+        owner_id: Uuid,
+        amount: i64,
+        period: BudgetPeriod,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            category_id,
+            ledger_id,
+            owner_id,
+            amount,
+            currency: reference_currency(),
+            period,
+            created_at,
+        }
+    }
+
+    /// Sets the currency of the amount, the [REFERENCE_CURRENCY] unless set otherwise.
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn category_id(&self) -> &Uuid {
+        &self.category_id
+    }
+
+    /// The ledger of the budgeted category, or `None` for a personal budget.
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    /// The user who set the budget.
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    /// The limit for each period, in minor units of [Budget::currency].
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn period(&self) -> BudgetPeriod {
+        self.period
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// The currency of budgets set without one.
+fn reference_currency() -> Currency {
+    Currency::new(REFERENCE_CURRENCY).expect("the reference currency is a valid code")
+}
+
+/// How much of a [Budget] was spent in the period containing a given day.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BudgetStatus {
+    budget: Budget,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    spent: i64,
+}
+
+impl BudgetStatus {
+    /// `period_end` is exclusive.
+    pub fn new(budget: Budget, period_start: NaiveDate, period_end: NaiveDate, spent: i64) -> Self {
+        Self {
+            budget,
+            period_start,
+            period_end,
+            spent,
+        }
+    }
+
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// The first day of the period.
+    pub fn period_start(&self) -> &NaiveDate {
+        &self.period_start
+    }
+
+    /// The first day after the period.
+    pub fn period_end(&self) -> &NaiveDate {
+        &self.period_end
+    }
+
+    /// The total spent in the category during the period, in minor units.
+    pub fn spent(&self) -> i64 {
+        self.spent
+    }
+
+    /// What is left to spend, negative once the budget is exceeded.
+    pub fn remaining(&self) -> i64 {
+        self.budget.amount - self.spent
+    }
+
+    /// The share of the budget spent so far, in percent. May exceed 100.
+    pub fn percentage(&self) -> f64 {
+        self.spent as f64 * 100.0 / self.budget.amount as f64
+    }
+
+    /// The [BudgetThreshold]s reached so far in the period, lowest first.
+    pub fn reached_thresholds(&self) -> Vec<BudgetThreshold> {
+        BudgetThreshold::ALL
+            .into_iter()
+            .filter(|t| self.spent * 100 >= self.budget.amount * i64::from(t.percent()))
+            .collect()
+    }
+}
+
+/// A share of a [Budget] whose crossing is worth telling its members about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BudgetThreshold {
+    /// 80% of the budget is spent.
+    Warning,
+    /// The whole budget is spent.
+    Exceeded,
+}
+
+impl BudgetThreshold {
+    pub const ALL: [BudgetThreshold; 2] = [BudgetThreshold::Warning, BudgetThreshold::Exceeded];
+
+    /// The share of the budget, in percent, at which the threshold is reached.
+    pub fn percent(&self) -> i16 {
+        match self {
+            BudgetThreshold::Warning => 80,
+            BudgetThreshold::Exceeded => 100,
+        }
+    }
+}
+
+impl Display for BudgetThreshold {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
+
+/// A [BudgetThreshold] reached by the spending reported in a [BudgetStatus].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BudgetAlert {
+    status: BudgetStatus,
+    threshold: BudgetThreshold,
+}
+
+impl BudgetAlert {
+    pub fn new(status: BudgetStatus, threshold: BudgetThreshold) -> Self {
+        Self { status, threshold }
+    }
+
+    pub fn status(&self) -> &BudgetStatus {
+        &self.status
+    }
+
+    pub fn threshold(&self) -> BudgetThreshold {
+        self.threshold
+    }
+}
+
+/// The fields required by the domain to create a [Budget].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateBudgetRequest {
+    category_id: Uuid,
+    owner_id: Uuid,
+    amount: i64,
+    currency: Currency,
+    period: BudgetPeriod,
+}
+
+impl CreateBudgetRequest {
+    pub fn new(
+        category_id: Uuid,
+        owner_id: Uuid,
+        amount: i64,
+        period: BudgetPeriod,
+    ) -> Result<Self, BudgetError> {
+        if amount <= 0 {
+            return Err(BudgetError::InvalidAmount { amount });
+        }
+        Ok(Self {
+            category_id,
+            owner_id,
+            amount,
+            currency: reference_currency(),
+            period,
+        })
+    }
+
+    /// Sets the currency of the amount, the [REFERENCE_CURRENCY] unless set otherwise.
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn category_id(&self) -> &Uuid {
+        &self.category_id
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn period(&self) -> BudgetPeriod {
+        self.period
+    }
+}
+
+/// The fields required by the domain to change a [Budget].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpdateBudgetRequest {
+    id: Uuid,
+    amount: i64,
+    currency: Option<Currency>,
+    period: BudgetPeriod,
+}
+
+impl UpdateBudgetRequest {
+    pub fn new(id: Uuid, amount: i64, period: BudgetPeriod) -> Result<Self, BudgetError> {
+        if amount <= 0 {
+            return Err(BudgetError::InvalidAmount { amount });
+        }
+        Ok(Self {
+            id,
+            amount,
+            currency: None,
+            period,
+        })
+    }
+
+    /// Changes the currency of the amount, which is kept otherwise.
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    /// The new currency of the amount, or `None` to keep the current one.
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    pub fn period(&self) -> BudgetPeriod {
+        self.period
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BudgetError {
+    #[error("budget amount must be positive, got {amount}")]
+    InvalidAmount { amount: i64 },
+    #[error("budget {id} not found")]
+    NotFound { id: Uuid },
+    #[error("category {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("category {category_id} already has a {period} budget")]
+    Duplicate {
+        category_id: Uuid,
+        period: BudgetPeriod,
+    },
+    #[error(transparent)]
+    MissingExchangeRate(#[from] MissingExchangeRate),
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_window_starts_on_monday() {
+        // 2026-10-18 is a Sunday.
+        assert_eq!(
+            BudgetPeriod::Weekly.window(day(2026, 10, 18)),
+            (day(2026, 10, 12), day(2026, 10, 19))
+        );
+        assert_eq!(
+            BudgetPeriod::Weekly.window(day(2026, 10, 19)),
+            (day(2026, 10, 19), day(2026, 10, 26))
+        );
+    }
+
+    #[test]
+    fn test_monthly_window_spans_calendar_month() {
+        assert_eq!(
+            BudgetPeriod::Monthly.window(day(2028, 2, 29)),
+            (day(2028, 2, 1), day(2028, 3, 1))
+        );
+        assert_eq!(
+            BudgetPeriod::Monthly.window(day(2026, 12, 31)),
+            (day(2026, 12, 1), day(2027, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_status_past_budget() {
+        let budget = Budget::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Uuid::new_v4(),
+            20000,
+            BudgetPeriod::Monthly,
+            Utc::now(),
+        );
+        let status = BudgetStatus::new(budget, day(2026, 10, 1), day(2026, 11, 1), 25000);
+
+        assert_eq!(status.remaining(), -5000);
+        assert_eq!(status.percentage(), 125.0);
+    }
+
+    #[test]
+    fn test_reached_thresholds() {
+        let budget = Budget::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Uuid::new_v4(),
+            10000,
+            BudgetPeriod::Weekly,
+            Utc::now(),
+        );
+        let status =
+            |spent| BudgetStatus::new(budget.clone(), day(2026, 10, 12), day(2026, 10, 19), spent);
+
+        assert!(status(7999).reached_thresholds().is_empty());
+        assert_eq!(
+            status(8000).reached_thresholds(),
+            vec![BudgetThreshold::Warning]
+        );
+        assert_eq!(
+            status(10000).reached_thresholds(),
+            vec![BudgetThreshold::Warning, BudgetThreshold::Exceeded]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+/// A label grouping expenses, either shared by the members of a ledger or private to the user
+/// who created it.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category {
+    id: Uuid,
+    name: CategoryName,
+    ledger_id: Option<Uuid>,
+    owner_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl Category {
+    pub fn new(
+        id: Uuid,
+        name: CategoryName,
+        ledger_id: Option<Uuid>,
+        owner_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            ledger_id,
+            owner_id,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn name(&self) -> &CategoryName {
+        &self.name
+    }
+
+    /// The ledger sharing the category, or `None` for a personal category.
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    /// The user who created the category.
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// A validated and formatted category name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("category name cannot be empty")]
+pub struct CategoryNameEmptyError;
+
+impl CategoryName {
+    pub fn new(raw: &str) -> Result<Self, CategoryNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(CategoryNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for CategoryName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The fields required by the domain to create a [Category].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateCategoryRequest {
+    name: CategoryName,
+    ledger_id: Option<Uuid>,
+    owner_id: Uuid,
+}
+
+impl CreateCategoryRequest {
+    pub fn new(name: &str, owner_id: Uuid) -> Result<Self, CategoryNameEmptyError> {
+        let name = CategoryName::new(name)?;
+        Ok(Self {
+            name,
+            ledger_id: None,
+            owner_id,
+        })
+    }
+
+    /// Share the category with the members of a ledger.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    pub fn name(&self) -> &CategoryName {
+        &self.name
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CategoryError {
+    #[error("category with name {name} already exists")]
+    Duplicate { name: CategoryName },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
@@ -0,0 +1,457 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::expense::{CreateExpenseRequest, Expense};
+use super::transaction::TransactionKind;
+
+/// The widest [DuplicateSettings::date_window_days] allowed.
+pub const MAX_DATE_WINDOW_DAYS: u32 = 31;
+
+/// What happens when an expense about to be created looks like one already recorded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DuplicateMode {
+    /// Duplicates are not looked for on creation, though they are still listed for review.
+    Off,
+    /// The expense is created, and the expenses it looks like are reported alongside.
+    #[default]
+    Warn,
+    /// The expense is refused, and imported rows looking like existing expenses are skipped.
+    Block,
+}
+
+impl DuplicateMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateMode::Off => "off",
+            DuplicateMode::Warn => "warn",
+            DuplicateMode::Block => "block",
+        }
+    }
+}
+
+impl Display for DuplicateMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown duplicate mode {0}")]
+pub struct UnknownDuplicateModeError(pub String);
+
+impl FromStr for DuplicateMode {
+    type Err = UnknownDuplicateModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(DuplicateMode::Off),
+            "warn" => Ok(DuplicateMode::Warn),
+            "block" => Ok(DuplicateMode::Block),
+            _ => Err(UnknownDuplicateModeError(s.to_string())),
+        }
+    }
+}
+
+/// How a user wants duplicate expenses to be detected: two expenses are suspected duplicates
+/// when they are of the same kind and amount, were spent at most
+/// [date_window_days](DuplicateSettings::date_window_days) apart, and their names are at least
+/// [min_similarity](DuplicateSettings::min_similarity) percent similar.
+///
+/// Occurrences of recurring expenses are never suspected: they are meant to look alike.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DuplicateSettings {
+    mode: DuplicateMode,
+    date_window_days: u32,
+    min_similarity: u8,
+}
+
+impl Default for DuplicateSettings {
+    /// Warn about expenses of the same amount spent within 3 days under 80% similar names.
+    fn default() -> Self {
+        Self {
+            mode: DuplicateMode::default(),
+            date_window_days: 3,
+            min_similarity: 80,
+        }
+    }
+}
+
+impl DuplicateSettings {
+    /// # Errors
+    ///
+    /// - [DuplicateError::InvalidDateWindow] if `date_window_days` exceeds
+    ///   [MAX_DATE_WINDOW_DAYS].
+    /// - [DuplicateError::InvalidSimilarity] if `min_similarity` exceeds 100.
+    pub fn new(
+        mode: DuplicateMode,
+        date_window_days: u32,
+        min_similarity: u8,
+    ) -> Result<Self, DuplicateError> {
+        if date_window_days > MAX_DATE_WINDOW_DAYS {
+            return Err(DuplicateError::InvalidDateWindow {
+                days: date_window_days,
+            });
+        }
+        if min_similarity > 100 {
+            return Err(DuplicateError::InvalidSimilarity {
+                value: min_similarity,
+            });
+        }
+        Ok(Self {
+            mode,
+            date_window_days,
+            min_similarity,
+        })
+    }
+
+    pub fn mode(&self) -> DuplicateMode {
+        self.mode
+    }
+
+    /// How many days apart duplicates may have been spent.
+    pub fn date_window_days(&self) -> u32 {
+        self.date_window_days
+    }
+
+    /// How similar the names of duplicates must be, in percent.
+    pub fn min_similarity(&self) -> u8 {
+        self.min_similarity
+    }
+
+    /// The days expenses looking like one spent on `day` may have been spent on, inclusive.
+    pub fn window(&self, day: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let days = chrono::Days::new(u64::from(self.date_window_days));
+        (
+            day.checked_sub_days(days).unwrap_or(NaiveDate::MIN),
+            day.checked_add_days(days).unwrap_or(NaiveDate::MAX),
+        )
+    }
+
+    /// Whether `a` and `b` look like the same transaction recorded twice.
+    pub fn is_duplicate(&self, a: &DuplicateSubject, b: &DuplicateSubject) -> bool {
+        !a.recurring
+            && !b.recurring
+            && a.kind == b.kind
+            && a.amount == b.amount
+            && (a.spent_on - b.spent_on).num_days().unsigned_abs()
+                <= u64::from(self.date_window_days)
+            && name_similarity(a.name, b.name) >= self.min_similarity
+    }
+
+    /// Group `expenses` into clusters of suspected duplicates, each in date order, the most
+    /// recent clusters first. Expenses that look like no other are left out.
+    ///
+    /// An expense joins a cluster if it looks like any expense of it, so the first and last
+    /// expenses of a cluster may be further apart than the settings allow.
+    pub fn clusters(&self, expenses: Vec<Expense>) -> Vec<DuplicateCluster> {
+        let subjects: Vec<DuplicateSubject> = expenses.iter().map(Into::into).collect();
+        let mut parents: Vec<usize> = (0..expenses.len()).collect();
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+        for i in 0..subjects.len() {
+            for j in i + 1..subjects.len() {
+                if self.is_duplicate(&subjects[i], &subjects[j]) {
+                    let (a, b) = (root(&mut parents, i), root(&mut parents, j));
+                    parents[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut clusters: Vec<Vec<Expense>> = vec![Vec::new(); expenses.len()];
+        for (i, expense) in expenses.into_iter().enumerate() {
+            let r = root(&mut parents, i);
+            clusters[r].push(expense);
+        }
+        let mut clusters: Vec<DuplicateCluster> = clusters
+            .into_iter()
+            .filter(|cluster| cluster.len() > 1)
+            .map(DuplicateCluster::new)
+            .collect();
+        clusters.sort_by(|a, b| b.last_spent_on().cmp(&a.last_spent_on()));
+        clusters
+    }
+}
+
+/// The fields of an expense duplicates are detected on.
+#[derive(Clone, Copy, Debug)]
+pub struct DuplicateSubject<'a> {
+    name: &'a str,
+    kind: TransactionKind,
+    amount: i64,
+    spent_on: NaiveDate,
+    recurring: bool,
+}
+
+impl<'a> From<&'a CreateExpenseRequest> for DuplicateSubject<'a> {
+    fn from(req: &'a CreateExpenseRequest) -> Self {
+        Self {
+            name: req.name().as_str(),
+            kind: req.kind(),
+            amount: req.amount(),
+            spent_on: *req.spent_on(),
+            recurring: req.recurring_expense_id().is_some(),
+        }
+    }
+}
+
+impl<'a> From<&'a Expense> for DuplicateSubject<'a> {
+    fn from(expense: &'a Expense) -> Self {
+        Self {
+            name: expense.name().as_str(),
+            kind: expense.kind(),
+            amount: expense.amount(),
+            spent_on: *expense.spent_on(),
+            recurring: expense.recurring_expense_id().is_some(),
+        }
+    }
+}
+
+/// How similar two expense names are, in percent, ignoring case, punctuation and spacing: 100
+/// minus the share of characters to edit to turn one into the other.
+pub fn name_similarity(a: &str, b: &str) -> u8 {
+    let normalize = |name: &str| -> Vec<char> {
+        name.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+            .chars()
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0;
+    }
+
+    // Levenshtein distance, keeping a single row of the matrix.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    let distance = row[b.len()];
+    (100 - distance * 100 / longest) as u8
+}
+
+/// Expenses suspected to record the same transaction, in date order.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DuplicateCluster {
+    expenses: Vec<Expense>,
+}
+
+impl DuplicateCluster {
+    pub fn new(mut expenses: Vec<Expense>) -> Self {
+        expenses.sort_by(|a, b| (a.spent_on(), a.id()).cmp(&(b.spent_on(), b.id())));
+        Self { expenses }
+    }
+
+    pub fn expenses(&self) -> &[Expense] {
+        &self.expenses
+    }
+
+    fn last_spent_on(&self) -> Option<&NaiveDate> {
+        self.expenses.last().map(Expense::spent_on)
+    }
+}
+
+/// The fields required by the domain to merge suspected duplicates into one expense.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MergeDuplicatesRequest {
+    keep_id: Uuid,
+    duplicate_ids: Vec<Uuid>,
+}
+
+impl MergeDuplicatesRequest {
+    /// # Errors
+    ///
+    /// - [DuplicateError::NothingToMerge] if `duplicate_ids` is empty.
+    /// - [DuplicateError::MergeIntoItself] if `duplicate_ids` contains `keep_id`.
+    pub fn new(keep_id: Uuid, mut duplicate_ids: Vec<Uuid>) -> Result<Self, DuplicateError> {
+        duplicate_ids.sort();
+        duplicate_ids.dedup();
+        if duplicate_ids.is_empty() {
+            return Err(DuplicateError::NothingToMerge);
+        }
+        if duplicate_ids.contains(&keep_id) {
+            return Err(DuplicateError::MergeIntoItself { id: keep_id });
+        }
+        Ok(Self {
+            keep_id,
+            duplicate_ids,
+        })
+    }
+
+    /// The expense that remains.
+    pub fn keep_id(&self) -> &Uuid {
+        &self.keep_id
+    }
+
+    /// The expenses deleted, once their tags and category are carried over.
+    pub fn duplicate_ids(&self) -> &[Uuid] {
+        &self.duplicate_ids
+    }
+}
+
+/// Suspected duplicates merged into the expense kept: it gains their tags and, if it has none,
+/// the first of their categories, and they are deleted.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DuplicateMerge {
+    expense: Expense,
+    removed_ids: Vec<Uuid>,
+}
+
+impl DuplicateMerge {
+    pub fn new(keep: Expense, duplicates: &[Expense]) -> Self {
+        let mut tags = keep.tags().to_vec();
+        tags.extend(duplicates.iter().flat_map(|d| d.tags().iter().cloned()));
+        tags.sort();
+        tags.dedup();
+        let category_id = keep
+            .category_id()
+            .or_else(|| duplicates.iter().find_map(Expense::category_id))
+            .copied();
+        let mut expense = keep.with_tags(tags);
+        if let Some(category_id) = category_id {
+            expense = expense.with_category(category_id);
+        }
+        Self {
+            expense,
+            removed_ids: duplicates.iter().map(|d| *d.id()).collect(),
+        }
+    }
+
+    /// The expense kept, as merged.
+    pub fn expense(&self) -> &Expense {
+        &self.expense
+    }
+
+    pub fn removed_ids(&self) -> &[Uuid] {
+        &self.removed_ids
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DuplicateError {
+    #[error("date window of {days} days exceeds {MAX_DATE_WINDOW_DAYS} days")]
+    InvalidDateWindow { days: u32 },
+    #[error("similarity of {value}% exceeds 100%")]
+    InvalidSimilarity { value: u8 },
+    #[error("no duplicate to merge")]
+    NothingToMerge,
+    #[error("cannot merge expense {id} into itself")]
+    MergeIntoItself { id: Uuid },
+    #[error("expense {id} not found")]
+    ExpenseNotFound { id: Uuid },
+    #[error("only expenses of the same ledger, or all personal, can be merged")]
+    ScopeMismatch,
+    #[error("expense {id} has attachments, keep it instead")]
+    HasAttachments { id: Uuid },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::finance::models::expense::ExpenseName;
+    use crate::domain::finance::models::tag::Tag;
+
+    fn expense(name: &str, amount: i64, day: u32) -> Expense {
+        Expense::new(Uuid::new_v4(), ExpenseName::new(name).unwrap())
+            .with_amount(amount)
+            .with_spent_on(NaiveDate::from_ymd_opt(2026, 10, day).unwrap())
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("Coffee", "coffee!"), 100);
+        assert_eq!(
+            name_similarity("CARD  STARBUCKS-PARIS", "card starbucks paris"),
+            100
+        );
+        assert_eq!(name_similarity("Starbucks Paris", "Starbucks Pariss"), 94);
+        assert!(name_similarity("Rent", "Groceries") < 50);
+        assert_eq!(name_similarity("", "..."), 0);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let settings = DuplicateSettings::default();
+        let expenses = vec![
+            expense("Starbucks Paris", 450, 1),
+            expense("Groceries", 450, 1),
+            expense("STARBUCKS PARIS", 450, 3),
+            expense("Starbucks Paris", 450, 10),
+            expense("starbucks paris.", 450, 5),
+            expense("Starbucks Paris", 460, 2),
+            expense("Rent", 90000, 1),
+            expense("Rent", 90000, 2),
+        ];
+        let ids: Vec<Uuid> = expenses.iter().map(|e| *e.id()).collect();
+
+        let clusters = settings.clusters(expenses);
+
+        let clustered: Vec<Vec<Uuid>> = clusters
+            .iter()
+            .map(|c| c.expenses().iter().map(|e| *e.id()).collect())
+            .collect();
+        // Day 1 and day 5 are too far apart, but both close to day 3.
+        assert_eq!(
+            clustered,
+            [vec![ids[0], ids[2], ids[4]], vec![ids[6], ids[7]]]
+        );
+
+        let recurring = expense("Rent", 90000, 2).with_recurring_expense(Uuid::new_v4());
+        assert!(!settings.is_duplicate(
+            &DuplicateSubject::from(&recurring),
+            &DuplicateSubject::from(&recurring)
+        ));
+        assert!(matches!(
+            DuplicateSettings::new(DuplicateMode::Block, 60, 80),
+            Err(DuplicateError::InvalidDateWindow { days: 60 })
+        ));
+    }
+
+    #[test]
+    fn test_merge_carries_tags_and_category() {
+        let category_id = Uuid::new_v4();
+        let keep = expense("Coffee", 450, 1).with_tags(vec![Tag::new("paris").unwrap()]);
+        let duplicate = expense("COFFEE", 450, 1)
+            .with_category(category_id)
+            .with_tags(vec![
+                Tag::new("coffee").unwrap(),
+                Tag::new("paris").unwrap(),
+            ]);
+
+        let merge = DuplicateMerge::new(keep.clone(), std::slice::from_ref(&duplicate));
+
+        assert_eq!(merge.expense().id(), keep.id());
+        assert_eq!(merge.expense().category_id(), Some(&category_id));
+        let tags: Vec<&str> = merge.expense().tags().iter().map(Tag::as_str).collect();
+        assert_eq!(tags, ["coffee", "paris"]);
+        assert_eq!(merge.removed_ids(), [*duplicate.id()]);
+        assert!(matches!(
+            MergeDuplicatesRequest::new(*keep.id(), vec![*keep.id()]),
+            Err(DuplicateError::MergeIntoItself { .. })
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use super::account::Currency;
+
+/// The currency every [ExchangeRate] is quoted against, as in the ECB reference rates.
+pub const REFERENCE_CURRENCY: &str = "EUR";
+
+/// How many decimal places of a [Rate] are kept.
+const RATE_SCALE: u32 = 8;
+
+/// A strictly positive exchange rate, kept as a fixed-point decimal so that it round-trips
+/// exactly through storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rate(i64);
+
+#[derive(Clone, Debug, Error)]
+#[error("invalid exchange rate {0}, expected a positive decimal with at most 8 decimal places")]
+pub struct InvalidRateError(pub String);
+
+impl FromStr for Rate {
+    type Err = InvalidRateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidRateError(s.to_string());
+        let trimmed = s.trim();
+        let (whole, fraction) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+        if whole.is_empty()
+            || fraction.len() > RATE_SCALE as usize
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let padded = format!("{whole}{fraction:0<width$}", width = RATE_SCALE as usize);
+        match padded.parse::<i64>() {
+            Ok(units) if units > 0 => Ok(Self(units)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let scale = 10_i64.pow(RATE_SCALE);
+        let fraction = format!("{:0width$}", self.0 % scale, width = RATE_SCALE as usize);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}", self.0 / scale)
+        } else {
+            write!(f, "{}.{}", self.0 / scale, fraction)
+        }
+    }
+}
+
+/// How many units of a currency one [REFERENCE_CURRENCY] unit was worth on a given day.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExchangeRate {
+    currency: Currency,
+    rate_on: NaiveDate,
+    rate: Rate,
+}
+
+impl ExchangeRate {
+    pub fn new(currency: Currency, rate_on: NaiveDate, rate: Rate) -> Self {
+        Self {
+            currency,
+            rate_on,
+            rate,
+        }
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// The day the rate was published for.
+    pub fn rate_on(&self) -> &NaiveDate {
+        &self.rate_on
+    }
+
+    pub fn rate(&self) -> Rate {
+        self.rate
+    }
+}
+
+/// Raised when an amount must be converted on a day for which no rate is known.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("no exchange rate from {from} to {to} on {on}")]
+pub struct MissingExchangeRate {
+    pub from: Currency,
+    pub to: Currency,
+    pub on: NaiveDate,
+}
+
+#[derive(Debug, Error)]
+pub enum ExchangeRateError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_round_trips() {
+        for raw in ["1.0876", "161.25", "7.4603", "26", "0.00000001"] {
+            assert_eq!(Rate::from_str(raw).unwrap().to_string(), raw);
+        }
+        for raw in ["", "0", "-1.2", "1.123456789", "1,5", "N/A"] {
+            assert!(Rate::from_str(raw).is_err(), "{raw:?} should be rejected");
+        }
+    }
+}
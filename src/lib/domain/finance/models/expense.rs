@@ -1,18 +1,142 @@
 use std::fmt::{Display, Formatter};
 
+use chrono::{NaiveDate, Utc};
 use derive_more::From;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::domain::finance::policy::PolicyError;
+
+use super::account::Currency;
+use super::attachment::Attachment;
+use super::split::{ExpenseSplit, SplitError, SplitMethod, SplitParticipant};
+use super::tag::Tag;
+use super::transaction::TransactionKind;
+
+/// A recorded movement of money. Despite the name, kept for backwards compatibility, it may also
+/// be an income or a transfer: see [TransactionKind].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Expense {
     id: Uuid,
     name: ExpenseName,
+    kind: TransactionKind,
+    ledger_id: Option<Uuid>,
+    amount: i64,
+    split: Option<ExpenseSplit>,
+    category_id: Option<Uuid>,
+    spent_on: NaiveDate,
+    recurring_expense_id: Option<Uuid>,
+    account_id: Option<Uuid>,
+    to_account_id: Option<Uuid>,
+    tags: Vec<Tag>,
+    counterparty: Option<String>,
+    reference: Option<String>,
+    attachments: Vec<Attachment>,
 }
 
 impl Expense {
     pub fn new(id: Uuid, name: ExpenseName) -> Self {
-        Self { id, name }
+        Self {
+            id,
+            name,
+            kind: TransactionKind::Expense,
+            ledger_id: None,
+            amount: 0,
+            split: None,
+            category_id: None,
+            spent_on: Utc::now().date_naive(),
+            recurring_expense_id: None,
+            account_id: None,
+            to_account_id: None,
+            tags: Vec::new(),
+            counterparty: None,
+            reference: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Renames the [Expense].
+    pub fn with_name(mut self, name: ExpenseName) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Sets what kind of transaction the [Expense] is.
+    pub fn with_kind(mut self, kind: TransactionKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets the total of the [Expense], in minor units.
+    pub fn with_amount(mut self, amount: i64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Records how the [Expense] is split between ledger members.
+    pub fn with_split(mut self, split: ExpenseSplit) -> Self {
+        self.split = Some(split);
+        self
+    }
+
+    /// Places the [Expense] in the shared ledger identified by `ledger_id`.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Files the [Expense] under the category identified by `category_id`.
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Sets the day the money was spent.
+    pub fn with_spent_on(mut self, spent_on: NaiveDate) -> Self {
+        self.spent_on = spent_on;
+        self
+    }
+
+    /// Records the recurring expense the [Expense] is an occurrence of.
+    pub fn with_recurring_expense(mut self, recurring_expense_id: Uuid) -> Self {
+        self.recurring_expense_id = Some(recurring_expense_id);
+        self
+    }
+
+    /// Sets the account the money was paid from, or received into for an income.
+    pub fn with_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Sets the account a transfer moved the money to.
+    pub fn with_to_account(mut self, to_account_id: Uuid) -> Self {
+        self.to_account_id = Some(to_account_id);
+        self
+    }
+
+    /// Labels the [Expense] with `tags`.
+    pub fn with_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Records who was paid, or who paid for an income, as written on a bank statement.
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        self.counterparty = Some(counterparty.to_string());
+        self
+    }
+
+    /// Records the reference of the payment, as written on a bank statement.
+    pub fn with_reference(mut self, reference: &str) -> Self {
+        self.reference = Some(reference.to_string());
+        self
+    }
+
+    /// Sets the files attached to the [Expense], such as receipts.
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
     }
 
     pub fn id(&self) -> &Uuid {
@@ -22,6 +146,68 @@ impl Expense {
     pub fn name(&self) -> &ExpenseName {
         &self.name
     }
+
+    pub fn kind(&self) -> TransactionKind {
+        self.kind
+    }
+
+    /// The ledger the [Expense] belongs to, or `None` for a personal expense.
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    /// The total of the [Expense], in minor units.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn split(&self) -> Option<&ExpenseSplit> {
+        self.split.as_ref()
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    /// The day the money was spent, which decides the budget period the [Expense] counts
+    /// towards.
+    pub fn spent_on(&self) -> &NaiveDate {
+        &self.spent_on
+    }
+
+    /// The recurring expense the [Expense] was created from, if any.
+    pub fn recurring_expense_id(&self) -> Option<&Uuid> {
+        self.recurring_expense_id.as_ref()
+    }
+
+    /// The account the [Expense] was recorded against. `None` only for expenses recorded before
+    /// accounts existed.
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    /// The destination account of a transfer.
+    pub fn to_account_id(&self) -> Option<&Uuid> {
+        self.to_account_id.as_ref()
+    }
+
+    /// The tags of the [Expense], sorted.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn counterparty(&self) -> Option<&str> {
+        self.counterparty.as_deref()
+    }
+
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    /// The files attached to the [Expense], in upload order.
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
 }
 
 /// A validated and formatted name.
@@ -41,6 +227,10 @@ impl ExpenseName {
             Ok(Self(trimmed.to_string()))
         }
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl Display for ExpenseName {
@@ -49,27 +239,250 @@ impl Display for ExpenseName {
     }
 }
 
+/// An [Expense] just created, with the existing expenses it looks like, when duplicates are
+/// only warned about.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreatedExpense {
+    expense: Expense,
+    suspected_duplicates: Vec<Uuid>,
+}
+
+impl CreatedExpense {
+    pub fn new(expense: Expense, suspected_duplicates: Vec<Uuid>) -> Self {
+        Self {
+            expense,
+            suspected_duplicates,
+        }
+    }
+
+    pub fn expense(&self) -> &Expense {
+        &self.expense
+    }
+
+    /// The ids of the existing expenses the new one looks like.
+    pub fn suspected_duplicates(&self) -> &[Uuid] {
+        &self.suspected_duplicates
+    }
+
+    pub fn into_expense(self) -> Expense {
+        self.expense
+    }
+}
+
 /// The fields required by the domain to create an [Expense].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From)]
 pub struct CreateExpenseRequest {
     name: ExpenseName,
+    kind: TransactionKind,
+    ledger_id: Option<Uuid>,
+    amount: i64,
+    split: Option<ExpenseSplit>,
+    category_id: Option<Uuid>,
+    spent_on: NaiveDate,
+    recurring_expense_id: Option<Uuid>,
+    account_id: Option<Uuid>,
+    to_account_id: Option<Uuid>,
+    tags: Vec<Tag>,
+    import_id: Option<String>,
+    counterparty: Option<String>,
+    reference: Option<String>,
 }
 
+#[derive(Clone, Debug, Error)]
+#[error("expense amount cannot be negative, got {0}")]
+pub struct ExpenseAmountNegativeError(pub i64);
+
 impl CreateExpenseRequest {
     pub fn new(name: &str) -> Result<Self, ExpenseNameEmptyError> {
         let name = ExpenseName::new(name)?;
-        Ok(Self { name })
+        Ok(Self {
+            name,
+            kind: TransactionKind::Expense,
+            ledger_id: None,
+            amount: 0,
+            split: None,
+            category_id: None,
+            spent_on: Utc::now().date_naive(),
+            recurring_expense_id: None,
+            account_id: None,
+            to_account_id: None,
+            tags: Vec::new(),
+            import_id: None,
+            counterparty: None,
+            reference: None,
+        })
+    }
+
+    /// Renames the [Expense] to be created.
+    pub fn with_name(mut self, name: ExpenseName) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Records an income or a transfer instead of an expense.
+    pub fn with_kind(mut self, kind: TransactionKind) -> Self {
+        self.kind = kind;
+        self
     }
+
+    /// Sets the total of the [Expense], in minor units.
+    pub fn with_amount(mut self, amount: i64) -> Result<Self, ExpenseAmountNegativeError> {
+        if amount < 0 {
+            return Err(ExpenseAmountNegativeError(amount));
+        }
+        self.amount = amount;
+        Ok(self)
+    }
+
+    /// Splits the amount of the [Expense], paid by `paid_by`, among `participants`.
+    ///
+    /// The amount must be set beforehand, since the split is resolved against it.
+    pub fn with_split(
+        mut self,
+        paid_by: Uuid,
+        method: SplitMethod,
+        participants: &[SplitParticipant],
+    ) -> Result<Self, SplitError> {
+        self.split = Some(ExpenseSplit::new(
+            paid_by,
+            method,
+            participants,
+            self.amount,
+        )?);
+        Ok(self)
+    }
+
+    /// Creates the [Expense] in the ledger identified by `ledger_id`.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Files the [Expense] under the category identified by `category_id`, which must live in
+    /// the same ledger, or be one of the caller's personal categories for a personal expense.
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Sets the day the money was spent. Defaults to today.
+    pub fn with_spent_on(mut self, spent_on: NaiveDate) -> Self {
+        self.spent_on = spent_on;
+        self
+    }
+
+    /// Marks the [Expense] as the occurrence of the recurring expense `recurring_expense_id` on
+    /// its `spent_on` day. Each occurrence can only be created once.
+    pub fn with_recurring_expense(mut self, recurring_expense_id: Uuid) -> Self {
+        self.recurring_expense_id = Some(recurring_expense_id);
+        self
+    }
+
+    /// Records the [Expense] against the account identified by `account_id`, which must belong
+    /// to the caller. Required.
+    pub fn with_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Sets the account a transfer moves the money to. Required for transfers, and only allowed
+    /// for them.
+    pub fn with_to_account(mut self, to_account_id: Uuid) -> Self {
+        self.to_account_id = Some(to_account_id);
+        self
+    }
+
+    /// Labels the [Expense] with `tags`, ignoring repeated ones.
+    pub fn with_tags(mut self, mut tags: Vec<Tag>) -> Self {
+        tags.sort();
+        tags.dedup();
+        self.tags = tags;
+        self
+    }
+
+    /// Records the identifier the bank gave the transaction in an imported statement. It is
+    /// unique per account, so that the transaction is not imported twice.
+    pub fn with_import_id(mut self, import_id: &str) -> Self {
+        self.import_id = Some(import_id.to_string());
+        self
+    }
+
+    /// Records who was paid, or who paid for an income, as written on a bank statement.
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        self.counterparty = Some(counterparty.to_string());
+        self
+    }
+
+    /// Records the reference of the payment, as written on a bank statement.
+    pub fn with_reference(mut self, reference: &str) -> Self {
+        self.reference = Some(reference.to_string());
+        self
+    }
+
     pub fn name(&self) -> &ExpenseName {
         &self.name
     }
+
+    pub fn kind(&self) -> TransactionKind {
+        self.kind
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn split(&self) -> Option<&ExpenseSplit> {
+        self.split.as_ref()
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    pub fn spent_on(&self) -> &NaiveDate {
+        &self.spent_on
+    }
+
+    pub fn recurring_expense_id(&self) -> Option<&Uuid> {
+        self.recurring_expense_id.as_ref()
+    }
+
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    pub fn to_account_id(&self) -> Option<&Uuid> {
+        self.to_account_id.as_ref()
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn import_id(&self) -> Option<&str> {
+        self.import_id.as_deref()
+    }
+
+    pub fn counterparty(&self) -> Option<&str> {
+        self.counterparty.as_deref()
+    }
+
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
 }
 
-/// The fields required by the domain to create an [Expense].
+/// The fields required by the domain to list [Expense].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From)]
 pub struct ListExpensesRequest {
     page: u32,
     size: u32,
+    ledger_id: Option<Uuid>,
+    kind: Option<TransactionKind>,
 }
 
 impl ListExpensesRequest {
@@ -77,15 +490,102 @@ impl ListExpensesRequest {
         if page == 0 || size == 0 {
             Err(PaginationError::InvalidPage { page, size })
         } else {
-            Ok(Self { page, size })
+            Ok(Self {
+                page,
+                size,
+                ledger_id: None,
+                kind: None,
+            })
         }
     }
+
+    /// Lists every expense on a single page.
+    pub fn all() -> Self {
+        Self {
+            page: 1,
+            size: u32::MAX,
+            ledger_id: None,
+            kind: None,
+        }
+    }
+
+    /// Restricts the listing to the ledger identified by `ledger_id`. Without a ledger only
+    /// personal expenses are listed.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Restricts the listing to transactions of `kind`. Without a kind every transaction is
+    /// listed.
+    pub fn with_kind(mut self, kind: TransactionKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn kind(&self) -> Option<TransactionKind> {
+        self.kind
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum CreateExpenseError {
-    #[error("expense with name {name} already exists")]
-    Duplicate { name: String },
+    #[error("expense looks like a duplicate of {}", join_ids(duplicate_ids))]
+    Duplicate { duplicate_ids: Vec<Uuid> },
+    #[error("only expenses in a ledger can be split")]
+    SplitWithoutLedger,
+    #[error("only expenses can be split, not {kind}")]
+    SplitNotExpense { kind: TransactionKind },
+    #[error("user {user_id} is not a member of ledger {ledger_id}")]
+    NotLedgerMember { ledger_id: Uuid, user_id: Uuid },
+    #[error("category {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("recurring expense {recurring_expense_id} already occurred on {spent_on}")]
+    DuplicateOccurrence {
+        recurring_expense_id: Uuid,
+        spent_on: NaiveDate,
+    },
+    #[error("an account is required")]
+    AccountRequired,
+    #[error("account {id} not found")]
+    AccountNotFound { id: Uuid },
+    #[error("a transfer requires a destination account")]
+    TransferWithoutDestination,
+    #[error("only transfers have a destination account, not {kind}")]
+    DestinationNotTransfer { kind: TransactionKind },
+    #[error("cannot transfer from account {id} to itself")]
+    TransferToSameAccount { id: Uuid },
+    #[error("cannot transfer between currencies {from} and {to}")]
+    CurrencyMismatch { from: Currency, to: Currency },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+fn join_ids(ids: &[Uuid]) -> String {
+    ids.iter()
+        .map(Uuid::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Error)]
+pub enum ListExpensesError {
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -0,0 +1,195 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// How long a response is kept for replay after the first request carrying its key.
+pub const IDEMPOTENCY_KEY_TTL: TimeDelta = TimeDelta::hours(24);
+
+/// The longest key a client may send, in bytes.
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// A key chosen by the client, such as a UUID, identifying one logical request across retries.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("idempotency key must be 1 to {MAX_IDEMPOTENCY_KEY_LENGTH} visible ASCII characters")]
+pub struct InvalidIdempotencyKeyError;
+
+impl IdempotencyKey {
+    pub fn new(raw: &str) -> Result<Self, InvalidIdempotencyKeyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty()
+            || trimmed.len() > MAX_IDEMPOTENCY_KEY_LENGTH
+            || !trimmed.bytes().all(|b| b.is_ascii_graphic())
+        {
+            Err(InvalidIdempotencyKeyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for IdempotencyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A hash of what a request asks for, telling a retry apart from another request reusing its
+/// key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestFingerprint(String);
+
+impl RequestFingerprint {
+    /// Hash the method, the path with its query and the body of a request.
+    pub fn new(method: &str, path: &str, body: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        for part in [method.as_bytes(), path.as_bytes()] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        hasher.update(body);
+        Self(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Restore a fingerprint computed earlier from its hexadecimal form.
+    pub fn from_hex(hex: String) -> Self {
+        Self(hex)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The response to the first request carrying a key, replayed to its retries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl StoredResponse {
+    pub fn new(status: u16, body: Vec<u8>) -> Self {
+        Self { status, body }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// The fields required by the domain to reserve an [IdempotencyKey] for a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimIdempotencyKeyRequest {
+    owner_id: Uuid,
+    key: IdempotencyKey,
+    fingerprint: RequestFingerprint,
+    expires_at: DateTime<Utc>,
+}
+
+impl ClaimIdempotencyKeyRequest {
+    /// Reserve `key` for [IDEMPOTENCY_KEY_TTL] from `now`.
+    pub fn new(
+        owner_id: Uuid,
+        key: IdempotencyKey,
+        fingerprint: RequestFingerprint,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            owner_id,
+            key,
+            fingerprint,
+            expires_at: now + IDEMPOTENCY_KEY_TTL,
+        }
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn key(&self) -> &IdempotencyKey {
+        &self.key
+    }
+
+    pub fn fingerprint(&self) -> &RequestFingerprint {
+        &self.fingerprint
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+}
+
+/// What became of an attempt to reserve an [IdempotencyKey].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IdempotencyClaim {
+    /// The key was free, or had expired: the request is to be processed.
+    Acquired,
+    /// A request with the same key and fingerprint was already answered.
+    Replay(StoredResponse),
+    /// A request with the same key and fingerprint is still being processed.
+    InProgress,
+    /// The key was used for another request.
+    Mismatch,
+}
+
+#[derive(Debug, Error)]
+pub enum IdempotencyError {
+    #[error("idempotency key {key} was already used for a different request")]
+    KeyReused { key: IdempotencyKey },
+    #[error("a request with idempotency key {key} is still being processed")]
+    InProgress { key: IdempotencyKey },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_is_validated() {
+        assert_eq!(
+            IdempotencyKey::new(" abc-123 ").unwrap().as_str(),
+            "abc-123"
+        );
+        assert!(IdempotencyKey::new("  ").is_err());
+        assert!(IdempotencyKey::new("two words").is_err());
+        assert!(IdempotencyKey::new(&"k".repeat(MAX_IDEMPOTENCY_KEY_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_every_part() {
+        let fingerprint = RequestFingerprint::new("POST", "/api/expenses", b"{}");
+        assert_eq!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/api/expenses", b"{}")
+        );
+        assert_ne!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/api/expenses", b"{ }")
+        );
+        assert_ne!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/api/transactions", b"{}")
+        );
+        // The path cannot absorb the start of the body.
+        assert_ne!(
+            RequestFingerprint::new("POST", "/a", b"b"),
+            RequestFingerprint::new("POST", "/ab", b"")
+        );
+    }
+}
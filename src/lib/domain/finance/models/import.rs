@@ -0,0 +1,385 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::account::Currency;
+use super::expense::ExpenseName;
+
+/// An exact decimal amount as written in an imported file, before it is scaled to the minor
+/// units of the account it is imported into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportedAmount {
+    mantissa: i64,
+    scale: u32,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("invalid amount {0:?}")]
+pub struct InvalidAmountError(pub String);
+
+/// The most decimal places an [ImportedAmount] may have, so that its scale fits in an `i64`.
+const MAX_FRACTION_DIGITS: usize = 18;
+
+impl ImportedAmount {
+    /// Parse a signed decimal such as `-1234.5`, reading `decimal_separator` as the decimal
+    /// point. A leading `+` is accepted, and so is a trailing `-` as some banks write debits.
+    /// At most [MAX_FRACTION_DIGITS] decimal places are accepted.
+    pub fn parse(raw: &str, decimal_separator: char) -> Result<Self, InvalidAmountError> {
+        let invalid = || InvalidAmountError(raw.to_string());
+        let trimmed = raw.trim();
+        let (negative, unsigned) = if let Some(rest) = trimmed.strip_prefix('-') {
+            (true, rest)
+        } else if let Some(rest) = trimmed.strip_suffix('-') {
+            (true, rest)
+        } else {
+            (false, trimmed.strip_prefix('+').unwrap_or(trimmed))
+        };
+        let (whole, fraction) = unsigned
+            .split_once(decimal_separator)
+            .unwrap_or((unsigned, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || fraction.len() > MAX_FRACTION_DIGITS
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let digits = format!("{whole}{fraction}");
+        let mantissa = digits.parse::<i64>().map_err(|_| invalid())?;
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fraction.len() as u32,
+        })
+    }
+
+    /// Whether the amount leaves the account.
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// The same amount with the opposite sign, for files listing expenses as positive amounts.
+    pub fn negated(&self) -> Self {
+        Self {
+            mantissa: -self.mantissa,
+            scale: self.scale,
+        }
+    }
+
+    /// The amount in minor units of `currency`, or `None` if it has more decimal places than the
+    /// currency allows or does not fit.
+    pub fn to_minor_units(&self, currency: &Currency) -> Option<i64> {
+        let exponent = currency.exponent();
+        if self.scale > exponent {
+            let divisor = 10_i64.checked_pow(self.scale - exponent)?;
+            (self.mantissa % divisor == 0).then(|| self.mantissa / divisor)
+        } else {
+            self.mantissa
+                .checked_mul(10_i64.checked_pow(exponent - self.scale)?)
+        }
+    }
+}
+
+impl Display for ImportedAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let scale = self.scale as usize;
+        let sign = if self.is_negative() { "-" } else { "" };
+        let digits = format!(
+            "{:0width$}",
+            self.mantissa.unsigned_abs(),
+            width = scale + 1
+        );
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        if fraction.is_empty() {
+            write!(f, "{sign}{whole}")
+        } else {
+            write!(f, "{sign}{whole}.{fraction}")
+        }
+    }
+}
+
+/// A transaction read from an imported file, before it is matched against the caller's data.
+///
+/// Amounts follow bank statements: negative when money leaves the account, which is imported as
+/// an expense, and positive otherwise, which is imported as an income.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportedTransaction {
+    line: usize,
+    name: ExpenseName,
+    amount: ImportedAmount,
+    spent_on: NaiveDate,
+    category: Option<String>,
+    import_id: Option<String>,
+    counterparty: Option<String>,
+    reference: Option<String>,
+}
+
+impl ImportedTransaction {
+    pub fn new(
+        line: usize,
+        name: ExpenseName,
+        amount: ImportedAmount,
+        spent_on: NaiveDate,
+    ) -> Self {
+        Self {
+            line,
+            name,
+            amount,
+            spent_on,
+            category: None,
+            import_id: None,
+            counterparty: None,
+            reference: None,
+        }
+    }
+
+    /// Files the transaction under the caller's personal category named `category`, compared
+    /// case-insensitively.
+    pub fn with_category(mut self, category: &str) -> Self {
+        self.category = Some(category.trim().to_string());
+        self
+    }
+
+    /// Sets the identifier the bank gave the transaction, such as an OFX `FITID`. A transaction
+    /// whose identifier was already imported into the account is skipped.
+    pub fn with_import_id(mut self, import_id: &str) -> Self {
+        self.import_id = Some(import_id.trim().to_string());
+        self
+    }
+
+    /// Records who was paid, or who paid for an income. Blank names are ignored.
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        self.counterparty = non_blank(counterparty);
+        self
+    }
+
+    /// Records the reference of the payment, such as the remittance information of a transfer.
+    /// Blank references are ignored.
+    pub fn with_reference(mut self, reference: &str) -> Self {
+        self.reference = non_blank(reference);
+        self
+    }
+
+    /// The line of the file the transaction was read from, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn name(&self) -> &ExpenseName {
+        &self.name
+    }
+
+    pub fn amount(&self) -> &ImportedAmount {
+        &self.amount
+    }
+
+    pub fn spent_on(&self) -> &NaiveDate {
+        &self.spent_on
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn import_id(&self) -> Option<&str> {
+        self.import_id.as_deref()
+    }
+
+    pub fn counterparty(&self) -> Option<&str> {
+        self.counterparty.as_deref()
+    }
+
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+}
+
+fn non_blank(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Why a row of an imported file cannot be imported.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+#[error("line {line}: {message}")]
+pub struct ImportRowError {
+    line: usize,
+    message: String,
+}
+
+impl ImportRowError {
+    pub fn new(line: usize, message: impl Display) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The fields required by the domain to import transactions into an account.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportRequest {
+    account_id: Uuid,
+    rows: Vec<Result<ImportedTransaction, ImportRowError>>,
+    dry_run: bool,
+}
+
+impl ImportRequest {
+    /// Imports `rows` into the caller's account `account_id`. Rows that could not be read are
+    /// reported alongside those failing validation.
+    pub fn new(account_id: Uuid, rows: Vec<Result<ImportedTransaction, ImportRowError>>) -> Self {
+        Self {
+            account_id,
+            rows,
+            dry_run: false,
+        }
+    }
+
+    /// Validates the rows and reports on them without saving anything.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    pub fn account_id(&self) -> &Uuid {
+        &self.account_id
+    }
+
+    pub fn rows(&self) -> &[Result<ImportedTransaction, ImportRowError>] {
+        &self.rows
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+/// The outcome of an [ImportRequest].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportReport {
+    dry_run: bool,
+    rows: usize,
+    imported: usize,
+    skipped: usize,
+    errors: Vec<ImportRowError>,
+}
+
+impl ImportReport {
+    pub fn new(
+        dry_run: bool,
+        rows: usize,
+        imported: usize,
+        skipped: usize,
+        errors: Vec<ImportRowError>,
+    ) -> Self {
+        Self {
+            dry_run,
+            rows,
+            imported,
+            skipped,
+            errors,
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// How many rows were read.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// How many transactions were saved or, for a dry run, would have been.
+    pub fn imported(&self) -> usize {
+        self.imported
+    }
+
+    /// How many transactions were left out as already imported.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// The rows left out as invalid, in file order.
+    pub fn errors(&self) -> &[ImportRowError] {
+        &self.errors
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("account {id} not found")]
+    AccountNotFound { id: Uuid },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_is_scaled_to_currency() {
+        let eur = Currency::new("EUR").unwrap();
+        let jpy = Currency::new("JPY").unwrap();
+        assert_eq!(
+            ImportedAmount::parse("-12,5", ',')
+                .unwrap()
+                .to_minor_units(&eur),
+            Some(-1250)
+        );
+        assert_eq!(
+            ImportedAmount::parse("1200.00", '.')
+                .unwrap()
+                .to_minor_units(&jpy),
+            Some(1200)
+        );
+        assert_eq!(
+            ImportedAmount::parse("0.125", '.')
+                .unwrap()
+                .to_minor_units(&eur),
+            None
+        );
+        assert!(ImportedAmount::parse("12.5.0", '.').is_err());
+        assert!(ImportedAmount::parse("", '.').is_err());
+    }
+
+    #[test]
+    fn test_amount_is_displayed_as_written() {
+        for raw in [
+            "-12.50",
+            "0.05",
+            "1200",
+            "0.000000000000000001",
+            "-9.223372036854775807",
+        ] {
+            assert_eq!(ImportedAmount::parse(raw, '.').unwrap().to_string(), raw);
+        }
+        assert_eq!(
+            ImportedAmount::parse("+.5", '.').unwrap().to_string(),
+            "0.5"
+        );
+    }
+
+    #[test]
+    fn test_amount_rejects_more_than_eighteen_decimals() {
+        let raw = format!("0.{}1", "0".repeat(64));
+        assert!(ImportedAmount::parse(&raw, '.').is_err());
+        assert!(ImportedAmount::parse("0.0000000000000000001", '.').is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+/// How long an invitation token remains valid after it has been issued.
+pub const INVITATION_TTL_DAYS: i64 = 7;
+
+/// A ledger groups the expenses shared by a household or a team.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ledger {
+    id: Uuid,
+    name: LedgerName,
+    created_at: DateTime<Utc>,
+}
+
+impl Ledger {
+    pub fn new(id: Uuid, name: LedgerName, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            name,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn name(&self) -> &LedgerName {
+        &self.name
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// A validated and formatted ledger name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedgerName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("ledger name cannot be empty")]
+pub struct LedgerNameEmptyError;
+
+impl LedgerName {
+    pub fn new(raw: &str) -> Result<Self, LedgerNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(LedgerNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for LedgerName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The role of a member within a [Ledger].
+///
+/// Roles are ordered by privilege, so `role >= LedgerRole::Editor` reads as "may edit".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LedgerRole {
+    /// May read the ledger and its expenses.
+    Viewer,
+    /// May additionally create expenses in the ledger.
+    Editor,
+    /// May additionally manage members and invitations.
+    Owner,
+}
+
+impl LedgerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerRole::Viewer => "viewer",
+            LedgerRole::Editor => "editor",
+            LedgerRole::Owner => "owner",
+        }
+    }
+}
+
+impl Display for LedgerRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown ledger role {0}")]
+pub struct UnknownLedgerRoleError(pub String);
+
+impl FromStr for LedgerRole {
+    type Err = UnknownLedgerRoleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Ok(LedgerRole::Viewer),
+            "editor" => Ok(LedgerRole::Editor),
+            "owner" => Ok(LedgerRole::Owner),
+            _ => Err(UnknownLedgerRoleError(s.to_string())),
+        }
+    }
+}
+
+/// A user's membership of a [Ledger].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedgerMember {
+    ledger_id: Uuid,
+    user_id: Uuid,
+    role: LedgerRole,
+    joined_at: DateTime<Utc>,
+}
+
+impl LedgerMember {
+    pub fn new(ledger_id: Uuid, user_id: Uuid, role: LedgerRole, joined_at: DateTime<Utc>) -> Self {
+        Self {
+            ledger_id,
+            user_id,
+            role,
+            joined_at,
+        }
+    }
+
+    pub fn ledger_id(&self) -> &Uuid {
+        &self.ledger_id
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn role(&self) -> LedgerRole {
+        self.role
+    }
+
+    pub fn joined_at(&self) -> &DateTime<Utc> {
+        &self.joined_at
+    }
+}
+
+/// A single-use token granting `role` in a [Ledger] to whoever accepts it first.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LedgerInvitation {
+    token: String,
+    ledger_id: Uuid,
+    role: LedgerRole,
+    expires_at: DateTime<Utc>,
+}
+
+impl LedgerInvitation {
+    pub fn new(
+        token: String,
+        ledger_id: Uuid,
+        role: LedgerRole,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            token,
+            ledger_id,
+            role,
+            expires_at,
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn ledger_id(&self) -> &Uuid {
+        &self.ledger_id
+    }
+
+    pub fn role(&self) -> LedgerRole {
+        self.role
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+}
+
+/// The fields required by the domain to create a [Ledger].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateLedgerRequest {
+    name: LedgerName,
+    owner_id: Uuid,
+}
+
+impl CreateLedgerRequest {
+    pub fn new(name: &str, owner_id: Uuid) -> Result<Self, LedgerNameEmptyError> {
+        let name = LedgerName::new(name)?;
+        Ok(Self { name, owner_id })
+    }
+
+    pub fn name(&self) -> &LedgerName {
+        &self.name
+    }
+
+    /// The user that becomes the [LedgerRole::Owner] of the new [Ledger].
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+}
+
+/// The fields required by the domain to issue a [LedgerInvitation].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateInvitationRequest {
+    ledger_id: Uuid,
+    role: LedgerRole,
+    invited_by: Uuid,
+    expires_at: DateTime<Utc>,
+}
+
+impl CreateInvitationRequest {
+    /// Ownership cannot be handed out by invitation, so `role` must be [LedgerRole::Viewer] or
+    /// [LedgerRole::Editor].
+    pub fn new(
+        ledger_id: Uuid,
+        role: LedgerRole,
+        invited_by: Uuid,
+    ) -> Result<Self, InvitationError> {
+        if role == LedgerRole::Owner {
+            return Err(InvitationError::InvalidRole { role });
+        }
+        Ok(Self {
+            ledger_id,
+            role,
+            invited_by,
+            expires_at: Utc::now() + Duration::days(INVITATION_TTL_DAYS),
+        })
+    }
+
+    pub fn ledger_id(&self) -> &Uuid {
+        &self.ledger_id
+    }
+
+    pub fn role(&self) -> LedgerRole {
+        self.role
+    }
+
+    pub fn invited_by(&self) -> &Uuid {
+        &self.invited_by
+    }
+
+    pub fn expires_at(&self) -> &DateTime<Utc> {
+        &self.expires_at
+    }
+}
+
+/// The fields required by the domain to accept a [LedgerInvitation].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AcceptInvitationRequest {
+    token: String,
+    user_id: Uuid,
+}
+
+impl AcceptInvitationRequest {
+    pub fn new(token: &str, user_id: Uuid) -> Self {
+        Self {
+            token: token.trim().to_string(),
+            user_id,
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    #[error("ledger {id} not found")]
+    NotFound { id: Uuid },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum InvitationError {
+    #[error("invitation not found")]
+    NotFound,
+    #[error("invitation expired at {expires_at}")]
+    Expired { expires_at: DateTime<Utc> },
+    #[error("user {user_id} is already a member of ledger {ledger_id}")]
+    AlreadyMember { ledger_id: Uuid, user_id: Uuid },
+    #[error("cannot invite members with role {role}")]
+    InvalidRole { role: LedgerRole },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
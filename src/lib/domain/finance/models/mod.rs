@@ -1 +1,19 @@
+pub mod account;
+pub mod attachment;
+pub mod balance;
+pub mod budget;
+pub mod category;
+pub mod duplicate;
+pub mod exchange;
 pub mod expense;
+pub mod idempotency;
+pub mod import;
+pub mod ledger;
+pub mod quick;
+pub mod recurring;
+pub mod report;
+pub mod rule;
+pub mod settlement;
+pub mod split;
+pub mod tag;
+pub mod transaction;
@@ -0,0 +1,684 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::account::Currency;
+use super::expense::{CreateExpenseRequest, Expense};
+use super::import::ImportedAmount;
+
+/// Currency codes recognized when written in lowercase next to an amount, such as `4.50 eur`.
+/// Any code written in uppercase is recognized, so that words like `tea` are not mistaken for
+/// one.
+const COMMON_CURRENCIES: [&str; 24] = [
+    "aud", "brl", "cad", "chf", "cny", "czk", "dkk", "eur", "gbp", "hkd", "huf", "inr", "jpy",
+    "krw", "mxn", "nok", "nzd", "pln", "ron", "sek", "sgd", "try", "usd", "zar",
+];
+
+/// What was read from a one-line description of an expense, such as
+/// `coffee 4.50 EUR yesterday #food @cash`, before categories and accounts are looked up.
+///
+/// - `#word` names the category, and `@word` the account.
+/// - The amount is a number, with `.` or `,` as decimal point, optionally preceded or followed
+///   by a currency symbol or code.
+/// - The date is `today`, `yesterday`, `N days ago`, a weekday (the latest one, today
+///   included), an ISO date or a `day/month[/year]` date. It defaults to today.
+/// - The remaining words make up the name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickExpense {
+    name: Option<String>,
+    amount: Option<ImportedAmount>,
+    currency: Option<Currency>,
+    spent_on: NaiveDate,
+    category: Option<String>,
+    account: Option<String>,
+    issues: Vec<QuickExpenseIssue>,
+}
+
+impl QuickExpense {
+    /// Read `text`, relative dates being relative to `today`. Parsing never fails: what cannot
+    /// be read is reported as a [QuickExpenseIssue].
+    pub fn parse(text: &str, today: NaiveDate) -> Self {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut consumed = vec![false; tokens.len()];
+        let mut issues = Vec::new();
+
+        let category = take_marked(&tokens, &mut consumed, '#', &mut issues);
+        let account = take_marked(&tokens, &mut consumed, '@', &mut issues);
+        let spent_on = take_date(&tokens, &mut consumed, today, &mut issues).unwrap_or(today);
+        let (amount, currency) = take_amount(&tokens, &mut consumed, &mut issues);
+
+        let name = tokens
+            .iter()
+            .zip(&consumed)
+            .filter(|(_, consumed)| !**consumed)
+            .map(|(token, _)| *token)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let name = if name.is_empty() {
+            // "4.50 #groceries" is named after its category.
+            category.clone()
+        } else {
+            Some(name)
+        };
+        if name.is_none() {
+            issues.push(QuickExpenseIssue::MissingName);
+        }
+        if amount.is_none() {
+            issues.push(QuickExpenseIssue::MissingAmount);
+        }
+
+        Self {
+            name,
+            amount,
+            currency,
+            spent_on,
+            category,
+            account,
+            issues,
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn amount(&self) -> Option<&ImportedAmount> {
+        self.amount.as_ref()
+    }
+
+    /// The currency written next to the amount, if any.
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    pub fn spent_on(&self) -> &NaiveDate {
+        &self.spent_on
+    }
+
+    /// The category named with `#`, to be looked up among the caller's personal categories.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// The account named with `@`, to be looked up among the caller's accounts.
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// What could not be read unambiguously, in the order it was found.
+    pub fn issues(&self) -> &[QuickExpenseIssue] {
+        &self.issues
+    }
+}
+
+/// Takes the first `#word` or `@word`, reporting any further one.
+fn take_marked(
+    tokens: &[&str],
+    consumed: &mut [bool],
+    marker: char,
+    issues: &mut Vec<QuickExpenseIssue>,
+) -> Option<String> {
+    let mut found: Option<String> = None;
+    for (index, token) in tokens.iter().enumerate() {
+        let Some(word) = token.strip_prefix(marker).filter(|word| !word.is_empty()) else {
+            continue;
+        };
+        consumed[index] = true;
+        match &found {
+            None => found = Some(word.to_string()),
+            Some(used) => issues.push(QuickExpenseIssue::SeveralReferences {
+                marker,
+                used: used.clone(),
+                ignored: word.to_string(),
+            }),
+        }
+    }
+    found
+}
+
+/// Takes the first date expression, reporting any further one.
+fn take_date(
+    tokens: &[&str],
+    consumed: &mut [bool],
+    today: NaiveDate,
+    issues: &mut Vec<QuickExpenseIssue>,
+) -> Option<NaiveDate> {
+    let mut found = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        if consumed[index] {
+            index += 1;
+            continue;
+        }
+        let Some((date, length)) = read_date(&tokens[index..], today, issues) else {
+            index += 1;
+            continue;
+        };
+        consumed[index..index + length].fill(true);
+        match found {
+            None => found = Some(date),
+            Some(used) => issues.push(QuickExpenseIssue::SeveralDates {
+                used,
+                ignored: tokens[index..index + length].join(" "),
+            }),
+        }
+        index += length;
+    }
+    found
+}
+
+/// Reads the date expression starting `tokens`, returning it with how many tokens it spans.
+fn read_date(
+    tokens: &[&str],
+    today: NaiveDate,
+    issues: &mut Vec<QuickExpenseIssue>,
+) -> Option<(NaiveDate, usize)> {
+    let word = tokens[0].to_lowercase();
+    match word.as_str() {
+        "today" => return Some((today, 1)),
+        "yesterday" => return Some((today - Days::new(1), 1)),
+        _ => {}
+    }
+    if let Ok(weekday) = word.parse::<Weekday>()
+        && word.len() > 3
+    {
+        let back =
+            (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+        return Some((today - Days::new(back.into()), 1));
+    }
+    if let Some([count, unit, ago]) = tokens.get(..3)
+        && matches!(unit.to_lowercase().as_str(), "day" | "days")
+        && ago.eq_ignore_ascii_case("ago")
+        && let Ok(count) = count.parse::<u32>()
+    {
+        return today
+            .checked_sub_days(Days::new(count.into()))
+            .map(|date| (date, 3));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+    read_day_month(&word, today, issues).map(|date| (date, 1))
+}
+
+/// Reads `day/month` or `day/month/year`. A date without a year is the latest one up to
+/// `today`; one that also reads as `month/day` is reported as ambiguous.
+fn read_day_month(
+    word: &str,
+    today: NaiveDate,
+    issues: &mut Vec<QuickExpenseIssue>,
+) -> Option<NaiveDate> {
+    let parts: Vec<u32> = word
+        .split('/')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let date = |day: u32, month: u32| -> Option<NaiveDate> {
+        match parts.get(2) {
+            Some(year) if *year < 100 => NaiveDate::from_ymd_opt(2000 + *year as i32, month, day),
+            Some(year) => NaiveDate::from_ymd_opt(*year as i32, month, day),
+            None => NaiveDate::from_ymd_opt(today.year(), month, day).map(|date| {
+                if date > today {
+                    date.with_year(today.year() - 1).unwrap_or(date)
+                } else {
+                    date
+                }
+            }),
+        }
+    };
+    let (day, month) = match parts.as_slice() {
+        [day, month] | [day, month, _] => (*day, *month),
+        _ => return None,
+    };
+    let read = date(day, month)?;
+    if day != month
+        && let Some(alternative) = date(month, day)
+    {
+        issues.push(QuickExpenseIssue::AmbiguousDate {
+            raw: word.to_string(),
+            read,
+            alternative,
+        });
+    }
+    Some(read)
+}
+
+/// Takes the amount and the currency written next to it. When several numbers are found, the
+/// last one with a decimal part or a currency is preferred, the others staying in the name.
+fn take_amount(
+    tokens: &[&str],
+    consumed: &mut [bool],
+    issues: &mut Vec<QuickExpenseIssue>,
+) -> (Option<ImportedAmount>, Option<Currency>) {
+    let candidates: Vec<(usize, ReadAmount)> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !consumed[*index])
+        .filter_map(|(index, token)| read_amount(token).map(|amount| (index, amount)))
+        .map(|(index, mut amount)| {
+            if amount.currency.is_none()
+                && let Some((code_index, currency)) = adjacent_currency(tokens, consumed, index)
+            {
+                amount.currency = Some(currency);
+                amount.code_token = Some(code_index);
+            }
+            (index, amount)
+        })
+        .collect();
+    let Some((index, chosen)) = candidates
+        .iter()
+        .rev()
+        .find(|(_, amount)| amount.has_decimals || amount.currency.is_some())
+        .or_else(|| candidates.last())
+        .cloned()
+    else {
+        return (None, None);
+    };
+    if candidates.len() > 1 {
+        issues.push(QuickExpenseIssue::SeveralAmounts {
+            used: tokens[index].to_string(),
+            ignored: candidates
+                .iter()
+                .filter(|(other, _)| *other != index)
+                .map(|(other, _)| tokens[*other].to_string())
+                .collect(),
+        });
+    }
+    consumed[index] = true;
+    if let Some(code_index) = chosen.code_token {
+        consumed[code_index] = true;
+    }
+    issues.extend(chosen.issues);
+    (Some(chosen.amount), chosen.currency)
+}
+
+/// An amount read from a token, before the currency next to it is looked for.
+#[derive(Clone, Debug)]
+struct ReadAmount {
+    amount: ImportedAmount,
+    currency: Option<Currency>,
+    /// The token holding the currency code, when written apart from the amount.
+    code_token: Option<usize>,
+    has_decimals: bool,
+    issues: Vec<QuickExpenseIssue>,
+}
+
+/// Reads a number such as `4.50`, `4,50`, `1,234.56`, `€4.50` or `4.50$`.
+fn read_amount(token: &str) -> Option<ReadAmount> {
+    let symbols = [('€', "EUR"), ('£', "GBP"), ('$', "USD"), ('¥', "JPY")];
+    let mut currency = None;
+    let mut issues = Vec::new();
+    let mut number = token;
+    for (symbol, code) in symbols {
+        if let Some(rest) = number
+            .strip_prefix(symbol)
+            .or_else(|| number.strip_suffix(symbol))
+        {
+            number = rest;
+            currency = Currency::new(code).ok();
+            if symbol == '$' || symbol == '¥' {
+                issues.push(QuickExpenseIssue::AssumedCurrency {
+                    symbol,
+                    currency: code.to_string(),
+                });
+            }
+            break;
+        }
+    }
+    if number.is_empty() || !number.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let grouped = number
+        .split_once('.')
+        .is_some_and(|(whole, _)| whole.contains(','));
+    let (decimal, has_decimals) = match number.split_once(',') {
+        // "1,234.56": commas group thousands.
+        _ if grouped => (number.replace(',', ""), true),
+        // "1,500" reads as fifteen hundred, but could be one and a half.
+        Some((whole, fraction)) if fraction.len() == 3 && !fraction.contains(',') => {
+            let read = format!("{whole}{fraction}");
+            issues.push(QuickExpenseIssue::AmbiguousAmount {
+                raw: number.to_string(),
+                read: read.clone(),
+            });
+            (read, false)
+        }
+        Some((whole, fraction)) => (format!("{whole}.{fraction}"), true),
+        None => (number.to_string(), number.contains('.')),
+    };
+    let amount = ImportedAmount::parse(&decimal, '.').ok()?;
+    Some(ReadAmount {
+        amount,
+        currency,
+        code_token: None,
+        has_decimals,
+        issues,
+    })
+}
+
+/// The currency code written right after, or else right before, the amount at `index`, with
+/// the index of its token.
+fn adjacent_currency(
+    tokens: &[&str],
+    consumed: &[bool],
+    index: usize,
+) -> Option<(usize, Currency)> {
+    [index + 1, index.wrapping_sub(1)]
+        .into_iter()
+        .filter(|neighbour| *neighbour < tokens.len() && !consumed[*neighbour])
+        .find_map(|neighbour| {
+            let token = tokens[neighbour];
+            let is_code = token.len() == 3
+                && (token.chars().all(|c| c.is_ascii_uppercase())
+                    || COMMON_CURRENCIES.contains(&token.to_ascii_lowercase().as_str()));
+            is_code
+                .then(|| Currency::new(token).ok())
+                .flatten()
+                .map(|currency| (neighbour, currency))
+        })
+}
+
+/// Something a [QuickExpense] could not read unambiguously. Blocking issues prevent the
+/// expense from being created; the others only report a guess.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum QuickExpenseIssue {
+    #[error("no amount found")]
+    MissingAmount,
+    #[error("no name found")]
+    MissingName,
+    #[error("several amounts found, using {used} and leaving {} in the name", ignored.join(", "))]
+    SeveralAmounts { used: String, ignored: Vec<String> },
+    #[error("{raw} was read as {read}, not as a decimal amount")]
+    AmbiguousAmount { raw: String, read: String },
+    #[error("{symbol} was read as {currency}")]
+    AssumedCurrency { symbol: char, currency: String },
+    #[error("several dates found, using {used} and ignoring {ignored}")]
+    SeveralDates { used: NaiveDate, ignored: String },
+    #[error("{raw} was read as {read} (day/month), not {alternative} (month/day)")]
+    AmbiguousDate {
+        raw: String,
+        read: NaiveDate,
+        alternative: NaiveDate,
+    },
+    #[error("several {marker} references found, using {used} and ignoring {ignored}")]
+    SeveralReferences {
+        marker: char,
+        used: String,
+        ignored: String,
+    },
+    #[error("no category named {name}")]
+    UnknownCategory { name: String },
+    #[error("several categories match {name}: {}", candidates.join(", "))]
+    SeveralCategories {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("no account named {name}")]
+    UnknownAccount { name: String },
+    #[error("several accounts match {name}: {}", candidates.join(", "))]
+    SeveralAccounts {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("no account given, name one with @")]
+    AccountRequired,
+    #[error("the amount is in {amount}, but the account is in {account}")]
+    CurrencyMismatch { amount: Currency, account: Currency },
+    #[error("the amount has more decimals than {currency} allows")]
+    InvalidAmount { currency: Currency },
+    #[error("the expense looks like a duplicate of {}", duplicate_ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", "))]
+    SuspectedDuplicate { duplicate_ids: Vec<Uuid> },
+}
+
+impl QuickExpenseIssue {
+    /// A short identifier of the kind of issue, for clients to act on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingAmount => "missing_amount",
+            Self::MissingName => "missing_name",
+            Self::SeveralAmounts { .. } => "several_amounts",
+            Self::AmbiguousAmount { .. } => "ambiguous_amount",
+            Self::AssumedCurrency { .. } => "assumed_currency",
+            Self::SeveralDates { .. } => "several_dates",
+            Self::AmbiguousDate { .. } => "ambiguous_date",
+            Self::SeveralReferences { .. } => "several_references",
+            Self::UnknownCategory { .. } => "unknown_category",
+            Self::SeveralCategories { .. } => "several_categories",
+            Self::UnknownAccount { .. } => "unknown_account",
+            Self::SeveralAccounts { .. } => "several_accounts",
+            Self::AccountRequired => "account_required",
+            Self::CurrencyMismatch { .. } => "currency_mismatch",
+            Self::InvalidAmount { .. } => "invalid_amount",
+            Self::SuspectedDuplicate { .. } => "suspected_duplicate",
+        }
+    }
+
+    /// Whether the issue prevents the expense from being created.
+    pub fn is_blocking(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingAmount
+                | Self::MissingName
+                | Self::UnknownCategory { .. }
+                | Self::SeveralCategories { .. }
+                | Self::UnknownAccount { .. }
+                | Self::SeveralAccounts { .. }
+                | Self::AccountRequired
+                | Self::CurrencyMismatch { .. }
+                | Self::InvalidAmount { .. }
+        )
+    }
+}
+
+/// The fields required by the domain to create an [Expense] from a one-line description.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickExpenseRequest {
+    text: String,
+    today: NaiveDate,
+    dry_run: bool,
+}
+
+impl QuickExpenseRequest {
+    /// Reads `text`, relative dates being relative to `today` in the caller's time zone.
+    pub fn new(text: &str, today: NaiveDate) -> Self {
+        Self {
+            text: text.to_string(),
+            today,
+            dry_run: false,
+        }
+    }
+
+    /// Interprets the text without creating anything.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn today(&self) -> &NaiveDate {
+        &self.today
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+/// The outcome of a [QuickExpenseRequest]: how the text was read, what it resolved to, and the
+/// expense created from it unless the request was a dry run or a blocking issue was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickExpenseOutcome {
+    parsed: QuickExpense,
+    category_id: Option<Uuid>,
+    account_id: Option<Uuid>,
+    currency: Option<Currency>,
+    issues: Vec<QuickExpenseIssue>,
+    request: Option<CreateExpenseRequest>,
+    expense: Option<Expense>,
+}
+
+impl QuickExpenseOutcome {
+    pub fn new(parsed: QuickExpense) -> Self {
+        Self {
+            issues: parsed.issues().to_vec(),
+            currency: parsed.currency().cloned(),
+            parsed,
+            category_id: None,
+            account_id: None,
+            request: None,
+            expense: None,
+        }
+    }
+
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Records the account the expense is paid from, whose currency the amount is in.
+    pub fn with_account(mut self, account_id: Uuid, currency: Currency) -> Self {
+        self.account_id = Some(account_id);
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn with_issue(mut self, issue: QuickExpenseIssue) -> Self {
+        self.issues.push(issue);
+        self
+    }
+
+    pub fn with_request(mut self, request: CreateExpenseRequest) -> Self {
+        self.request = Some(request);
+        self
+    }
+
+    pub fn with_expense(mut self, expense: Expense) -> Self {
+        self.expense = Some(expense);
+        self
+    }
+
+    pub fn parsed(&self) -> &QuickExpense {
+        &self.parsed
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    /// The currency of the amount: the account's once resolved, else the one written.
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    /// Every issue found while reading the text and resolving what it names.
+    pub fn issues(&self) -> &[QuickExpenseIssue] {
+        &self.issues
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.issues.iter().any(QuickExpenseIssue::is_blocking)
+    }
+
+    /// The request the text resolved to, unless a blocking issue was found.
+    pub fn request(&self) -> Option<&CreateExpenseRequest> {
+        self.request.as_ref()
+    }
+
+    /// The expense created, unless the request was a dry run or a blocking issue was found.
+    pub fn expense(&self) -> Option<&Expense> {
+        self.expense.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        // A Sunday.
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    #[test]
+    fn test_description_is_parsed() {
+        let parsed = QuickExpense::parse("coffee 4.50 EUR yesterday #food @cash", today());
+        assert_eq!(parsed.name(), Some("coffee"));
+        assert_eq!(parsed.amount().unwrap().to_string(), "4.50");
+        assert_eq!(parsed.currency().unwrap().as_str(), "EUR");
+        assert_eq!(
+            parsed.spent_on(),
+            &NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+        );
+        assert_eq!(parsed.category(), Some("food"));
+        assert_eq!(parsed.account(), Some("cash"));
+        assert!(parsed.issues().is_empty());
+
+        let parsed = QuickExpense::parse("€12,90 lunch with Ana friday", today());
+        assert_eq!(parsed.name(), Some("lunch with Ana"));
+        assert_eq!(parsed.amount().unwrap().to_string(), "12.90");
+        assert_eq!(parsed.currency().unwrap().as_str(), "EUR");
+        assert_eq!(
+            parsed.spent_on(),
+            &NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+        );
+
+        let parsed = QuickExpense::parse("4.50 tea 3 days ago", today());
+        assert_eq!(parsed.name(), Some("tea"));
+        assert_eq!(parsed.currency(), None);
+        assert_eq!(
+            parsed.spent_on(),
+            &NaiveDate::from_ymd_opt(2026, 10, 15).unwrap()
+        );
+
+        let parsed = QuickExpense::parse("15 #groceries sunday", today());
+        assert_eq!(parsed.name(), Some("groceries"));
+        assert_eq!(parsed.spent_on(), &today());
+    }
+
+    #[test]
+    fn test_ambiguities_are_reported() {
+        let parsed = QuickExpense::parse("2 coffees 7.80 3/10 #food #drinks $", today());
+        assert_eq!(parsed.name(), Some("2 coffees $"));
+        assert_eq!(parsed.amount().unwrap().to_string(), "7.80");
+        assert_eq!(
+            parsed.spent_on(),
+            &NaiveDate::from_ymd_opt(2026, 10, 3).unwrap()
+        );
+        let codes: Vec<&str> = parsed.issues().iter().map(|i| i.code()).collect();
+        assert_eq!(
+            codes,
+            ["several_references", "ambiguous_date", "several_amounts"]
+        );
+        assert!(!parsed.issues().iter().any(QuickExpenseIssue::is_blocking));
+
+        let parsed = QuickExpense::parse("rent 1,500", today());
+        assert_eq!(parsed.amount().unwrap().to_string(), "1500");
+        assert_eq!(parsed.issues()[0].code(), "ambiguous_amount");
+
+        // A date without a year is in the past.
+        let parsed = QuickExpense::parse("ski pass 25/12 $80", today());
+        assert_eq!(
+            parsed.spent_on(),
+            &NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()
+        );
+        assert_eq!(parsed.currency().unwrap().as_str(), "USD");
+        assert_eq!(parsed.issues()[0].code(), "assumed_currency");
+
+        let parsed = QuickExpense::parse("   ", today());
+        assert_eq!(
+            parsed.issues(),
+            [
+                QuickExpenseIssue::MissingName,
+                QuickExpenseIssue::MissingAmount
+            ]
+        );
+        assert!(parsed.issues().iter().all(QuickExpenseIssue::is_blocking));
+    }
+}
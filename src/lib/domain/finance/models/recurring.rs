@@ -0,0 +1,538 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::expense::{ExpenseAmountNegativeError, ExpenseName, ExpenseNameEmptyError};
+
+/// How often a [Recurrence] repeats, before its interval is applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    Daily,
+    /// On the weekday of the first occurrence.
+    Weekly,
+    /// On a given day of the month, the last day in shorter months.
+    Monthly,
+    /// On the anniversary of the first occurrence, February 28th for February 29th.
+    Yearly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+        }
+    }
+}
+
+impl Display for Frequency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown frequency {0}")]
+pub struct UnknownFrequencyError(pub String);
+
+impl FromStr for Frequency {
+    type Err = UnknownFrequencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "daily" => Ok(Frequency::Daily),
+            "weekly" => Ok(Frequency::Weekly),
+            "monthly" => Ok(Frequency::Monthly),
+            "yearly" => Ok(Frequency::Yearly),
+            _ => Err(UnknownFrequencyError(s.to_string())),
+        }
+    }
+}
+
+/// A simplified RRULE: every `interval` days, weeks, months or years from `starts_on`, optionally
+/// until `ends_on`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    day_of_month: Option<u32>,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    /// Repeat every period of `frequency` from `starts_on`, forever.
+    pub fn new(frequency: Frequency, starts_on: NaiveDate) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            day_of_month: None,
+            starts_on,
+            ends_on: None,
+        }
+    }
+
+    /// Repeat every `interval` periods instead of every period.
+    pub fn with_interval(mut self, interval: u32) -> Result<Self, RecurrenceError> {
+        if interval == 0 {
+            return Err(RecurrenceError::InvalidInterval);
+        }
+        self.interval = interval;
+        Ok(self)
+    }
+
+    /// Repeat monthly on `day` rather than on the day of `starts_on`.
+    pub fn with_day_of_month(mut self, day: u32) -> Result<Self, RecurrenceError> {
+        if self.frequency != Frequency::Monthly {
+            return Err(RecurrenceError::DayOfMonthNotMonthly);
+        }
+        if !(1..=31).contains(&day) {
+            return Err(RecurrenceError::InvalidDayOfMonth { day });
+        }
+        self.day_of_month = Some(day);
+        Ok(self)
+    }
+
+    /// Stop repeating after `ends_on`, inclusive.
+    pub fn with_ends_on(mut self, ends_on: NaiveDate) -> Result<Self, RecurrenceError> {
+        if ends_on < self.starts_on {
+            return Err(RecurrenceError::EndsBeforeStart {
+                starts_on: self.starts_on,
+                ends_on,
+            });
+        }
+        self.ends_on = Some(ends_on);
+        Ok(self)
+    }
+
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// The day of the month a monthly recurrence falls on, if not that of `starts_on`.
+    pub fn day_of_month(&self) -> Option<u32> {
+        self.day_of_month
+    }
+
+    pub fn starts_on(&self) -> &NaiveDate {
+        &self.starts_on
+    }
+
+    /// The last day an occurrence may fall on, or `None` if the recurrence never ends.
+    pub fn ends_on(&self) -> Option<&NaiveDate> {
+        self.ends_on.as_ref()
+    }
+
+    /// The occurrences falling on or after `from`, in order.
+    pub fn occurrences_from(&self, from: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        let from = from.max(self.starts_on);
+        (0..)
+            .map_while(|n| self.nth(n))
+            .take_while(|day| self.ends_on.is_none_or(|ends_on| *day <= ends_on))
+            .filter(move |day| *day >= from)
+    }
+
+    /// The `n`th candidate date, which may precede `starts_on` for a monthly recurrence on an
+    /// earlier day of the month. `None` once dates overflow.
+    fn nth(&self, n: u32) -> Option<NaiveDate> {
+        let step = n.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => self.starts_on.checked_add_days(Days::new(u64::from(step))),
+            Frequency::Weekly => self
+                .starts_on
+                .checked_add_days(Days::new(u64::from(step) * 7)),
+            Frequency::Monthly => {
+                let day = self.day_of_month.unwrap_or(self.starts_on.day());
+                day_in_month(self.starts_on, step, day)
+            }
+            Frequency::Yearly => {
+                day_in_month(self.starts_on, step.checked_mul(12)?, self.starts_on.day())
+            }
+        }
+    }
+}
+
+/// `day` of the month `months` after that of `start`, clamped to the length of that month.
+fn day_in_month(start: NaiveDate, months: u32, day: u32) -> Option<NaiveDate> {
+    let first = start.with_day(1)?.checked_add_months(Months::new(months))?;
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?.day();
+    first.with_day(day.min(last))
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum RecurrenceError {
+    #[error("recurrence interval must be at least 1")]
+    InvalidInterval,
+    #[error("day of month must be between 1 and 31, got {day}")]
+    InvalidDayOfMonth { day: u32 },
+    #[error("a day of month only applies to monthly recurrences")]
+    DayOfMonthNotMonthly,
+    #[error("recurrence ends on {ends_on}, before it starts on {starts_on}")]
+    EndsBeforeStart {
+        starts_on: NaiveDate,
+        ends_on: NaiveDate,
+    },
+}
+
+/// A template from which an expense is created on every occurrence of its [Recurrence], such as
+/// rent or a subscription.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecurringExpense {
+    id: Uuid,
+    owner_id: Uuid,
+    ledger_id: Option<Uuid>,
+    name: ExpenseName,
+    amount: i64,
+    category_id: Option<Uuid>,
+    account_id: Option<Uuid>,
+    recurrence: Recurrence,
+    materialized_through: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+}
+
+impl RecurringExpense {
+    pub fn new(
+        id: Uuid,
+        owner_id: Uuid,
+        name: ExpenseName,
+        amount: i64,
+        recurrence: Recurrence,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            owner_id,
+            ledger_id: None,
+            name,
+            amount,
+            category_id: None,
+            account_id: None,
+            recurrence,
+            materialized_through: None,
+            created_at,
+        }
+    }
+
+    /// Places the [RecurringExpense] in the shared ledger identified by `ledger_id`.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Files the created expenses under the category identified by `category_id`.
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Records the created expenses against the account identified by `account_id`.
+    pub fn with_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Records the last day up to which occurrences were turned into expenses.
+    pub fn with_materialized_through(mut self, materialized_through: Option<NaiveDate>) -> Self {
+        self.materialized_through = materialized_through;
+        self
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    /// The user who set up the [RecurringExpense], on whose behalf expenses are created.
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    /// The ledger the created expenses belong to, or `None` for personal expenses.
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn name(&self) -> &ExpenseName {
+        &self.name
+    }
+
+    /// The amount of each created expense, in minor units.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    /// The account the created expenses are recorded against. `None` only for recurring
+    /// expenses set up before accounts existed, which can no longer create expenses.
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+
+    /// The last day up to which occurrences were turned into expenses, or `None` if none were
+    /// yet.
+    pub fn materialized_through(&self) -> Option<&NaiveDate> {
+        self.materialized_through.as_ref()
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    /// The occurrences not yet turned into expenses, in order.
+    pub fn pending_occurrences(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        let from = self
+            .materialized_through
+            .and_then(|day| day.succ_opt())
+            .unwrap_or(self.recurrence.starts_on);
+        self.recurrence.occurrences_from(from)
+    }
+}
+
+/// A day on which a [RecurringExpense] will create an expense.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Occurrence {
+    occurs_on: NaiveDate,
+    recurring_expense: RecurringExpense,
+}
+
+impl Occurrence {
+    pub fn new(occurs_on: NaiveDate, recurring_expense: RecurringExpense) -> Self {
+        Self {
+            occurs_on,
+            recurring_expense,
+        }
+    }
+
+    pub fn occurs_on(&self) -> &NaiveDate {
+        &self.occurs_on
+    }
+
+    pub fn recurring_expense(&self) -> &RecurringExpense {
+        &self.recurring_expense
+    }
+}
+
+/// The fields required by the domain to create a [RecurringExpense].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CreateRecurringExpenseRequest {
+    owner_id: Uuid,
+    ledger_id: Option<Uuid>,
+    name: ExpenseName,
+    amount: i64,
+    category_id: Option<Uuid>,
+    account_id: Option<Uuid>,
+    recurrence: Recurrence,
+}
+
+impl CreateRecurringExpenseRequest {
+    pub fn new(
+        owner_id: Uuid,
+        name: &str,
+        recurrence: Recurrence,
+    ) -> Result<Self, ExpenseNameEmptyError> {
+        let name = ExpenseName::new(name)?;
+        Ok(Self {
+            owner_id,
+            ledger_id: None,
+            name,
+            amount: 0,
+            category_id: None,
+            account_id: None,
+            recurrence,
+        })
+    }
+
+    /// Sets the amount of each created expense, in minor units.
+    pub fn with_amount(mut self, amount: i64) -> Result<Self, ExpenseAmountNegativeError> {
+        if amount < 0 {
+            return Err(ExpenseAmountNegativeError(amount));
+        }
+        self.amount = amount;
+        Ok(self)
+    }
+
+    /// Creates the expenses in the ledger identified by `ledger_id`.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Files the created expenses under the category identified by `category_id`.
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Records the created expenses against the account identified by `account_id`, which must
+    /// belong to the owner. Required.
+    pub fn with_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn name(&self) -> &ExpenseName {
+        &self.name
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecurringExpenseError {
+    #[error("recurring expense {id} not found")]
+    NotFound { id: Uuid },
+    #[error("category {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("an account is required")]
+    AccountRequired,
+    #[error("account {id} not found")]
+    AccountNotFound { id: Uuid },
+    #[error("cannot list more than {max} days of upcoming occurrences, got {days}")]
+    RangeTooLong { days: i64, max: i64 },
+    #[error("{failed} recurring expenses could not be materialized, {created} occurrences were")]
+    MaterializationFailed { failed: usize, created: usize },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn first(recurrence: &Recurrence, n: usize) -> Vec<NaiveDate> {
+        recurrence
+            .occurrences_from(NaiveDate::MIN)
+            .take(n)
+            .collect()
+    }
+
+    #[test]
+    fn test_weekly_every_other_week() {
+        let recurrence = Recurrence::new(Frequency::Weekly, day(2026, 10, 5))
+            .with_interval(2)
+            .unwrap();
+
+        assert_eq!(
+            first(&recurrence, 3),
+            vec![day(2026, 10, 5), day(2026, 10, 19), day(2026, 11, 2)]
+        );
+    }
+
+    #[test]
+    fn test_monthly_on_day_clamps_to_month_end() {
+        let recurrence = Recurrence::new(Frequency::Monthly, day(2027, 1, 15))
+            .with_day_of_month(31)
+            .unwrap();
+
+        assert_eq!(
+            first(&recurrence, 3),
+            vec![day(2027, 1, 31), day(2027, 2, 28), day(2027, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn test_monthly_skips_day_before_start() {
+        let recurrence = Recurrence::new(Frequency::Monthly, day(2026, 10, 18))
+            .with_day_of_month(1)
+            .unwrap();
+
+        assert_eq!(first(&recurrence, 1), vec![day(2026, 11, 1)]);
+    }
+
+    #[test]
+    fn test_yearly_until_end_date() {
+        let recurrence = Recurrence::new(Frequency::Yearly, day(2028, 2, 29))
+            .with_ends_on(day(2030, 3, 1))
+            .unwrap();
+
+        assert_eq!(
+            first(&recurrence, 10),
+            vec![day(2028, 2, 29), day(2029, 2, 28), day(2030, 2, 28)]
+        );
+    }
+
+    #[test]
+    fn test_pending_occurrences_resume_after_materialized_day() {
+        let recurrence = Recurrence::new(Frequency::Daily, day(2026, 10, 1));
+        let recurring = RecurringExpense::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            ExpenseName::new("Coffee").unwrap(),
+            350,
+            recurrence,
+            Utc::now(),
+        )
+        .with_materialized_through(Some(day(2026, 10, 17)));
+
+        assert_eq!(
+            recurring.pending_occurrences().next(),
+            Some(day(2026, 10, 18))
+        );
+    }
+
+    #[test]
+    fn test_invalid_recurrences() {
+        let start = day(2026, 10, 18);
+        assert!(matches!(
+            Recurrence::new(Frequency::Daily, start).with_interval(0),
+            Err(RecurrenceError::InvalidInterval)
+        ));
+        assert!(matches!(
+            Recurrence::new(Frequency::Weekly, start).with_day_of_month(3),
+            Err(RecurrenceError::DayOfMonthNotMonthly)
+        ));
+        assert!(matches!(
+            Recurrence::new(Frequency::Monthly, start).with_day_of_month(32),
+            Err(RecurrenceError::InvalidDayOfMonth { day: 32 })
+        ));
+        assert!(matches!(
+            Recurrence::new(Frequency::Daily, start).with_ends_on(day(2026, 10, 17)),
+            Err(RecurrenceError::EndsBeforeStart { .. })
+        ));
+    }
+}
@@ -0,0 +1,483 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::account::Currency;
+use super::exchange::MissingExchangeRate;
+
+/// Whose expenses a report covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReportScope {
+    /// The personal expenses recorded against the accounts of the user.
+    Personal(Uuid),
+    /// Every expense of the ledger.
+    Ledger(Uuid),
+}
+
+/// What the buckets of a spending summary are keyed by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SummaryGrouping {
+    /// The calendar month the money was spent in.
+    Month,
+    Category,
+    /// An expense with several tags counts towards each of them.
+    Tag,
+}
+
+impl SummaryGrouping {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryGrouping::Month => "month",
+            SummaryGrouping::Category => "category",
+            SummaryGrouping::Tag => "tag",
+        }
+    }
+}
+
+impl Display for SummaryGrouping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown summary grouping {0}, expected month, category or tag")]
+pub struct UnknownSummaryGroupingError(pub String);
+
+impl FromStr for SummaryGrouping {
+    type Err = UnknownSummaryGroupingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "month" => Ok(SummaryGrouping::Month),
+            "category" => Ok(SummaryGrouping::Category),
+            "tag" => Ok(SummaryGrouping::Tag),
+            _ => Err(UnknownSummaryGroupingError(s.to_string())),
+        }
+    }
+}
+
+/// The fields required by the domain to summarize spending.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SummaryRequest {
+    grouping: SummaryGrouping,
+    ledger_id: Option<Uuid>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    currency: Option<Currency>,
+}
+
+impl SummaryRequest {
+    pub fn new(grouping: SummaryGrouping) -> Self {
+        Self {
+            grouping,
+            ledger_id: None,
+            from: None,
+            to: None,
+            currency: None,
+        }
+    }
+
+    /// Summarizes the expenses of the ledger identified by `ledger_id` rather than the caller's
+    /// personal expenses.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Leaves out expenses spent before `from`.
+    pub fn with_from(mut self, from: NaiveDate) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Leaves out expenses spent after `to`, inclusive.
+    pub fn with_to(mut self, to: NaiveDate) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Converts every amount to `currency` at the rate of the day it was spent. Without a
+    /// currency, buckets are further split by the currency of the accounts.
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn grouping(&self) -> SummaryGrouping {
+        self.grouping
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn from(&self) -> Option<&NaiveDate> {
+        self.from.as_ref()
+    }
+
+    pub fn to(&self) -> Option<&NaiveDate> {
+        self.to.as_ref()
+    }
+
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+}
+
+/// The spending aggregated under one key of a [SummaryGrouping].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SummaryBucket {
+    key: Option<String>,
+    label: Option<String>,
+    currency: Currency,
+    total: i64,
+    count: i64,
+    average: i64,
+}
+
+impl SummaryBucket {
+    pub fn new(
+        key: Option<String>,
+        currency: Currency,
+        total: i64,
+        count: i64,
+        average: i64,
+    ) -> Self {
+        Self {
+            key,
+            label: None,
+            currency,
+            total,
+            count,
+            average,
+        }
+    }
+
+    /// Sets a human readable name for the key, such as the name of a category.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// The month as `YYYY-MM`, the category id or the tag. `None` gathers the uncategorized or
+    /// untagged expenses.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// The sum of the amounts, in minor units.
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    /// How many expenses were summed.
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    /// The mean amount of an expense, in minor units, rounded.
+    pub fn average(&self) -> i64 {
+        self.average
+    }
+}
+
+/// The width of the points of a spending time series.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SeriesInterval {
+    Day,
+    /// Monday to Sunday.
+    Week,
+    /// First to last day of the calendar month.
+    Month,
+}
+
+impl SeriesInterval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeriesInterval::Day => "day",
+            SeriesInterval::Week => "week",
+            SeriesInterval::Month => "month",
+        }
+    }
+
+    /// The first day of the interval containing `day`.
+    pub fn start_of(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            SeriesInterval::Day => day,
+            SeriesInterval::Week => {
+                day - Days::new(u64::from(day.weekday().num_days_from_monday()))
+            }
+            SeriesInterval::Month => day.with_day(1).expect("every month has a first day"),
+        }
+    }
+
+    /// The first day of the interval `count` intervals before the one starting on `start`.
+    pub fn back(&self, start: NaiveDate, count: u32) -> NaiveDate {
+        match self {
+            SeriesInterval::Day => start - Days::new(u64::from(count)),
+            SeriesInterval::Week => start - Days::new(7 * u64::from(count)),
+            SeriesInterval::Month => start - Months::new(count),
+        }
+    }
+
+    /// How many intervals are needed to cover `[from, to]`, or 0 if `from` is after `to`.
+    pub fn count_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        if from > to {
+            return 0;
+        }
+        let (from, to) = (self.start_of(from), self.start_of(to));
+        match self {
+            SeriesInterval::Day => (to - from).num_days() + 1,
+            SeriesInterval::Week => (to - from).num_days() / 7 + 1,
+            SeriesInterval::Month => {
+                i64::from(to.year() - from.year()) * 12 + i64::from(to.month())
+                    - i64::from(from.month())
+                    + 1
+            }
+        }
+    }
+}
+
+impl Display for SeriesInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown series interval {0}, expected day, week or month")]
+pub struct UnknownSeriesIntervalError(pub String);
+
+impl FromStr for SeriesInterval {
+    type Err = UnknownSeriesIntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "day" | "daily" => Ok(SeriesInterval::Day),
+            "week" | "weekly" => Ok(SeriesInterval::Week),
+            "month" | "monthly" => Ok(SeriesInterval::Month),
+            _ => Err(UnknownSeriesIntervalError(s.to_string())),
+        }
+    }
+}
+
+/// The fields required by the domain to compute a spending time series.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeseriesRequest {
+    interval: SeriesInterval,
+    from: NaiveDate,
+    to: NaiveDate,
+    ledger_id: Option<Uuid>,
+    currency: Option<Currency>,
+    cumulative: bool,
+    comparison: bool,
+}
+
+impl TimeseriesRequest {
+    /// A series of `interval`s covering `[from, to]`, widened to whole intervals.
+    pub fn new(interval: SeriesInterval, from: NaiveDate, to: NaiveDate) -> Self {
+        Self {
+            interval,
+            from,
+            to,
+            ledger_id: None,
+            currency: None,
+            cumulative: false,
+            comparison: false,
+        }
+    }
+
+    /// Covers the expenses of the ledger identified by `ledger_id` rather than the caller's
+    /// personal expenses.
+    pub fn with_ledger(mut self, ledger_id: Uuid) -> Self {
+        self.ledger_id = Some(ledger_id);
+        self
+    }
+
+    /// Converts every amount to `currency` at the rate of the day it was spent. Without a
+    /// currency, there is one series per currency of the accounts.
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Makes every point a running total since the start of the series.
+    pub fn with_cumulative(mut self) -> Self {
+        self.cumulative = true;
+        self
+    }
+
+    /// Adds to every point the total of the matching point of the previous period: the same
+    /// number of intervals, right before the series starts.
+    pub fn with_comparison(mut self) -> Self {
+        self.comparison = true;
+        self
+    }
+
+    pub fn interval(&self) -> SeriesInterval {
+        self.interval
+    }
+
+    /// The first day of the first interval of the series.
+    pub fn start(&self) -> NaiveDate {
+        self.interval.start_of(self.from)
+    }
+
+    /// The first day of the last interval of the series.
+    pub fn last_start(&self) -> NaiveDate {
+        self.interval.start_of(self.to)
+    }
+
+    pub fn from(&self) -> &NaiveDate {
+        &self.from
+    }
+
+    pub fn to(&self) -> &NaiveDate {
+        &self.to
+    }
+
+    /// How many points the series holds.
+    pub fn points(&self) -> i64 {
+        self.interval.count_between(self.from, self.to)
+    }
+
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    pub fn is_cumulative(&self) -> bool {
+        self.cumulative
+    }
+
+    pub fn has_comparison(&self) -> bool {
+        self.comparison
+    }
+}
+
+/// The spending of one interval of a [Series].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SeriesPoint {
+    start: NaiveDate,
+    total: i64,
+    previous_total: Option<i64>,
+}
+
+impl SeriesPoint {
+    pub fn new(start: NaiveDate, total: i64) -> Self {
+        Self {
+            start,
+            total,
+            previous_total: None,
+        }
+    }
+
+    /// Sets the total of the matching interval of the previous period.
+    pub fn with_previous_total(mut self, previous_total: i64) -> Self {
+        self.previous_total = Some(previous_total);
+        self
+    }
+
+    /// The first day of the interval.
+    pub fn start(&self) -> &NaiveDate {
+        &self.start
+    }
+
+    /// The amount spent in the interval, or up to its end for a cumulative series, in minor
+    /// units. Zero for intervals without expenses.
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    /// The same total for the previous period, if a comparison was requested.
+    pub fn previous_total(&self) -> Option<i64> {
+        self.previous_total
+    }
+}
+
+/// A spending time series in one currency, with a point for every interval.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Series {
+    currency: Currency,
+    points: Vec<SeriesPoint>,
+}
+
+impl Series {
+    pub fn new(currency: Currency, points: Vec<SeriesPoint>) -> Self {
+        Self { currency, points }
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// The points of the series, in date order.
+    pub fn points(&self) -> &[SeriesPoint] {
+        &self.points
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("report range starts on {from}, after its end on {to}")]
+    InvalidRange { from: NaiveDate, to: NaiveDate },
+    #[error("series of {points} points exceeds the maximum of {max}")]
+    TooManyPoints { points: i64, max: i64 },
+    #[error(transparent)]
+    MissingExchangeRate(#[from] MissingExchangeRate),
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_summary_grouping() {
+        assert_eq!(
+            SummaryGrouping::from_str(" Category ").unwrap(),
+            SummaryGrouping::Category
+        );
+        assert!(SummaryGrouping::from_str("week").is_err());
+    }
+
+    #[test]
+    fn test_series_interval_counts_whole_intervals() {
+        let from = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(SeriesInterval::Day.count_between(from, to), 30);
+        // Monday 2026-01-26 through Monday 2026-02-23
+        assert_eq!(SeriesInterval::Week.count_between(from, to), 5);
+        assert_eq!(SeriesInterval::Month.count_between(from, to), 3);
+        assert_eq!(SeriesInterval::Month.count_between(to, from), 0);
+    }
+
+    #[test]
+    fn test_series_interval_goes_back_whole_intervals() {
+        let start = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(
+            SeriesInterval::Month.back(start, 3),
+            NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,681 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+use super::expense::{CreateExpenseRequest, Expense, ExpenseName};
+use super::tag::Tag;
+use super::transaction::TransactionKind;
+
+/// The largest compiled size of a name pattern, in bytes, so that a rule cannot make every
+/// expense creation slow.
+const MAX_PATTERN_SIZE: usize = 256 * 1024;
+
+/// A user-defined rule filing the caller's personal expenses as they are created or imported:
+/// when every condition of the rule matches an expense, its actions are applied.
+///
+/// Rules are evaluated by ascending [priority](Rule::priority), and every matching rule applies:
+/// the first one to rename the expense or to file it under a category wins, while tags add up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    id: Uuid,
+    owner_id: Uuid,
+    name: RuleName,
+    priority: i32,
+    conditions: RuleConditions,
+    actions: RuleActions,
+    created_at: DateTime<Utc>,
+}
+
+impl Rule {
+    pub fn new(
+        id: Uuid,
+        owner_id: Uuid,
+        name: RuleName,
+        priority: i32,
+        conditions: RuleConditions,
+        actions: RuleActions,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            owner_id,
+            name,
+            priority,
+            conditions,
+            actions,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn name(&self) -> &RuleName {
+        &self.name
+    }
+
+    /// Rules with a lower priority are evaluated first.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn conditions(&self) -> &RuleConditions {
+        &self.conditions
+    }
+
+    pub fn actions(&self) -> &RuleActions {
+        &self.actions
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// A validated and formatted rule name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleName(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("rule name cannot be empty")]
+pub struct RuleNameEmptyError;
+
+impl RuleName {
+    pub fn new(raw: &str) -> Result<Self, RuleNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(RuleNameEmptyError)
+        } else {
+            Ok(Self(trimmed.to_string()))
+        }
+    }
+}
+
+impl Display for RuleName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A regular expression matched against expense names, anywhere in the name unless anchored.
+#[derive(Clone, Debug)]
+pub struct NamePattern(Regex);
+
+impl NamePattern {
+    pub fn new(raw: &str) -> Result<Self, RuleError> {
+        RegexBuilder::new(raw)
+            .size_limit(MAX_PATTERN_SIZE)
+            .build()
+            .map(Self)
+            .map_err(|e| RuleError::InvalidPattern {
+                pattern: raw.to_string(),
+                reason: e.to_string(),
+            })
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+impl PartialEq for NamePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for NamePattern {}
+
+/// What an expense must look like for a [Rule] to apply to it. Conditions left out match any
+/// expense, but a rule has at least one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleConditions {
+    name_pattern: Option<NamePattern>,
+    min_amount: Option<i64>,
+    max_amount: Option<i64>,
+    account_id: Option<Uuid>,
+    counterparty: Option<String>,
+    kind: Option<TransactionKind>,
+}
+
+impl RuleConditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_name_pattern(mut self, pattern: NamePattern) -> Self {
+        self.name_pattern = Some(pattern);
+        self
+    }
+
+    /// Matches amounts within `[min, max]`, in minor units; either bound may be left out.
+    pub fn with_amount_range(
+        mut self,
+        min: Option<i64>,
+        max: Option<i64>,
+    ) -> Result<Self, RuleError> {
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(RuleError::InvalidAmountRange { min, max });
+        }
+        self.min_amount = min;
+        self.max_amount = max;
+        Ok(self)
+    }
+
+    pub fn with_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Matches counterparties containing `counterparty`, compared case-insensitively.
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        let trimmed = counterparty.trim();
+        self.counterparty = (!trimmed.is_empty()).then(|| trimmed.to_lowercase());
+        self
+    }
+
+    pub fn with_kind(mut self, kind: TransactionKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn name_pattern(&self) -> Option<&NamePattern> {
+        self.name_pattern.as_ref()
+    }
+
+    pub fn min_amount(&self) -> Option<i64> {
+        self.min_amount
+    }
+
+    pub fn max_amount(&self) -> Option<i64> {
+        self.max_amount
+    }
+
+    pub fn account_id(&self) -> Option<&Uuid> {
+        self.account_id.as_ref()
+    }
+
+    /// The lowercase text counterparties must contain.
+    pub fn counterparty(&self) -> Option<&str> {
+        self.counterparty.as_deref()
+    }
+
+    pub fn kind(&self) -> Option<TransactionKind> {
+        self.kind
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether every condition matches `subject`.
+    pub fn matches(&self, subject: &RuleSubject) -> bool {
+        self.name_pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(subject.name.as_str()))
+            && self.min_amount.is_none_or(|min| subject.amount >= min)
+            && self.max_amount.is_none_or(|max| subject.amount <= max)
+            && self
+                .account_id
+                .is_none_or(|account_id| subject.account_id == Some(account_id))
+            && self.counterparty.as_ref().is_none_or(|counterparty| {
+                subject
+                    .counterparty
+                    .is_some_and(|c| c.to_lowercase().contains(counterparty))
+            })
+            && self.kind.is_none_or(|kind| subject.kind == kind)
+    }
+}
+
+/// What a [Rule] does to the expenses it matches. A rule has at least one action.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleActions {
+    category_id: Option<Uuid>,
+    tags: Vec<Tag>,
+    rename: Option<ExpenseName>,
+}
+
+impl RuleActions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files matching expenses under one of the owner's personal categories, unless they
+    /// already have a category.
+    pub fn with_category(mut self, category_id: Uuid) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Adds `tags` to matching expenses.
+    pub fn with_tags(mut self, mut tags: Vec<Tag>) -> Self {
+        tags.sort();
+        tags.dedup();
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_rename(mut self, name: ExpenseName) -> Self {
+        self.rename = Some(name);
+        self
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn rename(&self) -> Option<&ExpenseName> {
+        self.rename.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// The fields of an expense [Rule]s are matched against.
+#[derive(Clone, Copy, Debug)]
+pub struct RuleSubject<'a> {
+    name: &'a ExpenseName,
+    amount: i64,
+    account_id: Option<Uuid>,
+    counterparty: Option<&'a str>,
+    kind: TransactionKind,
+}
+
+impl<'a> From<&'a CreateExpenseRequest> for RuleSubject<'a> {
+    fn from(req: &'a CreateExpenseRequest) -> Self {
+        Self {
+            name: req.name(),
+            amount: req.amount(),
+            account_id: req.account_id().copied(),
+            counterparty: req.counterparty(),
+            kind: req.kind(),
+        }
+    }
+}
+
+impl<'a> From<&'a Expense> for RuleSubject<'a> {
+    fn from(expense: &'a Expense) -> Self {
+        Self {
+            name: expense.name(),
+            amount: expense.amount(),
+            account_id: expense.account_id().copied(),
+            counterparty: expense.counterparty(),
+            kind: expense.kind(),
+        }
+    }
+}
+
+/// How an expense is filed: what [Rule]s change.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Classification {
+    name: ExpenseName,
+    category_id: Option<Uuid>,
+    tags: Vec<Tag>,
+}
+
+impl Classification {
+    pub fn new(name: ExpenseName, category_id: Option<Uuid>, mut tags: Vec<Tag>) -> Self {
+        tags.sort();
+        tags.dedup();
+        Self {
+            name,
+            category_id,
+            tags,
+        }
+    }
+
+    pub fn name(&self) -> &ExpenseName {
+        &self.name
+    }
+
+    pub fn category_id(&self) -> Option<&Uuid> {
+        self.category_id.as_ref()
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Apply the actions of every rule of `rules` matching `subject`, in order, returning the
+    /// new classification and the ids of the rules that matched. Conditions are matched against
+    /// the expense as given, not as changed by earlier rules.
+    pub fn apply(&self, rules: &[Rule], subject: &RuleSubject) -> (Self, Vec<Uuid>) {
+        let mut classified = self.clone();
+        let mut renamed = false;
+        let mut applied = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.conditions.matches(subject)) {
+            let actions = &rule.actions;
+            if classified.category_id.is_none() {
+                classified.category_id = actions.category_id;
+            }
+            if let Some(name) = &actions.rename
+                && !renamed
+            {
+                classified.name = name.clone();
+                renamed = true;
+            }
+            classified.tags.extend(actions.tags.iter().cloned());
+            applied.push(rule.id);
+        }
+        classified.tags.sort();
+        classified.tags.dedup();
+        (classified, applied)
+    }
+}
+
+impl From<&CreateExpenseRequest> for Classification {
+    fn from(req: &CreateExpenseRequest) -> Self {
+        Self::new(
+            req.name().clone(),
+            req.category_id().copied(),
+            req.tags().to_vec(),
+        )
+    }
+}
+
+impl From<&Expense> for Classification {
+    fn from(expense: &Expense) -> Self {
+        Self::new(
+            expense.name().clone(),
+            expense.category_id().copied(),
+            expense.tags().to_vec(),
+        )
+    }
+}
+
+/// The fields required by the domain to create a [Rule].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateRuleRequest {
+    owner_id: Uuid,
+    name: RuleName,
+    priority: i32,
+    conditions: RuleConditions,
+    actions: RuleActions,
+}
+
+impl CreateRuleRequest {
+    /// # Errors
+    ///
+    /// - [RuleError::NoCondition] if `conditions` would match every expense.
+    /// - [RuleError::NoAction] if `actions` would change nothing.
+    pub fn new(
+        owner_id: Uuid,
+        name: RuleName,
+        priority: i32,
+        conditions: RuleConditions,
+        actions: RuleActions,
+    ) -> Result<Self, RuleError> {
+        if conditions.is_empty() {
+            return Err(RuleError::NoCondition);
+        }
+        if actions.is_empty() {
+            return Err(RuleError::NoAction);
+        }
+        Ok(Self {
+            owner_id,
+            name,
+            priority,
+            conditions,
+            actions,
+        })
+    }
+
+    pub fn owner_id(&self) -> &Uuid {
+        &self.owner_id
+    }
+
+    pub fn name(&self) -> &RuleName {
+        &self.name
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn conditions(&self) -> &RuleConditions {
+        &self.conditions
+    }
+
+    pub fn actions(&self) -> &RuleActions {
+        &self.actions
+    }
+}
+
+/// A change [Rule]s make to an existing expense.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleChange {
+    expense_id: Uuid,
+    rule_ids: Vec<Uuid>,
+    before: Classification,
+    after: Classification,
+}
+
+impl RuleChange {
+    pub fn new(
+        expense_id: Uuid,
+        rule_ids: Vec<Uuid>,
+        before: Classification,
+        after: Classification,
+    ) -> Self {
+        Self {
+            expense_id,
+            rule_ids,
+            before,
+            after,
+        }
+    }
+
+    pub fn expense_id(&self) -> &Uuid {
+        &self.expense_id
+    }
+
+    /// The rules that matched, in the order they were applied.
+    pub fn rule_ids(&self) -> &[Uuid] {
+        &self.rule_ids
+    }
+
+    pub fn before(&self) -> &Classification {
+        &self.before
+    }
+
+    pub fn after(&self) -> &Classification {
+        &self.after
+    }
+}
+
+/// The outcome of applying the caller's [Rule]s to their existing expenses.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleApplicationReport {
+    dry_run: bool,
+    examined: usize,
+    changes: Vec<RuleChange>,
+}
+
+impl RuleApplicationReport {
+    pub fn new(dry_run: bool, examined: usize, changes: Vec<RuleChange>) -> Self {
+        Self {
+            dry_run,
+            examined,
+            changes,
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// How many expenses the rules were matched against.
+    pub fn examined(&self) -> usize {
+        self.examined
+    }
+
+    /// The expenses changed or, for a dry run, that would have been.
+    pub fn changes(&self) -> &[RuleChange] {
+        &self.changes
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RuleError {
+    #[error("invalid name pattern {pattern:?}: {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[error("invalid amount range: {min} is greater than {max}")]
+    InvalidAmountRange { min: i64, max: i64 },
+    #[error("a rule needs at least one condition")]
+    NoCondition,
+    #[error("a rule needs at least one action")]
+    NoAction,
+    #[error("rule {id} not found")]
+    NotFound { id: Uuid },
+    #[error("category {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("account {id} not found")]
+    AccountNotFound { id: Uuid },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(priority: i32, conditions: RuleConditions, actions: RuleActions) -> Rule {
+        Rule::new(
+            Uuid::new_v4(),
+            Uuid::nil(),
+            RuleName::new("rule").unwrap(),
+            priority,
+            conditions,
+            actions,
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_conditions_match() {
+        let req = CreateExpenseRequest::new("CARD 1234 STARBUCKS PARIS")
+            .unwrap()
+            .with_amount(450)
+            .unwrap()
+            .with_counterparty("Starbucks Coffee France");
+        let subject = RuleSubject::from(&req);
+
+        let conditions = RuleConditions::new()
+            .with_name_pattern(NamePattern::new("(?i)starbucks").unwrap())
+            .with_amount_range(Some(100), Some(1000))
+            .unwrap()
+            .with_counterparty("STARBUCKS");
+        assert!(conditions.matches(&subject));
+        assert!(
+            !conditions
+                .clone()
+                .with_kind(TransactionKind::Income)
+                .matches(&subject)
+        );
+        assert!(
+            !RuleConditions::new()
+                .with_amount_range(None, Some(449))
+                .unwrap()
+                .matches(&subject)
+        );
+        assert!(
+            !RuleConditions::new()
+                .with_account(Uuid::nil())
+                .matches(&subject)
+        );
+
+        assert!(matches!(
+            NamePattern::new("(unclosed"),
+            Err(RuleError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            RuleConditions::new().with_amount_range(Some(10), Some(1)),
+            Err(RuleError::InvalidAmountRange { .. })
+        ));
+        assert!(matches!(
+            CreateRuleRequest::new(
+                Uuid::nil(),
+                RuleName::new("empty").unwrap(),
+                0,
+                RuleConditions::new(),
+                RuleActions::new().with_category(Uuid::nil()),
+            ),
+            Err(RuleError::NoCondition)
+        ));
+    }
+
+    #[test]
+    fn test_rules_apply_in_priority_order() {
+        let coffee = Uuid::new_v4();
+        let food = Uuid::new_v4();
+        let any_amount = || {
+            RuleConditions::new()
+                .with_amount_range(Some(0), None)
+                .unwrap()
+        };
+        let rules = [
+            rule(
+                0,
+                any_amount(),
+                RuleActions::new()
+                    .with_category(coffee)
+                    .with_rename(ExpenseName::new("Coffee").unwrap())
+                    .with_tags(vec![Tag::new("drinks").unwrap()]),
+            ),
+            rule(
+                1,
+                any_amount(),
+                RuleActions::new()
+                    .with_category(food)
+                    .with_rename(ExpenseName::new("Food").unwrap())
+                    .with_tags(vec![Tag::new("daily").unwrap()]),
+            ),
+            rule(
+                2,
+                RuleConditions::new().with_kind(TransactionKind::Income),
+                RuleActions::new().with_tags(vec![Tag::new("income").unwrap()]),
+            ),
+        ];
+        let req = CreateExpenseRequest::new("STARBUCKS")
+            .unwrap()
+            .with_tags(vec![Tag::new("paris").unwrap()]);
+
+        let (classified, applied) =
+            Classification::from(&req).apply(&rules, &RuleSubject::from(&req));
+
+        assert_eq!(classified.name().to_string(), "Coffee");
+        assert_eq!(classified.category_id(), Some(&coffee));
+        let tags: Vec<&str> = classified.tags().iter().map(Tag::as_str).collect();
+        assert_eq!(tags, ["daily", "drinks", "paris"]);
+        assert_eq!(applied, [*rules[0].id(), *rules[1].id()]);
+
+        // A category given explicitly is kept.
+        let req = req.with_category(food);
+        let (classified, _) = Classification::from(&req).apply(&rules, &RuleSubject::from(&req));
+        assert_eq!(classified.category_id(), Some(&food));
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::finance::policy::PolicyError;
+
+/// A payment between two ledger members that settles (part of) a debt.
+///
+/// Settlements are recorded apart from expenses: they move money between members without being
+/// spending, so they only ever affect balances.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Settlement {
+    id: Uuid,
+    ledger_id: Uuid,
+    from: Uuid,
+    to: Uuid,
+    amount: i64,
+    recorded_at: DateTime<Utc>,
+}
+
+impl Settlement {
+    pub fn new(
+        id: Uuid,
+        ledger_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+        amount: i64,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            ledger_id,
+            from,
+            to,
+            amount,
+            recorded_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn ledger_id(&self) -> &Uuid {
+        &self.ledger_id
+    }
+
+    /// The member who paid.
+    pub fn from(&self) -> &Uuid {
+        &self.from
+    }
+
+    /// The member who was paid.
+    pub fn to(&self) -> &Uuid {
+        &self.to
+    }
+
+    /// The amount paid, in minor units.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn recorded_at(&self) -> &DateTime<Utc> {
+        &self.recorded_at
+    }
+}
+
+/// The fields required by the domain to record a [Settlement].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecordSettlementRequest {
+    ledger_id: Uuid,
+    from: Uuid,
+    to: Uuid,
+    amount: i64,
+    recorded_by: Uuid,
+}
+
+impl RecordSettlementRequest {
+    pub fn new(
+        ledger_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+        amount: i64,
+        recorded_by: Uuid,
+    ) -> Result<Self, SettlementError> {
+        if amount <= 0 {
+            return Err(SettlementError::InvalidAmount { amount });
+        }
+        if from == to {
+            return Err(SettlementError::SameMember { user_id: from });
+        }
+        Ok(Self {
+            ledger_id,
+            from,
+            to,
+            amount,
+            recorded_by,
+        })
+    }
+
+    pub fn ledger_id(&self) -> &Uuid {
+        &self.ledger_id
+    }
+
+    pub fn from(&self) -> &Uuid {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Uuid {
+        &self.to
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    pub fn recorded_by(&self) -> &Uuid {
+        &self.recorded_by
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SettlementError {
+    #[error("settlement amount must be positive, got {amount}")]
+    InvalidAmount { amount: i64 },
+    #[error("user {user_id} cannot settle with themselves")]
+    SameMember { user_id: Uuid },
+    #[error("user {user_id} is not a member of ledger {ledger_id}")]
+    NotLedgerMember { ledger_id: Uuid, user_id: Uuid },
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
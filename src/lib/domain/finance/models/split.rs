@@ -0,0 +1,380 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Percentages are expressed in basis points, so a full split is 100.00%.
+pub const FULL_PERCENTAGE_BASIS_POINTS: i64 = 10_000;
+
+/// How the amount of an expense is divided among its participants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SplitMethod {
+    /// Every participant owes the same amount.
+    Equal,
+    /// Participants owe proportionally to an integer number of shares.
+    Shares,
+    /// Participants owe a percentage of the total, in basis points.
+    Percentage,
+    /// Participants owe exact amounts, in minor units.
+    Exact,
+}
+
+impl SplitMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitMethod::Equal => "equal",
+            SplitMethod::Shares => "shares",
+            SplitMethod::Percentage => "percentage",
+            SplitMethod::Exact => "exact",
+        }
+    }
+}
+
+impl Display for SplitMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SplitMethod {
+    type Err = SplitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "equal" => Ok(SplitMethod::Equal),
+            "shares" => Ok(SplitMethod::Shares),
+            "percentage" => Ok(SplitMethod::Percentage),
+            "exact" => Ok(SplitMethod::Exact),
+            _ => Err(SplitError::UnknownMethod(s.to_string())),
+        }
+    }
+}
+
+/// A participant of a split as requested, before the amounts are resolved.
+///
+/// The meaning of `weight` depends on the [SplitMethod]: it is ignored for
+/// [SplitMethod::Equal], a number of shares for [SplitMethod::Shares], basis points for
+/// [SplitMethod::Percentage] and minor units for [SplitMethod::Exact].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SplitParticipant {
+    user_id: Uuid,
+    weight: i64,
+}
+
+impl SplitParticipant {
+    pub fn new(user_id: Uuid, weight: i64) -> Self {
+        Self { user_id, weight }
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn weight(&self) -> i64 {
+        self.weight
+    }
+}
+
+/// The amount, in minor units, a participant owes for an expense.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpenseShare {
+    user_id: Uuid,
+    amount: i64,
+}
+
+impl ExpenseShare {
+    pub fn new(user_id: Uuid, amount: i64) -> Self {
+        Self { user_id, amount }
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+}
+
+/// A validated split of an expense paid by one member among several participants.
+///
+/// The resolved shares always sum to the total of the expense; rounding remainders are handed
+/// out one minor unit at a time to the participants in the order they were given.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpenseSplit {
+    paid_by: Uuid,
+    method: SplitMethod,
+    shares: Vec<ExpenseShare>,
+}
+
+impl ExpenseSplit {
+    /// Resolve how `total` is divided among `participants` according to `method`.
+    ///
+    /// # Errors
+    ///
+    /// - [SplitError::NoParticipants] if `participants` is empty.
+    /// - [SplitError::DuplicateParticipant] if a user appears more than once.
+    /// - [SplitError::InvalidWeight] if a share, percentage or amount is negative, or a share
+    ///   count is zero.
+    /// - [SplitError::WeightsOverflow] if percentages or amounts add up to more than an `i64`
+    ///   holds.
+    /// - [SplitError::PercentagesDoNotSum] if percentages do not add up to 100%.
+    /// - [SplitError::AmountsDoNotSum] if exact amounts do not add up to `total`.
+    pub fn new(
+        paid_by: Uuid,
+        method: SplitMethod,
+        participants: &[SplitParticipant],
+        total: i64,
+    ) -> Result<Self, SplitError> {
+        if participants.is_empty() {
+            return Err(SplitError::NoParticipants);
+        }
+        let mut seen = HashSet::with_capacity(participants.len());
+        for participant in participants {
+            if !seen.insert(participant.user_id) {
+                return Err(SplitError::DuplicateParticipant {
+                    user_id: participant.user_id,
+                });
+            }
+            let invalid = match method {
+                SplitMethod::Equal => false,
+                SplitMethod::Shares => participant.weight <= 0,
+                SplitMethod::Percentage | SplitMethod::Exact => participant.weight < 0,
+            };
+            if invalid {
+                return Err(SplitError::InvalidWeight {
+                    user_id: participant.user_id,
+                    weight: participant.weight,
+                });
+            }
+        }
+
+        let amounts = match method {
+            SplitMethod::Equal => allocate(total, &vec![1; participants.len()]),
+            SplitMethod::Shares => allocate(total, &weights(participants)),
+            SplitMethod::Percentage => {
+                let sum = weight_sum(participants)?;
+                if sum != FULL_PERCENTAGE_BASIS_POINTS {
+                    return Err(SplitError::PercentagesDoNotSum { basis_points: sum });
+                }
+                allocate(total, &weights(participants))
+            }
+            SplitMethod::Exact => {
+                let sum = weight_sum(participants)?;
+                if sum != total {
+                    return Err(SplitError::AmountsDoNotSum {
+                        expected: total,
+                        actual: sum,
+                    });
+                }
+                weights(participants)
+            }
+        };
+
+        let shares = participants
+            .iter()
+            .zip(amounts)
+            .map(|(participant, amount)| ExpenseShare::new(participant.user_id, amount))
+            .collect();
+        Ok(Self {
+            paid_by,
+            method,
+            shares,
+        })
+    }
+
+    /// Rebuild a split from shares that were already resolved and persisted.
+    pub fn from_shares(paid_by: Uuid, method: SplitMethod, shares: Vec<ExpenseShare>) -> Self {
+        Self {
+            paid_by,
+            method,
+            shares,
+        }
+    }
+
+    pub fn paid_by(&self) -> &Uuid {
+        &self.paid_by
+    }
+
+    pub fn method(&self) -> SplitMethod {
+        self.method
+    }
+
+    pub fn shares(&self) -> &[ExpenseShare] {
+        &self.shares
+    }
+
+    /// The sum of all shares.
+    pub fn total(&self) -> i64 {
+        self.shares.iter().map(ExpenseShare::amount).sum()
+    }
+}
+
+fn weights(participants: &[SplitParticipant]) -> Vec<i64> {
+    participants.iter().map(|p| p.weight).collect()
+}
+
+/// The sum of the weights of `participants`, unless it overflows.
+fn weight_sum(participants: &[SplitParticipant]) -> Result<i64, SplitError> {
+    participants
+        .iter()
+        .try_fold(0_i64, |sum, p| sum.checked_add(p.weight))
+        .ok_or(SplitError::WeightsOverflow)
+}
+
+/// Divide `total` proportionally to `weights`, handing the rounding remainder out one minor unit
+/// at a time from the first weight onwards so that the result always sums to `total`.
+fn allocate(total: i64, weights: &[i64]) -> Vec<i64> {
+    let weight_sum: i128 = weights.iter().map(|&w| w as i128).sum();
+    if weight_sum == 0 {
+        return vec![0; weights.len()];
+    }
+    let mut amounts: Vec<i64> = weights
+        .iter()
+        .map(|&w| (total as i128 * w as i128 / weight_sum) as i64)
+        .collect();
+    let mut remainder = total - amounts.iter().sum::<i64>();
+    let step = remainder.signum();
+    let mut index = 0;
+    while remainder != 0 {
+        if weights[index % weights.len()] > 0 {
+            amounts[index % weights.len()] += step;
+            remainder -= step;
+        }
+        index += 1;
+    }
+    amounts
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SplitError {
+    #[error("unknown split method {0}")]
+    UnknownMethod(String),
+    #[error("a split needs at least one participant")]
+    NoParticipants,
+    #[error("user {user_id} appears more than once in the split")]
+    DuplicateParticipant { user_id: Uuid },
+    #[error("invalid split value {weight} for user {user_id}")]
+    InvalidWeight { user_id: Uuid, weight: i64 },
+    #[error("split values add up to more than {}", i64::MAX)]
+    WeightsOverflow,
+    #[error("split percentages add up to {basis_points} basis points instead of 10000")]
+    PercentagesDoNotSum { basis_points: i64 },
+    #[error("split amounts add up to {actual} instead of the expense total {expected}")]
+    AmountsDoNotSum { expected: i64, actual: i64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participants(weights: &[i64]) -> Vec<SplitParticipant> {
+        weights
+            .iter()
+            .map(|&w| SplitParticipant::new(Uuid::new_v4(), w))
+            .collect()
+    }
+
+    fn amounts(split: &ExpenseSplit) -> Vec<i64> {
+        split.shares().iter().map(ExpenseShare::amount).collect()
+    }
+
+    #[test]
+    fn test_equal_split_distributes_remainder() {
+        let split = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Equal,
+            &participants(&[0, 0, 0]),
+            1000,
+        )
+        .unwrap();
+        assert_eq!(amounts(&split), vec![334, 333, 333]);
+        assert_eq!(split.total(), 1000);
+    }
+
+    #[test]
+    fn test_shares_split_is_proportional() {
+        let split = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Shares,
+            &participants(&[2, 1, 1]),
+            1001,
+        )
+        .unwrap();
+        assert_eq!(amounts(&split), vec![501, 250, 250]);
+    }
+
+    #[test]
+    fn test_percentage_split_must_sum_to_hundred() {
+        let result = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Percentage,
+            &participants(&[5000, 4000]),
+            1000,
+        );
+        assert_eq!(
+            result,
+            Err(SplitError::PercentagesDoNotSum { basis_points: 9000 })
+        );
+
+        let split = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Percentage,
+            &participants(&[3333, 3333, 3334]),
+            100,
+        )
+        .unwrap();
+        assert_eq!(split.total(), 100);
+    }
+
+    #[test]
+    fn test_exact_split_must_sum_to_total() {
+        let result = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Exact,
+            &participants(&[400, 500]),
+            1000,
+        );
+        assert_eq!(
+            result,
+            Err(SplitError::AmountsDoNotSum {
+                expected: 1000,
+                actual: 900
+            })
+        );
+    }
+
+    #[test]
+    fn test_overflowing_weights_rejected() {
+        // Wrapped around, each set of weights would add up to exactly what the method expects.
+        for (method, last) in [
+            (SplitMethod::Percentage, 10_002),
+            (SplitMethod::Exact, 1_002),
+        ] {
+            let result = ExpenseSplit::new(
+                Uuid::new_v4(),
+                method,
+                &participants(&[i64::MAX, i64::MAX, last]),
+                1_000,
+            );
+            assert_eq!(result, Err(SplitError::WeightsOverflow), "{method:?} split");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_participant_rejected() {
+        let user_id = Uuid::new_v4();
+        let result = ExpenseSplit::new(
+            Uuid::new_v4(),
+            SplitMethod::Equal,
+            &[
+                SplitParticipant::new(user_id, 0),
+                SplitParticipant::new(user_id, 0),
+            ],
+            1000,
+        );
+        assert_eq!(result, Err(SplitError::DuplicateParticipant { user_id }));
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+
+/// A free-form label on an [Expense](super::expense::Expense), lowercased so that `Trip` and
+/// `trip` are the same tag.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag(String);
+
+#[derive(Clone, Debug, Error)]
+#[error("tag cannot be empty")]
+pub struct TagEmptyError;
+
+impl Tag {
+    pub fn new(raw: &str) -> Result<Self, TagEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            Err(TagEmptyError)
+        } else {
+            Ok(Self(trimmed.to_lowercase()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_is_normalized() {
+        assert_eq!(Tag::new("  Summer Trip ").unwrap().as_str(), "summer trip");
+        assert!(Tag::new("   ").is_err());
+    }
+}
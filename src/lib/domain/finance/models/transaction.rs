@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// What a recorded movement of money is. Every kind is stored and listed like an
+/// [Expense](super::expense::Expense), but aggregates treat each differently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransactionKind {
+    /// Money spent. The only kind that counts towards budgets and can be split.
+    #[default]
+    Expense,
+    /// Money received.
+    Income,
+    /// Money moved between the user's own accounts, which leaves net worth unchanged.
+    Transfer,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Expense => "expense",
+            TransactionKind::Income => "income",
+            TransactionKind::Transfer => "transfer",
+        }
+    }
+
+    /// The effect of a transaction of this kind and `amount` on net cash flow: expenses flow
+    /// out, income flows in and transfers cancel out.
+    pub fn cash_flow(&self, amount: i64) -> i64 {
+        match self {
+            TransactionKind::Expense => -amount,
+            TransactionKind::Income => amount,
+            TransactionKind::Transfer => 0,
+        }
+    }
+}
+
+impl Display for TransactionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("unknown transaction kind {0}")]
+pub struct UnknownTransactionKindError(pub String);
+
+impl FromStr for TransactionKind {
+    type Err = UnknownTransactionKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "expense" => Ok(TransactionKind::Expense),
+            "income" => Ok(TransactionKind::Income),
+            "transfer" => Ok(TransactionKind::Transfer),
+            _ => Err(UnknownTransactionKindError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cash_flow_by_kind() {
+        let flows: i64 = [
+            (TransactionKind::Income, 250000),
+            (TransactionKind::Expense, 120000),
+            (TransactionKind::Transfer, 50000),
+        ]
+        .iter()
+        .map(|(kind, amount)| kind.cash_flow(*amount))
+        .sum();
+
+        assert_eq!(flows, 130000);
+    }
+}
@@ -0,0 +1,561 @@
+/*!
+    Module `policy` decides whether a [Principal] may perform an [Action] on a [Resource].
+
+    Decisions are pure: the caller resolves the principal's [LedgerRole] in the resource's ledger
+    beforehand, so the rules below can be read, and tested, as plain tables.
+*/
+
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::auth::models::principal::{Principal, Scope};
+
+use super::models::ledger::LedgerRole;
+
+/// Something a [Principal] attempts to do with a [Resource].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    View,
+    Create,
+    Update,
+    Delete,
+    /// Invite new members into a ledger.
+    Invite,
+    /// Record settlement payments between ledger members.
+    Settle,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::View => "view",
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Invite => "invite",
+            Action::Settle => "settle",
+        }
+    }
+
+    /// The [Scope] a [Principal] needs to attempt the action at all.
+    pub fn required_scope(&self) -> Scope {
+        match self {
+            Action::View => Scope::Read,
+            _ => Scope::ReadWrite,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kinds of [Resource] the policy knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResourceKind {
+    Expense,
+    Category,
+    Budget,
+    RecurringExpense,
+    Account,
+    Rule,
+    Ledger,
+}
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Expense => "expense",
+            ResourceKind::Category => "category",
+            ResourceKind::Budget => "budget",
+            ResourceKind::RecurringExpense => "recurring expense",
+            ResourceKind::Account => "account",
+            ResourceKind::Rule => "rule",
+            ResourceKind::Ledger => "ledger",
+        }
+    }
+}
+
+impl Display for ResourceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The target of an [Action], reduced to what the policy needs to know about it: its kind, the
+/// ledger, if any, whose members share it and, for existing personal resources, their owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Resource {
+    kind: ResourceKind,
+    ledger_id: Option<Uuid>,
+    owner_id: Option<Uuid>,
+}
+
+impl Resource {
+    /// An expense, shared in `ledger_id` or personal when `None`.
+    pub fn expense(ledger_id: Option<Uuid>) -> Self {
+        Self {
+            kind: ResourceKind::Expense,
+            ledger_id,
+            owner_id: None,
+        }
+    }
+
+    /// A category, shared in `ledger_id` or owned by the caller when `None`.
+    pub fn category(ledger_id: Option<Uuid>) -> Self {
+        Self {
+            kind: ResourceKind::Category,
+            ledger_id,
+            owner_id: None,
+        }
+    }
+
+    /// A budget, shared in `ledger_id` or owned by the caller when `None`.
+    pub fn budget(ledger_id: Option<Uuid>) -> Self {
+        Self {
+            kind: ResourceKind::Budget,
+            ledger_id,
+            owner_id: None,
+        }
+    }
+
+    /// A recurring expense, shared in `ledger_id` or owned by the caller when `None`.
+    pub fn recurring_expense(ledger_id: Option<Uuid>) -> Self {
+        Self {
+            kind: ResourceKind::RecurringExpense,
+            ledger_id,
+            owner_id: None,
+        }
+    }
+
+    /// An account, always owned by the caller: accounts are never shared in a ledger.
+    pub fn account() -> Self {
+        Self {
+            kind: ResourceKind::Account,
+            ledger_id: None,
+            owner_id: None,
+        }
+    }
+
+    /// A categorization rule, always owned by the caller since it only files personal expenses.
+    pub fn rule() -> Self {
+        Self {
+            kind: ResourceKind::Rule,
+            ledger_id: None,
+            owner_id: None,
+        }
+    }
+
+    /// An existing ledger.
+    pub fn ledger(ledger_id: Uuid) -> Self {
+        Self {
+            kind: ResourceKind::Ledger,
+            ledger_id: Some(ledger_id),
+            owner_id: None,
+        }
+    }
+
+    /// A ledger that is about to be created.
+    pub fn new_ledger() -> Self {
+        Self {
+            kind: ResourceKind::Ledger,
+            ledger_id: None,
+            owner_id: None,
+        }
+    }
+
+    /// Restrict an existing personal resource to the user who owns it. Ignored for shared
+    /// resources, which are governed by ledger roles instead.
+    pub fn owned_by(mut self, owner_id: Uuid) -> Self {
+        self.owner_id = Some(owner_id);
+        self
+    }
+
+    pub fn kind(&self) -> ResourceKind {
+        self.kind
+    }
+
+    /// The ledger whose members share the resource, or `None` for a personal resource.
+    pub fn ledger_id(&self) -> Option<&Uuid> {
+        self.ledger_id.as_ref()
+    }
+}
+
+/// The least [LedgerRole] a member needs to perform `action` on a `kind` of resource inside a
+/// ledger, or `None` if no role allows it.
+pub fn required_role(kind: ResourceKind, action: Action) -> Option<LedgerRole> {
+    use Action::*;
+    use ResourceKind::*;
+
+    match (kind, action) {
+        (_, View) => Some(LedgerRole::Viewer),
+        (Expense | RecurringExpense, Create | Update | Delete) => Some(LedgerRole::Editor),
+        (Category | Budget, Create) => Some(LedgerRole::Editor),
+        (Category | Budget, Update | Delete) => Some(LedgerRole::Owner),
+        (Ledger, Settle) => Some(LedgerRole::Editor),
+        (Ledger, Update | Delete | Invite) => Some(LedgerRole::Owner),
+        (Expense | Category | Budget | RecurringExpense, Invite | Settle)
+        | (Account | Rule, Create | Update | Delete | Invite | Settle)
+        | (Ledger, Create) => None,
+    }
+}
+
+/// Decide whether `principal` may perform `action` on `resource`.
+///
+/// `role` is the principal's [LedgerRole] in the resource's ledger, or `None` if they are not a
+/// member or the resource is personal.
+///
+/// # Errors
+///
+/// - [PolicyError::Unauthenticated] if an anonymous caller targets anything but a personal
+///   expense, which stay open to anonymous callers for backwards compatibility.
+/// - [PolicyError::LedgerNotFound] if the principal is not a member of the resource's ledger, so
+///   that ledger ids cannot be probed.
+/// - [PolicyError::NotOwner] if the principal does not own the personal resource, for the same
+///   reason.
+/// - [PolicyError::Denied] if the principal's [Scope] or [LedgerRole] falls short, or the action
+///   makes no sense for the resource.
+pub fn authorize(
+    principal: Option<&Principal>,
+    role: Option<LedgerRole>,
+    action: Action,
+    resource: &Resource,
+) -> Result<(), PolicyError> {
+    let deny = |reason| Denial {
+        action,
+        resource: *resource,
+        reason,
+    };
+
+    let Some(principal) = principal else {
+        return match (resource.kind, resource.ledger_id) {
+            (ResourceKind::Expense, None) => Ok(()),
+            _ => Err(PolicyError::Unauthenticated {
+                action,
+                resource: resource.kind,
+            }),
+        };
+    };
+
+    let required_scope = action.required_scope();
+    if principal.scope() < required_scope {
+        return Err(deny(DenialReason::InsufficientScope {
+            required: required_scope,
+            actual: principal.scope(),
+        })
+        .into());
+    }
+
+    let Some(ledger_id) = resource.ledger_id else {
+        if resource
+            .owner_id
+            .is_some_and(|owner| owner != *principal.user_id())
+        {
+            return Err(PolicyError::NotOwner {
+                resource: resource.kind,
+            });
+        }
+        return match (resource.kind, action) {
+            (ResourceKind::Ledger, Action::Create) => Ok(()),
+            (ResourceKind::Ledger, _) | (_, Action::Invite | Action::Settle) => {
+                Err(deny(DenialReason::NotPermitted).into())
+            }
+            _ => Ok(()),
+        };
+    };
+
+    let role = role.ok_or(PolicyError::LedgerNotFound { ledger_id })?;
+    match required_role(resource.kind, action) {
+        None => Err(deny(DenialReason::NotPermitted).into()),
+        Some(required) if role < required => Err(deny(DenialReason::InsufficientRole {
+            required,
+            actual: role,
+        })
+        .into()),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Why a [Denial] was issued.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DenialReason {
+    /// The principal's role in the ledger is too low.
+    InsufficientRole {
+        required: LedgerRole,
+        actual: LedgerRole,
+    },
+    /// The principal authenticated with an API key whose scope is too narrow.
+    InsufficientScope { required: Scope, actual: Scope },
+    /// Nobody may perform the action on this kind of resource.
+    NotPermitted,
+}
+
+/// A refusal to let an identified principal perform an [Action] on a [Resource].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Denial {
+    action: Action,
+    resource: Resource,
+    reason: DenialReason,
+}
+
+impl Denial {
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn reason(&self) -> DenialReason {
+        self.reason
+    }
+}
+
+impl Display for Denial {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            DenialReason::InsufficientRole { required, .. } => write!(f, "{required} role")?,
+            DenialReason::InsufficientScope { required, .. } => write!(f, "{required} scope")?,
+            DenialReason::NotPermitted => {
+                return write!(f, "cannot {} {}", self.action.as_str(), self.resource.kind);
+            }
+        }
+        write!(f, " required to {} {}", self.action, self.resource.kind)?;
+        if let Some(ledger_id) = self.resource.ledger_id {
+            write!(f, " in ledger {ledger_id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Denial {}
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("authentication required to {action} {resource}")]
+    Unauthenticated {
+        action: Action,
+        resource: ResourceKind,
+    },
+    #[error("ledger {ledger_id} not found")]
+    LedgerNotFound { ledger_id: Uuid },
+    #[error("{resource} not found")]
+    NotOwner { resource: ResourceKind },
+    #[error(transparent)]
+    Denied(#[from] Denial),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIONS: [Action; 6] = [
+        Action::View,
+        Action::Create,
+        Action::Update,
+        Action::Delete,
+        Action::Invite,
+        Action::Settle,
+    ];
+
+    /// Decide `action` on `kind` inside a ledger for a full-access member holding `role`, or a
+    /// non-member when `None`.
+    fn decide(kind: ResourceKind, action: Action, role: Option<LedgerRole>) -> &'static str {
+        let resource = Resource {
+            kind,
+            ledger_id: Some(Uuid::new_v4()),
+            owner_id: None,
+        };
+        match authorize(
+            Some(&Principal::new(Uuid::new_v4())),
+            role,
+            action,
+            &resource,
+        ) {
+            Ok(()) => "allow",
+            Err(PolicyError::LedgerNotFound { .. }) => "hidden",
+            Err(PolicyError::Denied(_)) => "deny",
+            Err(e) => panic!("unexpected error {e:?}"),
+        }
+    }
+
+    fn assert_table(kind: ResourceKind, table: [[&str; 6]; 3]) {
+        let roles = [LedgerRole::Viewer, LedgerRole::Editor, LedgerRole::Owner];
+        for (role, row) in roles.into_iter().zip(table) {
+            for (action, expected) in ACTIONS.into_iter().zip(row) {
+                assert_eq!(
+                    decide(kind, action, Some(role)),
+                    expected,
+                    "{role} attempting to {action} {kind}"
+                );
+            }
+        }
+        for action in ACTIONS {
+            assert_eq!(decide(kind, action, None), "hidden", "non-member {action}");
+        }
+    }
+
+    #[test]
+    fn test_expense_policy() {
+        assert_table(
+            ResourceKind::Expense,
+            [
+                // view, create, update, delete, invite, settle
+                ["allow", "deny", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_category_policy() {
+        assert_table(
+            ResourceKind::Category,
+            [
+                ["allow", "deny", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_budget_policy() {
+        assert_table(
+            ResourceKind::Budget,
+            [
+                ["allow", "deny", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_recurring_expense_policy() {
+        assert_table(
+            ResourceKind::RecurringExpense,
+            [
+                ["allow", "deny", "deny", "deny", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+                ["allow", "allow", "allow", "allow", "deny", "deny"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_ledger_policy() {
+        assert_table(
+            ResourceKind::Ledger,
+            [
+                ["allow", "deny", "deny", "deny", "deny", "deny"],
+                ["allow", "deny", "deny", "deny", "deny", "allow"],
+                ["allow", "deny", "allow", "allow", "allow", "allow"],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_anonymous_callers_only_reach_personal_expenses() {
+        for action in [Action::View, Action::Create] {
+            assert!(authorize(None, None, action, &Resource::expense(None)).is_ok());
+        }
+        for resource in [
+            Resource::expense(Some(Uuid::new_v4())),
+            Resource::category(None),
+            Resource::recurring_expense(None),
+            Resource::account(),
+            Resource::new_ledger(),
+        ] {
+            assert!(matches!(
+                authorize(None, None, Action::Create, &resource),
+                Err(PolicyError::Unauthenticated { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_read_scope_cannot_write() {
+        let principal = Principal::from_api_key(Uuid::new_v4(), Uuid::new_v4(), Scope::Read);
+        let resource = Resource::expense(Some(Uuid::new_v4()));
+
+        assert!(
+            authorize(
+                Some(&principal),
+                Some(LedgerRole::Owner),
+                Action::View,
+                &resource
+            )
+            .is_ok()
+        );
+        let denied = authorize(
+            Some(&principal),
+            Some(LedgerRole::Owner),
+            Action::Create,
+            &resource,
+        );
+        assert!(matches!(
+            denied,
+            Err(PolicyError::Denied(Denial {
+                reason: DenialReason::InsufficientScope { .. },
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_personal_resources() {
+        let principal = Principal::new(Uuid::new_v4());
+        let allowed = [
+            (Action::Create, Resource::new_ledger()),
+            (Action::Create, Resource::category(None)),
+            (Action::Create, Resource::account()),
+            (Action::Delete, Resource::expense(None)),
+        ];
+        for (action, resource) in allowed {
+            assert!(authorize(Some(&principal), None, action, &resource).is_ok());
+        }
+        assert!(matches!(
+            authorize(
+                Some(&principal),
+                None,
+                Action::Settle,
+                &Resource::expense(None)
+            ),
+            Err(PolicyError::Denied(_))
+        ));
+    }
+
+    #[test]
+    fn test_personal_resources_are_hidden_from_other_users() {
+        let owner = Uuid::new_v4();
+        let resource = Resource::budget(None).owned_by(owner);
+
+        assert!(
+            authorize(
+                Some(&Principal::new(owner)),
+                None,
+                Action::Update,
+                &resource
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            authorize(
+                Some(&Principal::new(Uuid::new_v4())),
+                None,
+                Action::View,
+                &resource
+            ),
+            Err(PolicyError::NotOwner { .. })
+        ));
+    }
+}
@@ -1,31 +1,541 @@
 use thiserror::Error;
 
-#[allow(unused_imports)] // Used in comment
-use super::models::expense::ExpenseName;
+use std::collections::HashSet;
 
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::stream::BoxStream;
+use uuid::Uuid;
+
+use crate::domain::auth::models::principal::Principal;
+
+use super::models::account::{
+    Account, AccountBalance, AccountError, BalancePoint, CreateAccountRequest, Currency,
+};
+use super::models::attachment::{
+    Attachment, AttachmentContentType, AttachmentError, AttachmentFile, AttachmentVariant,
+    CreateAttachmentRequest, ThumbnailStatus,
+};
+use super::models::balance::{Debt, LedgerBalances};
+use super::models::budget::{
+    Budget, BudgetAlert, BudgetError, BudgetStatus, BudgetThreshold, CreateBudgetRequest,
+    UpdateBudgetRequest,
+};
+use super::models::category::{Category, CategoryError, CreateCategoryRequest};
+use super::models::duplicate::{
+    DuplicateCluster, DuplicateError, DuplicateMerge, DuplicateSettings, MergeDuplicatesRequest,
+};
+use super::models::exchange::{ExchangeRate, ExchangeRateError};
 use super::models::expense::{
-    CreateExpenseError, CreateExpenseRequest, Expense, ListExpensesRequest,
+    CreateExpenseError, CreateExpenseRequest, CreatedExpense, Expense, ListExpensesError,
+    ListExpensesRequest,
 };
+use super::models::idempotency::{
+    ClaimIdempotencyKeyRequest, IdempotencyClaim, IdempotencyError, IdempotencyKey,
+    RequestFingerprint, StoredResponse,
+};
+use super::models::import::{ImportError, ImportReport, ImportRequest};
+use super::models::ledger::{
+    AcceptInvitationRequest, CreateInvitationRequest, CreateLedgerRequest, InvitationError, Ledger,
+    LedgerError, LedgerInvitation, LedgerMember,
+};
+use super::models::quick::{QuickExpenseOutcome, QuickExpenseRequest};
+use super::models::recurring::{
+    CreateRecurringExpenseRequest, Occurrence, RecurringExpense, RecurringExpenseError,
+};
+use super::models::report::{
+    ReportError, ReportScope, Series, SummaryBucket, SummaryRequest, TimeseriesRequest,
+};
+use super::models::rule::{CreateRuleRequest, Rule, RuleApplicationReport, RuleChange, RuleError};
+use super::models::settlement::{RecordSettlementRequest, Settlement, SettlementError};
 
 /// `FinanceService` is the public API for the finance domain.
 ///
 /// External modules must conform to this contract – the domain is not concerned with the
 /// implementation details or underlying technology of any external code.
+///
+/// Methods acting on behalf of a caller take its [Principal] and check it against the
+/// [policy](super::policy) before doing anything, failing with a [PolicyError] variant otherwise.
+///
+/// [PolicyError]: super::policy::PolicyError
 pub trait FinanceService: Clone + Send + Sync + 'static {
-    /// Asynchronously create a new [Author].
+    /// Asynchronously create a new [Expense], reporting the existing expenses it looks like
+    /// under the principal's [DuplicateSettings].
     ///
     /// # Errors
     ///
-    /// - [CreateExpenseError::Duplicate] if an [Expense] with the same [ExpenseName] already exists.
+    /// - [CreateExpenseError::Duplicate] if the [Expense] looks like an existing one and the
+    ///   principal blocks duplicates.
     fn create_expense(
         &self,
+        principal: Option<&Principal>,
         req: &CreateExpenseRequest,
-    ) -> impl Future<Output = Result<Expense, CreateExpenseError>> + Send;
+    ) -> impl Future<Output = Result<CreatedExpense, CreateExpenseError>> + Send;
+
+    /// Read a one-line description of a personal expense, such as
+    /// `coffee 4.50 EUR yesterday #food @cash`, looking up the category and account it names
+    /// among the principal's, then create the expense through
+    /// [FinanceService::create_expense] unless the request is a dry run or an issue blocks it.
+    ///
+    /// # Errors
+    ///
+    /// - [CreateExpenseError::Policy] if the principal may not create expenses.
+    /// - Propagates any [CreateExpenseError] returned by [FinanceService::create_expense].
+    fn quick_add_expense(
+        &self,
+        principal: &Principal,
+        req: &QuickExpenseRequest,
+    ) -> impl Future<Output = Result<QuickExpenseOutcome, CreateExpenseError>> + Send;
 
     fn list_expenses(
         &self,
+        principal: &Principal,
+        req: &ListExpensesRequest,
+    ) -> impl Future<Output = Result<Vec<Expense>, ListExpensesError>> + Send;
+
+    /// Stream every [Expense] matching the filters of `req`, ignoring its pagination, for
+    /// exports too large to be listed at once.
+    fn export_expenses(
+        &self,
+        principal: &Principal,
         req: &ListExpensesRequest,
-    ) -> impl Future<Output = Result<Vec<Expense>, anyhow::Error>> + Send;
+    ) -> impl Future<Output = Result<ExpenseStream, ListExpensesError>> + Send;
+
+    /// Attach a file, such as the photo of a receipt, to an [Expense] the principal may update.
+    ///
+    /// # Errors
+    ///
+    /// - [AttachmentError::ExpenseNotFound] if the [Expense] does not exist, or is a personal
+    ///   expense of someone else.
+    /// - [AttachmentError::Policy] if the principal may not update the ledger's expenses.
+    fn add_attachment(
+        &self,
+        principal: &Principal,
+        req: &CreateAttachmentRequest,
+    ) -> impl Future<Output = Result<Attachment, AttachmentError>> + Send;
+
+    /// Retrieve an [Attachment] of an [Expense] the principal may view, with the content of
+    /// `variant`.
+    ///
+    /// # Errors
+    ///
+    /// - [AttachmentError::ExpenseNotFound] if the [Expense] does not exist, or is a personal
+    ///   expense of someone else.
+    /// - [AttachmentError::NotFound] if the [Expense] has no such [Attachment], or its thumbnail
+    ///   was asked for and is not ready.
+    /// - [AttachmentError::Policy] if the principal may not view the ledger's expenses.
+    fn get_attachment(
+        &self,
+        principal: &Principal,
+        expense_id: &Uuid,
+        attachment_id: &Uuid,
+        variant: AttachmentVariant,
+    ) -> impl Future<Output = Result<AttachmentFile, AttachmentError>> + Send;
+
+    /// Generate the thumbnails of up to `limit` attachments whose thumbnail is pending, oldest
+    /// first, returning how many were processed.
+    ///
+    /// An attachment whose thumbnail cannot be rendered or stored is marked as never getting one,
+    /// so that it is not retried and does not hold back the attachments after it.
+    fn generate_thumbnails(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<usize, AttachmentError>> + Send;
+
+    /// Asynchronously create a new [Ledger] owned by the requesting user.
+    fn create_ledger(
+        &self,
+        principal: &Principal,
+        req: &CreateLedgerRequest,
+    ) -> impl Future<Output = Result<Ledger, LedgerError>> + Send;
+
+    /// Retrieve a [Ledger] by its id.
+    ///
+    /// # Errors
+    ///
+    /// - [LedgerError::NotFound] if no [Ledger] with the given id exists.
+    fn get_ledger(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Ledger, LedgerError>> + Send;
+
+    /// List the [Ledger]s the user identified by `user_id` is a member of.
+    fn list_ledgers(
+        &self,
+        user_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Ledger>, LedgerError>> + Send;
+
+    /// List the members of a [Ledger].
+    fn list_ledger_members(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<LedgerMember>, LedgerError>> + Send;
+
+    /// Issue a [LedgerInvitation] token.
+    fn create_invitation(
+        &self,
+        principal: &Principal,
+        req: &CreateInvitationRequest,
+    ) -> impl Future<Output = Result<LedgerInvitation, InvitationError>> + Send;
+
+    /// Compute who owes whom within a [Ledger] from its split expenses.
+    fn ledger_balances(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> impl Future<Output = Result<LedgerBalances, LedgerError>> + Send;
+
+    /// Compute a small set of transfers that would settle every debt within a [Ledger].
+    fn settle_up(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Debt>, LedgerError>> + Send;
+
+    /// Record a [Settlement] payment between two members of a [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - [SettlementError::NotLedgerMember] if either party does not belong to the [Ledger].
+    fn record_settlement(
+        &self,
+        principal: &Principal,
+        req: &RecordSettlementRequest,
+    ) -> impl Future<Output = Result<Settlement, SettlementError>> + Send;
+
+    /// Redeem a [LedgerInvitation] token, adding the user to the [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - [InvitationError::NotFound] if the token is unknown or was already used.
+    /// - [InvitationError::Expired] if the token is past its expiry.
+    /// - [InvitationError::AlreadyMember] if the user already belongs to the [Ledger].
+    fn accept_invitation(
+        &self,
+        req: &AcceptInvitationRequest,
+    ) -> impl Future<Output = Result<LedgerMember, InvitationError>> + Send;
+
+    /// Create a [Category], personal or shared in a [Ledger].
+    fn create_category(
+        &self,
+        principal: &Principal,
+        req: &CreateCategoryRequest,
+    ) -> impl Future<Output = Result<Category, CategoryError>> + Send;
+
+    /// List the caller's personal [Category]s, or those of a [Ledger] when `ledger_id` is given.
+    fn list_categories(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<Category>, CategoryError>> + Send;
+
+    /// Set a [Budget] on a [Category]. The budget is shared or personal like its category.
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::CategoryNotFound] if the category does not exist or is not visible.
+    /// - [BudgetError::Duplicate] if the category already has a budget for the same period.
+    fn create_budget(
+        &self,
+        principal: &Principal,
+        req: &CreateBudgetRequest,
+    ) -> impl Future<Output = Result<Budget, BudgetError>> + Send;
+
+    /// Retrieve a [Budget].
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::NotFound] if no [Budget] with the given id exists.
+    fn get_budget(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Budget, BudgetError>> + Send;
+
+    /// List the caller's personal [Budget]s, or those of a [Ledger] when `ledger_id` is given.
+    fn list_budgets(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<Budget>, BudgetError>> + Send;
+
+    /// Change the amount, currency or period of a [Budget].
+    fn update_budget(
+        &self,
+        principal: &Principal,
+        req: &UpdateBudgetRequest,
+    ) -> impl Future<Output = Result<Budget, BudgetError>> + Send;
+
+    /// Delete a [Budget].
+    fn delete_budget(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<(), BudgetError>> + Send;
+
+    /// Report how much of a [Budget] was spent in the period containing `on`, in the currency of
+    /// the [Budget].
+    fn budget_status(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+        on: NaiveDate,
+    ) -> impl Future<Output = Result<BudgetStatus, BudgetError>> + Send;
+
+    /// Set up a [RecurringExpense], personal or shared in a [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::CategoryNotFound] if the category does not exist where the
+    ///   expenses will live.
+    fn create_recurring_expense(
+        &self,
+        principal: &Principal,
+        req: &CreateRecurringExpenseRequest,
+    ) -> impl Future<Output = Result<RecurringExpense, RecurringExpenseError>> + Send;
+
+    /// List the caller's personal [RecurringExpense]s, or those of a [Ledger] when `ledger_id`
+    /// is given.
+    fn list_recurring_expenses(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<RecurringExpense>, RecurringExpenseError>> + Send;
+
+    /// Stop a [RecurringExpense]. Expenses it already created are kept.
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::NotFound] if no [RecurringExpense] with the given id exists.
+    fn delete_recurring_expense(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<(), RecurringExpenseError>> + Send;
+
+    /// List the [Occurrence]s of the caller's personal [RecurringExpense]s, or those of a
+    /// [Ledger], falling within `[from, until]`, in date order.
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::RangeTooLong] if the range spans more than a year.
+    fn upcoming_occurrences(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<Occurrence>, RecurringExpenseError>> + Send;
+
+    /// Create, through [FinanceService::create_expense], an [Expense] for every occurrence of
+    /// every [RecurringExpense] due up to `today` that was not created yet, returning how many
+    /// were created.
+    ///
+    /// A recurring expense whose occurrences cannot all be created does not stop the others:
+    /// its remaining occurrences are retried on the next call.
+    ///
+    /// Safe to call repeatedly and concurrently: each occurrence is created at most once.
+    fn materialize_recurring_expenses(
+        &self,
+        today: NaiveDate,
+    ) -> impl Future<Output = Result<usize, RecurringExpenseError>> + Send;
+
+    /// Open a new [Account] owned by the caller.
+    fn create_account(
+        &self,
+        principal: &Principal,
+        req: &CreateAccountRequest,
+    ) -> impl Future<Output = Result<Account, AccountError>> + Send;
+
+    /// List the caller's [Account]s.
+    fn list_accounts(
+        &self,
+        principal: &Principal,
+    ) -> impl Future<Output = Result<Vec<Account>, AccountError>> + Send;
+
+    /// Retrieve an [Account] with its balance as of `today`, and its end-of-day balances over
+    /// `[from, until]`, in `currency` if given or else in that of the account.
+    ///
+    /// Converted balances add up each transaction converted at the rate of its own day, and the
+    /// opening balance at the rate of the day the account was opened.
+    ///
+    /// # Errors
+    ///
+    /// - [AccountError::NotFound] if no [Account] with the given id exists.
+    /// - [AccountError::RangeTooLong] if the range spans more than a year.
+    /// - [AccountError::MissingExchangeRate] if a required rate is unknown.
+    fn account_balance(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+        currency: Option<&Currency>,
+        today: NaiveDate,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> impl Future<Output = Result<AccountBalance, AccountError>> + Send;
+
+    /// Store [ExchangeRate]s, replacing any already known for the same currency and day, and
+    /// return how many were stored.
+    fn import_exchange_rates(
+        &self,
+        rates: &[ExchangeRate],
+    ) -> impl Future<Output = Result<usize, ExchangeRateError>> + Send;
+
+    /// Import transactions read from a file into one of the caller's accounts, all at once or
+    /// not at all. Rows failing validation are left out and reported.
+    ///
+    /// # Errors
+    ///
+    /// - [ImportError::AccountNotFound] if the account does not exist or belongs to someone
+    ///   else.
+    fn import_transactions(
+        &self,
+        principal: &Principal,
+        req: &ImportRequest,
+    ) -> impl Future<Output = Result<ImportReport, ImportError>> + Send;
+
+    /// Retrieve how the caller wants duplicate expenses to be detected, or the defaults if they
+    /// never said.
+    fn get_duplicate_settings(
+        &self,
+        principal: &Principal,
+    ) -> impl Future<Output = Result<DuplicateSettings, DuplicateError>> + Send;
+
+    /// Change how the caller wants duplicate expenses to be detected.
+    fn update_duplicate_settings(
+        &self,
+        principal: &Principal,
+        settings: &DuplicateSettings,
+    ) -> impl Future<Output = Result<DuplicateSettings, DuplicateError>> + Send;
+
+    /// List the clusters of suspected duplicates among the caller's personal expenses, or those
+    /// of a [Ledger] when `ledger_id` is given, under the caller's [DuplicateSettings].
+    fn list_duplicates(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<DuplicateCluster>, DuplicateError>> + Send;
+
+    /// Merge duplicates into the [Expense] kept, which gains their tags and, if it has none,
+    /// their category, then delete them. Returns the merged [Expense].
+    ///
+    /// # Errors
+    ///
+    /// - [DuplicateError::ExpenseNotFound] if an expense does not exist or is not visible.
+    /// - [DuplicateError::ScopeMismatch] if the expenses do not all live in the same ledger, or
+    ///   are not all personal.
+    /// - [DuplicateError::HasAttachments] if a duplicate has attachments, which would be lost.
+    fn merge_duplicates(
+        &self,
+        principal: &Principal,
+        req: &MergeDuplicatesRequest,
+    ) -> impl Future<Output = Result<Expense, DuplicateError>> + Send;
+
+    /// Reserve `key` for the request identified by `fingerprint`, made by the caller. Returns
+    /// `None` if the request is to be processed, then answered with
+    /// [FinanceService::complete_idempotent_request] or
+    /// [FinanceService::release_idempotency_key], or the response to replay if it was already
+    /// answered.
+    ///
+    /// # Errors
+    ///
+    /// - [IdempotencyError::KeyReused] if the key was used for another request.
+    /// - [IdempotencyError::InProgress] if the same request is still being processed.
+    fn claim_idempotency_key(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> impl Future<Output = Result<Option<StoredResponse>, IdempotencyError>> + Send;
+
+    /// Keep the response to the request `key` was claimed for, to replay it on retries.
+    fn complete_idempotent_request(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        response: &StoredResponse,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+
+    /// Free `key` after its request failed, so that it can be retried.
+    fn release_idempotency_key(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+
+    /// Forget the idempotency keys expired by `now`, returning how many were.
+    fn purge_idempotency_keys(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<usize, IdempotencyError>> + Send;
+
+    /// Set up a [Rule] filing the caller's personal expenses as they are created or imported.
+    ///
+    /// # Errors
+    ///
+    /// - [RuleError::CategoryNotFound] if the category to file expenses under is not one of the
+    ///   caller's personal categories.
+    /// - [RuleError::AccountNotFound] if the account to match is not one of the caller's.
+    fn create_rule(
+        &self,
+        principal: &Principal,
+        req: &CreateRuleRequest,
+    ) -> impl Future<Output = Result<Rule, RuleError>> + Send;
+
+    /// List the caller's [Rule]s, in the order they are evaluated.
+    fn list_rules(
+        &self,
+        principal: &Principal,
+    ) -> impl Future<Output = Result<Vec<Rule>, RuleError>> + Send;
+
+    /// Delete a [Rule]. Expenses it already filed are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// - [RuleError::NotFound] if no [Rule] with the given id exists.
+    fn delete_rule(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<(), RuleError>> + Send;
+
+    /// Apply the caller's [Rule]s to their existing personal expenses, reporting every expense
+    /// they change. With `dry_run`, nothing is saved, previewing what would change.
+    fn apply_rules(
+        &self,
+        principal: &Principal,
+        dry_run: bool,
+    ) -> impl Future<Output = Result<RuleApplicationReport, RuleError>> + Send;
+
+    /// Summarize the caller's personal spending, or that of a ledger, into buckets.
+    ///
+    /// # Errors
+    ///
+    /// - [ReportError::InvalidRange] if the range starts after it ends.
+    /// - [ReportError::Policy] if the principal may not view the ledger's expenses.
+    /// - [ReportError::MissingExchangeRate] if an amount cannot be converted to the requested
+    ///   currency.
+    fn spending_summary(
+        &self,
+        principal: &Principal,
+        req: &SummaryRequest,
+    ) -> impl Future<Output = Result<Vec<SummaryBucket>, ReportError>> + Send;
+
+    /// Compute the caller's personal spending, or that of a ledger, over time.
+    ///
+    /// # Errors
+    ///
+    /// - [ReportError::InvalidRange] if the range starts after it ends.
+    /// - [ReportError::TooManyPoints] if the series would hold too many points.
+    /// - [ReportError::Policy] if the principal may not view the ledger's expenses.
+    /// - [ReportError::MissingExchangeRate] if an amount cannot be converted to the requested
+    ///   currency.
+    fn spending_timeseries(
+        &self,
+        principal: &Principal,
+        req: &TimeseriesRequest,
+    ) -> impl Future<Output = Result<Vec<Series>, ReportError>> + Send;
 }
 
 /// `ExpenseRepository` represents a store of expense data.
@@ -34,21 +544,494 @@ pub trait ExpenseRepository: Clone + Send + Sync + 'static {
     ///
     /// # Errors
     ///
-    /// - MUST return [CreateExpenseError::Duplicate] if an [Expense] with the same [ExpenseName]
-    ///   already exists.
+    /// - MUST return [CreateExpenseError::DuplicateOccurrence] if the occurrence of the recurring
+    ///   expense was already created.
     fn create_expense(
         &self,
         req: &CreateExpenseRequest,
     ) -> impl Future<Output = Result<Expense, CreateExpenseError>> + Send;
 
-    /// Retrieve a list of [Expense].
-    ///
+    /// Persist several new [Expense]s in a single transaction: either all are saved or none.
+    fn create_expenses(
+        &self,
+        reqs: &[CreateExpenseRequest],
+    ) -> impl Future<Output = Result<Vec<Expense>, ImportError>> + Send;
+
+    /// Retrieve which of `import_ids` were already imported into the account `account_id`.
+    fn find_import_ids(
+        &self,
+        account_id: &Uuid,
+        import_ids: &[&str],
+    ) -> impl Future<Output = Result<HashSet<String>, ImportError>> + Send;
+
+    /// Retrieve an [Expense] by its id, or `None` if it does not exist.
+    fn find_expense(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Expense>, ExpenseRepositoryError>> + Send;
+
+    /// Retrieve a list of [Expense]: those of the ledger of `req`, or the personal expenses
+    /// recorded against the accounts of `owner_id` without one.
     fn list_expenses(
         &self,
+        owner_id: &Uuid,
         req: &ListExpensesRequest,
     ) -> impl Future<Output = Result<Vec<Expense>, ExpenseRepositoryError>> + Send;
+
+    /// Stream the [Expense]s matching the filters of `req`, ignoring its pagination, ordered by
+    /// date. Rows are read as the stream is polled rather than all at once.
+    fn stream_expenses(&self, owner_id: &Uuid, req: &ListExpensesRequest) -> ExpenseStream;
+
+    /// Sum, for every pair of members of a ledger, what the participants of its split expenses
+    /// owe to the payers, less what they already paid back through settlements. Debts in both
+    /// directions are reported separately.
+    fn list_ledger_debts(
+        &self,
+        ledger_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Debt>, ExpenseRepositoryError>> + Send;
+}
+
+/// `AttachmentRepository` represents a store of the files attached to expenses, without their
+/// content.
+pub trait AttachmentRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Attachment], whose id was chosen beforehand to store its content under.
+    fn create_attachment(
+        &self,
+        attachment: &Attachment,
+    ) -> impl Future<Output = Result<(), AttachmentError>> + Send;
+
+    /// Retrieve an [Attachment] by its id, or `None` if it does not exist.
+    fn find_attachment(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Attachment>, AttachmentError>> + Send;
+
+    /// List up to `limit` attachments whose thumbnail is [ThumbnailStatus::Pending], oldest
+    /// first.
+    fn list_pending_thumbnails(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<Attachment>, AttachmentError>> + Send;
+
+    /// Record where the generation of the thumbnail of the [Attachment] `id` stands.
+    fn set_thumbnail_status(
+        &self,
+        id: &Uuid,
+        status: ThumbnailStatus,
+    ) -> impl Future<Output = Result<(), AttachmentError>> + Send;
+}
+
+/// `LedgerRepository` represents a store of ledgers and their memberships.
+pub trait LedgerRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Ledger] together with the owner's membership.
+    fn create_ledger(
+        &self,
+        req: &CreateLedgerRequest,
+    ) -> impl Future<Output = Result<Ledger, LedgerError>> + Send;
+
+    /// Retrieve a [Ledger] by its id, or `None` if it does not exist.
+    fn find_ledger(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Ledger>, LedgerError>> + Send;
+
+    /// Retrieve the [Ledger]s a user is a member of.
+    fn list_ledgers_for_user(
+        &self,
+        user_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Ledger>, LedgerError>> + Send;
+
+    /// Retrieve the members of a [Ledger].
+    fn list_ledger_members(
+        &self,
+        ledger_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<LedgerMember>, LedgerError>> + Send;
+
+    /// Retrieve the membership of a user in a [Ledger], if any.
+    fn find_ledger_member(
+        &self,
+        ledger_id: &Uuid,
+        user_id: &Uuid,
+    ) -> impl Future<Output = Result<Option<LedgerMember>, LedgerError>> + Send;
+
+    /// Persist a new [LedgerInvitation] with a freshly generated token.
+    fn create_invitation(
+        &self,
+        req: &CreateInvitationRequest,
+    ) -> impl Future<Output = Result<LedgerInvitation, InvitationError>> + Send;
+
+    /// Atomically consume an invitation token and add the membership it grants.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [InvitationError::NotFound] if the token is unknown or already consumed.
+    /// - MUST return [InvitationError::Expired] if the token is past its expiry.
+    /// - MUST return [InvitationError::AlreadyMember] if the user already belongs to the
+    ///   [Ledger], leaving the token unconsumed.
+    fn accept_invitation(
+        &self,
+        req: &AcceptInvitationRequest,
+    ) -> impl Future<Output = Result<LedgerMember, InvitationError>> + Send;
+}
+
+/// `SettlementRepository` represents a store of settlement payments.
+pub trait SettlementRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Settlement].
+    fn record_settlement(
+        &self,
+        req: &RecordSettlementRequest,
+    ) -> impl Future<Output = Result<Settlement, SettlementError>> + Send;
+}
+
+/// `RecurringExpenseRepository` represents a store of recurring expense templates.
+pub trait RecurringExpenseRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [RecurringExpense].
+    fn create_recurring_expense(
+        &self,
+        req: &CreateRecurringExpenseRequest,
+    ) -> impl Future<Output = Result<RecurringExpense, RecurringExpenseError>> + Send;
+
+    /// Retrieve a [RecurringExpense] by its id, or `None` if it does not exist.
+    fn find_recurring_expense(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<RecurringExpense>, RecurringExpenseError>> + Send;
+
+    /// Retrieve the [RecurringExpense]s of a ledger, or the personal ones of `owner_id` when
+    /// `ledger_id` is `None`.
+    fn list_recurring_expenses(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<RecurringExpense>, RecurringExpenseError>> + Send;
+
+    /// Delete a [RecurringExpense], returning whether it existed.
+    fn delete_recurring_expense(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<bool, RecurringExpenseError>> + Send;
+
+    /// Retrieve every [RecurringExpense] that started by `today`, has not ended before it was
+    /// last materialized, and was not materialized through `today` yet.
+    fn list_due_recurring_expenses(
+        &self,
+        today: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<RecurringExpense>, RecurringExpenseError>> + Send;
+
+    /// Record that every occurrence of a [RecurringExpense] up to `through` was created.
+    fn mark_recurring_expense_materialized(
+        &self,
+        id: &Uuid,
+        through: NaiveDate,
+    ) -> impl Future<Output = Result<(), RecurringExpenseError>> + Send;
+}
+
+/// `CategoryRepository` represents a store of expense categories.
+pub trait CategoryRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Category].
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [CategoryError::Duplicate] if a [Category] with the same name already exists
+    ///   in the same ledger, or among the owner's personal categories.
+    fn create_category(
+        &self,
+        req: &CreateCategoryRequest,
+    ) -> impl Future<Output = Result<Category, CategoryError>> + Send;
+
+    /// Retrieve a [Category] by its id, or `None` if it does not exist.
+    fn find_category(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Category>, CategoryError>> + Send;
+
+    /// Retrieve the [Category]s of a ledger, or the personal ones of `owner_id` when `ledger_id`
+    /// is `None`.
+    fn list_categories(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<Category>, CategoryError>> + Send;
+}
+
+/// `BudgetRepository` represents a store of budgets, able to aggregate the spending they track.
+pub trait BudgetRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Budget] on `category`.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [BudgetError::Duplicate] if the category already has a budget for the same
+    ///   period.
+    fn create_budget(
+        &self,
+        req: &CreateBudgetRequest,
+        category: &Category,
+    ) -> impl Future<Output = Result<Budget, BudgetError>> + Send;
+
+    /// Retrieve a [Budget] by its id, or `None` if it does not exist.
+    fn find_budget(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Budget>, BudgetError>> + Send;
+
+    /// Retrieve the [Budget]s of a ledger, or the personal ones of `owner_id` when `ledger_id` is
+    /// `None`.
+    fn list_budgets(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> impl Future<Output = Result<Vec<Budget>, BudgetError>> + Send;
+
+    /// Persist new values for `budget`, returning `None` if it no longer exists.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [BudgetError::Duplicate] if the category already has a budget for the new
+    ///   period.
+    fn update_budget(
+        &self,
+        budget: &Budget,
+        req: &UpdateBudgetRequest,
+    ) -> impl Future<Output = Result<Option<Budget>, BudgetError>> + Send;
+
+    /// Delete a [Budget], returning whether it existed.
+    fn delete_budget(&self, id: &Uuid) -> impl Future<Output = Result<bool, BudgetError>> + Send;
+
+    /// Sum the amounts of the expenses filed under `category_id` and spent within `[from, to)`,
+    /// each converted to `currency` at the rates of the day it was spent.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [BudgetError::MissingExchangeRate] if an amount cannot be converted to
+    ///   `currency`.
+    fn sum_category_spending(
+        &self,
+        category_id: &Uuid,
+        currency: &Currency,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Future<Output = Result<i64, BudgetError>> + Send;
+
+    /// Retrieve every [Budget] set on `category_id`, whatever its period.
+    fn list_category_budgets(
+        &self,
+        category_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Budget>, BudgetError>> + Send;
+
+    /// Record that `threshold` was reached in the period of `budget_id` starting on
+    /// `period_start`, returning `false` if it had already been recorded.
+    ///
+    /// Implementations MUST make this atomic so concurrent callers cannot both get `true`.
+    fn record_budget_alert(
+        &self,
+        budget_id: &Uuid,
+        period_start: NaiveDate,
+        threshold: BudgetThreshold,
+    ) -> impl Future<Output = Result<bool, BudgetError>> + Send;
+}
+
+/// `AccountRepository` represents a store of accounts, able to compute their balances from the
+/// transactions recorded against them.
+pub trait AccountRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Account].
+    fn create_account(
+        &self,
+        req: &CreateAccountRequest,
+    ) -> impl Future<Output = Result<Account, AccountError>> + Send;
+
+    /// Retrieve an [Account] by its id, or `None` if it does not exist.
+    fn find_account(
+        &self,
+        id: &Uuid,
+    ) -> impl Future<Output = Result<Option<Account>, AccountError>> + Send;
+
+    /// Retrieve the [Account]s of `owner_id`.
+    fn list_accounts(
+        &self,
+        owner_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Account>, AccountError>> + Send;
+
+    /// Compute the balance of `account` at the end of `as_of`, in `currency`: its opening
+    /// balance, plus incomes and incoming transfers, minus expenses and outgoing transfers.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [AccountError::MissingExchangeRate] if an amount cannot be converted to
+    ///   `currency`.
+    fn account_balance(
+        &self,
+        account: &Account,
+        currency: &Currency,
+        as_of: NaiveDate,
+    ) -> impl Future<Output = Result<i64, AccountError>> + Send;
+
+    /// Compute the balance of `account` in `currency` at the end of every day within
+    /// `[from, until]`, in date order, including days without transactions.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [AccountError::MissingExchangeRate] if an amount cannot be converted to
+    ///   `currency`.
+    fn account_balance_history(
+        &self,
+        account: &Account,
+        currency: &Currency,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<BalancePoint>, AccountError>> + Send;
+}
+
+/// `DuplicateRepository` represents a store of duplicate detection settings, able to look up and
+/// merge suspected duplicates.
+pub trait DuplicateRepository: Clone + Send + Sync + 'static {
+    /// Retrieve the [DuplicateSettings] of `owner_id`, or `None` if they were never saved.
+    fn find_duplicate_settings(
+        &self,
+        owner_id: &Uuid,
+    ) -> impl Future<Output = Result<Option<DuplicateSettings>, DuplicateError>> + Send;
+
+    /// Persist the [DuplicateSettings] of `owner_id`, replacing any saved before.
+    fn save_duplicate_settings(
+        &self,
+        owner_id: &Uuid,
+        settings: &DuplicateSettings,
+    ) -> impl Future<Output = Result<(), DuplicateError>> + Send;
+
+    /// Retrieve the [Expense]s of a ledger, or the personal ones recorded against the accounts
+    /// of `owner_id` when `ledger_id` is `None`, spent within `[from, until]` when given.
+    fn list_duplicate_candidates(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+        from: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> impl Future<Output = Result<Vec<Expense>, DuplicateError>> + Send;
+
+    /// Persist the tags and category of the merged expense and delete the duplicates, all at
+    /// once or not at all.
+    fn merge_duplicates(
+        &self,
+        merge: &DuplicateMerge,
+    ) -> impl Future<Output = Result<(), DuplicateError>> + Send;
+}
+
+/// `IdempotencyRepository` represents a store of the idempotency keys sent by clients, along
+/// with the responses to replay.
+pub trait IdempotencyRepository: Clone + Send + Sync + 'static {
+    /// Reserve the key of `req` for its owner, unless it is reserved and not expired yet.
+    ///
+    /// # Requirements
+    ///
+    /// - MUST reserve a key at most once across concurrent calls.
+    /// - MUST treat an expired key as free, replacing its fingerprint and response.
+    fn claim_idempotency_key(
+        &self,
+        req: &ClaimIdempotencyKeyRequest,
+    ) -> impl Future<Output = Result<IdempotencyClaim, IdempotencyError>> + Send;
+
+    /// Persist the response to the request `key` was reserved for by `owner_id`.
+    fn save_idempotent_response(
+        &self,
+        owner_id: &Uuid,
+        key: &IdempotencyKey,
+        response: &StoredResponse,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+
+    /// Delete the reservation of `key` by `owner_id`, if it has no response yet.
+    fn release_idempotency_key(
+        &self,
+        owner_id: &Uuid,
+        key: &IdempotencyKey,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+
+    /// Delete the keys expired by `now`, returning how many were.
+    fn delete_expired_idempotency_keys(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<usize, IdempotencyError>> + Send;
 }
 
+/// `RuleRepository` represents a store of categorization rules, able to re-file the expenses they
+/// match.
+pub trait RuleRepository: Clone + Send + Sync + 'static {
+    /// Persist a new [Rule].
+    fn create_rule(
+        &self,
+        req: &CreateRuleRequest,
+    ) -> impl Future<Output = Result<Rule, RuleError>> + Send;
+
+    /// Retrieve a [Rule] by its id, or `None` if it does not exist.
+    fn find_rule(&self, id: &Uuid) -> impl Future<Output = Result<Option<Rule>, RuleError>> + Send;
+
+    /// Retrieve the [Rule]s of `owner_id` by ascending priority, then in creation order.
+    fn list_rules(
+        &self,
+        owner_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Rule>, RuleError>> + Send;
+
+    /// Delete a [Rule], returning whether it existed.
+    fn delete_rule(&self, id: &Uuid) -> impl Future<Output = Result<bool, RuleError>> + Send;
+
+    /// Retrieve the personal [Expense]s recorded against the accounts of `owner_id`, which
+    /// their rules apply to.
+    fn list_rule_candidates(
+        &self,
+        owner_id: &Uuid,
+    ) -> impl Future<Output = Result<Vec<Expense>, RuleError>> + Send;
+
+    /// Persist the new name, category and tags of every changed expense, all at once or not at
+    /// all.
+    fn save_rule_changes(
+        &self,
+        changes: &[RuleChange],
+    ) -> impl Future<Output = Result<(), RuleError>> + Send;
+}
+
+/// `ExchangeRateRepository` represents a store of daily exchange rates.
+pub trait ExchangeRateRepository: Clone + Send + Sync + 'static {
+    /// Persist `rates`, replacing any stored for the same currency and day, returning how many
+    /// were written.
+    fn save_exchange_rates(
+        &self,
+        rates: &[ExchangeRate],
+    ) -> impl Future<Output = Result<usize, ExchangeRateError>> + Send;
+}
+
+/// `ReportRepository` represents a store able to aggregate expenses for reporting, without
+/// loading them one by one.
+pub trait ReportRepository: Clone + Send + Sync + 'static {
+    /// Sum, count and average the expenses of `scope` spent within the range of `req`, one
+    /// bucket per key of its grouping and currency, months in date order and other keys by
+    /// decreasing total.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [ReportError::MissingExchangeRate] if an amount cannot be converted to the
+    ///   requested currency.
+    fn spending_summary(
+        &self,
+        scope: &ReportScope,
+        req: &SummaryRequest,
+    ) -> impl Future<Output = Result<Vec<SummaryBucket>, ReportError>> + Send;
+
+    /// Sum the expenses of `scope` per interval of `req`, with a point for every interval even
+    /// when nothing was spent, one [Series] per currency, or a single one in the requested
+    /// currency.
+    ///
+    /// # Errors
+    ///
+    /// - MUST return [ReportError::MissingExchangeRate] if an amount cannot be converted to the
+    ///   requested currency.
+    fn spending_timeseries(
+        &self,
+        scope: &ReportScope,
+        req: &TimeseriesRequest,
+    ) -> impl Future<Output = Result<Vec<Series>, ReportError>> + Send;
+}
+
+/// [Expense]s read one at a time from an [ExpenseRepository].
+pub type ExpenseStream = BoxStream<'static, Result<Expense, ExpenseRepositoryError>>;
+
 #[derive(Debug, Error)]
 pub enum ExpenseRepositoryError {
     #[error("Repository Timed out")]
@@ -73,4 +1056,40 @@ pub trait FinanceMetrics: Send + Sync + Clone + 'static {
 /// `ExpenseNotifier` triggers notifications to expenses.
 pub trait ExpenseNotifier: Send + Sync + Clone + 'static {
     fn expense_created(&self, expense: &Expense) -> impl Future<Output = ()> + Send;
+
+    /// Called once per [Budget] period for each [BudgetThreshold] an expense pushed it past.
+    fn budget_threshold_reached(&self, alert: &BudgetAlert) -> impl Future<Output = ()> + Send;
+}
+
+/// `AttachmentStorage` keeps the content of attachments by key, such as in a directory or an
+/// object store.
+pub trait AttachmentStorage: Send + Sync + Clone + 'static {
+    /// Store `content` under `key`, replacing anything stored there.
+    fn put(
+        &self,
+        key: &str,
+        content_type: AttachmentContentType,
+        content: &[u8],
+    ) -> impl Future<Output = Result<(), AttachmentError>> + Send;
+
+    /// Retrieve the content stored under `key`, or `None` if there is none.
+    fn get(
+        &self,
+        key: &str,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, AttachmentError>> + Send;
+
+    /// Remove the content stored under `key`, if any.
+    fn delete(&self, key: &str) -> impl Future<Output = Result<(), AttachmentError>> + Send;
+}
+
+/// `ThumbnailRenderer` draws the thumbnail of the content of an attachment.
+pub trait ThumbnailRenderer: Send + Sync + Clone + 'static {
+    /// Render `content`, of type `content_type`, as a JPEG picture of at most
+    /// [THUMBNAIL_SIZE](crate::domain::finance::models::attachment::THUMBNAIL_SIZE) pixels a
+    /// side, or `None` if it cannot be decoded.
+    fn render(
+        &self,
+        content_type: AttachmentContentType,
+        content: Vec<u8>,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, AttachmentError>> + Send;
 }
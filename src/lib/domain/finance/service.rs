@@ -1,79 +1,2277 @@
 use super::{
-    models::expense::{CreateExpenseError, CreateExpenseRequest, Expense, ListExpensesRequest},
-    ports::{ExpenseNotifier, ExpenseRepository, FinanceMetrics, FinanceService},
+    models::{
+        account::{Account, AccountBalance, AccountError, CreateAccountRequest, Currency},
+        attachment::{
+            Attachment, AttachmentError, AttachmentFile, AttachmentVariant,
+            CreateAttachmentRequest, ThumbnailStatus,
+        },
+        balance::{Debt, LedgerBalances},
+        budget::{
+            Budget, BudgetAlert, BudgetError, BudgetStatus, CreateBudgetRequest,
+            UpdateBudgetRequest,
+        },
+        category::{Category, CategoryError, CreateCategoryRequest},
+        duplicate::{
+            DuplicateCluster, DuplicateError, DuplicateMerge, DuplicateMode, DuplicateSettings,
+            DuplicateSubject, MergeDuplicatesRequest,
+        },
+        exchange::{ExchangeRate, ExchangeRateError},
+        expense::{
+            CreateExpenseError, CreateExpenseRequest, CreatedExpense, Expense, ListExpensesError,
+            ListExpensesRequest,
+        },
+        idempotency::{
+            ClaimIdempotencyKeyRequest, IdempotencyClaim, IdempotencyError, IdempotencyKey,
+            RequestFingerprint, StoredResponse,
+        },
+        import::{ImportError, ImportReport, ImportRequest, ImportRowError, ImportedTransaction},
+        ledger::{
+            AcceptInvitationRequest, CreateInvitationRequest, CreateLedgerRequest, InvitationError,
+            Ledger, LedgerError, LedgerInvitation, LedgerMember,
+        },
+        quick::{QuickExpense, QuickExpenseIssue, QuickExpenseOutcome, QuickExpenseRequest},
+        recurring::{
+            CreateRecurringExpenseRequest, Occurrence, RecurringExpense, RecurringExpenseError,
+        },
+        report::{
+            ReportError, ReportScope, Series, SummaryBucket, SummaryRequest, TimeseriesRequest,
+        },
+        rule::{
+            Classification, CreateRuleRequest, Rule, RuleApplicationReport, RuleChange, RuleError,
+            RuleSubject,
+        },
+        settlement::{RecordSettlementRequest, Settlement, SettlementError},
+        transaction::TransactionKind,
+    },
+    policy::{self, Action, PolicyError, Resource},
+    ports::{
+        AccountRepository, AttachmentRepository, AttachmentStorage, BudgetRepository,
+        CategoryRepository, DuplicateRepository, ExchangeRateRepository, ExpenseNotifier,
+        ExpenseRepository, ExpenseStream, FinanceMetrics, FinanceService, IdempotencyRepository,
+        LedgerRepository, RecurringExpenseRepository, ReportRepository, RuleRepository,
+        SettlementRepository, ThumbnailRenderer,
+    },
 };
+use crate::domain::auth::models::principal::Principal;
 use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
-/// Canonical implementation of the [BlogService] port, through which the blog domain API is
+/// How far ahead [FinanceService::upcoming_occurrences] may look, in days.
+pub const MAX_UPCOMING_DAYS: i64 = 366;
+
+/// How many days of history [FinanceService::account_balance] may report.
+pub const MAX_BALANCE_HISTORY_DAYS: i64 = 366;
+
+/// How many points [FinanceService::spending_timeseries] may return per series.
+pub const MAX_SERIES_POINTS: i64 = 1_000;
+
+/// Canonical implementation of the [FinanceService] port, through which the finance domain API is
 /// consumed.
 #[derive(Debug, Clone)]
-pub struct Service<R, M, N>
+pub struct Service<R, M, N, S, T>
 where
-    R: ExpenseRepository,
+    R: ExpenseRepository
+        + LedgerRepository
+        + SettlementRepository
+        + CategoryRepository
+        + BudgetRepository
+        + RecurringExpenseRepository
+        + AccountRepository
+        + ExchangeRateRepository
+        + ReportRepository
+        + AttachmentRepository
+        + RuleRepository
+        + DuplicateRepository
+        + IdempotencyRepository,
     M: FinanceMetrics,
     N: ExpenseNotifier,
+    S: AttachmentStorage,
+    T: ThumbnailRenderer,
 {
     repo: R,
     metrics: M,
     expense_notifier: N,
+    storage: S,
+    thumbnail_renderer: T,
 }
 
-impl<R, M, N> Service<R, M, N>
+impl<R, M, N, S, T> Service<R, M, N, S, T>
 where
-    R: ExpenseRepository,
+    R: ExpenseRepository
+        + LedgerRepository
+        + SettlementRepository
+        + CategoryRepository
+        + BudgetRepository
+        + RecurringExpenseRepository
+        + AccountRepository
+        + ExchangeRateRepository
+        + ReportRepository
+        + AttachmentRepository
+        + RuleRepository
+        + DuplicateRepository
+        + IdempotencyRepository,
     M: FinanceMetrics,
     N: ExpenseNotifier,
+    S: AttachmentStorage,
+    T: ThumbnailRenderer,
 {
-    pub fn new(repo: R, metrics: M, expense_notifier: N) -> Self {
+    pub fn new(
+        repo: R,
+        metrics: M,
+        expense_notifier: N,
+        storage: S,
+        thumbnail_renderer: T,
+    ) -> Self {
         Self {
             repo,
             metrics,
             expense_notifier,
+            storage,
+            thumbnail_renderer,
+        }
+    }
+
+    /// Check `action` on `resource` against the [policy], resolving the principal's role in the
+    /// resource's ledger first.
+    async fn authorize(
+        &self,
+        principal: Option<&Principal>,
+        action: Action,
+        resource: Resource,
+    ) -> Result<(), PolicyError> {
+        let role = match (principal, resource.ledger_id()) {
+            (Some(principal), Some(ledger_id)) => self
+                .repo
+                .find_ledger_member(ledger_id, principal.user_id())
+                .await
+                .map_err(|e| anyhow!("Failed to resolve ledger membership: {}", e))?
+                .map(|m| m.role()),
+            _ => None,
+        };
+        policy::authorize(principal, role, action, &resource)
+    }
+
+    async fn balances(&self, ledger_id: &Uuid) -> Result<LedgerBalances, LedgerError> {
+        let members = self.repo.list_ledger_members(ledger_id).await?;
+        let debts = self
+            .repo
+            .list_ledger_debts(ledger_id)
+            .await
+            .map_err(|e| anyhow!("Failed to list ledger debts: {}", e))?;
+        let member_ids: Vec<Uuid> = members.iter().map(|m| *m.user_id()).collect();
+        Ok(LedgerBalances::from_debts(&member_ids, &debts))
+    }
+
+    /// Whether the category `category_id` exists where an expense of `ledger_id` lives: in the
+    /// same ledger, or among the caller's personal categories.
+    async fn category_visible(
+        &self,
+        principal: Option<&Principal>,
+        ledger_id: Option<&Uuid>,
+        category_id: &Uuid,
+    ) -> anyhow::Result<bool> {
+        let category = self
+            .repo
+            .find_category(category_id)
+            .await
+            .map_err(|e| anyhow!("Failed to find category: {}", e))?;
+        Ok(category.is_some_and(|c| match c.ledger_id() {
+            Some(category_ledger_id) => Some(category_ledger_id) == ledger_id,
+            None => ledger_id.is_none() && principal.is_some_and(|p| p.user_id() == c.owner_id()),
+        }))
+    }
+
+    /// Ensure that the category of an expense exists where the expense will live.
+    async fn validate_category(
+        &self,
+        principal: Option<&Principal>,
+        req: &CreateExpenseRequest,
+    ) -> Result<(), CreateExpenseError> {
+        let Some(category_id) = req.category_id() else {
+            return Ok(());
+        };
+        if !self
+            .category_visible(principal, req.ledger_id(), category_id)
+            .await?
+        {
+            return Err(CreateExpenseError::CategoryNotFound { id: *category_id });
+        }
+        Ok(())
+    }
+
+    /// Retrieve the account `account_id` if it exists and belongs to the caller.
+    async fn owned_account(
+        &self,
+        principal: Option<&Principal>,
+        account_id: &Uuid,
+    ) -> anyhow::Result<Option<Account>> {
+        let account = self
+            .repo
+            .find_account(account_id)
+            .await
+            .map_err(|e| anyhow!("Failed to find account: {}", e))?;
+        Ok(account.filter(|a| principal.is_some_and(|p| p.user_id() == a.owner_id())))
+    }
+
+    /// Ensure that an expense is recorded against one of the caller's accounts and, for a
+    /// transfer only, moves the money to another of them in the same currency.
+    async fn validate_account(
+        &self,
+        principal: Option<&Principal>,
+        req: &CreateExpenseRequest,
+    ) -> Result<(), CreateExpenseError> {
+        let account_id = req
+            .account_id()
+            .ok_or(CreateExpenseError::AccountRequired)?;
+        let account = self
+            .owned_account(principal, account_id)
+            .await?
+            .ok_or(CreateExpenseError::AccountNotFound { id: *account_id })?;
+        match (req.kind(), req.to_account_id()) {
+            (TransactionKind::Transfer, None) => {
+                Err(CreateExpenseError::TransferWithoutDestination)
+            }
+            (TransactionKind::Transfer, Some(to_account_id)) => {
+                if to_account_id == account_id {
+                    return Err(CreateExpenseError::TransferToSameAccount { id: *account_id });
+                }
+                let destination = self
+                    .owned_account(principal, to_account_id)
+                    .await?
+                    .ok_or(CreateExpenseError::AccountNotFound { id: *to_account_id })?;
+                if destination.currency() != account.currency() {
+                    return Err(CreateExpenseError::CurrencyMismatch {
+                        from: account.currency().clone(),
+                        to: destination.currency().clone(),
+                    });
+                }
+                Ok(())
+            }
+            (kind, Some(_)) => Err(CreateExpenseError::DestinationNotTransfer { kind }),
+            (_, None) => Ok(()),
+        }
+    }
+
+    /// Apply the principal's [Rule]s to a personal expense about to be created. Shared expenses
+    /// are left as they are, since rules only file personal ones.
+    async fn classify(
+        &self,
+        principal: Option<&Principal>,
+        req: &CreateExpenseRequest,
+    ) -> Result<CreateExpenseRequest, CreateExpenseError> {
+        let (Some(principal), None) = (principal, req.ledger_id()) else {
+            return Ok(req.clone());
+        };
+        let rules = self
+            .repo
+            .list_rules(principal.user_id())
+            .await
+            .map_err(|e| anyhow!("Failed to list rules: {}", e))?;
+        Ok(classify_request(&rules, req.clone()))
+    }
+
+    /// Retrieve the [DuplicateSettings] of the principal, or the defaults if they never saved any.
+    async fn duplicate_settings(
+        &self,
+        principal: &Principal,
+    ) -> Result<DuplicateSettings, DuplicateError> {
+        Ok(self
+            .repo
+            .find_duplicate_settings(principal.user_id())
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Look for the existing expenses an expense about to be created looks like, under the
+    /// principal's [DuplicateSettings], returning their ids unless duplicates are blocked.
+    async fn check_duplicates(
+        &self,
+        principal: Option<&Principal>,
+        req: &CreateExpenseRequest,
+    ) -> Result<Vec<Uuid>, CreateExpenseError> {
+        let Some(principal) = principal else {
+            return Ok(Vec::new());
+        };
+        let settings = self
+            .duplicate_settings(principal)
+            .await
+            .map_err(|e| anyhow!("Failed to find duplicate settings: {}", e))?;
+        if settings.mode() == DuplicateMode::Off {
+            return Ok(Vec::new());
+        }
+        let (from, until) = settings.window(*req.spent_on());
+        let subject = DuplicateSubject::from(req);
+        let duplicate_ids: Vec<Uuid> = self
+            .repo
+            .list_duplicate_candidates(
+                principal.user_id(),
+                req.ledger_id(),
+                Some(from),
+                Some(until),
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to look for duplicates: {}", e))?
+            .iter()
+            .filter(|&expense| settings.is_duplicate(&subject, &expense.into()))
+            .map(|expense| *expense.id())
+            .collect();
+        if settings.mode() == DuplicateMode::Block && !duplicate_ids.is_empty() {
+            return Err(CreateExpenseError::Duplicate { duplicate_ids });
+        }
+        Ok(duplicate_ids)
+    }
+
+    /// Retrieve an expense to merge, if the principal may perform `action` on it.
+    async fn mergeable_expense(
+        &self,
+        principal: &Principal,
+        action: Action,
+        id: &Uuid,
+    ) -> Result<Expense, DuplicateError> {
+        self.accessible_expense(principal, action, id)
+            .await?
+            .ok_or(DuplicateError::ExpenseNotFound { id: *id })
+    }
+
+    /// Run every check an expense must pass before it is saved.
+    async fn validate_expense(
+        &self,
+        principal: Option<&Principal>,
+        req: &CreateExpenseRequest,
+    ) -> Result<(), CreateExpenseError> {
+        self.validate_split(req).await?;
+        self.validate_category(principal, req).await?;
+        self.validate_account(principal, req).await
+    }
+
+    /// Retrieve the expense `expense_id` if the principal may perform `action` on it: with the
+    /// role it requires in the expense's ledger or, for a personal expense, as the owner of its
+    /// account. Returns `None` if it does not exist or is someone else's personal expense.
+    async fn accessible_expense(
+        &self,
+        principal: &Principal,
+        action: Action,
+        expense_id: &Uuid,
+    ) -> Result<Option<Expense>, PolicyError> {
+        let Some(expense) = self
+            .repo
+            .find_expense(expense_id)
+            .await
+            .map_err(|e| anyhow!("Failed to find expense: {}", e))?
+        else {
+            return Ok(None);
+        };
+        let resource = Resource::expense(expense.ledger_id().copied());
+        self.authorize(Some(principal), action, resource).await?;
+        if expense.ledger_id().is_none() {
+            let Some(account_id) = expense.account_id() else {
+                return Ok(None);
+            };
+            if self
+                .owned_account(Some(principal), account_id)
+                .await?
+                .is_none()
+            {
+                return Ok(None);
+            }
+        }
+        Ok(Some(expense))
+    }
+
+    /// Create the expenses of the occurrences of `recurring` due up to `today`, counting them
+    /// in `created`, then remember up to which day they were.
+    ///
+    /// Occurrences that already exist are skipped. The first occurrence that cannot be created
+    /// stops the others, and is retried along with them on the next call rather than lost.
+    async fn materialize(
+        &self,
+        recurring: &RecurringExpense,
+        today: NaiveDate,
+        created: &mut usize,
+    ) -> Result<(), RecurringExpenseError> {
+        let principal = Principal::new(*recurring.owner_id());
+        let due: Vec<NaiveDate> = recurring
+            .pending_occurrences()
+            .take_while(|day| *day <= today)
+            .collect();
+        let mut materialized_through = None;
+        let mut failure = None;
+        for occurs_on in due {
+            let req = match CreateExpenseRequest::new(&recurring.name().to_string())
+                .map_err(anyhow::Error::from)
+                .and_then(|req| Ok(req.with_amount(recurring.amount())?))
+            {
+                Ok(req) => req,
+                Err(e) => {
+                    failure = Some(anyhow!(
+                        "Invalid recurring expense {}: {}",
+                        recurring.id(),
+                        e
+                    ));
+                    break;
+                }
+            };
+            let mut req = req
+                .with_spent_on(occurs_on)
+                .with_recurring_expense(*recurring.id());
+            if let Some(ledger_id) = recurring.ledger_id() {
+                req = req.with_ledger(*ledger_id);
+            }
+            if let Some(category_id) = recurring.category_id() {
+                req = req.with_category(*category_id);
+            }
+            if let Some(account_id) = recurring.account_id() {
+                req = req.with_account(*account_id);
+            }
+            match self.create_expense(Some(&principal), &req).await {
+                Ok(_) => *created += 1,
+                Err(CreateExpenseError::DuplicateOccurrence { .. }) => {}
+                Err(e) => {
+                    failure = Some(anyhow!(
+                        "Failed to create the occurrence of recurring expense {} on {}: {}",
+                        recurring.id(),
+                        occurs_on,
+                        e
+                    ));
+                    break;
+                }
+            }
+            materialized_through = Some(occurs_on);
+        }
+        if failure.is_none() {
+            materialized_through = Some(today);
+        }
+        if let Some(materialized_through) = materialized_through {
+            self.repo
+                .mark_recurring_expense_materialized(recurring.id(), materialized_through)
+                .await?;
+        }
+        failure.map_or(Ok(()), |e| Err(e.into()))
+    }
+
+    /// Load a [RecurringExpense] and check `action` on it.
+    async fn authorized_recurring_expense(
+        &self,
+        principal: &Principal,
+        action: Action,
+        id: &Uuid,
+    ) -> Result<RecurringExpense, RecurringExpenseError> {
+        let recurring = self
+            .repo
+            .find_recurring_expense(id)
+            .await?
+            .ok_or(RecurringExpenseError::NotFound { id: *id })?;
+        let resource = Resource::recurring_expense(recurring.ledger_id().copied())
+            .owned_by(*recurring.owner_id());
+        self.authorize(Some(principal), action, resource).await?;
+        Ok(recurring)
+    }
+
+    /// Load a [Budget] and check `action` on it.
+    async fn authorized_budget(
+        &self,
+        principal: &Principal,
+        action: Action,
+        id: &Uuid,
+    ) -> Result<Budget, BudgetError> {
+        let budget = self
+            .repo
+            .find_budget(id)
+            .await?
+            .ok_or(BudgetError::NotFound { id: *id })?;
+        let resource = Resource::budget(budget.ledger_id().copied()).owned_by(*budget.owner_id());
+        self.authorize(Some(principal), action, resource).await?;
+        Ok(budget)
+    }
+
+    /// Resolve whose expenses a report covers, checking the principal may view them.
+    async fn authorized_report_scope(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<ReportScope, PolicyError> {
+        let resource = Resource::expense(ledger_id.copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        Ok(match ledger_id {
+            Some(ledger_id) => ReportScope::Ledger(*ledger_id),
+            None => ReportScope::Personal(*principal.user_id()),
+        })
+    }
+
+    /// Notify about every budget threshold that `expense` pushed its category past, at most once
+    /// per threshold and budget period.
+    async fn evaluate_budget_thresholds(&self, expense: &Expense) -> Result<(), BudgetError> {
+        let Some(category_id) = expense.category_id() else {
+            return Ok(());
+        };
+        if expense.kind() != TransactionKind::Expense {
+            return Ok(());
+        }
+        for budget in self.repo.list_category_budgets(category_id).await? {
+            let (start, end) = budget.period().window(*expense.spent_on());
+            let spent = self
+                .repo
+                .sum_category_spending(category_id, budget.currency(), start, end)
+                .await?;
+            let status = BudgetStatus::new(budget, start, end, spent);
+            for threshold in status.reached_thresholds() {
+                if self
+                    .repo
+                    .record_budget_alert(status.budget().id(), start, threshold)
+                    .await?
+                {
+                    let alert = BudgetAlert::new(status.clone(), threshold);
+                    self.expense_notifier.budget_threshold_reached(&alert).await;
+                }
+            }
         }
+        Ok(())
+    }
+
+    /// Ensure that a split transaction is an expense, lives in a ledger and that its payer and participants all
+    /// belong to that ledger.
+    async fn validate_split(&self, req: &CreateExpenseRequest) -> Result<(), CreateExpenseError> {
+        let Some(split) = req.split() else {
+            return Ok(());
+        };
+        if req.kind() != TransactionKind::Expense {
+            return Err(CreateExpenseError::SplitNotExpense { kind: req.kind() });
+        }
+        let ledger_id = req
+            .ledger_id()
+            .ok_or(CreateExpenseError::SplitWithoutLedger)?;
+        let members = self
+            .repo
+            .list_ledger_members(ledger_id)
+            .await
+            .map_err(|e| anyhow!("Failed to list ledger members: {}", e))?;
+
+        let users =
+            std::iter::once(split.paid_by()).chain(split.shares().iter().map(|s| s.user_id()));
+        for user_id in users {
+            if !members.iter().any(|m| m.user_id() == user_id) {
+                return Err(CreateExpenseError::NotLedgerMember {
+                    ledger_id: *ledger_id,
+                    user_id: *user_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Render and store the thumbnail of `attachment`, returning its new status.
+    async fn render_thumbnail(
+        &self,
+        attachment: &Attachment,
+    ) -> Result<ThumbnailStatus, AttachmentError> {
+        let Some(content) = self.storage.get(&attachment.storage_key()).await? else {
+            tracing::warn!(
+                "Content of attachment {} is missing from the storage",
+                attachment.id()
+            );
+            return Ok(ThumbnailStatus::Unavailable);
+        };
+        let Some(thumbnail) = self
+            .thumbnail_renderer
+            .render(attachment.content_type(), content)
+            .await?
+        else {
+            return Ok(ThumbnailStatus::Unavailable);
+        };
+        let variant = AttachmentVariant::Thumb;
+        self.storage
+            .put(
+                &attachment.variant_key(variant),
+                attachment.variant_content_type(variant),
+                &thumbnail,
+            )
+            .await?;
+        Ok(ThumbnailStatus::Ready)
     }
 }
 
-impl<R, M, N> FinanceService for Service<R, M, N>
+impl<R, M, N, S, T> FinanceService for Service<R, M, N, S, T>
 where
-    R: ExpenseRepository,
+    R: ExpenseRepository
+        + LedgerRepository
+        + SettlementRepository
+        + CategoryRepository
+        + BudgetRepository
+        + RecurringExpenseRepository
+        + AccountRepository
+        + ExchangeRateRepository
+        + ReportRepository
+        + AttachmentRepository
+        + RuleRepository
+        + DuplicateRepository
+        + IdempotencyRepository,
     M: FinanceMetrics,
     N: ExpenseNotifier,
+    S: AttachmentStorage,
+    T: ThumbnailRenderer,
 {
-    /// Create the [Expense] specified in `req` and trigger notifications.
+    /// Create the [Expense] specified in `req` and trigger notifications, including an alert for
+    /// each budget threshold of its category the expense crosses.
     ///
     /// # Errors
     ///
+    /// - [CreateExpenseError::Policy] if the principal may not create expenses in the ledger.
+    /// - [CreateExpenseError::SplitNotExpense] if a split is requested for an income or a
+    ///   transfer.
+    /// - [CreateExpenseError::SplitWithoutLedger] if a split is requested for a personal expense.
+    /// - [CreateExpenseError::NotLedgerMember] if the payer or a participant of the split does not
+    ///   belong to the ledger.
+    /// - [CreateExpenseError::CategoryNotFound] if the category does not exist where the expense
+    ///   lives.
+    /// - [CreateExpenseError::AccountRequired] if no account is given, and
+    ///   [CreateExpenseError::AccountNotFound] if it does not belong to the principal.
+    /// - [CreateExpenseError::TransferWithoutDestination],
+    ///   [CreateExpenseError::DestinationNotTransfer],
+    ///   [CreateExpenseError::TransferToSameAccount] and [CreateExpenseError::CurrencyMismatch]
+    ///   if the destination account does not suit the kind of transaction.
+    /// - [CreateExpenseError::Duplicate] if the expense looks like existing ones and the
+    ///   principal blocks duplicates.
     /// - Propagates any [CreateExpenseError] returned by the [ExpenseRepository].
     async fn create_expense(
         &self,
+        principal: Option<&Principal>,
         req: &CreateExpenseRequest,
-    ) -> Result<Expense, CreateExpenseError> {
-        let result = self.repo.create_expense(req).await;
-        if result.is_err() {
-            self.metrics.record_expense_creation_failure().await;
-        } else {
-            self.metrics.record_expense_creation_success().await;
-            self.expense_notifier
-                .expense_created(result.as_ref().unwrap())
-                .await;
+    ) -> Result<CreatedExpense, CreateExpenseError> {
+        let resource = Resource::expense(req.ledger_id().copied());
+        let result = async {
+            self.authorize(principal, Action::Create, resource).await?;
+            let req = self.classify(principal, req).await?;
+            self.validate_expense(principal, &req).await?;
+            let suspected_duplicates = self.check_duplicates(principal, &req).await?;
+            let expense = self.repo.create_expense(&req).await?;
+            Ok(CreatedExpense::new(expense, suspected_duplicates))
+        }
+        .await;
+        match &result {
+            Ok(created) => {
+                self.metrics.record_expense_creation_success().await;
+                self.expense_notifier
+                    .expense_created(created.expense())
+                    .await;
+                // The expense is already saved, so a failed evaluation must not fail the request.
+                if let Err(e) = self.evaluate_budget_thresholds(created.expense()).await {
+                    tracing::warn!("Failed to evaluate budget thresholds: {}", e);
+                }
+            }
+            Err(_) => self.metrics.record_expense_creation_failure().await,
         }
 
         result
     }
 
+    /// Read the description of `req`, resolve the category and account it names by name,
+    /// case-insensitively and then by prefix, and create the resulting [Expense].
+    ///
+    /// Without an `@account`, the principal's only account is used. An amount written in
+    /// another currency than the account's is not converted, but reported as blocking.
+    ///
+    /// # Errors
+    ///
+    /// - [CreateExpenseError::Policy] if the principal may not create expenses.
+    /// - Propagates any [CreateExpenseError] returned by [FinanceService::create_expense].
+    async fn quick_add_expense(
+        &self,
+        principal: &Principal,
+        req: &QuickExpenseRequest,
+    ) -> Result<QuickExpenseOutcome, CreateExpenseError> {
+        self.authorize(Some(principal), Action::Create, Resource::expense(None))
+            .await?;
+        let parsed = QuickExpense::parse(req.text(), *req.today());
+        let mut outcome = QuickExpenseOutcome::new(parsed.clone());
+
+        if let Some(name) = parsed.category() {
+            let categories = self
+                .repo
+                .list_categories(principal.user_id(), None)
+                .await
+                .map_err(|e| anyhow!("Failed to list categories: {}", e))?;
+            outcome = match match_by_name(&categories, name, |c| c.name().to_string()).as_slice() {
+                [category] => outcome.with_category(*category.id()),
+                [] => outcome.with_issue(QuickExpenseIssue::UnknownCategory {
+                    name: name.to_string(),
+                }),
+                candidates => outcome.with_issue(QuickExpenseIssue::SeveralCategories {
+                    name: name.to_string(),
+                    candidates: candidates.iter().map(|c| c.name().to_string()).collect(),
+                }),
+            };
+        }
+
+        let accounts = self
+            .repo
+            .list_accounts(principal.user_id())
+            .await
+            .map_err(|e| anyhow!("Failed to list accounts: {}", e))?;
+        let matching = match parsed.account() {
+            Some(name) => match_by_name(&accounts, name, |a| a.name().to_string()),
+            None => accounts.iter().collect(),
+        };
+        let account = match (matching.as_slice(), parsed.account()) {
+            ([account], _) => Some(*account),
+            ([], Some(name)) => {
+                outcome = outcome.with_issue(QuickExpenseIssue::UnknownAccount {
+                    name: name.to_string(),
+                });
+                None
+            }
+            (candidates, Some(name)) => {
+                outcome = outcome.with_issue(QuickExpenseIssue::SeveralAccounts {
+                    name: name.to_string(),
+                    candidates: candidates.iter().map(|a| a.name().to_string()).collect(),
+                });
+                None
+            }
+            (_, None) => {
+                outcome = outcome.with_issue(QuickExpenseIssue::AccountRequired);
+                None
+            }
+        };
+
+        let mut amount = None;
+        if let Some(account) = account {
+            outcome = outcome.with_account(*account.id(), account.currency().clone());
+            if let Some(currency) = parsed.currency()
+                && currency != account.currency()
+            {
+                outcome = outcome.with_issue(QuickExpenseIssue::CurrencyMismatch {
+                    amount: currency.clone(),
+                    account: account.currency().clone(),
+                });
+            } else if let Some(written) = parsed.amount() {
+                amount = written.to_minor_units(account.currency());
+                if amount.is_none() {
+                    outcome = outcome.with_issue(QuickExpenseIssue::InvalidAmount {
+                        currency: account.currency().clone(),
+                    });
+                }
+            }
+        }
+
+        if outcome.is_blocked() {
+            return Ok(outcome);
+        }
+        let (Some(name), Some(amount), Some(account)) = (parsed.name(), amount, account) else {
+            return Ok(outcome);
+        };
+        let mut expense_req = CreateExpenseRequest::new(name)
+            .map_err(|e| anyhow!(e))?
+            .with_amount(amount)
+            .map_err(|e| anyhow!(e))?
+            .with_account(*account.id())
+            .with_spent_on(*parsed.spent_on());
+        if let Some(category_id) = outcome.category_id() {
+            expense_req = expense_req.with_category(*category_id);
+        }
+        outcome = outcome.with_request(expense_req.clone());
+        if req.is_dry_run() {
+            return Ok(outcome);
+        }
+        let created = self.create_expense(Some(principal), &expense_req).await?;
+        if !created.suspected_duplicates().is_empty() {
+            outcome = outcome.with_issue(QuickExpenseIssue::SuspectedDuplicate {
+                duplicate_ids: created.suspected_duplicates().to_vec(),
+            });
+        }
+        Ok(outcome.with_expense(created.into_expense()))
+    }
+
     /// List [Expense].
     ///
     /// # Errors
     ///
+    /// - [ListExpensesError::Policy] if the principal may not view the ledger.
     /// - Propagates any [ExpenseRepositoryError] returned by the [ExpenseRepository].
     async fn list_expenses(
         &self,
+        principal: &Principal,
         req: &ListExpensesRequest,
-    ) -> Result<Vec<Expense>, anyhow::Error> {
-        let result = self.repo.list_expenses(req).await;
+    ) -> Result<Vec<Expense>, ListExpensesError> {
+        let resource = Resource::expense(req.ledger_id().copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        let result = self.repo.list_expenses(principal.user_id(), req).await;
         if result.is_ok() {
             self.metrics.record_expense_list_success().await;
         }
-        result.map_err(|e| anyhow!("Failed to list expenses: {}", e))
+        result.map_err(|e| anyhow!("Failed to list expenses: {}", e).into())
+    }
+
+    /// Stream the [Expense]s matching the filters of `req`.
+    ///
+    /// # Errors
+    ///
+    /// - [ListExpensesError::Policy] if the principal may not view the ledger.
+    async fn export_expenses(
+        &self,
+        principal: &Principal,
+        req: &ListExpensesRequest,
+    ) -> Result<ExpenseStream, ListExpensesError> {
+        let resource = Resource::expense(req.ledger_id().copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        Ok(self.repo.stream_expenses(principal.user_id(), req))
+    }
+
+    /// Store the content of the file of `req`, then record it as an [Attachment] of its
+    /// [Expense]. The content is removed again if the [Attachment] cannot be recorded.
+    ///
+    /// # Errors
+    ///
+    /// - [AttachmentError::ExpenseNotFound] if the [Expense] does not exist or, for a personal
+    ///   expense, is recorded against an account of someone else.
+    /// - [AttachmentError::Policy] if the principal may not update the ledger's expenses.
+    /// - Propagates any [AttachmentError] returned by the [AttachmentStorage] or the
+    ///   [AttachmentRepository].
+    async fn add_attachment(
+        &self,
+        principal: &Principal,
+        req: &CreateAttachmentRequest,
+    ) -> Result<Attachment, AttachmentError> {
+        self.accessible_expense(principal, Action::Update, req.expense_id())
+            .await?
+            .ok_or(AttachmentError::ExpenseNotFound {
+                id: *req.expense_id(),
+            })?;
+        let attachment = Attachment::new(
+            Uuid::new_v4(),
+            *req.expense_id(),
+            req.file_name(),
+            req.content_type(),
+            req.content().len() as u64,
+            Utc::now(),
+        );
+        let key = attachment.storage_key();
+        self.storage
+            .put(&key, req.content_type(), req.content())
+            .await?;
+        if let Err(e) = self.repo.create_attachment(&attachment).await {
+            if let Err(e) = self.storage.delete(&key).await {
+                tracing::warn!("Failed to remove unrecorded attachment {}: {}", key, e);
+            }
+            return Err(e);
+        }
+        Ok(attachment)
+    }
+
+    /// Retrieve an [Attachment] of an [Expense] and the content of `variant`.
+    ///
+    /// # Errors
+    ///
+    /// - [AttachmentError::ExpenseNotFound] if the [Expense] does not exist or, for a personal
+    ///   expense, is recorded against an account of someone else.
+    /// - [AttachmentError::NotFound] if the [Attachment] does not exist, belongs to another
+    ///   [Expense], its thumbnail was asked for and is not [ThumbnailStatus::Ready], or the
+    ///   content is missing from the [AttachmentStorage].
+    /// - [AttachmentError::Policy] if the principal may not view the ledger's expenses.
+    async fn get_attachment(
+        &self,
+        principal: &Principal,
+        expense_id: &Uuid,
+        attachment_id: &Uuid,
+        variant: AttachmentVariant,
+    ) -> Result<AttachmentFile, AttachmentError> {
+        self.accessible_expense(principal, Action::View, expense_id)
+            .await?
+            .ok_or(AttachmentError::ExpenseNotFound { id: *expense_id })?;
+        let not_found = || AttachmentError::NotFound { id: *attachment_id };
+        let attachment = self
+            .repo
+            .find_attachment(attachment_id)
+            .await?
+            .filter(|attachment| attachment.expense_id() == expense_id)
+            .filter(|attachment| {
+                variant == AttachmentVariant::Original
+                    || attachment.thumbnail_status() == ThumbnailStatus::Ready
+            })
+            .ok_or_else(not_found)?;
+        let content = self
+            .storage
+            .get(&attachment.variant_key(variant))
+            .await?
+            .ok_or_else(not_found)?;
+        Ok(AttachmentFile::new(attachment, content))
+    }
+
+    /// Render and store the thumbnails of the pending attachments, marking those that fail as
+    /// unavailable so that they do not hold back the rest of the batch.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [AttachmentError] returned by the [AttachmentRepository].
+    async fn generate_thumbnails(&self, limit: usize) -> Result<usize, AttachmentError> {
+        let pending = self.repo.list_pending_thumbnails(limit).await?;
+        for attachment in &pending {
+            let status = match self.render_thumbnail(attachment).await {
+                Ok(status) => status,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to generate the thumbnail of attachment {}: {}",
+                        attachment.id(),
+                        e
+                    );
+                    ThumbnailStatus::Unavailable
+                }
+            };
+            self.repo
+                .set_thumbnail_status(attachment.id(), status)
+                .await?;
+        }
+        Ok(pending.len())
+    }
+
+    /// Create the [Ledger] specified in `req`, making the requesting user its owner.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [LedgerError] returned by the [LedgerRepository].
+    async fn create_ledger(
+        &self,
+        principal: &Principal,
+        req: &CreateLedgerRequest,
+    ) -> Result<Ledger, LedgerError> {
+        self.authorize(Some(principal), Action::Create, Resource::new_ledger())
+            .await?;
+        self.repo.create_ledger(req).await
+    }
+
+    /// Retrieve a [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - [LedgerError::Policy] if the principal is not a member of the [Ledger].
+    /// - [LedgerError::NotFound] if the [LedgerRepository] has no such [Ledger].
+    async fn get_ledger(&self, principal: &Principal, id: &Uuid) -> Result<Ledger, LedgerError> {
+        self.authorize(Some(principal), Action::View, Resource::ledger(*id))
+            .await?;
+        self.repo
+            .find_ledger(id)
+            .await?
+            .ok_or(LedgerError::NotFound { id: *id })
+    }
+
+    /// List the [Ledger]s a user belongs to.
+    async fn list_ledgers(&self, user_id: &Uuid) -> Result<Vec<Ledger>, LedgerError> {
+        self.repo.list_ledgers_for_user(user_id).await
+    }
+
+    /// List the members of a [Ledger].
+    async fn list_ledger_members(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> Result<Vec<LedgerMember>, LedgerError> {
+        self.authorize(Some(principal), Action::View, Resource::ledger(*ledger_id))
+            .await?;
+        self.repo.list_ledger_members(ledger_id).await
+    }
+
+    /// Issue an invitation to the [Ledger] specified in `req`.
+    async fn create_invitation(
+        &self,
+        principal: &Principal,
+        req: &CreateInvitationRequest,
+    ) -> Result<LedgerInvitation, InvitationError> {
+        let resource = Resource::ledger(*req.ledger_id());
+        self.authorize(Some(principal), Action::Invite, resource)
+            .await?;
+        self.repo.create_invitation(req).await
+    }
+
+    /// Compute who owes whom within a [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any error returned by the repositories.
+    async fn ledger_balances(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> Result<LedgerBalances, LedgerError> {
+        self.authorize(Some(principal), Action::View, Resource::ledger(*ledger_id))
+            .await?;
+        self.balances(ledger_id).await
+    }
+
+    /// Compute the transfers that would settle a [Ledger], based on its current balances.
+    async fn settle_up(
+        &self,
+        principal: &Principal,
+        ledger_id: &Uuid,
+    ) -> Result<Vec<Debt>, LedgerError> {
+        self.authorize(Some(principal), Action::View, Resource::ledger(*ledger_id))
+            .await?;
+        Ok(self.balances(ledger_id).await?.simplified_transfers())
+    }
+
+    /// Record a [Settlement] between two members of a [Ledger].
+    ///
+    /// # Errors
+    ///
+    /// - [SettlementError::Policy] if the principal may not settle in the [Ledger].
+    /// - [SettlementError::NotLedgerMember] if either party does not belong to the [Ledger].
+    /// - Propagates any [SettlementError] returned by the [SettlementRepository].
+    async fn record_settlement(
+        &self,
+        principal: &Principal,
+        req: &RecordSettlementRequest,
+    ) -> Result<Settlement, SettlementError> {
+        let resource = Resource::ledger(*req.ledger_id());
+        self.authorize(Some(principal), Action::Settle, resource)
+            .await?;
+        let members = self
+            .repo
+            .list_ledger_members(req.ledger_id())
+            .await
+            .map_err(|e| anyhow!("Failed to list ledger members: {}", e))?;
+        for user_id in [req.from(), req.to()] {
+            if !members.iter().any(|m| m.user_id() == user_id) {
+                return Err(SettlementError::NotLedgerMember {
+                    ledger_id: *req.ledger_id(),
+                    user_id: *user_id,
+                });
+            }
+        }
+        self.repo.record_settlement(req).await
+    }
+
+    /// Redeem an invitation token.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [InvitationError] returned by the [LedgerRepository].
+    async fn accept_invitation(
+        &self,
+        req: &AcceptInvitationRequest,
+    ) -> Result<LedgerMember, InvitationError> {
+        self.repo.accept_invitation(req).await
+    }
+
+    /// Create the [Category] specified in `req`.
+    ///
+    /// # Errors
+    ///
+    /// - [CategoryError::Policy] if the principal may not create categories in the ledger.
+    /// - Propagates any [CategoryError] returned by the [CategoryRepository].
+    async fn create_category(
+        &self,
+        principal: &Principal,
+        req: &CreateCategoryRequest,
+    ) -> Result<Category, CategoryError> {
+        let resource = Resource::category(req.ledger_id().copied());
+        self.authorize(Some(principal), Action::Create, resource)
+            .await?;
+        self.repo.create_category(req).await
+    }
+
+    /// List the [Category]s visible in the requested scope.
+    ///
+    /// # Errors
+    ///
+    /// - [CategoryError::Policy] if the principal may not view the ledger.
+    async fn list_categories(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<Category>, CategoryError> {
+        let resource = Resource::category(ledger_id.copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        self.repo
+            .list_categories(principal.user_id(), ledger_id)
+            .await
+    }
+
+    /// Set the [Budget] specified in `req` on a category visible to the principal.
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::CategoryNotFound] if the [CategoryRepository] has no such category.
+    /// - [BudgetError::Policy] if the principal may not budget in the category's ledger, or does
+    ///   not own the personal category.
+    /// - Propagates any [BudgetError] returned by the [BudgetRepository].
+    async fn create_budget(
+        &self,
+        principal: &Principal,
+        req: &CreateBudgetRequest,
+    ) -> Result<Budget, BudgetError> {
+        let category = self
+            .repo
+            .find_category(req.category_id())
+            .await
+            .map_err(|e| anyhow!("Failed to find category: {}", e))?
+            .ok_or(BudgetError::CategoryNotFound {
+                id: *req.category_id(),
+            })?;
+        let resource =
+            Resource::budget(category.ledger_id().copied()).owned_by(*category.owner_id());
+        self.authorize(Some(principal), Action::Create, resource)
+            .await?;
+        self.repo.create_budget(req, &category).await
+    }
+
+    /// Retrieve a [Budget].
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::NotFound] if the [BudgetRepository] has no such [Budget].
+    /// - [BudgetError::Policy] if the [Budget] is not visible to the principal.
+    async fn get_budget(&self, principal: &Principal, id: &Uuid) -> Result<Budget, BudgetError> {
+        self.authorized_budget(principal, Action::View, id).await
+    }
+
+    /// List the [Budget]s visible in the requested scope.
+    async fn list_budgets(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<Budget>, BudgetError> {
+        let resource = Resource::budget(ledger_id.copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        self.repo.list_budgets(principal.user_id(), ledger_id).await
+    }
+
+    /// Change the amount, currency or period of a [Budget].
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::NotFound] if the [BudgetRepository] has no such [Budget].
+    /// - [BudgetError::Policy] if the principal may not change the [Budget].
+    async fn update_budget(
+        &self,
+        principal: &Principal,
+        req: &UpdateBudgetRequest,
+    ) -> Result<Budget, BudgetError> {
+        let budget = self
+            .authorized_budget(principal, Action::Update, req.id())
+            .await?;
+        self.repo
+            .update_budget(&budget, req)
+            .await?
+            .ok_or(BudgetError::NotFound { id: *req.id() })
+    }
+
+    /// Delete a [Budget].
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::NotFound] if the [BudgetRepository] has no such [Budget].
+    /// - [BudgetError::Policy] if the principal may not delete the [Budget].
+    async fn delete_budget(&self, principal: &Principal, id: &Uuid) -> Result<(), BudgetError> {
+        self.authorized_budget(principal, Action::Delete, id)
+            .await?;
+        match self.repo.delete_budget(id).await? {
+            true => Ok(()),
+            false => Err(BudgetError::NotFound { id: *id }),
+        }
+    }
+
+    /// Compare what was spent in the budgeted category during the period containing `on` with
+    /// the [Budget]. The total is aggregated by the [BudgetRepository], in the currency of the
+    /// [Budget].
+    ///
+    /// # Errors
+    ///
+    /// - [BudgetError::NotFound] if the [BudgetRepository] has no such [Budget].
+    /// - [BudgetError::Policy] if the [Budget] is not visible to the principal.
+    /// - [BudgetError::MissingExchangeRate] if an expense cannot be converted to the currency of
+    ///   the [Budget].
+    async fn budget_status(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+        on: NaiveDate,
+    ) -> Result<BudgetStatus, BudgetError> {
+        let budget = self.authorized_budget(principal, Action::View, id).await?;
+        let (start, end) = budget.period().window(on);
+        let spent = self
+            .repo
+            .sum_category_spending(budget.category_id(), budget.currency(), start, end)
+            .await?;
+        Ok(BudgetStatus::new(budget, start, end, spent))
+    }
+
+    /// Set up the [RecurringExpense] specified in `req`. Nothing is created until the scheduler
+    /// next materializes occurrences.
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::Policy] if the principal may not create expenses in the ledger.
+    /// - [RecurringExpenseError::CategoryNotFound] if the category does not exist where the
+    ///   expenses will live.
+    /// - [RecurringExpenseError::AccountRequired] if no account is given, and
+    ///   [RecurringExpenseError::AccountNotFound] if it does not belong to the principal.
+    /// - Propagates any [RecurringExpenseError] returned by the [RecurringExpenseRepository].
+    async fn create_recurring_expense(
+        &self,
+        principal: &Principal,
+        req: &CreateRecurringExpenseRequest,
+    ) -> Result<RecurringExpense, RecurringExpenseError> {
+        let resource = Resource::recurring_expense(req.ledger_id().copied());
+        self.authorize(Some(principal), Action::Create, resource)
+            .await?;
+        if let Some(category_id) = req.category_id()
+            && !self
+                .category_visible(Some(principal), req.ledger_id(), category_id)
+                .await?
+        {
+            return Err(RecurringExpenseError::CategoryNotFound { id: *category_id });
+        }
+        let account_id = req
+            .account_id()
+            .ok_or(RecurringExpenseError::AccountRequired)?;
+        if self
+            .owned_account(Some(principal), account_id)
+            .await?
+            .is_none()
+        {
+            return Err(RecurringExpenseError::AccountNotFound { id: *account_id });
+        }
+        self.repo.create_recurring_expense(req).await
+    }
+
+    /// List the [RecurringExpense]s visible in the requested scope.
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::Policy] if the principal may not view the ledger.
+    async fn list_recurring_expenses(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<RecurringExpense>, RecurringExpenseError> {
+        let resource = Resource::recurring_expense(ledger_id.copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        self.repo
+            .list_recurring_expenses(principal.user_id(), ledger_id)
+            .await
+    }
+
+    /// Delete a [RecurringExpense].
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::NotFound] if the [RecurringExpense] does not exist.
+    /// - [RecurringExpenseError::Policy] if the principal may not delete it.
+    async fn delete_recurring_expense(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+    ) -> Result<(), RecurringExpenseError> {
+        self.authorized_recurring_expense(principal, Action::Delete, id)
+            .await?;
+        if !self.repo.delete_recurring_expense(id).await? {
+            return Err(RecurringExpenseError::NotFound { id: *id });
+        }
+        Ok(())
+    }
+
+    /// List the upcoming [Occurrence]s in the requested scope.
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::RangeTooLong] if `[from, until]` spans more than
+    ///   [MAX_UPCOMING_DAYS].
+    /// - [RecurringExpenseError::Policy] if the principal may not view the ledger.
+    async fn upcoming_occurrences(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<Occurrence>, RecurringExpenseError> {
+        let days = (until - from).num_days();
+        if days > MAX_UPCOMING_DAYS {
+            return Err(RecurringExpenseError::RangeTooLong {
+                days,
+                max: MAX_UPCOMING_DAYS,
+            });
+        }
+        let mut occurrences: Vec<Occurrence> = self
+            .list_recurring_expenses(principal, ledger_id)
+            .await?
+            .into_iter()
+            .flat_map(|recurring| {
+                recurring
+                    .recurrence()
+                    .occurrences_from(from)
+                    .take_while(|day| *day <= until)
+                    .map(|day| Occurrence::new(day, recurring.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        occurrences.sort();
+        Ok(occurrences)
+    }
+
+    /// Materialize the due occurrences of every [RecurringExpense].
+    ///
+    /// # Errors
+    ///
+    /// - [RecurringExpenseError::MaterializationFailed] if the occurrences of some recurring
+    ///   expenses could not all be created, once every other recurring expense was
+    ///   materialized. The failed ones are retried on the next call.
+    /// - Propagates any [RecurringExpenseError] returned by the [RecurringExpenseRepository]
+    ///   while listing the due recurring expenses.
+    async fn materialize_recurring_expenses(
+        &self,
+        today: NaiveDate,
+    ) -> Result<usize, RecurringExpenseError> {
+        let mut created = 0;
+        let mut failed = 0;
+        for recurring in self.repo.list_due_recurring_expenses(today).await? {
+            if let Err(e) = self.materialize(&recurring, today, &mut created).await {
+                tracing::error!(
+                    "Failed to materialize recurring expense {}: {:?}",
+                    recurring.id(),
+                    e
+                );
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            return Err(RecurringExpenseError::MaterializationFailed { failed, created });
+        }
+        Ok(created)
+    }
+
+    /// Open an [Account] for the caller.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [AccountError] returned by the [AccountRepository].
+    async fn create_account(
+        &self,
+        principal: &Principal,
+        req: &CreateAccountRequest,
+    ) -> Result<Account, AccountError> {
+        self.authorize(Some(principal), Action::Create, Resource::account())
+            .await?;
+        self.repo.create_account(req).await
+    }
+
+    /// List the caller's [Account]s.
+    async fn list_accounts(&self, principal: &Principal) -> Result<Vec<Account>, AccountError> {
+        self.authorize(Some(principal), Action::View, Resource::account())
+            .await?;
+        self.repo.list_accounts(principal.user_id()).await
+    }
+
+    /// Retrieve an [Account] with its current balance and recent history.
+    ///
+    /// # Errors
+    ///
+    /// - [AccountError::RangeTooLong] if `[from, until]` spans more than
+    ///   [MAX_BALANCE_HISTORY_DAYS].
+    /// - [AccountError::NotFound] if the [Account] does not exist.
+    /// - [AccountError::Policy] if the principal does not own it.
+    /// - [AccountError::MissingExchangeRate] if a balance cannot be converted to `currency`.
+    async fn account_balance(
+        &self,
+        principal: &Principal,
+        id: &Uuid,
+        currency: Option<&Currency>,
+        today: NaiveDate,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<AccountBalance, AccountError> {
+        let days = (until - from).num_days();
+        if days > MAX_BALANCE_HISTORY_DAYS {
+            return Err(AccountError::RangeTooLong {
+                days,
+                max: MAX_BALANCE_HISTORY_DAYS,
+            });
+        }
+        let account = self
+            .repo
+            .find_account(id)
+            .await?
+            .ok_or(AccountError::NotFound { id: *id })?;
+        let resource = Resource::account().owned_by(*account.owner_id());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        let currency = currency.unwrap_or(account.currency()).clone();
+        let balance = self
+            .repo
+            .account_balance(&account, &currency, today)
+            .await?;
+        let history = self
+            .repo
+            .account_balance_history(&account, &currency, from, until)
+            .await?;
+        Ok(AccountBalance::new(account, currency, balance, history))
+    }
+
+    /// Store [ExchangeRate]s, typically read from an ECB reference rates file.
+    ///
+    /// # Errors
+    ///
+    /// - Propagates any [ExchangeRateError] returned by the [ExchangeRateRepository].
+    async fn import_exchange_rates(
+        &self,
+        rates: &[ExchangeRate],
+    ) -> Result<usize, ExchangeRateError> {
+        let saved = self.repo.save_exchange_rates(rates).await?;
+        tracing::info!("Imported {} exchange rates", saved);
+        Ok(saved)
+    }
+
+    /// Import transactions into one of the caller's accounts. Expenses and incomes are told
+    /// apart by the sign of their amount, and categories are matched by name among the caller's
+    /// personal ones.
+    ///
+    /// Transactions carrying an import identifier already recorded for the account are skipped.
+    /// Imported transactions are saved without notifications nor budget alerts, which would
+    /// mostly concern past periods.
+    ///
+    /// # Errors
+    ///
+    /// - [ImportError::AccountNotFound] if the account does not exist or belongs to someone
+    ///   else.
+    /// - [ImportError::Policy] if the principal may not create expenses.
+    /// - Propagates any [ImportError] returned by the [ExpenseRepository].
+    async fn import_transactions(
+        &self,
+        principal: &Principal,
+        req: &ImportRequest,
+    ) -> Result<ImportReport, ImportError> {
+        self.authorize(Some(principal), Action::Create, Resource::expense(None))
+            .await?;
+        let account = self
+            .owned_account(Some(principal), req.account_id())
+            .await?
+            .ok_or(ImportError::AccountNotFound {
+                id: *req.account_id(),
+            })?;
+        let categories: HashMap<String, Uuid> = self
+            .repo
+            .list_categories(principal.user_id(), None)
+            .await
+            .map_err(|e| anyhow!("Failed to list categories: {}", e))?
+            .iter()
+            .map(|c| (c.name().to_string().to_lowercase(), *c.id()))
+            .collect();
+
+        let mut valid = Vec::new();
+        let mut errors = Vec::new();
+        for row in req.rows() {
+            match row
+                .clone()
+                .and_then(|transaction| import_request(&account, &categories, &transaction))
+            {
+                Ok(expense) => valid.push(expense),
+                Err(e) => errors.push(e),
+            }
+        }
+        // Transactions already imported, or repeated in the file, are skipped.
+        let import_ids: Vec<&str> = valid
+            .iter()
+            .filter_map(CreateExpenseRequest::import_id)
+            .collect();
+        let mut seen = if import_ids.is_empty() {
+            HashSet::new()
+        } else {
+            self.repo.find_import_ids(account.id(), &import_ids).await?
+        };
+        let before = valid.len();
+        valid.retain(|expense| {
+            expense
+                .import_id()
+                .is_none_or(|import_id| seen.insert(import_id.to_string()))
+        });
+        let rules = self
+            .repo
+            .list_rules(principal.user_id())
+            .await
+            .map_err(|e| anyhow!("Failed to list rules: {}", e))?;
+        let mut valid: Vec<CreateExpenseRequest> = valid
+            .into_iter()
+            .map(|expense| classify_request(&rules, expense))
+            .collect();
+        // Rows looking like transactions already recorded, though under another import id or
+        // none, are skipped too when the caller blocks duplicates.
+        let settings = self
+            .duplicate_settings(principal)
+            .await
+            .map_err(|e| anyhow!("Failed to find duplicate settings: {}", e))?;
+        if settings.mode() == DuplicateMode::Block
+            && let (Some(first), Some(last)) = (
+                valid.iter().map(|r| *r.spent_on()).min(),
+                valid.iter().map(|r| *r.spent_on()).max(),
+            )
+        {
+            let existing = self
+                .repo
+                .list_duplicate_candidates(
+                    principal.user_id(),
+                    None,
+                    Some(settings.window(first).0),
+                    Some(settings.window(last).1),
+                )
+                .await
+                .map_err(|e| anyhow!("Failed to look for duplicates: {}", e))?;
+            valid.retain(|expense| {
+                let subject = DuplicateSubject::from(expense);
+                !existing
+                    .iter()
+                    .any(|e| settings.is_duplicate(&subject, &e.into()))
+            });
+        }
+        let skipped = before - valid.len();
+
+        let imported = if req.is_dry_run() || valid.is_empty() {
+            valid.len()
+        } else {
+            self.repo.create_expenses(&valid).await?.len()
+        };
+        tracing::info!(
+            "Imported {} of {} rows into account {}, skipping {}{}",
+            imported,
+            req.rows().len(),
+            account.id(),
+            skipped,
+            if req.is_dry_run() { " (dry run)" } else { "" }
+        );
+        Ok(ImportReport::new(
+            req.is_dry_run(),
+            req.rows().len(),
+            imported,
+            skipped,
+            errors,
+        ))
+    }
+
+    /// Retrieve the caller's [DuplicateSettings].
+    async fn get_duplicate_settings(
+        &self,
+        principal: &Principal,
+    ) -> Result<DuplicateSettings, DuplicateError> {
+        self.authorize(Some(principal), Action::View, Resource::expense(None))
+            .await?;
+        self.duplicate_settings(principal).await
+    }
+
+    /// Save the caller's [DuplicateSettings].
+    async fn update_duplicate_settings(
+        &self,
+        principal: &Principal,
+        settings: &DuplicateSettings,
+    ) -> Result<DuplicateSettings, DuplicateError> {
+        self.authorize(Some(principal), Action::Update, Resource::expense(None))
+            .await?;
+        self.repo
+            .save_duplicate_settings(principal.user_id(), settings)
+            .await?;
+        Ok(*settings)
+    }
+
+    /// Cluster the suspected duplicates among the expenses visible in the requested scope.
+    ///
+    /// # Errors
+    ///
+    /// - [DuplicateError::Policy] if the principal may not view the ledger.
+    async fn list_duplicates(
+        &self,
+        principal: &Principal,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<DuplicateCluster>, DuplicateError> {
+        let resource = Resource::expense(ledger_id.copied());
+        self.authorize(Some(principal), Action::View, resource)
+            .await?;
+        let settings = self.duplicate_settings(principal).await?;
+        let expenses = self
+            .repo
+            .list_duplicate_candidates(principal.user_id(), ledger_id, None, None)
+            .await?;
+        Ok(settings.clusters(expenses))
+    }
+
+    /// Merge suspected duplicates into one expense, which the principal must be allowed to
+    /// change, while being allowed to delete the others.
+    ///
+    /// # Errors
+    ///
+    /// - [DuplicateError::ExpenseNotFound] if an expense does not exist or is not visible.
+    /// - [DuplicateError::ScopeMismatch] if the expenses do not all live in the same place.
+    /// - [DuplicateError::HasAttachments] if a duplicate has attachments.
+    /// - [DuplicateError::Policy] if the principal may not change or delete the expenses.
+    /// - Propagates any [DuplicateError] returned by the [DuplicateRepository].
+    async fn merge_duplicates(
+        &self,
+        principal: &Principal,
+        req: &MergeDuplicatesRequest,
+    ) -> Result<Expense, DuplicateError> {
+        let keep = self
+            .mergeable_expense(principal, Action::Update, req.keep_id())
+            .await?;
+        let mut duplicates = Vec::with_capacity(req.duplicate_ids().len());
+        for id in req.duplicate_ids() {
+            let duplicate = self
+                .mergeable_expense(principal, Action::Delete, id)
+                .await?;
+            if duplicate.ledger_id() != keep.ledger_id() {
+                return Err(DuplicateError::ScopeMismatch);
+            }
+            if !duplicate.attachments().is_empty() {
+                return Err(DuplicateError::HasAttachments { id: *id });
+            }
+            duplicates.push(duplicate);
+        }
+        let merge = DuplicateMerge::new(keep, &duplicates);
+        self.repo.merge_duplicates(&merge).await?;
+        Ok(merge.expense().clone())
+    }
+
+    /// Reserve `key` for the caller, keys of different callers never colliding.
+    ///
+    /// # Errors
+    ///
+    /// - [IdempotencyError::KeyReused] if the key was used for another request.
+    /// - [IdempotencyError::InProgress] if the same request is still being processed.
+    /// - Propagates any [IdempotencyError] returned by the [IdempotencyRepository].
+    async fn claim_idempotency_key(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> Result<Option<StoredResponse>, IdempotencyError> {
+        let req = ClaimIdempotencyKeyRequest::new(
+            *principal.user_id(),
+            key.clone(),
+            fingerprint.clone(),
+            Utc::now(),
+        );
+        match self.repo.claim_idempotency_key(&req).await? {
+            IdempotencyClaim::Acquired => Ok(None),
+            IdempotencyClaim::Replay(response) => {
+                tracing::info!("Replaying the response to idempotency key {}", key);
+                Ok(Some(response))
+            }
+            IdempotencyClaim::InProgress => Err(IdempotencyError::InProgress { key: key.clone() }),
+            IdempotencyClaim::Mismatch => Err(IdempotencyError::KeyReused { key: key.clone() }),
+        }
+    }
+
+    async fn complete_idempotent_request(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+        response: &StoredResponse,
+    ) -> Result<(), IdempotencyError> {
+        self.repo
+            .save_idempotent_response(principal.user_id(), key, response)
+            .await
+    }
+
+    async fn release_idempotency_key(
+        &self,
+        principal: &Principal,
+        key: &IdempotencyKey,
+    ) -> Result<(), IdempotencyError> {
+        self.repo
+            .release_idempotency_key(principal.user_id(), key)
+            .await
+    }
+
+    async fn purge_idempotency_keys(&self, now: DateTime<Utc>) -> Result<usize, IdempotencyError> {
+        self.repo.delete_expired_idempotency_keys(now).await
+    }
+
+    /// Set up the [Rule] specified in `req` for the caller.
+    ///
+    /// # Errors
+    ///
+    /// - [RuleError::CategoryNotFound] if the category is not one of the caller's personal
+    ///   categories.
+    /// - [RuleError::AccountNotFound] if the account does not belong to the caller.
+    /// - Propagates any [RuleError] returned by the [RuleRepository].
+    async fn create_rule(
+        &self,
+        principal: &Principal,
+        req: &CreateRuleRequest,
+    ) -> Result<Rule, RuleError> {
+        self.authorize(Some(principal), Action::Create, Resource::rule())
+            .await?;
+        if let Some(category_id) = req.actions().category_id()
+            && !self
+                .category_visible(Some(principal), None, category_id)
+                .await?
+        {
+            return Err(RuleError::CategoryNotFound { id: *category_id });
+        }
+        if let Some(account_id) = req.conditions().account_id()
+            && self
+                .owned_account(Some(principal), account_id)
+                .await?
+                .is_none()
+        {
+            return Err(RuleError::AccountNotFound { id: *account_id });
+        }
+        self.repo.create_rule(req).await
+    }
+
+    /// List the caller's [Rule]s.
+    async fn list_rules(&self, principal: &Principal) -> Result<Vec<Rule>, RuleError> {
+        self.authorize(Some(principal), Action::View, Resource::rule())
+            .await?;
+        self.repo.list_rules(principal.user_id()).await
+    }
+
+    /// Delete a [Rule].
+    ///
+    /// # Errors
+    ///
+    /// - [RuleError::NotFound] if the [RuleRepository] has no such [Rule].
+    /// - [RuleError::Policy] if the principal does not own it.
+    async fn delete_rule(&self, principal: &Principal, id: &Uuid) -> Result<(), RuleError> {
+        let rule = self
+            .repo
+            .find_rule(id)
+            .await?
+            .ok_or(RuleError::NotFound { id: *id })?;
+        let resource = Resource::rule().owned_by(*rule.owner_id());
+        self.authorize(Some(principal), Action::Delete, resource)
+            .await?;
+        match self.repo.delete_rule(id).await? {
+            true => Ok(()),
+            false => Err(RuleError::NotFound { id: *id }),
+        }
+    }
+
+    /// Match the caller's personal expenses against their [Rule]s, saving the changes unless
+    /// `dry_run` is set.
+    ///
+    /// # Errors
+    ///
+    /// - [RuleError::Policy] if the principal may not change expenses.
+    /// - Propagates any [RuleError] returned by the [RuleRepository].
+    async fn apply_rules(
+        &self,
+        principal: &Principal,
+        dry_run: bool,
+    ) -> Result<RuleApplicationReport, RuleError> {
+        let action = if dry_run {
+            Action::View
+        } else {
+            Action::Update
+        };
+        self.authorize(Some(principal), action, Resource::rule())
+            .await?;
+        let rules = self.repo.list_rules(principal.user_id()).await?;
+        let expenses = self.repo.list_rule_candidates(principal.user_id()).await?;
+        let changes: Vec<RuleChange> = expenses
+            .iter()
+            .filter_map(|expense| {
+                let before = Classification::from(expense);
+                let (after, rule_ids) = before.apply(&rules, &RuleSubject::from(expense));
+                (after != before).then(|| RuleChange::new(*expense.id(), rule_ids, before, after))
+            })
+            .collect();
+        if !dry_run && !changes.is_empty() {
+            self.repo.save_rule_changes(&changes).await?;
+        }
+        tracing::info!(
+            "Rules changed {} of {} expenses of user {}{}",
+            changes.len(),
+            expenses.len(),
+            principal.user_id(),
+            if dry_run { " (dry run)" } else { "" }
+        );
+        Ok(RuleApplicationReport::new(dry_run, expenses.len(), changes))
+    }
+
+    /// Summarize spending, checking the principal may view the expenses of the ledger when one
+    /// is targeted.
+    ///
+    /// # Errors
+    ///
+    /// - [ReportError::InvalidRange] if the range starts after it ends.
+    /// - [ReportError::Policy] if the principal may not view the ledger's expenses.
+    /// - Propagates any [ReportError] returned by the [ReportRepository].
+    async fn spending_summary(
+        &self,
+        principal: &Principal,
+        req: &SummaryRequest,
+    ) -> Result<Vec<SummaryBucket>, ReportError> {
+        if let (Some(from), Some(to)) = (req.from(), req.to())
+            && from > to
+        {
+            return Err(ReportError::InvalidRange {
+                from: *from,
+                to: *to,
+            });
+        }
+        let scope = self
+            .authorized_report_scope(principal, req.ledger_id())
+            .await?;
+        self.repo.spending_summary(&scope, req).await
+    }
+
+    /// Compute spending over time, checking the principal may view the expenses of the ledger
+    /// when one is targeted.
+    ///
+    /// # Errors
+    ///
+    /// - [ReportError::InvalidRange] if the range starts after it ends.
+    /// - [ReportError::TooManyPoints] if the series would hold more than [MAX_SERIES_POINTS].
+    /// - [ReportError::Policy] if the principal may not view the ledger's expenses.
+    /// - Propagates any [ReportError] returned by the [ReportRepository].
+    async fn spending_timeseries(
+        &self,
+        principal: &Principal,
+        req: &TimeseriesRequest,
+    ) -> Result<Vec<Series>, ReportError> {
+        if req.from() > req.to() {
+            return Err(ReportError::InvalidRange {
+                from: *req.from(),
+                to: *req.to(),
+            });
+        }
+        if req.points() > MAX_SERIES_POINTS {
+            return Err(ReportError::TooManyPoints {
+                points: req.points(),
+                max: MAX_SERIES_POINTS,
+            });
+        }
+        let scope = self
+            .authorized_report_scope(principal, req.ledger_id())
+            .await?;
+        self.repo.spending_timeseries(&scope, req).await
+    }
+}
+
+/// Turn an imported transaction into a request to record it against `account`, resolving its
+/// category among `categories`, keyed by lowercase name.
+fn import_request(
+    account: &Account,
+    categories: &HashMap<String, Uuid>,
+    transaction: &ImportedTransaction,
+) -> Result<CreateExpenseRequest, ImportRowError> {
+    let line = transaction.line();
+    let amount = transaction
+        .amount()
+        .to_minor_units(account.currency())
+        .ok_or_else(|| {
+            ImportRowError::new(
+                line,
+                format!(
+                    "amount {} cannot be expressed in minor units of {}",
+                    transaction.amount(),
+                    account.currency()
+                ),
+            )
+        })?;
+    let kind = if transaction.amount().is_negative() {
+        TransactionKind::Expense
+    } else {
+        TransactionKind::Income
+    };
+    let mut req = CreateExpenseRequest::new(&transaction.name().to_string())
+        .map_err(|e| ImportRowError::new(line, e))?
+        .with_kind(kind)
+        .with_amount(amount.abs())
+        .map_err(|e| ImportRowError::new(line, e))?
+        .with_spent_on(*transaction.spent_on())
+        .with_account(*account.id());
+    if let Some(import_id) = transaction.import_id() {
+        req = req.with_import_id(import_id);
+    }
+    if let Some(counterparty) = transaction.counterparty() {
+        req = req.with_counterparty(counterparty);
+    }
+    if let Some(reference) = transaction.reference() {
+        req = req.with_reference(reference);
+    }
+    if let Some(category) = transaction.category() {
+        let category_id = categories
+            .get(&category.to_lowercase())
+            .ok_or_else(|| ImportRowError::new(line, format!("category {category:?} not found")))?;
+        req = req.with_category(*category_id);
+    }
+    Ok(req)
+}
+
+/// Apply `rules` to an expense about to be created, returning it renamed, filed and tagged as
+/// they direct.
+fn classify_request(rules: &[Rule], req: CreateExpenseRequest) -> CreateExpenseRequest {
+    let (classified, _) = Classification::from(&req).apply(rules, &RuleSubject::from(&req));
+    let req = req
+        .with_name(classified.name().clone())
+        .with_tags(classified.tags().to_vec());
+    match classified.category_id() {
+        Some(category_id) => req.with_category(*category_id),
+        None => req,
+    }
+}
+
+/// The items named `name`, compared case-insensitively, or else those whose name starts with
+/// it.
+fn match_by_name<'a, T>(items: &'a [T], name: &str, name_of: impl Fn(&T) -> String) -> Vec<&'a T> {
+    let name = name.to_lowercase();
+    let exact: Vec<&T> = items
+        .iter()
+        .filter(|item| name_of(item).to_lowercase() == name)
+        .collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    items
+        .iter()
+        .filter(|item| name_of(item).to_lowercase().starts_with(&name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::finance::models::account::{AccountKind, AccountName};
+    use crate::domain::finance::models::attachment::AttachmentContentType;
+    use crate::domain::finance::models::budget::{BudgetPeriod, BudgetThreshold};
+    use crate::domain::finance::models::category::CategoryName;
+    use crate::domain::finance::models::expense::ExpenseName;
+    use crate::domain::finance::models::ledger::LedgerRole;
+    use crate::domain::finance::models::recurring::{Frequency, Recurrence};
+    use crate::domain::finance::models::split::{SplitMethod, SplitParticipant};
+    use crate::domain::finance::testing::{MockExpenseRepository, MockNotifier};
+    use crate::outbound::prometheus::Prometheus;
+    use crate::outbound::storage::local::LocalStorage;
+    use crate::outbound::thumbnail::Thumbnailer;
+    use image::ImageEncoder;
+
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn account(owner_id: Uuid) -> Account {
+        Account::new(
+            Uuid::new_v4(),
+            owner_id,
+            AccountName::new("Checking").unwrap(),
+            AccountKind::Bank,
+            Currency::new("EUR").unwrap(),
+            0,
+            Utc::now(),
+        )
+    }
+
+    fn service(
+        repo: MockExpenseRepository,
+        notifier: MockNotifier,
+    ) -> Service<MockExpenseRepository, Prometheus, MockNotifier, LocalStorage, Thumbnailer> {
+        Service::new(
+            repo,
+            Prometheus::new(),
+            notifier,
+            LocalStorage::new(std::env::temp_dir()),
+            Thumbnailer::pictures_only(),
+        )
+    }
+
+    fn category(owner_id: Uuid) -> Category {
+        Category::new(
+            Uuid::new_v4(),
+            CategoryName::new("Groceries").unwrap(),
+            None,
+            owner_id,
+            Utc::now(),
+        )
+    }
+
+    fn monthly_budget(category: &Category, amount: i64) -> Budget {
+        Budget::new(
+            Uuid::new_v4(),
+            *category.id(),
+            None,
+            *category.owner_id(),
+            amount,
+            BudgetPeriod::Monthly,
+            Utc::now(),
+        )
+    }
+
+    fn daily(
+        owner_id: Uuid,
+        account_id: Uuid,
+        name: &str,
+        starts_on: NaiveDate,
+    ) -> RecurringExpense {
+        RecurringExpense::new(
+            Uuid::new_v4(),
+            owner_id,
+            ExpenseName::new(name).unwrap(),
+            350,
+            Recurrence::new(Frequency::Daily, starts_on),
+            Utc::now(),
+        )
+        .with_account(account_id)
+    }
+
+    #[tokio::test]
+    async fn test_materialize_continues_past_a_failing_recurring_expense() {
+        let user_id = Uuid::new_v4();
+        let account = account(user_id);
+        let coffee = daily(user_id, *account.id(), "Coffee", day(2026, 10, 1));
+        let paper = daily(user_id, *account.id(), "Newspaper", day(2026, 10, 4));
+        let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account);
+        repo.recurring_expenses = vec![coffee.clone(), paper.clone()];
+        repo.rejected_days = HashSet::from([day(2026, 10, 3)]);
+
+        let result = service(repo.clone(), MockNotifier::default())
+            .materialize_recurring_expenses(day(2026, 10, 5))
+            .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(RecurringExpenseError::MaterializationFailed {
+                    failed: 1,
+                    created: 4
+                })
+            ),
+            "expected one failed recurring expense and 4 occurrences, but got {:?}",
+            result
+        );
+        let created: Vec<(String, NaiveDate)> = repo
+            .expenses()
+            .iter()
+            .map(|e| (e.name().to_string(), *e.spent_on()))
+            .collect();
+        assert_eq!(
+            created,
+            vec![
+                ("Coffee".to_string(), day(2026, 10, 1)),
+                ("Coffee".to_string(), day(2026, 10, 2)),
+                ("Newspaper".to_string(), day(2026, 10, 4)),
+                ("Newspaper".to_string(), day(2026, 10, 5)),
+            ]
+        );
+        // The rejected occurrence and those after it are left to be retried.
+        assert_eq!(
+            *repo.materialized.lock().unwrap(),
+            vec![
+                (*coffee.id(), day(2026, 10, 2)),
+                (*paper.id(), day(2026, 10, 5)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thumbnails_continue_past_a_failing_attachment() {
+        let attachment = |content_type| {
+            Attachment::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                "receipt",
+                content_type,
+                0,
+                Utc::now(),
+            )
+        };
+        let unreadable = attachment(AttachmentContentType::Png);
+        let picture = attachment(AttachmentContentType::Png);
+        let storage = std::env::temp_dir();
+        // A directory where the content should be cannot be read.
+        std::fs::create_dir_all(storage.join(unreadable.storage_key())).unwrap();
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(&[0, 0, 0], 1, 1, image::ExtendedColorType::Rgb8)
+            .unwrap();
+        std::fs::create_dir_all(storage.join(picture.storage_key()).parent().unwrap()).unwrap();
+        std::fs::write(storage.join(picture.storage_key()), png).unwrap();
+        let mut repo = MockExpenseRepository::new();
+        repo.pending_thumbnails = vec![unreadable.clone(), picture.clone()];
+
+        let processed = service(repo.clone(), MockNotifier::default())
+            .generate_thumbnails(10)
+            .await
+            .unwrap();
+
+        assert_eq!(processed, 2);
+        assert_eq!(
+            *repo.thumbnail_statuses.lock().unwrap(),
+            vec![
+                (*unreadable.id(), ThumbnailStatus::Unavailable),
+                (*picture.id(), ThumbnailStatus::Ready),
+            ]
+        );
+        assert!(
+            storage
+                .join(picture.variant_key(AttachmentVariant::Thumb))
+                .exists()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_does_not_advance_past_a_first_failure() {
+        let user_id = Uuid::new_v4();
+        let account = account(user_id);
+        let rent = daily(user_id, *account.id(), "Rent", day(2026, 10, 1));
+        let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account);
+        repo.recurring_expenses = vec![rent];
+        repo.rejected_days = HashSet::from([day(2026, 10, 1)]);
+
+        let result = service(repo.clone(), MockNotifier::default())
+            .materialize_recurring_expenses(day(2026, 10, 2))
+            .await;
+
+        assert!(result.is_err());
+        assert!(repo.expenses().is_empty());
+        assert!(repo.materialized.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_budget_thresholds_are_notified_once_per_period() {
+        let user_id = Uuid::new_v4();
+        let principal = Principal::new(user_id);
+        let account = account(user_id);
+        let groceries = category(user_id);
+        let budget = monthly_budget(&groceries, 10_000);
+        let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account.clone());
+        repo.category = Some(groceries.clone());
+        repo.budgets = vec![budget.clone()];
+        let notifier = MockNotifier::default();
+        let service = service(repo, notifier.clone());
+
+        // 50%, 80%, 90%, 110% and 115% of October's budget, then 90% and 120% of November's
+        // and 130% of December's in one go.
+        for (amount, spent_on) in [
+            (5_000, day(2026, 10, 1)),
+            (3_000, day(2026, 10, 9)),
+            (1_000, day(2026, 10, 15)),
+            (2_000, day(2026, 10, 20)),
+            (500, day(2026, 10, 31)),
+            (9_000, day(2026, 11, 1)),
+            (3_000, day(2026, 11, 2)),
+            (13_000, day(2026, 12, 24)),
+        ] {
+            let req = CreateExpenseRequest::new("Supermarket")
+                .unwrap()
+                .with_amount(amount)
+                .unwrap()
+                .with_spent_on(spent_on)
+                .with_account(*account.id())
+                .with_category(*groceries.id());
+            service
+                .create_expense(Some(&principal), &req)
+                .await
+                .unwrap();
+        }
+
+        let alerts: Vec<(BudgetThreshold, NaiveDate, i64)> = notifier
+            .alerts()
+            .iter()
+            .map(|a| {
+                assert_eq!(a.status().budget(), &budget);
+                (
+                    a.threshold(),
+                    *a.status().period_start(),
+                    a.status().spent(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            alerts,
+            vec![
+                (BudgetThreshold::Warning, day(2026, 10, 1), 8_000),
+                (BudgetThreshold::Exceeded, day(2026, 10, 1), 11_000),
+                (BudgetThreshold::Warning, day(2026, 11, 1), 9_000),
+                (BudgetThreshold::Exceeded, day(2026, 11, 1), 12_000),
+                (BudgetThreshold::Warning, day(2026, 12, 1), 13_000),
+                (BudgetThreshold::Exceeded, day(2026, 12, 1), 13_000),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_incomes_and_transfers_leave_budgets_alone() {
+        let user_id = Uuid::new_v4();
+        let principal = Principal::new(user_id);
+        let account = account(user_id);
+        let groceries = category(user_id);
+        let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account.clone());
+        repo.category = Some(groceries.clone());
+        repo.budgets = vec![monthly_budget(&groceries, 10_000)];
+        let notifier = MockNotifier::default();
+        let service = service(repo, notifier.clone());
+        let req = |kind, amount| {
+            CreateExpenseRequest::new("Supermarket")
+                .unwrap()
+                .with_kind(kind)
+                .with_amount(amount)
+                .unwrap()
+                .with_spent_on(day(2026, 10, 18))
+                .with_account(*account.id())
+                .with_category(*groceries.id())
+        };
+
+        for req in [
+            req(TransactionKind::Income, 50_000),
+            req(TransactionKind::Transfer, 50_000).with_to_account(Uuid::new_v4()),
+            req(TransactionKind::Expense, 1_000),
+        ] {
+            service
+                .create_expense(Some(&principal), &req)
+                .await
+                .unwrap();
+        }
+
+        assert!(notifier.alerts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_incomes_and_transfers_cannot_be_split() {
+        let ledger_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let principal = Principal::new(user_id);
+        let mut repo = MockExpenseRepository::new();
+        repo.ledger_member = Some(LedgerMember::new(
+            ledger_id,
+            user_id,
+            LedgerRole::Owner,
+            Utc::now(),
+        ));
+        let service = service(repo.clone(), MockNotifier::default());
+
+        for kind in [TransactionKind::Income, TransactionKind::Transfer] {
+            let req = CreateExpenseRequest::new("Refund")
+                .unwrap()
+                .with_kind(kind)
+                .with_amount(3_000)
+                .unwrap()
+                .with_ledger(ledger_id)
+                .with_split(
+                    user_id,
+                    SplitMethod::Equal,
+                    &[
+                        SplitParticipant::new(user_id, 1),
+                        SplitParticipant::new(Uuid::new_v4(), 1),
+                    ],
+                )
+                .unwrap();
+
+            let result = service.create_expense(Some(&principal), &req).await;
+
+            assert!(
+                matches!(result, Err(CreateExpenseError::SplitNotExpense { kind: k }) if k == kind),
+                "expected a {} not to be split, but got {:?}",
+                kind,
+                result
+            );
+        }
+        assert!(repo.expenses().is_empty());
     }
 }
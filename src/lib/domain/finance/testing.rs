@@ -0,0 +1,570 @@
+/*!
+    Module `testing` provides test doubles of the finance ports.
+*/
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::{StreamExt, stream};
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::{
+    Account, AccountError, BalancePoint, CreateAccountRequest, Currency,
+};
+use crate::domain::finance::models::attachment::{Attachment, AttachmentError, ThumbnailStatus};
+use crate::domain::finance::models::balance::Debt;
+use crate::domain::finance::models::budget::{
+    Budget, BudgetAlert, BudgetError, BudgetThreshold, CreateBudgetRequest, UpdateBudgetRequest,
+};
+use crate::domain::finance::models::category::{Category, CategoryError, CreateCategoryRequest};
+use crate::domain::finance::models::duplicate::{
+    DuplicateError, DuplicateMerge, DuplicateSettings,
+};
+use crate::domain::finance::models::exchange::{ExchangeRate, ExchangeRateError};
+use crate::domain::finance::models::expense::{
+    CreateExpenseError, CreateExpenseRequest, Expense, ListExpensesRequest,
+};
+use crate::domain::finance::models::idempotency::{
+    ClaimIdempotencyKeyRequest, IdempotencyClaim, IdempotencyError, IdempotencyKey, StoredResponse,
+};
+use crate::domain::finance::models::import::ImportError;
+use crate::domain::finance::models::ledger::{
+    AcceptInvitationRequest, CreateInvitationRequest, CreateLedgerRequest, InvitationError, Ledger,
+    LedgerError, LedgerInvitation, LedgerMember,
+};
+use crate::domain::finance::models::recurring::{
+    CreateRecurringExpenseRequest, RecurringExpense, RecurringExpenseError,
+};
+use crate::domain::finance::models::report::{
+    ReportError, ReportScope, Series, SummaryBucket, SummaryRequest, TimeseriesRequest,
+};
+use crate::domain::finance::models::rule::{CreateRuleRequest, Rule, RuleChange, RuleError};
+use crate::domain::finance::models::settlement::{
+    RecordSettlementRequest, Settlement, SettlementError,
+};
+use crate::domain::finance::models::transaction::TransactionKind;
+use crate::domain::finance::ports::{
+    AccountRepository, AttachmentRepository, BudgetRepository, CategoryRepository,
+    DuplicateRepository, ExchangeRateRepository, ExpenseNotifier, ExpenseRepository,
+    ExpenseRepositoryError, ExpenseStream, IdempotencyRepository, LedgerRepository,
+    RecurringExpenseRepository, ReportRepository, RuleRepository, SettlementRepository,
+};
+
+/// The error of the repository methods no test exercises yet.
+fn not_supported<T, E: From<anyhow::Error>>() -> Result<T, E> {
+    Err(anyhow!("not supported by MockExpenseRepository").into())
+}
+
+/// A repository returning canned results for the few methods the tests exercise, and an error
+/// from every other method.
+#[derive(Clone)]
+pub(crate) struct MockExpenseRepository {
+    /// Returned by the next call to `create_expense` instead of saving the expense.
+    pub(crate) create_expense_result: Arc<Mutex<Option<Result<Expense, CreateExpenseError>>>>,
+    /// The expenses saved by `create_expense`.
+    pub(crate) expenses: Arc<Mutex<Vec<Expense>>>,
+    /// The days on which `create_expense` fails.
+    pub(crate) rejected_days: HashSet<NaiveDate>,
+    pub(crate) ledger_member: Option<LedgerMember>,
+    pub(crate) account: Option<Account>,
+    pub(crate) category: Option<Category>,
+    /// Listed by `list_category_budgets` for their category.
+    pub(crate) budgets: Vec<Budget>,
+    /// The alerts recorded by `record_budget_alert`, by budget, period start and threshold.
+    pub(crate) budget_alerts: Arc<Mutex<HashSet<(Uuid, NaiveDate, BudgetThreshold)>>>,
+    /// Listed as due by `list_due_recurring_expenses`.
+    pub(crate) recurring_expenses: Vec<RecurringExpense>,
+    /// The days passed to `mark_recurring_expense_materialized`, per recurring expense.
+    pub(crate) materialized: Arc<Mutex<Vec<(Uuid, NaiveDate)>>>,
+    /// Listed by `list_pending_thumbnails`.
+    pub(crate) pending_thumbnails: Vec<Attachment>,
+    /// The statuses set by `set_thumbnail_status`, per attachment.
+    pub(crate) thumbnail_statuses: Arc<Mutex<Vec<(Uuid, ThumbnailStatus)>>>,
+}
+impl MockExpenseRepository {
+    pub(crate) fn new() -> Self {
+        Self {
+            create_expense_result: Arc::new(Mutex::new(None)),
+            expenses: Arc::new(Mutex::new(Vec::new())),
+            rejected_days: HashSet::new(),
+            ledger_member: None,
+            account: None,
+            category: None,
+            budgets: Vec::new(),
+            budget_alerts: Arc::new(Mutex::new(HashSet::new())),
+            recurring_expenses: Vec::new(),
+            materialized: Arc::new(Mutex::new(Vec::new())),
+            pending_thumbnails: Vec::new(),
+            thumbnail_statuses: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// The expenses saved so far.
+    pub(crate) fn expenses(&self) -> Vec<Expense> {
+        self.expenses.lock().unwrap().clone()
+    }
+
+    /// The saved expenses of the ledger and kind of `req`.
+    fn matching_expenses(&self, req: &ListExpensesRequest) -> Vec<Expense> {
+        self.expenses()
+            .into_iter()
+            .filter(|e| e.ledger_id() == req.ledger_id())
+            .filter(|e| req.kind().is_none_or(|kind| e.kind() == kind))
+            .collect()
+    }
+}
+
+/// The [Expense] a database would save for `req`.
+fn saved_expense(req: &CreateExpenseRequest) -> Expense {
+    let mut expense = Expense::new(Uuid::new_v4(), req.name().clone())
+        .with_kind(req.kind())
+        .with_amount(req.amount())
+        .with_spent_on(*req.spent_on());
+    if let Some(ledger_id) = req.ledger_id() {
+        expense = expense.with_ledger(*ledger_id);
+    }
+    if let Some(category_id) = req.category_id() {
+        expense = expense.with_category(*category_id);
+    }
+    if let Some(account_id) = req.account_id() {
+        expense = expense.with_account(*account_id);
+    }
+    if let Some(to_account_id) = req.to_account_id() {
+        expense = expense.with_to_account(*to_account_id);
+    }
+    if let Some(recurring_expense_id) = req.recurring_expense_id() {
+        expense = expense.with_recurring_expense(*recurring_expense_id);
+    }
+    expense
+}
+
+impl ExpenseRepository for MockExpenseRepository {
+    async fn create_expense(
+        &self,
+        req: &CreateExpenseRequest,
+    ) -> Result<Expense, CreateExpenseError> {
+        if let Some(result) = self.create_expense_result.lock().unwrap().take() {
+            return result;
+        }
+        if self.rejected_days.contains(req.spent_on()) {
+            return Err(anyhow!("rejected on {}", req.spent_on()).into());
+        }
+        let expense = saved_expense(req);
+        self.expenses.lock().unwrap().push(expense.clone());
+        Ok(expense)
+    }
+
+    async fn create_expenses(
+        &self,
+        _: &[CreateExpenseRequest],
+    ) -> Result<Vec<Expense>, ImportError> {
+        not_supported()
+    }
+    async fn find_import_ids(&self, _: &Uuid, _: &[&str]) -> Result<HashSet<String>, ImportError> {
+        not_supported()
+    }
+
+    async fn find_expense(&self, _: &Uuid) -> Result<Option<Expense>, ExpenseRepositoryError> {
+        not_supported()
+    }
+
+    /// Streams the saved expenses of the ledger and kind of `req`.
+    fn stream_expenses(&self, _: &Uuid, req: &ListExpensesRequest) -> ExpenseStream {
+        stream::iter(self.matching_expenses(req).into_iter().map(Ok)).boxed()
+    }
+
+    /// Lists the saved expenses of the ledger and kind of `req`, on a single page.
+    async fn list_expenses(
+        &self,
+        _: &Uuid,
+        req: &ListExpensesRequest,
+    ) -> Result<Vec<Expense>, ExpenseRepositoryError> {
+        Ok(self.matching_expenses(req))
+    }
+    async fn list_ledger_debts(&self, _: &Uuid) -> Result<Vec<Debt>, ExpenseRepositoryError> {
+        not_supported()
+    }
+}
+
+impl LedgerRepository for MockExpenseRepository {
+    async fn create_ledger(&self, _: &CreateLedgerRequest) -> Result<Ledger, LedgerError> {
+        not_supported()
+    }
+    async fn find_ledger(&self, _: &Uuid) -> Result<Option<Ledger>, LedgerError> {
+        not_supported()
+    }
+    async fn list_ledgers_for_user(&self, _: &Uuid) -> Result<Vec<Ledger>, LedgerError> {
+        not_supported()
+    }
+    async fn list_ledger_members(&self, _: &Uuid) -> Result<Vec<LedgerMember>, LedgerError> {
+        not_supported()
+    }
+    async fn find_ledger_member(
+        &self,
+        _: &Uuid,
+        _: &Uuid,
+    ) -> Result<Option<LedgerMember>, LedgerError> {
+        Ok(self.ledger_member.clone())
+    }
+    async fn create_invitation(
+        &self,
+        _: &CreateInvitationRequest,
+    ) -> Result<LedgerInvitation, InvitationError> {
+        not_supported()
+    }
+    async fn accept_invitation(
+        &self,
+        _: &AcceptInvitationRequest,
+    ) -> Result<LedgerMember, InvitationError> {
+        not_supported()
+    }
+}
+
+impl SettlementRepository for MockExpenseRepository {
+    async fn record_settlement(
+        &self,
+        _: &RecordSettlementRequest,
+    ) -> Result<Settlement, SettlementError> {
+        not_supported()
+    }
+}
+
+impl CategoryRepository for MockExpenseRepository {
+    async fn create_category(&self, _: &CreateCategoryRequest) -> Result<Category, CategoryError> {
+        not_supported()
+    }
+
+    async fn list_categories(
+        &self,
+        _: &Uuid,
+        _: Option<&Uuid>,
+    ) -> Result<Vec<Category>, CategoryError> {
+        not_supported()
+    }
+
+    async fn find_category(&self, _: &Uuid) -> Result<Option<Category>, CategoryError> {
+        Ok(self.category.clone())
+    }
+}
+
+impl RecurringExpenseRepository for MockExpenseRepository {
+    async fn create_recurring_expense(
+        &self,
+        _: &CreateRecurringExpenseRequest,
+    ) -> Result<RecurringExpense, RecurringExpenseError> {
+        not_supported()
+    }
+
+    async fn find_recurring_expense(
+        &self,
+        _: &Uuid,
+    ) -> Result<Option<RecurringExpense>, RecurringExpenseError> {
+        not_supported()
+    }
+
+    async fn list_recurring_expenses(
+        &self,
+        _: &Uuid,
+        _: Option<&Uuid>,
+    ) -> Result<Vec<RecurringExpense>, RecurringExpenseError> {
+        not_supported()
+    }
+
+    async fn delete_recurring_expense(&self, _: &Uuid) -> Result<bool, RecurringExpenseError> {
+        not_supported()
+    }
+
+    async fn list_due_recurring_expenses(
+        &self,
+        _: NaiveDate,
+    ) -> Result<Vec<RecurringExpense>, RecurringExpenseError> {
+        Ok(self.recurring_expenses.clone())
+    }
+
+    async fn mark_recurring_expense_materialized(
+        &self,
+        id: &Uuid,
+        materialized_through: NaiveDate,
+    ) -> Result<(), RecurringExpenseError> {
+        self.materialized
+            .lock()
+            .unwrap()
+            .push((*id, materialized_through));
+        Ok(())
+    }
+}
+
+impl BudgetRepository for MockExpenseRepository {
+    async fn create_budget(
+        &self,
+        _: &CreateBudgetRequest,
+        _: &Category,
+    ) -> Result<Budget, BudgetError> {
+        not_supported()
+    }
+
+    async fn find_budget(&self, _: &Uuid) -> Result<Option<Budget>, BudgetError> {
+        not_supported()
+    }
+
+    async fn list_budgets(&self, _: &Uuid, _: Option<&Uuid>) -> Result<Vec<Budget>, BudgetError> {
+        not_supported()
+    }
+
+    async fn update_budget(
+        &self,
+        _: &Budget,
+        _: &UpdateBudgetRequest,
+    ) -> Result<Option<Budget>, BudgetError> {
+        not_supported()
+    }
+
+    async fn delete_budget(&self, _: &Uuid) -> Result<bool, BudgetError> {
+        not_supported()
+    }
+
+    /// Sums the saved expenses of the category spent from `start` until before `end`, which are
+    /// all taken to be in the currency of the budget.
+    async fn sum_category_spending(
+        &self,
+        category_id: &Uuid,
+        _: &Currency,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<i64, BudgetError> {
+        Ok(self
+            .expenses()
+            .iter()
+            .filter(|e| e.kind() == TransactionKind::Expense)
+            .filter(|e| e.category_id() == Some(category_id))
+            .filter(|e| (start..end).contains(e.spent_on()))
+            .map(Expense::amount)
+            .sum())
+    }
+
+    async fn list_category_budgets(&self, category_id: &Uuid) -> Result<Vec<Budget>, BudgetError> {
+        Ok(self
+            .budgets
+            .iter()
+            .filter(|b| b.category_id() == category_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn record_budget_alert(
+        &self,
+        budget_id: &Uuid,
+        period_start: NaiveDate,
+        threshold: BudgetThreshold,
+    ) -> Result<bool, BudgetError> {
+        Ok(self
+            .budget_alerts
+            .lock()
+            .unwrap()
+            .insert((*budget_id, period_start, threshold)))
+    }
+}
+
+impl AccountRepository for MockExpenseRepository {
+    async fn create_account(&self, _: &CreateAccountRequest) -> Result<Account, AccountError> {
+        not_supported()
+    }
+
+    async fn find_account(&self, _: &Uuid) -> Result<Option<Account>, AccountError> {
+        Ok(self.account.clone())
+    }
+
+    async fn list_accounts(&self, _: &Uuid) -> Result<Vec<Account>, AccountError> {
+        not_supported()
+    }
+
+    async fn account_balance(
+        &self,
+        _: &Account,
+        _: &Currency,
+        _: NaiveDate,
+    ) -> Result<i64, AccountError> {
+        not_supported()
+    }
+
+    async fn account_balance_history(
+        &self,
+        _: &Account,
+        _: &Currency,
+        _: NaiveDate,
+        _: NaiveDate,
+    ) -> Result<Vec<BalancePoint>, AccountError> {
+        not_supported()
+    }
+}
+
+impl ReportRepository for MockExpenseRepository {
+    async fn spending_summary(
+        &self,
+        _: &ReportScope,
+        _: &SummaryRequest,
+    ) -> Result<Vec<SummaryBucket>, ReportError> {
+        not_supported()
+    }
+
+    async fn spending_timeseries(
+        &self,
+        _: &ReportScope,
+        _: &TimeseriesRequest,
+    ) -> Result<Vec<Series>, ReportError> {
+        not_supported()
+    }
+}
+
+impl ExchangeRateRepository for MockExpenseRepository {
+    async fn save_exchange_rates(&self, _: &[ExchangeRate]) -> Result<usize, ExchangeRateError> {
+        not_supported()
+    }
+}
+
+impl AttachmentRepository for MockExpenseRepository {
+    async fn create_attachment(&self, _: &Attachment) -> Result<(), AttachmentError> {
+        not_supported()
+    }
+
+    async fn find_attachment(&self, _: &Uuid) -> Result<Option<Attachment>, AttachmentError> {
+        not_supported()
+    }
+
+    async fn list_pending_thumbnails(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Attachment>, AttachmentError> {
+        Ok(self
+            .pending_thumbnails
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn set_thumbnail_status(
+        &self,
+        id: &Uuid,
+        status: ThumbnailStatus,
+    ) -> Result<(), AttachmentError> {
+        self.thumbnail_statuses.lock().unwrap().push((*id, status));
+        Ok(())
+    }
+}
+
+impl RuleRepository for MockExpenseRepository {
+    async fn create_rule(&self, _: &CreateRuleRequest) -> Result<Rule, RuleError> {
+        not_supported()
+    }
+
+    async fn find_rule(&self, _: &Uuid) -> Result<Option<Rule>, RuleError> {
+        not_supported()
+    }
+
+    /// No rules are set up, leaving expenses as they are created.
+    async fn list_rules(&self, _: &Uuid) -> Result<Vec<Rule>, RuleError> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_rule(&self, _: &Uuid) -> Result<bool, RuleError> {
+        not_supported()
+    }
+
+    async fn list_rule_candidates(&self, _: &Uuid) -> Result<Vec<Expense>, RuleError> {
+        not_supported()
+    }
+
+    async fn save_rule_changes(&self, _: &[RuleChange]) -> Result<(), RuleError> {
+        not_supported()
+    }
+}
+
+impl IdempotencyRepository for MockExpenseRepository {
+    async fn claim_idempotency_key(
+        &self,
+        _: &ClaimIdempotencyKeyRequest,
+    ) -> Result<IdempotencyClaim, IdempotencyError> {
+        not_supported()
+    }
+
+    async fn save_idempotent_response(
+        &self,
+        _: &Uuid,
+        _: &IdempotencyKey,
+        _: &StoredResponse,
+    ) -> Result<(), IdempotencyError> {
+        not_supported()
+    }
+
+    async fn release_idempotency_key(
+        &self,
+        _: &Uuid,
+        _: &IdempotencyKey,
+    ) -> Result<(), IdempotencyError> {
+        not_supported()
+    }
+
+    async fn delete_expired_idempotency_keys(
+        &self,
+        _: DateTime<Utc>,
+    ) -> Result<usize, IdempotencyError> {
+        not_supported()
+    }
+}
+
+impl DuplicateRepository for MockExpenseRepository {
+    /// No settings are saved, so duplicates are only warned about.
+    async fn find_duplicate_settings(
+        &self,
+        _: &Uuid,
+    ) -> Result<Option<DuplicateSettings>, DuplicateError> {
+        Ok(None)
+    }
+
+    async fn save_duplicate_settings(
+        &self,
+        _: &Uuid,
+        _: &DuplicateSettings,
+    ) -> Result<(), DuplicateError> {
+        not_supported()
+    }
+
+    /// No expense exists yet, so nothing looks like a duplicate.
+    async fn list_duplicate_candidates(
+        &self,
+        _: &Uuid,
+        _: Option<&Uuid>,
+        _: Option<NaiveDate>,
+        _: Option<NaiveDate>,
+    ) -> Result<Vec<Expense>, DuplicateError> {
+        Ok(Vec::new())
+    }
+
+    async fn merge_duplicates(&self, _: &DuplicateMerge) -> Result<(), DuplicateError> {
+        not_supported()
+    }
+}
+
+/// A notifier remembering the budget alerts it is sent.
+#[derive(Clone, Default)]
+pub(crate) struct MockNotifier {
+    alerts: Arc<Mutex<Vec<BudgetAlert>>>,
+}
+
+impl MockNotifier {
+    /// The alerts sent so far, oldest first.
+    pub(crate) fn alerts(&self) -> Vec<BudgetAlert> {
+        self.alerts.lock().unwrap().clone()
+    }
+}
+
+impl ExpenseNotifier for MockNotifier {
+    async fn expense_created(&self, _: &Expense) {}
+
+    async fn budget_threshold_reached(&self, alert: &BudgetAlert) {
+        self.alerts.lock().unwrap().push(alert.clone());
+    }
+}
@@ -0,0 +1,230 @@
+/*!
+    Module `ecb` feeds the finance domain with the euro foreign exchange reference rates
+    published by the European Central Bank, read from a file on disk in either of the formats
+    the ECB distributes them in:
+
+    - CSV, as in `eurofxref.csv` or `eurofxref-hist.csv`: a `Date` column followed by one column
+      per currency, with `N/A` where a currency was not quoted;
+    - XML, as in `eurofxref-daily.xml` or `eurofxref-hist.xml`: `Cube` elements carrying a `time`
+      attribute around `Cube` elements carrying `currency` and `rate` attributes.
+*/
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::domain::finance::models::account::{Currency, InvalidCurrencyError};
+use crate::domain::finance::models::exchange::{ExchangeRate, InvalidRateError, Rate};
+use crate::domain::finance::ports::FinanceService;
+
+/// How many rates are stored per call to the finance service. A full history holds hundreds of
+/// thousands of them.
+const IMPORT_BATCH_SIZE: usize = 5_000;
+
+/// Date formats found in the ECB files: ISO in the historical files, spelled out in the daily
+/// CSV.
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%d %B %Y"];
+
+#[derive(Debug, Error)]
+pub enum EcbError {
+    #[error("missing header line")]
+    MissingHeader,
+    #[error("line {line}: expected a Date column first")]
+    UnexpectedHeader { line: usize },
+    #[error("line {line}: invalid date {value:?}")]
+    InvalidDate { line: usize, value: String },
+    #[error("line {line}: {source}")]
+    InvalidCurrency {
+        line: usize,
+        source: InvalidCurrencyError,
+    },
+    #[error("line {line}: {source}")]
+    InvalidRate {
+        line: usize,
+        source: InvalidRateError,
+    },
+    #[error("cube element without a {attribute} attribute")]
+    MissingAttribute { attribute: &'static str },
+    #[error(transparent)]
+    Xml(#[from] roxmltree::Error),
+}
+
+/// Parse the rates of an ECB reference rates file, telling XML from CSV by its first character.
+pub fn parse_reference_rates(contents: &str) -> Result<Vec<ExchangeRate>, EcbError> {
+    if contents.trim_start().starts_with('<') {
+        parse_xml(contents)
+    } else {
+        parse_csv(contents)
+    }
+}
+
+fn parse_date(line: usize, value: &str) -> Result<NaiveDate, EcbError> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+        .ok_or_else(|| EcbError::InvalidDate {
+            line,
+            value: value.to_string(),
+        })
+}
+
+fn parse_csv(contents: &str) -> Result<Vec<ExchangeRate>, EcbError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (line, header) = lines.next().ok_or(EcbError::MissingHeader)?;
+    let mut columns = header.split(',').map(str::trim);
+    if columns.next() != Some("Date") {
+        return Err(EcbError::UnexpectedHeader { line });
+    }
+    // The files end every line with a comma, hence an empty last column.
+    let currencies = columns
+        .filter(|column| !column.is_empty())
+        .map(|column| {
+            Currency::new(column).map_err(|source| EcbError::InvalidCurrency { line, source })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rates = Vec::new();
+    for (line, row) in lines {
+        let mut values = row.split(',').map(str::trim);
+        let day = parse_date(line, values.next().unwrap_or_default())?;
+        for (currency, value) in currencies.iter().zip(values) {
+            if value.is_empty() || value == "N/A" {
+                continue;
+            }
+            let rate =
+                Rate::from_str(value).map_err(|source| EcbError::InvalidRate { line, source })?;
+            rates.push(ExchangeRate::new(currency.clone(), day, rate));
+        }
+    }
+    Ok(rates)
+}
+
+fn parse_xml(contents: &str) -> Result<Vec<ExchangeRate>, EcbError> {
+    let document = roxmltree::Document::parse(contents)?;
+    let mut rates = Vec::new();
+    let days = document
+        .descendants()
+        .filter(|node| node.has_tag_name("Cube") && node.has_attribute("time"));
+    for day_node in days {
+        let line = document.text_pos_at(day_node.range().start).row as usize;
+        let day = parse_date(line, day_node.attribute("time").unwrap_or_default())?;
+        for node in day_node.children().filter(|node| node.has_tag_name("Cube")) {
+            let line = document.text_pos_at(node.range().start).row as usize;
+            let currency = node
+                .attribute("currency")
+                .ok_or(EcbError::MissingAttribute {
+                    attribute: "currency",
+                })?;
+            let rate = node
+                .attribute("rate")
+                .ok_or(EcbError::MissingAttribute { attribute: "rate" })?;
+            rates.push(ExchangeRate::new(
+                Currency::new(currency)
+                    .map_err(|source| EcbError::InvalidCurrency { line, source })?,
+                day,
+                Rate::from_str(rate).map_err(|source| EcbError::InvalidRate { line, source })?,
+            ));
+        }
+    }
+    Ok(rates)
+}
+
+/// Imports ECB reference rates files through the finance service.
+pub struct ExchangeRateImporter<FS: FinanceService> {
+    finance_service: FS,
+}
+
+impl<FS: FinanceService> ExchangeRateImporter<FS> {
+    pub fn new(finance_service: FS) -> Self {
+        Self { finance_service }
+    }
+
+    /// Read the rates of the file at `path` and store them, replacing any already known for the
+    /// same currency and day, returning how many were stored.
+    pub async fn import_file(&self, path: &Path) -> anyhow::Result<usize> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let rates = parse_reference_rates(&contents)
+            .with_context(|| format!("invalid reference rates file {}", path.display()))?;
+        let mut imported = 0;
+        for batch in rates.chunks(IMPORT_BATCH_SIZE) {
+            imported += self.finance_service.import_exchange_rates(batch).await?;
+        }
+        Ok(imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(currency: &str, day: &str, rate: &str) -> ExchangeRate {
+        ExchangeRate::new(
+            Currency::new(currency).unwrap(),
+            NaiveDate::from_str(day).unwrap(),
+            Rate::from_str(rate).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_daily_csv() {
+        let contents = "Date, USD, JPY, \n16 October 2026, 1.0876, 161.25, \n";
+        assert_eq!(
+            parse_reference_rates(contents).unwrap(),
+            vec![
+                rate("USD", "2026-10-16", "1.0876"),
+                rate("JPY", "2026-10-16", "161.25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_historical_csv_skips_missing_quotes() {
+        let contents = "Date,USD,CYP,\n2026-10-16,1.0876,N/A,\n2026-10-15,1.0901,N/A,\n";
+        assert_eq!(
+            parse_reference_rates(contents).unwrap(),
+            vec![
+                rate("USD", "2026-10-16", "1.0876"),
+                rate("USD", "2026-10-15", "1.0901"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let contents = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <gesmes:subject>Reference rates</gesmes:subject>
+    <Cube>
+        <Cube time="2026-10-16">
+            <Cube currency="USD" rate="1.0876"/>
+            <Cube currency="GBP" rate="0.8583"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+        assert_eq!(
+            parse_reference_rates(contents).unwrap(),
+            vec![
+                rate("USD", "2026-10-16", "1.0876"),
+                rate("GBP", "2026-10-16", "0.8583"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rates() {
+        let contents = "Date,USD,\n2026-10-16,abc,\n";
+        assert!(matches!(
+            parse_reference_rates(contents),
+            Err(EcbError::InvalidRate { line: 2, .. })
+        ));
+    }
+}
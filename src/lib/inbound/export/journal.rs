@@ -0,0 +1,432 @@
+/*!
+    Module `journal` renders transactions as plain-text accounting journals, in the format read
+    by ledger-cli and hledger, or in that of beancount.
+
+    Accounts are named after the caller's own: `Assets:<Kind>:<Name>` for an account and
+    `Expenses:<Category>` or `Income:<Category>` for a category, each name being turned into a
+    component both tools accept. The same data always renders to the same text, so that
+    successive exports of a journal diff cleanly.
+*/
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::{Account, Currency};
+use crate::domain::finance::models::category::Category;
+use crate::domain::finance::models::expense::Expense;
+use crate::domain::finance::models::transaction::TransactionKind;
+
+use super::decimal;
+
+/// The account transactions without one, or with one of another user, are posted to.
+const UNKNOWN_ACCOUNT: &str = "Assets:Unknown";
+const UNCATEGORIZED: &str = "Uncategorized";
+const OPENING_BALANCES: &str = "Equity:Opening-Balances";
+/// The ISO 4217 code for transactions involving no currency, used for amounts whose currency is
+/// unknown.
+const NO_CURRENCY: &str = "XXX";
+/// The width accounts are padded to in postings, aligning most amounts.
+const ACCOUNT_WIDTH: usize = 40;
+
+/// The plain-text accounting formats a journal can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// The journal format shared by ledger-cli and hledger.
+    Ledger,
+    Beancount,
+}
+
+/// The journal names of the caller's accounts and categories.
+#[derive(Debug, Clone, Default)]
+pub struct Chart {
+    accounts: Vec<Account>,
+    account_names: HashMap<Uuid, String>,
+    category_names: HashMap<Uuid, String>,
+    /// The day each category was created, from which it is open.
+    category_days: BTreeMap<String, NaiveDate>,
+}
+
+impl Chart {
+    /// Names `accounts` and `categories`, telling apart those whose names read the same.
+    pub fn new(accounts: &[Account], categories: &[Category]) -> Self {
+        let mut accounts = accounts.to_vec();
+        accounts.sort_by(|a, b| (a.name(), a.id()).cmp(&(b.name(), b.id())));
+        let account_names = unique_names(accounts.iter().map(|account| {
+            let kind = component(account.kind().as_str());
+            let name = format!("Assets:{kind}:{}", component(&account.name().to_string()));
+            (*account.id(), name)
+        }));
+
+        let mut categories = categories.to_vec();
+        categories.sort_by(|a, b| (a.name(), a.id()).cmp(&(b.name(), b.id())));
+        let category_names = unique_names(
+            categories
+                .iter()
+                .map(|category| (*category.id(), component(&category.name().to_string()))),
+        );
+        let category_days = categories
+            .iter()
+            .map(|category| {
+                (
+                    category_names[category.id()].clone(),
+                    category.created_at().date_naive(),
+                )
+            })
+            .collect();
+        Self {
+            accounts,
+            account_names,
+            category_names,
+            category_days,
+        }
+    }
+
+    fn account(&self, id: Option<&Uuid>) -> (String, Currency) {
+        id.and_then(|id| {
+            let account = self.accounts.iter().find(|account| account.id() == id)?;
+            Some((self.account_names[id].clone(), account.currency().clone()))
+        })
+        .unwrap_or_else(|| (UNKNOWN_ACCOUNT.to_string(), no_currency()))
+    }
+
+    fn category(&self, id: Option<&Uuid>) -> &str {
+        id.and_then(|id| self.category_names.get(id))
+            .map_or(UNCATEGORIZED, String::as_str)
+    }
+}
+
+/// Writes a journal a piece at a time: the accounts first, then each transaction, and last
+/// whatever depends on the transactions written.
+#[derive(Debug, Clone)]
+pub struct JournalWriter {
+    dialect: Dialect,
+    chart: Chart,
+    /// The accounts posted to, with the first day they were and their currency if they have
+    /// one, for beancount to open them.
+    opened: BTreeMap<String, (NaiveDate, Option<Currency>)>,
+}
+
+impl JournalWriter {
+    pub fn new(dialect: Dialect, chart: Chart) -> Self {
+        Self {
+            dialect,
+            chart,
+            opened: BTreeMap::new(),
+        }
+    }
+
+    /// The declarations of the caller's accounts and of the expense accounts of their
+    /// categories, and the opening balance of each account.
+    pub fn header(&mut self) -> String {
+        let mut out = String::new();
+        let chart = self.chart.clone();
+        // Incomes are rarer, so their accounts are only opened once posted to.
+        for (name, day) in &chart.category_days {
+            self.open(&format!("Expenses:{name}"), *day, None);
+        }
+        for account in &chart.accounts {
+            let name = &chart.account_names[account.id()];
+            let day = account.created_at().date_naive();
+            self.open(name, day, Some(account.currency()));
+        }
+
+        if self.dialect == Dialect::Ledger {
+            for name in self.opened.keys() {
+                let _ = writeln!(out, "account {name}");
+            }
+            if !self.opened.is_empty() {
+                out.push('\n');
+            }
+        }
+        for account in chart
+            .accounts
+            .iter()
+            .filter(|account| account.opening_balance() != 0)
+        {
+            let name = &chart.account_names[account.id()];
+            let day = account.created_at().date_naive();
+            self.open(OPENING_BALANCES, day, None);
+            out.push_str(&self.transaction(
+                day,
+                "Opening balance",
+                &[],
+                &[],
+                &[
+                    (name, account.opening_balance(), account.currency()),
+                    (
+                        OPENING_BALANCES,
+                        -account.opening_balance(),
+                        account.currency(),
+                    ),
+                ],
+            ));
+        }
+        out
+    }
+
+    /// The entry of a transaction, posted from its account to its category, from its category
+    /// to its account for an income, or between its accounts for a transfer.
+    pub fn entry(&mut self, expense: &Expense) -> String {
+        let day = *expense.spent_on();
+        let (account, currency) = self.chart.account(expense.account_id());
+        let category = self.chart.category(expense.category_id()).to_string();
+        let amount = expense.amount();
+        let (to, from) = match expense.kind() {
+            TransactionKind::Expense => (format!("Expenses:{category}"), account),
+            TransactionKind::Income => (account, format!("Income:{category}")),
+            TransactionKind::Transfer => (self.chart.account(expense.to_account_id()).0, account),
+        };
+        self.open(&to, day, None);
+        self.open(&from, day, None);
+
+        let mut metadata = vec![("id", expense.id().to_string())];
+        if let Some(counterparty) = expense.counterparty() {
+            metadata.push(("counterparty", counterparty.to_string()));
+        }
+        if let Some(reference) = expense.reference() {
+            metadata.push(("reference", reference.to_string()));
+        }
+        let tags = expense
+            .tags()
+            .iter()
+            .map(|tag| tag_name(tag.as_str()))
+            .collect::<Vec<_>>();
+        self.transaction(
+            day,
+            &expense.name().to_string(),
+            &metadata,
+            &tags,
+            &[(&to, amount, &currency), (&from, -amount, &currency)],
+        )
+    }
+
+    /// What must follow the transactions: the beancount directives opening every account
+    /// posted to, on the first day it was.
+    pub fn footer(&self) -> String {
+        let mut out = String::new();
+        if self.dialect == Dialect::Beancount {
+            for (name, (day, currency)) in &self.opened {
+                let _ = match currency {
+                    Some(currency) => writeln!(out, "{day} open {name} {currency}"),
+                    None => writeln!(out, "{day} open {name}"),
+                };
+            }
+        }
+        out
+    }
+
+    /// Records that `account` is posted to on `day`, keeping the earliest day.
+    fn open(&mut self, account: &str, day: NaiveDate, currency: Option<&Currency>) {
+        let entry = self
+            .opened
+            .entry(account.to_string())
+            .or_insert((day, None));
+        entry.0 = entry.0.min(day);
+        if let Some(currency) = currency {
+            entry.1 = Some(currency.clone());
+        }
+    }
+
+    fn transaction(
+        &self,
+        day: NaiveDate,
+        description: &str,
+        metadata: &[(&str, String)],
+        tags: &[String],
+        postings: &[(&str, i64, &Currency)],
+    ) -> String {
+        let description = single_line(description);
+        let mut out = String::new();
+        match self.dialect {
+            Dialect::Ledger => {
+                let _ = writeln!(out, "{day} * {description}");
+                for (key, value) in metadata {
+                    let _ = writeln!(out, "    ; {key}: {}", single_line(value));
+                }
+                for tag in tags {
+                    let _ = writeln!(out, "    ; {tag}:");
+                }
+            }
+            Dialect::Beancount => {
+                let _ = write!(out, "{day} * {}", quoted(&description));
+                for tag in tags {
+                    let _ = write!(out, " #{tag}");
+                }
+                out.push('\n');
+                for (key, value) in metadata {
+                    let _ = writeln!(out, "  {key}: {}", quoted(&single_line(value)));
+                }
+            }
+        }
+        let indent = match self.dialect {
+            Dialect::Ledger => "    ",
+            Dialect::Beancount => "  ",
+        };
+        for (account, amount, currency) in postings {
+            let amount = decimal(*amount, currency);
+            let _ = writeln!(
+                out,
+                "{indent}{account:<ACCOUNT_WIDTH$}  {amount:>12} {currency}"
+            );
+        }
+        out.push('\n');
+        out
+    }
+}
+
+fn no_currency() -> Currency {
+    Currency::new(NO_CURRENCY).expect("XXX is a currency code")
+}
+
+/// Pairs each id with its name, suffixing names already taken with their rank.
+fn unique_names(names: impl Iterator<Item = (Uuid, String)>) -> HashMap<Uuid, String> {
+    let mut taken: HashMap<String, usize> = HashMap::new();
+    names
+        .map(|(id, name)| {
+            let rank = taken.entry(name.clone()).or_default();
+            *rank += 1;
+            match *rank {
+                1 => (id, name),
+                rank => (id, format!("{name}-{rank}")),
+            }
+        })
+        .collect()
+}
+
+/// An account name component made of the words of `name`, capitalized and joined by dashes,
+/// such as `Main-Checking` for `main checking`.
+fn component(name: &str) -> String {
+    let words = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        "Unnamed".to_string()
+    } else {
+        words.join("-")
+    }
+}
+
+/// A tag name both formats accept, dashes replacing other characters.
+fn tag_name(tag: &str) -> String {
+    tag.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_/.".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::finance::models::account::{AccountKind, AccountName};
+    use crate::domain::finance::models::category::CategoryName;
+    use crate::domain::finance::models::expense::ExpenseName;
+    use crate::domain::finance::models::tag::Tag;
+
+    fn chart() -> (Chart, Uuid, Uuid) {
+        let account_id = Uuid::from_u128(1);
+        let category_id = Uuid::from_u128(2);
+        let created_at = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let account = Account::new(
+            account_id,
+            Uuid::from_u128(3),
+            AccountName::new("main checking").unwrap(),
+            AccountKind::Bank,
+            Currency::new("EUR").unwrap(),
+            10_000,
+            created_at,
+        );
+        let category = Category::new(
+            category_id,
+            CategoryName::new("Eating out").unwrap(),
+            None,
+            Uuid::from_u128(3),
+            created_at,
+        );
+        (Chart::new(&[account], &[category]), account_id, category_id)
+    }
+
+    fn expense(account_id: Uuid, category_id: Uuid) -> Expense {
+        Expense::new(
+            Uuid::from_u128(4),
+            ExpenseName::new("Pizza \"Roma\"").unwrap(),
+        )
+        .with_amount(1250)
+        .with_account(account_id)
+        .with_category(category_id)
+        .with_spent_on(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap())
+        .with_tags(vec![Tag::new("summer trip").unwrap()])
+    }
+
+    #[test]
+    fn test_ledger_journal() {
+        let (chart, account_id, category_id) = chart();
+        let mut writer = JournalWriter::new(Dialect::Ledger, chart);
+
+        let journal = writer.header() + &writer.entry(&expense(account_id, category_id));
+
+        let account = format!("{:<ACCOUNT_WIDTH$}", "Assets:Bank:Main-Checking");
+        let food = format!("{:<ACCOUNT_WIDTH$}", "Expenses:Eating-Out");
+        assert_eq!(
+            journal,
+            format!(
+                "account Assets:Bank:Main-Checking\naccount Expenses:Eating-Out\n\n\
+                2026-01-01 * Opening balance\n    {account}        100.00 EUR\n    \
+                {:<ACCOUNT_WIDTH$}       -100.00 EUR\n\n\
+                2025-12-31 * Pizza \"Roma\"\n    ; id: {}\n    ; summer-trip:\n    \
+                {food}         12.50 EUR\n    {account}        -12.50 EUR\n\n",
+                OPENING_BALANCES,
+                Uuid::from_u128(4)
+            )
+        );
+        assert_eq!(writer.footer(), "");
+    }
+
+    #[test]
+    fn test_beancount_opens_accounts_when_first_used() {
+        let (chart, account_id, category_id) = chart();
+        let mut writer = JournalWriter::new(Dialect::Beancount, chart);
+
+        writer.header();
+        let entry = writer.entry(&expense(account_id, category_id));
+        let unknown = writer.entry(&Expense::new(
+            Uuid::from_u128(5),
+            ExpenseName::new("Cash").unwrap(),
+        ));
+
+        assert!(entry.starts_with("2025-12-31 * \"Pizza \\\"Roma\\\"\" #summer-trip\n  id: "));
+        assert!(unknown.contains("Assets:Unknown"));
+        assert!(unknown.contains("Expenses:Uncategorized"));
+        assert!(unknown.contains("0.00 XXX"));
+        let footer = writer.footer();
+        assert!(footer.contains("2025-12-31 open Assets:Bank:Main-Checking EUR\n"));
+        assert!(footer.contains("2025-12-31 open Expenses:Eating-Out\n"));
+        assert!(footer.contains("2026-01-01 open Equity:Opening-Balances\n"));
+        assert!(!footer.contains("Income:"));
+    }
+}
@@ -0,0 +1,28 @@
+/*!
+    Module `export` renders the caller's data in the file formats of other finance tools.
+*/
+
+use crate::domain::finance::models::account::Currency;
+
+pub mod journal;
+pub mod qif;
+pub mod records;
+
+/// An amount in minor units of `currency`, written as a decimal such as `-12.50`.
+pub fn decimal(minor_units: i64, currency: &Currency) -> String {
+    let exponent = currency.exponent();
+    let scale = 10_u64.pow(exponent);
+    let sign = if minor_units < 0 { "-" } else { "" };
+    let (whole, fraction) = (
+        minor_units.unsigned_abs() / scale,
+        minor_units.unsigned_abs() % scale,
+    );
+    if exponent == 0 {
+        format!("{sign}{whole}")
+    } else {
+        format!(
+            "{sign}{whole}.{fraction:0width$}",
+            width = exponent as usize
+        )
+    }
+}
@@ -0,0 +1,233 @@
+/*!
+    Module `qif` writes transactions as QIF files, for older personal finance applications.
+
+    Each account gets an `!Account` block naming it, followed by a `!Type:` section of its
+    transactions: `Bank` for a bank account, `Cash` for a wallet and `CCard` for a card. A
+    transfer is written in both of its accounts, as those applications expect.
+*/
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::{Account, AccountKind, Currency};
+use crate::domain::finance::models::category::Category;
+use crate::domain::finance::models::expense::Expense;
+use crate::domain::finance::models::transaction::TransactionKind;
+
+use super::decimal;
+
+/// The account transactions without one, or with one of another user, are written in.
+const UNKNOWN_ACCOUNT: &str = "Unknown";
+/// The ISO 4217 code for transactions involving no currency, used for amounts whose currency is
+/// unknown.
+const NO_CURRENCY: &str = "XXX";
+
+/// Collects transactions by account, for them to be written together.
+#[derive(Debug, Clone)]
+pub struct QifWriter {
+    date_format: String,
+    accounts: Vec<Account>,
+    categories: BTreeMap<Uuid, String>,
+    /// The records of each account by position in `accounts`, those of unknown accounts last.
+    records: BTreeMap<usize, String>,
+}
+
+impl QifWriter {
+    /// Writes the transactions of `accounts`, filed under `categories`, with dates in the
+    /// strftime `date_format`.
+    pub fn new(accounts: &[Account], categories: &[Category], date_format: &str) -> Self {
+        let mut accounts = accounts.to_vec();
+        accounts.sort_by(|a, b| (a.name(), a.id()).cmp(&(b.name(), b.id())));
+        Self {
+            date_format: date_format.to_string(),
+            accounts,
+            categories: categories
+                .iter()
+                .map(|category| (*category.id(), category.name().to_string()))
+                .collect(),
+            records: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the record of `expense` to its account, and to the account it was moved to for a
+    /// transfer.
+    pub fn push(&mut self, expense: &Expense) {
+        let (position, currency) = self.account(expense.account_id());
+        let category = match expense.kind() {
+            TransactionKind::Transfer => {
+                let (to_position, _) = self.account(expense.to_account_id());
+                let record = self.record(
+                    expense,
+                    expense.amount(),
+                    &currency,
+                    &format!("[{}]", self.account_name(position)),
+                );
+                self.records
+                    .entry(to_position)
+                    .or_default()
+                    .push_str(&record);
+                format!("[{}]", self.account_name(to_position))
+            }
+            _ => expense
+                .category_id()
+                .and_then(|id| self.categories.get(id))
+                .cloned()
+                .unwrap_or_default(),
+        };
+        let amount = match expense.kind() {
+            TransactionKind::Income => expense.amount(),
+            TransactionKind::Expense | TransactionKind::Transfer => -expense.amount(),
+        };
+        let record = self.record(expense, amount, &currency, &category);
+        self.records.entry(position).or_default().push_str(&record);
+    }
+
+    /// The file, with the accounts sorted by name.
+    pub fn finish(&self) -> String {
+        let mut out = String::new();
+        for (position, records) in &self.records {
+            let kind = self
+                .accounts
+                .get(*position)
+                .map_or("Bank", |account| section(account.kind()));
+            let _ = write!(
+                out,
+                "!Account\nN{}\nT{kind}\n^\n!Type:{kind}\n{records}",
+                self.account_name(*position)
+            );
+        }
+        out
+    }
+
+    /// The position of the account `id` and its currency.
+    fn account(&self, id: Option<&Uuid>) -> (usize, Currency) {
+        id.and_then(|id| self.accounts.iter().position(|account| account.id() == id))
+            .map_or_else(
+                || {
+                    let currency = Currency::new(NO_CURRENCY).expect("XXX is a currency code");
+                    (self.accounts.len(), currency)
+                },
+                |position| (position, self.accounts[position].currency().clone()),
+            )
+    }
+
+    fn account_name(&self, position: usize) -> String {
+        self.accounts.get(position).map_or_else(
+            || UNKNOWN_ACCOUNT.to_string(),
+            |account| single_line(&account.name().to_string()),
+        )
+    }
+
+    fn record(
+        &self,
+        expense: &Expense,
+        amount: i64,
+        currency: &Currency,
+        category: &str,
+    ) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "D{}", expense.spent_on().format(&self.date_format));
+        let _ = writeln!(out, "T{}", decimal(amount, currency));
+        let _ = writeln!(out, "P{}", single_line(&expense.name().to_string()));
+        if let Some(reference) = expense.reference() {
+            let _ = writeln!(out, "M{}", single_line(reference));
+        }
+        if !category.is_empty() {
+            let _ = writeln!(out, "L{}", single_line(category));
+        }
+        out.push_str("^\n");
+        out
+    }
+}
+
+/// The `!Type:` section of the transactions of an account of `kind`.
+fn section(kind: AccountKind) -> &'static str {
+    match kind {
+        AccountKind::Wallet => "Cash",
+        AccountKind::Bank => "Bank",
+        AccountKind::Card => "CCard",
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+    use crate::domain::finance::models::account::AccountName;
+    use crate::domain::finance::models::category::CategoryName;
+    use crate::domain::finance::models::expense::ExpenseName;
+    use crate::inbound::import::qif::{QifFormat, parse_transactions};
+
+    #[test]
+    fn test_written_file_reads_back() {
+        let created_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let account = |id, name, kind| {
+            Account::new(
+                Uuid::from_u128(id),
+                Uuid::from_u128(9),
+                AccountName::new(name).unwrap(),
+                kind,
+                Currency::new("EUR").unwrap(),
+                0,
+                created_at,
+            )
+        };
+        let category = Category::new(
+            Uuid::from_u128(3),
+            CategoryName::new("Food").unwrap(),
+            None,
+            Uuid::from_u128(9),
+            created_at,
+        );
+        let mut writer = QifWriter::new(
+            &[
+                account(1, "Wallet", AccountKind::Wallet),
+                account(2, "Checking", AccountKind::Bank),
+            ],
+            &[category],
+            "%d/%m/%Y",
+        );
+        let spent_on = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        writer.push(
+            &Expense::new(Uuid::from_u128(4), ExpenseName::new("Bakery").unwrap())
+                .with_amount(1250)
+                .with_account(Uuid::from_u128(1))
+                .with_category(Uuid::from_u128(3))
+                .with_spent_on(spent_on)
+                .with_reference("Invoice 42"),
+        );
+        writer.push(
+            &Expense::new(Uuid::from_u128(5), ExpenseName::new("Cash").unwrap())
+                .with_kind(TransactionKind::Transfer)
+                .with_amount(5000)
+                .with_account(Uuid::from_u128(2))
+                .with_to_account(Uuid::from_u128(1))
+                .with_spent_on(spent_on),
+        );
+
+        let file = writer.finish();
+
+        assert_eq!(
+            file,
+            "!Account\nNChecking\nTBank\n^\n!Type:Bank\n\
+            D18/10/2026\nT-50.00\nPCash\nL[Wallet]\n^\n\
+            !Account\nNWallet\nTCash\n^\n!Type:Cash\n\
+            D18/10/2026\nT-12.50\nPBakery\nMInvoice 42\nLFood\n^\n\
+            D18/10/2026\nT50.00\nPCash\nL[Checking]\n^\n"
+        );
+        let rows = parse_transactions(
+            file.as_bytes(),
+            &QifFormat::new().with_date_format("%d/%m/%Y"),
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].as_ref().unwrap().category(), Some("Food"));
+    }
+}
@@ -0,0 +1,227 @@
+/*!
+    Module `records` writes expenses one record at a time, as CSV lines, a JSON array or JSON
+    lines, so that an export is sent while it is still being read.
+
+    CSV files are meant to be opened in spreadsheets, which run any cell starting like a formula:
+    such fields are written with a leading `'` so that they are shown as text instead.
+*/
+
+use anyhow::{Context, anyhow};
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, future};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::expense::Expense;
+use crate::domain::finance::models::tag::Tag;
+use crate::domain::finance::ports::{ExpenseRepositoryError, ExpenseStream};
+
+/// The chunks of an export, or the error that cut it short.
+pub type ExportChunks = BoxStream<'static, Result<Vec<u8>, ExpenseRepositoryError>>;
+
+/// The columns of a CSV export.
+const CSV_HEADER: [&str; 8] = [
+    "id",
+    "spent_on",
+    "name",
+    "amount",
+    "category_id",
+    "account_id",
+    "paid_by",
+    "tags",
+];
+
+/// The first characters that make spreadsheets read a cell as a formula.
+const FORMULA_TRIGGERS: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Encodes expenses as CSV lines after a header line, tags being separated by spaces.
+pub fn encode_csv(expenses: ExpenseStream) -> ExportChunks {
+    let header = stream::once(future::ready(csv_line(&CSV_HEADER.map(String::from))));
+    let lines = expenses.map(|expense| {
+        let expense = expense?;
+        csv_line(&[
+            expense.id().to_string(),
+            expense.spent_on().to_string(),
+            expense.name().to_string(),
+            expense.amount().to_string(),
+            expense
+                .category_id()
+                .map(Uuid::to_string)
+                .unwrap_or_default(),
+            expense
+                .account_id()
+                .map(Uuid::to_string)
+                .unwrap_or_default(),
+            expense
+                .split()
+                .map(|split| split.paid_by().to_string())
+                .unwrap_or_default(),
+            expense
+                .tags()
+                .iter()
+                .map(Tag::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ])
+    });
+    header.chain(lines).boxed()
+}
+
+/// Writes a single CSV line, quoting fields as needed and defusing those read as formulas.
+fn csv_line(fields: &[String]) -> Result<Vec<u8>, ExpenseRepositoryError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(fields.iter().map(|field| as_text(field)))
+        .context("failed to write CSV line")?;
+    writer
+        .into_inner()
+        .map_err(|e| anyhow!("failed to write CSV line: {}", e.error()).into())
+}
+
+/// `field`, prefixed with `'` if a spreadsheet would otherwise run it as a formula.
+fn as_text(field: &str) -> String {
+    if field.starts_with(FORMULA_TRIGGERS) {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    }
+}
+
+/// Encodes expenses as a single JSON array of the records made by `record`, written one element
+/// at a time.
+pub fn encode_json<T: Serialize + 'static>(
+    expenses: ExpenseStream,
+    record: fn(&Expense) -> T,
+) -> ExportChunks {
+    let elements = expenses.enumerate().map(move |(index, expense)| {
+        let mut chunk = if index == 0 {
+            Vec::new()
+        } else {
+            b",".to_vec()
+        };
+        serde_json::to_writer(&mut chunk, &record(&expense?)).context("failed to write JSON")?;
+        Ok(chunk)
+    });
+    stream::once(future::ready(Ok(b"[".to_vec())))
+        .chain(elements)
+        .chain(stream::once(future::ready(Ok(b"]\n".to_vec()))))
+        .boxed()
+}
+
+/// Encodes expenses as the JSON records made by `record`, one per line.
+pub fn encode_ndjson<T: Serialize + 'static>(
+    expenses: ExpenseStream,
+    record: fn(&Expense) -> T,
+) -> ExportChunks {
+    expenses
+        .map(move |expense| {
+            let mut line =
+                serde_json::to_vec(&record(&expense?)).context("failed to write JSON")?;
+            line.push(b'\n');
+            Ok(line)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use futures::TryStreamExt;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::domain::finance::models::expense::ExpenseName;
+
+    fn expense(id: u128, name: &str) -> Expense {
+        Expense::new(Uuid::from_u128(id), ExpenseName::new(name).unwrap())
+            .with_amount(4250)
+            .with_spent_on(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap())
+    }
+
+    fn stream_of(expenses: Vec<Expense>) -> ExpenseStream {
+        stream::iter(expenses.into_iter().map(Ok)).boxed()
+    }
+
+    fn record(expense: &Expense) -> Value {
+        json!({ "id": expense.id(), "name": expense.name().to_string() })
+    }
+
+    async fn text(chunks: ExportChunks) -> String {
+        let chunks: Vec<Vec<u8>> = chunks.try_collect().await.unwrap();
+        String::from_utf8(chunks.concat()).unwrap()
+    }
+
+    fn expenses(count: u128) -> Vec<Expense> {
+        (1..=count).map(|id| expense(id, "Lunch")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_csv_escapes_fields() {
+        let expense = expense(0, "Dinner, \"Chez Paul\"").with_tags(vec![
+            Tag::new("food").unwrap(),
+            Tag::new("friends").unwrap(),
+        ]);
+
+        assert_eq!(
+            text(encode_csv(stream_of(vec![expense]))).await,
+            "id,spent_on,name,amount,category_id,account_id,paid_by,tags\n\
+             00000000-0000-0000-0000-000000000000,2026-10-18,\"Dinner, \"\"Chez Paul\"\"\",4250,,,,food friends\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_defuses_formulas() {
+        let expenses = ["=HYPERLINK(\"x\")", "+1", "-1", "@SUM(A1)", "a=b"]
+            .into_iter()
+            .map(|name| expense(0, name))
+            .collect();
+
+        let csv = text(encode_csv(stream_of(expenses))).await;
+
+        let names: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').nth(2).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "\"'=HYPERLINK(\"\"x\"\")\"",
+                "'+1",
+                "'-1",
+                "'@SUM(A1)",
+                "a=b"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_is_one_array() {
+        for count in [0, 1, 3] {
+            let json = text(encode_json(stream_of(expenses(count)), record)).await;
+
+            let parsed: Vec<Value> = serde_json::from_str(&json)
+                .unwrap_or_else(|e| panic!("invalid JSON for {count} rows: {e}: {json}"));
+            assert_eq!(parsed.len(), count as usize);
+            assert_eq!(
+                parsed.first().map(|record| record["name"].clone()),
+                (count > 0).then(|| json!("Lunch"))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_is_one_object_per_line() {
+        for count in [0, 1, 3] {
+            let ndjson = text(encode_ndjson(stream_of(expenses(count)), record)).await;
+
+            assert!(ndjson.is_empty() || ndjson.ends_with('\n'));
+            let ids: Vec<Value> = ndjson
+                .lines()
+                .map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].clone())
+                .collect();
+            let expected: Vec<Value> = (1..=count).map(|id| json!(Uuid::from_u128(id))).collect();
+            assert_eq!(ids, expected);
+        }
+    }
+}
@@ -3,12 +3,41 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use uuid::Uuid;
 
 use crate::{
-    domain::finance::models::expense::{
-        CreateExpenseError, ExpenseNameEmptyError, PaginationError,
+    domain::auth::models::{
+        api_key::{ApiKeyError, ApiKeyNameEmptyError},
+        principal::UnknownScopeError,
+    },
+    domain::finance::models::{
+        account::{
+            AccountError, AccountNameEmptyError, InvalidCurrencyError, UnknownAccountKindError,
+        },
+        attachment::AttachmentError,
+        budget::{BudgetError, UnknownBudgetPeriodError},
+        category::{CategoryError, CategoryNameEmptyError},
+        duplicate::{DuplicateError, UnknownDuplicateModeError},
+        expense::{
+            CreateExpenseError, ExpenseAmountNegativeError, ExpenseNameEmptyError,
+            ListExpensesError, PaginationError,
+        },
+        idempotency::{IdempotencyError, InvalidIdempotencyKeyError},
+        import::ImportError,
+        ledger::{InvitationError, LedgerError, LedgerNameEmptyError, UnknownLedgerRoleError},
+        recurring::{RecurrenceError, RecurringExpenseError, UnknownFrequencyError},
+        report::{ReportError, UnknownSeriesIntervalError, UnknownSummaryGroupingError},
+        rule::{RuleError, RuleNameEmptyError},
+        settlement::SettlementError,
+        split::SplitError,
+        tag::TagEmptyError,
+        transaction::UnknownTransactionKindError,
+    },
+    domain::finance::policy::{Denial, DenialReason, PolicyError},
+    inbound::http::responses::{ApiResponseBody, DenialData},
+    inbound::import::{
+        camt::CamtError, csv::CsvImportError, mt940::Mt940Error, ofx::OfxError, qif::QifError,
     },
-    inbound::http::responses::ApiResponseBody,
 };
 
 /// Represents errors that can occur in the API layer.
@@ -22,15 +51,81 @@ pub enum ApiError {
     UnprocessableEntity(String),
     /// Not found error (HTTP 404).
     NotFoundError(String),
+    /// Payload too large error (HTTP 413).
+    PayloadTooLarge(String),
+    /// Conflict error (HTTP 409): the request clashes with one still being processed.
+    Conflict(String),
+    /// Unauthorized error (HTTP 401): the caller could not be identified.
+    Unauthorized(String),
+    /// Forbidden error (HTTP 403): the caller is identified but not allowed to act.
+    Forbidden(String),
+    /// Forbidden error (HTTP 403) raised by the authorization policy, detailing what was refused.
+    PolicyDenied(Denial),
+}
+
+/// Converts `PolicyError` into an `ApiError`.
+impl From<PolicyError> for ApiError {
+    fn from(e: PolicyError) -> Self {
+        match e {
+            e @ PolicyError::Unauthenticated { .. } => Self::Unauthorized(e.to_string()),
+            e @ (PolicyError::LedgerNotFound { .. } | PolicyError::NotOwner { .. }) => {
+                Self::NotFoundError(e.to_string())
+            }
+            PolicyError::Denied(denial) => Self::PolicyDenied(denial),
+            PolicyError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+impl From<&Denial> for DenialData {
+    fn from(denial: &Denial) -> Self {
+        let (required, actual) = match denial.reason() {
+            DenialReason::InsufficientRole { required, actual } => {
+                (Some(required.to_string()), Some(actual.to_string()))
+            }
+            DenialReason::InsufficientScope { required, actual } => {
+                (Some(required.to_string()), Some(actual.to_string()))
+            }
+            DenialReason::NotPermitted => (None, None),
+        };
+        let reason = match denial.reason() {
+            DenialReason::InsufficientRole { .. } => "insufficient_role",
+            DenialReason::InsufficientScope { .. } => "insufficient_scope",
+            DenialReason::NotPermitted => "not_permitted",
+        };
+        Self {
+            action: denial.action().to_string(),
+            resource: denial.resource().kind().to_string(),
+            ledger_id: denial.resource().ledger_id().map(Uuid::to_string),
+            reason: reason.to_string(),
+            required,
+            actual,
+        }
+    }
 }
 
 /// Converts `CreateExpenseError` into an `ApiError`.
 impl From<CreateExpenseError> for ApiError {
     fn from(e: CreateExpenseError) -> Self {
         match e {
-            CreateExpenseError::Duplicate { name } => {
-                Self::UnprocessableEntity(format!("expense with name {} already exists", name))
+            e @ (CreateExpenseError::Duplicate { .. }
+            | CreateExpenseError::DuplicateOccurrence { .. }
+            | CreateExpenseError::SplitWithoutLedger
+            | CreateExpenseError::SplitNotExpense { .. }
+            | CreateExpenseError::NotLedgerMember { .. }
+            | CreateExpenseError::CategoryNotFound { .. }
+            | CreateExpenseError::AccountRequired
+            | CreateExpenseError::AccountNotFound { .. }
+            | CreateExpenseError::TransferWithoutDestination
+            | CreateExpenseError::DestinationNotTransfer { .. }
+            | CreateExpenseError::TransferToSameAccount { .. }
+            | CreateExpenseError::CurrencyMismatch { .. }) => {
+                Self::UnprocessableEntity(e.to_string())
             }
+            CreateExpenseError::Policy(e) => e.into(),
             CreateExpenseError::Unknown(cause) => {
                 tracing::error!("{:?}\n", cause);
                 Self::InternalServerError("Internal server error".to_string())
@@ -38,6 +133,19 @@ impl From<CreateExpenseError> for ApiError {
         }
     }
 }
+/// Converts `ListExpensesError` into an `ApiError`.
+impl From<ListExpensesError> for ApiError {
+    fn from(e: ListExpensesError) -> Self {
+        match e {
+            ListExpensesError::Policy(e) => e.into(),
+            ListExpensesError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
 /// Converts `PaginationError` into an `ApiError`.
 impl From<PaginationError> for ApiError {
     fn from(e: PaginationError) -> Self {
@@ -63,6 +171,421 @@ impl From<ExpenseNameEmptyError> for ApiError {
     }
 }
 
+/// Converts `ExpenseAmountNegativeError` into an `ApiError`.
+impl From<ExpenseAmountNegativeError> for ApiError {
+    fn from(e: ExpenseAmountNegativeError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `SplitError` into an `ApiError`.
+impl From<SplitError> for ApiError {
+    fn from(e: SplitError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `UnknownTransactionKindError` into an `ApiError`.
+impl From<UnknownTransactionKindError> for ApiError {
+    fn from(e: UnknownTransactionKindError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `LedgerNameEmptyError` into an `ApiError`.
+impl From<LedgerNameEmptyError> for ApiError {
+    fn from(_: LedgerNameEmptyError) -> Self {
+        Self::UnprocessableEntity("ledger name cannot be empty".to_string())
+    }
+}
+
+/// Converts `UnknownLedgerRoleError` into an `ApiError`.
+impl From<UnknownLedgerRoleError> for ApiError {
+    fn from(e: UnknownLedgerRoleError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `LedgerError` into an `ApiError`.
+impl From<LedgerError> for ApiError {
+    fn from(e: LedgerError) -> Self {
+        match e {
+            LedgerError::NotFound { id } => Self::NotFoundError(format!("ledger {id} not found")),
+            LedgerError::Policy(e) => e.into(),
+            LedgerError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `InvitationError` into an `ApiError`.
+impl From<InvitationError> for ApiError {
+    fn from(e: InvitationError) -> Self {
+        match e {
+            InvitationError::NotFound => Self::NotFoundError("invitation not found".to_string()),
+            e @ (InvitationError::Expired { .. }
+            | InvitationError::AlreadyMember { .. }
+            | InvitationError::InvalidRole { .. }) => Self::UnprocessableEntity(e.to_string()),
+            InvitationError::Policy(e) => e.into(),
+            InvitationError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `SettlementError` into an `ApiError`.
+impl From<SettlementError> for ApiError {
+    fn from(e: SettlementError) -> Self {
+        match e {
+            SettlementError::Policy(e) => e.into(),
+            SettlementError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+            e => Self::UnprocessableEntity(e.to_string()),
+        }
+    }
+}
+
+/// Converts `CategoryNameEmptyError` into an `ApiError`.
+impl From<CategoryNameEmptyError> for ApiError {
+    fn from(_: CategoryNameEmptyError) -> Self {
+        Self::UnprocessableEntity("category name cannot be empty".to_string())
+    }
+}
+
+/// Converts `CategoryError` into an `ApiError`.
+impl From<CategoryError> for ApiError {
+    fn from(e: CategoryError) -> Self {
+        match e {
+            e @ CategoryError::Duplicate { .. } => Self::UnprocessableEntity(e.to_string()),
+            CategoryError::Policy(e) => e.into(),
+            CategoryError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `UnknownBudgetPeriodError` into an `ApiError`.
+impl From<UnknownBudgetPeriodError> for ApiError {
+    fn from(e: UnknownBudgetPeriodError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `BudgetError` into an `ApiError`.
+impl From<BudgetError> for ApiError {
+    fn from(e: BudgetError) -> Self {
+        match e {
+            BudgetError::NotFound { id } => Self::NotFoundError(format!("budget {id} not found")),
+            e @ (BudgetError::InvalidAmount { .. }
+            | BudgetError::CategoryNotFound { .. }
+            | BudgetError::Duplicate { .. }
+            | BudgetError::MissingExchangeRate(_)) => Self::UnprocessableEntity(e.to_string()),
+            BudgetError::Policy(e) => e.into(),
+            BudgetError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `RuleNameEmptyError` into an `ApiError`.
+impl From<RuleNameEmptyError> for ApiError {
+    fn from(e: RuleNameEmptyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `RuleError` into an `ApiError`.
+impl From<RuleError> for ApiError {
+    fn from(e: RuleError) -> Self {
+        match e {
+            RuleError::NotFound { id } => Self::NotFoundError(format!("rule {id} not found")),
+            e @ (RuleError::InvalidPattern { .. }
+            | RuleError::InvalidAmountRange { .. }
+            | RuleError::NoCondition
+            | RuleError::NoAction
+            | RuleError::CategoryNotFound { .. }
+            | RuleError::AccountNotFound { .. }) => Self::UnprocessableEntity(e.to_string()),
+            RuleError::Policy(e) => e.into(),
+            RuleError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `UnknownDuplicateModeError` into an `ApiError`.
+impl From<UnknownDuplicateModeError> for ApiError {
+    fn from(e: UnknownDuplicateModeError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `DuplicateError` into an `ApiError`.
+impl From<DuplicateError> for ApiError {
+    fn from(e: DuplicateError) -> Self {
+        match e {
+            DuplicateError::ExpenseNotFound { id } => {
+                Self::NotFoundError(format!("expense {id} not found"))
+            }
+            e @ (DuplicateError::InvalidDateWindow { .. }
+            | DuplicateError::InvalidSimilarity { .. }
+            | DuplicateError::NothingToMerge
+            | DuplicateError::MergeIntoItself { .. }
+            | DuplicateError::ScopeMismatch
+            | DuplicateError::HasAttachments { .. }) => Self::UnprocessableEntity(e.to_string()),
+            DuplicateError::Policy(e) => e.into(),
+            DuplicateError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `InvalidIdempotencyKeyError` into an `ApiError`.
+impl From<InvalidIdempotencyKeyError> for ApiError {
+    fn from(e: InvalidIdempotencyKeyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `IdempotencyError` into an `ApiError`.
+impl From<IdempotencyError> for ApiError {
+    fn from(e: IdempotencyError) -> Self {
+        match e {
+            e @ IdempotencyError::KeyReused { .. } => Self::UnprocessableEntity(e.to_string()),
+            e @ IdempotencyError::InProgress { .. } => Self::Conflict(e.to_string()),
+            IdempotencyError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `UnknownFrequencyError` into an `ApiError`.
+impl From<UnknownFrequencyError> for ApiError {
+    fn from(e: UnknownFrequencyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `RecurrenceError` into an `ApiError`.
+impl From<RecurrenceError> for ApiError {
+    fn from(e: RecurrenceError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `RecurringExpenseError` into an `ApiError`.
+impl From<RecurringExpenseError> for ApiError {
+    fn from(e: RecurringExpenseError) -> Self {
+        match e {
+            RecurringExpenseError::NotFound { id } => {
+                Self::NotFoundError(format!("recurring expense {id} not found"))
+            }
+            e @ (RecurringExpenseError::CategoryNotFound { .. }
+            | RecurringExpenseError::AccountRequired
+            | RecurringExpenseError::AccountNotFound { .. }
+            | RecurringExpenseError::RangeTooLong { .. }) => {
+                Self::UnprocessableEntity(e.to_string())
+            }
+            RecurringExpenseError::Policy(e) => e.into(),
+            e @ RecurringExpenseError::MaterializationFailed { .. } => {
+                tracing::error!("{}", e);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+            RecurringExpenseError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `AccountNameEmptyError` into an `ApiError`.
+impl From<AccountNameEmptyError> for ApiError {
+    fn from(e: AccountNameEmptyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `UnknownAccountKindError` into an `ApiError`.
+impl From<UnknownAccountKindError> for ApiError {
+    fn from(e: UnknownAccountKindError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `InvalidCurrencyError` into an `ApiError`.
+impl From<InvalidCurrencyError> for ApiError {
+    fn from(e: InvalidCurrencyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `AccountError` into an `ApiError`.
+impl From<AccountError> for ApiError {
+    fn from(e: AccountError) -> Self {
+        match e {
+            AccountError::NotFound { id } => Self::NotFoundError(format!("account {id} not found")),
+            e @ (AccountError::RangeTooLong { .. } | AccountError::MissingExchangeRate(_)) => {
+                Self::UnprocessableEntity(e.to_string())
+            }
+            AccountError::Policy(e) => e.into(),
+            AccountError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `TagEmptyError` into an `ApiError`.
+impl From<TagEmptyError> for ApiError {
+    fn from(e: TagEmptyError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `UnknownSummaryGroupingError` into an `ApiError`.
+impl From<UnknownSummaryGroupingError> for ApiError {
+    fn from(e: UnknownSummaryGroupingError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `UnknownSeriesIntervalError` into an `ApiError`.
+impl From<UnknownSeriesIntervalError> for ApiError {
+    fn from(e: UnknownSeriesIntervalError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `ReportError` into an `ApiError`.
+impl From<ReportError> for ApiError {
+    fn from(e: ReportError) -> Self {
+        match e {
+            e @ (ReportError::InvalidRange { .. }
+            | ReportError::TooManyPoints { .. }
+            | ReportError::MissingExchangeRate(_)) => Self::UnprocessableEntity(e.to_string()),
+            ReportError::Policy(e) => e.into(),
+            ReportError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `ImportError` into an `ApiError`.
+impl From<ImportError> for ApiError {
+    fn from(e: ImportError) -> Self {
+        match e {
+            e @ ImportError::AccountNotFound { .. } => Self::UnprocessableEntity(e.to_string()),
+            ImportError::Policy(e) => e.into(),
+            ImportError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `AttachmentError` into an `ApiError`.
+impl From<AttachmentError> for ApiError {
+    fn from(e: AttachmentError) -> Self {
+        match e {
+            e @ (AttachmentError::ExpenseNotFound { .. } | AttachmentError::NotFound { .. }) => {
+                Self::NotFoundError(e.to_string())
+            }
+            e @ AttachmentError::TooLarge { .. } => Self::PayloadTooLarge(e.to_string()),
+            e @ AttachmentError::UnsupportedContentType => Self::UnprocessableEntity(e.to_string()),
+            AttachmentError::Policy(e) => e.into(),
+            AttachmentError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
+/// Converts `CsvImportError` into an `ApiError`.
+impl From<CsvImportError> for ApiError {
+    fn from(e: CsvImportError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `OfxError` into an `ApiError`.
+impl From<OfxError> for ApiError {
+    fn from(e: OfxError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `CamtError` into an `ApiError`.
+impl From<CamtError> for ApiError {
+    fn from(e: CamtError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `Mt940Error` into an `ApiError`.
+impl From<Mt940Error> for ApiError {
+    fn from(e: Mt940Error) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `QifError` into an `ApiError`.
+impl From<QifError> for ApiError {
+    fn from(e: QifError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `ApiKeyNameEmptyError` into an `ApiError`.
+impl From<ApiKeyNameEmptyError> for ApiError {
+    fn from(_: ApiKeyNameEmptyError) -> Self {
+        Self::UnprocessableEntity("api key name cannot be empty".to_string())
+    }
+}
+
+/// Converts `UnknownScopeError` into an `ApiError`.
+impl From<UnknownScopeError> for ApiError {
+    fn from(e: UnknownScopeError) -> Self {
+        Self::UnprocessableEntity(e.to_string())
+    }
+}
+
+/// Converts `ApiKeyError` into an `ApiError`.
+impl From<ApiKeyError> for ApiError {
+    fn from(e: ApiKeyError) -> Self {
+        match e {
+            e @ (ApiKeyError::Invalid | ApiKeyError::Revoked { .. }) => {
+                Self::Unauthorized(e.to_string())
+            }
+            ApiKeyError::NotFound { id } => Self::NotFoundError(format!("api key {id} not found")),
+            ApiKeyError::Unknown(cause) => {
+                tracing::error!("{:?}\n", cause);
+                Self::InternalServerError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
 /// Converts `anyhow::Error` into an `ApiError`.
 impl From<anyhow::Error> for ApiError {
     fn from(e: anyhow::Error) -> Self {
@@ -105,6 +628,41 @@ impl IntoResponse for ApiError {
                 )),
             )
                 .into_response(),
+            PayloadTooLarge(message) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(ApiResponseBody::new_error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    message,
+                )),
+            )
+                .into_response(),
+            Conflict(message) => (
+                StatusCode::CONFLICT,
+                Json(ApiResponseBody::new_error(StatusCode::CONFLICT, message)),
+            )
+                .into_response(),
+            Unauthorized(message) => (
+                StatusCode::UNAUTHORIZED,
+                Json(ApiResponseBody::new_error(
+                    StatusCode::UNAUTHORIZED,
+                    message,
+                )),
+            )
+                .into_response(),
+            Forbidden(message) => (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponseBody::new_error(StatusCode::FORBIDDEN, message)),
+            )
+                .into_response(),
+            PolicyDenied(denial) => (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponseBody::new_denial(
+                    StatusCode::FORBIDDEN,
+                    denial.to_string(),
+                    DenialData::from(&denial),
+                )),
+            )
+                .into_response(),
         }
     }
 }
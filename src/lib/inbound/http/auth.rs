@@ -0,0 +1,221 @@
+/*!
+    Module `auth` resolves the caller of each request into a domain [Principal].
+
+    Scripts and CI jobs authenticate with an API key sent as `Authorization: Bearer dlk_…`.
+    Otherwise identity is asserted by the upstream gateway through the `X-User-Id` header, which is
+    only trusted alongside the gateway's [TrustedProxySecret] in `X-Proxy-Secret`: from any other
+    client both headers are stripped, so a caller cannot claim to be someone else. Requests with
+    neither are anonymous, and every route that needs a [Principal] rejects them with 401
+    Unauthorized.
+*/
+
+use std::fmt::{Debug, Formatter};
+
+use axum::extract::{FromRequestParts, OptionalFromRequestParts, Request, State};
+use axum::http::HeaderValue;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::middleware::Next;
+use axum::response::Response;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::auth::models::api_key::API_KEY_MARKER;
+use crate::domain::auth::models::principal::{Principal, Scope};
+use crate::domain::auth::ports::AuthService;
+
+use super::api_error::ApiError;
+use super::server::AuthState;
+
+/// Header carrying the id of the authenticated user.
+pub const USER_ID_HEADER: &str = "x-user-id";
+
+/// Header by which the upstream gateway proves that it set [USER_ID_HEADER].
+pub const PROXY_SECRET_HEADER: &str = "x-proxy-secret";
+
+/// The secret shared with the upstream gateway, configured by `TRUSTED_PROXY_SECRET`.
+///
+/// Without one, no request may assert its identity through [USER_ID_HEADER].
+#[derive(Clone, PartialEq, Eq)]
+pub struct TrustedProxySecret(String);
+
+impl TrustedProxySecret {
+    pub fn new(secret: String) -> anyhow::Result<Self> {
+        anyhow::ensure!(!secret.trim().is_empty(), "trusted proxy secret is empty");
+        Ok(Self(secret))
+    }
+
+    /// Compare digests rather than the secrets themselves, so the time taken does not tell how
+    /// much of `candidate` is right.
+    fn matches(&self, candidate: &[u8]) -> bool {
+        Sha256::digest(self.0.as_bytes()) == Sha256::digest(candidate)
+    }
+}
+
+impl Debug for TrustedProxySecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TrustedProxySecret(..)")
+    }
+}
+
+/// Middleware resolving the [Principal] making the request and storing it in the request
+/// extensions for the [Authenticated] extractor.
+///
+/// An API key takes precedence over the identity header. Bearer tokens that are not API keys are
+/// left to the gateway and ignored here. The identity and proxy secret headers never reach the
+/// handlers.
+///
+/// # Responses
+///
+/// - 401 Unauthorized: the API key is invalid or revoked, or the identity header is malformed.
+pub async fn authenticate<AS: AuthService>(
+    State(state): State<AuthState<AS>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let asserted_user = take_asserted_user(&mut req, state.trusted_proxy_secret.as_ref());
+    if let Some(raw) = bearer_api_key(&req) {
+        let principal = state.auth_service.authenticate_api_key(&raw).await?;
+        req.extensions_mut().insert(principal);
+    } else if let Some(value) = asserted_user {
+        let user_id = value
+            .to_str()
+            .ok()
+            .and_then(|raw| Uuid::parse_str(raw.trim()).ok())
+            .ok_or_else(|| ApiError::Unauthorized(format!("invalid {} header", USER_ID_HEADER)))?;
+        req.extensions_mut().insert(Principal::new(user_id));
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Remove the identity and proxy secret headers from `req`, returning the identity header if it
+/// was sent by the trusted gateway.
+fn take_asserted_user(
+    req: &mut Request,
+    trusted_proxy_secret: Option<&TrustedProxySecret>,
+) -> Option<HeaderValue> {
+    let headers = req.headers_mut();
+    let proxy_secret = headers.remove(PROXY_SECRET_HEADER);
+    let user_id = headers.remove(USER_ID_HEADER)?;
+    let trusted = trusted_proxy_secret
+        .zip(proxy_secret)
+        .is_some_and(|(secret, candidate)| secret.matches(candidate.as_bytes()));
+    if !trusted {
+        tracing::warn!(
+            "Ignoring {} header not sent by the trusted proxy",
+            USER_ID_HEADER
+        );
+        return None;
+    }
+    Some(user_id)
+}
+
+fn bearer_api_key(req: &Request) -> Option<String> {
+    let value = req.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+    token
+        .starts_with(&format!("{}_", API_KEY_MARKER))
+        .then(|| token.to_string())
+}
+
+/// Route middleware rejecting callers whose [Scope] does not allow writes.
+///
+/// Anonymous callers pass through: routes that need a [Principal] reject them on their own.
+///
+/// # Responses
+///
+/// - 403 Forbidden: the caller authenticated with a read-only API key.
+pub async fn require_write_scope(req: Request, next: Next) -> Result<Response, ApiError> {
+    if let Some(principal) = req.extensions().get::<Principal>()
+        && principal.scope() < Scope::ReadWrite
+    {
+        return Err(ApiError::Forbidden(format!(
+            "{} scope required",
+            Scope::ReadWrite
+        )));
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Extractor for the [Principal] resolved by [authenticate].
+///
+/// Extracting `Authenticated` rejects anonymous requests with 401 Unauthorized, while
+/// `Option<Authenticated>` lets handlers serve both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authenticated(pub Principal);
+
+impl<S: Send + Sync> FromRequestParts<S> for Authenticated {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        <Self as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+            .await?
+            .ok_or_else(|| ApiError::Unauthorized("authentication required".to_string()))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for Authenticated {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .map(Authenticated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+
+    use super::*;
+
+    const USER_ID: &str = "7f0c9a52-8a8e-4d1b-9a44-3b1f0e0a5c11";
+
+    fn request(proxy_secret: Option<&str>) -> Request {
+        let mut builder = Request::builder().header(USER_ID_HEADER, USER_ID);
+        if let Some(proxy_secret) = proxy_secret {
+            builder = builder.header(PROXY_SECRET_HEADER, proxy_secret);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_identity_header_is_only_trusted_from_the_proxy() {
+        let secret = TrustedProxySecret::new("s3cret".to_string()).unwrap();
+        let cases = [
+            (Some(&secret), Some("s3cret"), true),
+            (Some(&secret), Some("guess"), false),
+            (Some(&secret), None, false),
+            (None, Some("s3cret"), false),
+            (None, None, false),
+        ];
+        for (configured, sent, trusted) in cases {
+            let mut req = request(sent);
+            let asserted = take_asserted_user(&mut req, configured);
+            assert_eq!(
+                asserted.is_some(),
+                trusted,
+                "configured: {:?}, sent: {:?}",
+                configured,
+                sent
+            );
+            assert!(req.headers().get(USER_ID_HEADER).is_none());
+            assert!(req.headers().get(PROXY_SECRET_HEADER).is_none());
+        }
+    }
+
+    #[test]
+    fn test_proxy_secret_is_not_logged() {
+        let secret = TrustedProxySecret::new("s3cret".to_string()).unwrap();
+        assert!(!format!("{:?}", secret).contains("s3cret"));
+        assert!(TrustedProxySecret::new(" ".to_string()).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::{Account, AccountBalance, BalancePoint, Currency};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::account_schema::{AccountBalanceQueryParams, CreateAccountHttpRequestBody};
+use super::expense::ListItemsResponseData;
+
+/// How far back the balance history goes when no start date is given, in days.
+const DEFAULT_HISTORY_DAYS: u64 = 30;
+
+///
+/// `AccountResponseData`
+/// The response body data field for [Account] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountResponseData {
+    id: String,
+    name: String,
+    kind: String,
+    currency: String,
+    opening_balance: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&Account> for AccountResponseData {
+    fn from(account: &Account) -> Self {
+        Self {
+            id: account.id().to_string(),
+            name: account.name().to_string(),
+            kind: account.kind().to_string(),
+            currency: account.currency().to_string(),
+            opening_balance: account.opening_balance(),
+            created_at: *account.created_at(),
+        }
+    }
+}
+
+///
+/// `AccountBalanceResponseData`
+/// The response body data field for an [Account] with its [AccountBalance].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountBalanceResponseData {
+    #[serde(flatten)]
+    account: AccountResponseData,
+    /// The currency of `balance` and `history`, which may differ from that of the account.
+    balance_currency: String,
+    balance: i64,
+    history: Vec<BalancePointResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalancePointResponseData {
+    day: NaiveDate,
+    balance: i64,
+}
+
+impl From<&BalancePoint> for BalancePointResponseData {
+    fn from(point: &BalancePoint) -> Self {
+        Self {
+            day: *point.day(),
+            balance: point.balance(),
+        }
+    }
+}
+
+impl From<&AccountBalance> for AccountBalanceResponseData {
+    fn from(balance: &AccountBalance) -> Self {
+        Self {
+            account: balance.account().into(),
+            balance_currency: balance.currency().to_string(),
+            balance: balance.balance(),
+            history: balance.history().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Open an [Account] for the caller.
+///
+/// # Responses
+///
+/// - 201 Created: the [Account] was successfully opened.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the name is empty, or the kind or currency invalid.
+pub async fn create_account<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateAccountHttpRequestBody>,
+) -> Result<ApiSuccess<AccountResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(*principal.user_id())?;
+    state
+        .finance_service
+        .create_account(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref account| ApiSuccess::new(StatusCode::CREATED, account.into()))
+}
+
+/// List the caller's [Account]s.
+///
+/// # Responses
+///
+/// - 200 OK: the [Account] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+pub async fn list_accounts<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<AccountResponseData>>, ApiError> {
+    state
+        .finance_service
+        .list_accounts(&principal)
+        .await
+        .map_err(ApiError::from)
+        .map(|accounts| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(accounts.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Retrieve an [Account] with its current balance and its end-of-day balances over the last 30
+/// days by default, optionally converted to another currency.
+///
+/// # Responses
+///
+/// - 200 OK: the [Account] and its [AccountBalance] are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Account] does not exist or belongs to someone else.
+/// - 422 Unprocessable entity: the range spans more than a year, the currency is invalid, or an
+///   exchange rate needed for the conversion is missing.
+pub async fn get_account<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<AccountBalanceQueryParams>,
+) -> Result<ApiSuccess<AccountBalanceResponseData>, ApiError> {
+    let currency = query.currency.as_deref().map(Currency::new).transpose()?;
+    let today = Utc::now().date_naive();
+    let until = query.until.unwrap_or(today);
+    let from = query
+        .from
+        .or_else(|| until.checked_sub_days(Days::new(DEFAULT_HISTORY_DAYS)))
+        .unwrap_or(until);
+    state
+        .finance_service
+        .account_balance(&principal, &id, currency.as_ref(), today, from, until)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref balance| ApiSuccess::new(StatusCode::OK, balance.into()))
+}
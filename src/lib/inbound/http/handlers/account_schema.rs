@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::{AccountKind, CreateAccountRequest, Currency};
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateAccountHttpRequestBody]
+/// The HTTP Request body for opening an [Account]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateAccountHttpRequestBody {
+    pub name: String,
+    /// One of `wallet`, `bank` or `card`.
+    pub kind: String,
+    /// ISO 4217 code, e.g. `EUR`.
+    pub currency: String,
+    /// Balance before any transaction in minor units. Defaults to 0.
+    pub opening_balance: Option<i64>,
+}
+
+impl CreateAccountHttpRequestBody {
+    /// Converts the HTTP request body into a domain request opened by `owner_id`.
+    pub fn try_into_domain(self, owner_id: Uuid) -> Result<CreateAccountRequest, ApiError> {
+        let kind = AccountKind::from_str(&self.kind)?;
+        let currency = Currency::new(&self.currency)?;
+        Ok(
+            CreateAccountRequest::new(owner_id, &self.name, kind, currency)?
+                .with_opening_balance(self.opening_balance.unwrap_or(0)),
+        )
+    }
+}
+
+///
+/// [AccountBalanceQueryParams]
+/// The query parameters bounding the balance history of an [Account]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AccountBalanceQueryParams {
+    /// Defaults to 30 days before `until`.
+    pub from: Option<NaiveDate>,
+    /// Inclusive. Defaults to today.
+    pub until: Option<NaiveDate>,
+    /// ISO 4217 code to convert the balances to. Defaults to the currency of the account.
+    pub currency: Option<String>,
+}
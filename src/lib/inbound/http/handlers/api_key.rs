@@ -0,0 +1,144 @@
+use axum::extract::Path;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::auth::models::api_key::{ApiKey, IssuedApiKey};
+use crate::domain::auth::models::principal::Principal;
+use crate::domain::auth::ports::AuthService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::ApiKeyState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::api_key_schema::CreateApiKeyHttpRequestBody;
+use super::expense::ListItemsResponseData;
+
+///
+/// `ApiKeyResponseData`
+/// The response body data field for [ApiKey] data. Never includes the secret.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiKeyResponseData {
+    id: String,
+    name: String,
+    prefix: String,
+    scope: String,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ApiKey> for ApiKeyResponseData {
+    fn from(api_key: &ApiKey) -> Self {
+        Self {
+            id: api_key.id().to_string(),
+            name: api_key.name().to_string(),
+            prefix: api_key.prefix().to_string(),
+            scope: api_key.scope().to_string(),
+            created_at: *api_key.created_at(),
+            last_used_at: api_key.last_used_at().copied(),
+            revoked_at: api_key.revoked_at().copied(),
+        }
+    }
+}
+
+///
+/// `IssuedApiKeyResponseData`
+/// The response body data field for a freshly issued [ApiKey], carrying its plaintext secret.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IssuedApiKeyResponseData {
+    #[serde(flatten)]
+    api_key: ApiKeyResponseData,
+    key: String,
+}
+
+impl From<&IssuedApiKey> for IssuedApiKeyResponseData {
+    fn from(issued: &IssuedApiKey) -> Self {
+        Self {
+            api_key: issued.api_key().into(),
+            key: issued.secret().expose(),
+        }
+    }
+}
+
+/// API keys are managed by their owner in person: a key cannot mint or revoke other keys.
+fn require_interactive(principal: &Principal) -> Result<(), ApiError> {
+    match principal.api_key_id() {
+        Some(_) => Err(ApiError::Forbidden(
+            "api keys cannot manage api keys".to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Issue a new [ApiKey] acting on behalf of the caller.
+///
+/// # Responses
+///
+/// - 201 Created: the [ApiKey] was issued. The plaintext key is only ever returned here.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller authenticated with an API key.
+/// - 422 Unprocessable entity: the name is empty or the scope is unknown.
+pub async fn create_api_key<AS: AuthService>(
+    State(state): State<ApiKeyState<AS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateApiKeyHttpRequestBody>,
+) -> Result<ApiSuccess<IssuedApiKeyResponseData>, ApiError> {
+    require_interactive(&principal)?;
+    let domain_req = body.try_into_domain(*principal.user_id())?;
+    state
+        .auth_service
+        .create_api_key(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref issued| ApiSuccess::new(StatusCode::CREATED, issued.into()))
+}
+
+/// List the caller's [ApiKey]s, including revoked ones.
+///
+/// # Responses
+///
+/// - 200 OK: the caller's keys.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller authenticated with an API key.
+pub async fn list_api_keys<AS: AuthService>(
+    State(state): State<ApiKeyState<AS>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<ApiKeyResponseData>>, ApiError> {
+    require_interactive(&principal)?;
+    state
+        .auth_service
+        .list_api_keys(principal.user_id())
+        .await
+        .map_err(ApiError::from)
+        .map(|api_keys| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(api_keys.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Revoke one of the caller's [ApiKey]s. Requests made with it are rejected from then on.
+///
+/// # Responses
+///
+/// - 200 OK: the [ApiKey] is revoked.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller authenticated with an API key.
+/// - 404 Not Found: the caller has no such key.
+pub async fn revoke_api_key<AS: AuthService>(
+    State(state): State<ApiKeyState<AS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ApiKeyResponseData>, ApiError> {
+    require_interactive(&principal)?;
+    state
+        .auth_service
+        .revoke_api_key(principal.user_id(), &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref api_key| ApiSuccess::new(StatusCode::OK, api_key.into()))
+}
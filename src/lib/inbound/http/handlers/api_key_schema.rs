@@ -0,0 +1,30 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::auth::models::api_key::CreateApiKeyRequest;
+use crate::domain::auth::models::principal::Scope;
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateApiKeyHttpRequestBody]
+/// The HTTP Request body for issuing an [ApiKey]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateApiKeyHttpRequestBody {
+    pub name: String,
+    /// Either `read` or `read_write`. Defaults to `read`.
+    pub scope: Option<String>,
+}
+
+impl CreateApiKeyHttpRequestBody {
+    /// Converts the HTTP request body into a domain request for a key acting as `user_id`.
+    pub fn try_into_domain(self, user_id: Uuid) -> Result<CreateApiKeyRequest, ApiError> {
+        let scope = match self.scope {
+            Some(raw) => Scope::from_str(&raw)?,
+            None => Scope::Read,
+        };
+        Ok(CreateApiKeyRequest::new(user_id, &self.name, scope)?)
+    }
+}
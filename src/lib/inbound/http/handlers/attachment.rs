@@ -0,0 +1,186 @@
+use axum::body::Body;
+use axum::extract::{Multipart, Path, Query};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::finance::models::attachment::{
+    Attachment, AttachmentVariant, CreateAttachmentRequest, MAX_ATTACHMENT_SIZE, ThumbnailStatus,
+};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::import::invalid_body;
+
+/// The largest multipart body accepted by an upload: the largest attachment, and room for the
+/// boundaries and headers around it.
+pub const MAX_ATTACHMENT_BODY_SIZE: usize = MAX_ATTACHMENT_SIZE + 64 * 1024;
+
+///
+/// `AttachmentResponseData`
+/// The response body data field for [Attachment] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttachmentResponseData {
+    id: String,
+    file_name: String,
+    content_type: String,
+    size: u64,
+    created_at: DateTime<Utc>,
+    /// Where the content can be downloaded, with the same credentials.
+    url: String,
+    /// `pending`, `ready` or `unavailable`.
+    thumbnail_status: String,
+    /// Where the thumbnail can be downloaded, once ready.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail_url: Option<String>,
+}
+
+impl From<&Attachment> for AttachmentResponseData {
+    fn from(attachment: &Attachment) -> Self {
+        let url = format!(
+            "/api/expenses/{}/attachments/{}",
+            attachment.expense_id(),
+            attachment.id()
+        );
+        Self {
+            id: attachment.id().to_string(),
+            file_name: attachment.file_name().to_string(),
+            content_type: attachment.content_type().to_string(),
+            size: attachment.size(),
+            created_at: *attachment.created_at(),
+            thumbnail_status: attachment.thumbnail_status().to_string(),
+            thumbnail_url: (attachment.thumbnail_status() == ThumbnailStatus::Ready)
+                .then(|| format!("{url}?variant=thumb")),
+            url,
+        }
+    }
+}
+
+///
+/// [AttachmentQueryParams]
+/// The query parameters of the download of an [Attachment]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AttachmentQueryParams {
+    /// `original`, the default, or `thumb`.
+    pub variant: Option<String>,
+}
+
+/// Attach a file, such as the photo of a receipt, to an expense.
+///
+/// The multipart body carries the file as `file`. Its type is read from its content, whatever
+/// its name or declared type say.
+///
+/// # Responses
+///
+/// - 201 Created: the attachment is returned, with the URL to download it from.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may not change expenses in the ledger.
+/// - 404 Not Found: the expense does not exist or is not visible to the caller.
+/// - 413 Payload Too Large: the file is larger than 10 MiB.
+/// - 422 Unprocessable entity: the body cannot be read, or the file is neither a JPEG, PNG, GIF,
+///   WebP or HEIC picture nor a PDF document.
+pub async fn add_attachment<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(expense_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    mut multipart: Multipart,
+) -> Result<ApiSuccess<AttachmentResponseData>, ApiError> {
+    let mut file = None;
+    while let Some(field) = multipart.next_field().await.map_err(invalid_body)? {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            file = Some((file_name, field.bytes().await.map_err(invalid_body)?));
+            break;
+        }
+    }
+    let (file_name, content) = file
+        .ok_or_else(|| ApiError::UnprocessableEntity("missing multipart field file".to_string()))?;
+
+    let domain_req = CreateAttachmentRequest::new(expense_id, &file_name, content.to_vec())?;
+    state
+        .finance_service
+        .add_attachment(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref attachment| ApiSuccess::new(StatusCode::CREATED, attachment.into()))
+}
+
+/// Download the content of an attachment of an expense, or with `?variant=thumb` its thumbnail.
+///
+/// The content is sent with the type read from it when it was uploaded, and browsers are told
+/// not to guess another one. Thumbnails are JPEG pictures, generated in the background shortly
+/// after the upload.
+///
+/// # Responses
+///
+/// - 200 OK: the content is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the expense or the attachment does not exist or is not visible to the
+///   caller, or its thumbnail is not ready.
+/// - 422 Unprocessable entity: the variant is unknown.
+pub async fn get_attachment<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path((expense_id, attachment_id)): Path<(Uuid, Uuid)>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<AttachmentQueryParams>,
+) -> Result<Response, ApiError> {
+    let variant = match query.variant.as_deref() {
+        Some(variant) => variant
+            .parse::<AttachmentVariant>()
+            .map_err(|e| ApiError::UnprocessableEntity(e.to_string()))?,
+        None => AttachmentVariant::Original,
+    };
+    let file = state
+        .finance_service
+        .get_attachment(&principal, &expense_id, &attachment_id, variant)
+        .await?;
+    let content_type = file.attachment().variant_content_type(variant).to_string();
+    let content_disposition = match variant {
+        AttachmentVariant::Original => content_disposition(file.attachment().file_name()),
+        AttachmentVariant::Thumb => {
+            content_disposition(&format!("{}-thumb.jpg", file.attachment().file_name()))
+        }
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_DISPOSITION, content_disposition),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "private, max-age=3600".to_string()),
+        ],
+        Body::from(file.into_content()),
+    )
+        .into_response())
+}
+
+/// An inline `Content-Disposition` naming the file, with an ASCII fallback for clients that do
+/// not read the UTF-8 `filename*` parameter.
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect();
+    format!("inline; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
@@ -0,0 +1,108 @@
+use axum::extract::Path;
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::balance::{Debt, LedgerBalances, MemberBalance};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::expense::ListItemsResponseData;
+
+///
+/// `LedgerBalancesResponseData`
+/// The response body data field for [LedgerBalances].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LedgerBalancesResponseData {
+    members: Vec<MemberBalanceResponseData>,
+    debts: Vec<DebtResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberBalanceResponseData {
+    user_id: String,
+    net: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DebtResponseData {
+    from: String,
+    to: String,
+    amount: i64,
+}
+
+impl From<&MemberBalance> for MemberBalanceResponseData {
+    fn from(balance: &MemberBalance) -> Self {
+        Self {
+            user_id: balance.user_id().to_string(),
+            net: balance.net(),
+        }
+    }
+}
+
+impl From<&Debt> for DebtResponseData {
+    fn from(debt: &Debt) -> Self {
+        Self {
+            from: debt.from().to_string(),
+            to: debt.to().to_string(),
+            amount: debt.amount(),
+        }
+    }
+}
+
+impl From<&LedgerBalances> for LedgerBalancesResponseData {
+    fn from(balances: &LedgerBalances) -> Self {
+        Self {
+            members: balances.members().iter().map(Into::into).collect(),
+            debts: balances.debts().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Compute who owes whom within a ledger.
+///
+/// # Responses
+///
+/// - 200 OK: the net balance of every member and the debts between them are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn ledger_balances<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<LedgerBalancesResponseData>, ApiError> {
+    state
+        .finance_service
+        .ledger_balances(&principal, &ledger_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref balances| ApiSuccess::new(StatusCode::OK, balances.into()))
+}
+
+/// Suggest the transfers that would settle every debt within a ledger.
+///
+/// # Responses
+///
+/// - 200 OK: the suggested transfers are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn settle_up<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<DebtResponseData>>, ApiError> {
+    state
+        .finance_service
+        .settle_up(&principal, &ledger_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|transfers| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(transfers.iter().map(Into::into).collect()),
+            )
+        })
+}
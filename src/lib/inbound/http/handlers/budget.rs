@@ -0,0 +1,218 @@
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::budget::{Budget, BudgetStatus};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::budget_schema::{
+    BudgetStatusQueryParams, CreateBudgetHttpRequestBody, UpdateBudgetHttpRequestBody,
+};
+use super::expense::ListItemsResponseData;
+
+///
+/// `BudgetResponseData`
+/// The response body data field for [Budget] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetResponseData {
+    id: String,
+    category_id: String,
+    ledger_id: Option<String>,
+    amount: i64,
+    currency: String,
+    period: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&Budget> for BudgetResponseData {
+    fn from(budget: &Budget) -> Self {
+        Self {
+            id: budget.id().to_string(),
+            category_id: budget.category_id().to_string(),
+            ledger_id: budget.ledger_id().map(Uuid::to_string),
+            amount: budget.amount(),
+            currency: budget.currency().as_str().to_string(),
+            period: budget.period().to_string(),
+            created_at: *budget.created_at(),
+        }
+    }
+}
+
+///
+/// `BudgetStatusResponseData`
+/// The response body data field for a [BudgetStatus] report.
+///
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatusResponseData {
+    budget: BudgetResponseData,
+    period_start: NaiveDate,
+    /// The last day of the period, inclusive.
+    period_end: NaiveDate,
+    spent: i64,
+    remaining: i64,
+    /// Share of the budget spent, rounded to two decimals.
+    percentage: f64,
+}
+
+impl From<&BudgetStatus> for BudgetStatusResponseData {
+    fn from(status: &BudgetStatus) -> Self {
+        Self {
+            budget: status.budget().into(),
+            period_start: *status.period_start(),
+            period_end: status
+                .period_end()
+                .pred_opt()
+                .unwrap_or(*status.period_end()),
+            spent: status.spent(),
+            remaining: status.remaining(),
+            percentage: (status.percentage() * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// Set a [Budget] on a category. The budget is shared in the category's ledger, or personal.
+///
+/// # Responses
+///
+/// - 201 Created: the [Budget] was successfully created.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may not budget in the category's ledger.
+/// - 404 Not Found: the category's ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the amount is not positive, the currency is invalid, the period
+///   or category is unknown, or the category already has a budget for the period.
+pub async fn create_budget<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateBudgetHttpRequestBody>,
+) -> Result<ApiSuccess<BudgetResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(*principal.user_id())?;
+    state
+        .finance_service
+        .create_budget(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref budget| ApiSuccess::new(StatusCode::CREATED, budget.into()))
+}
+
+/// List the caller's personal [Budget]s, or a ledger's when nested under `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 200 OK: the [Budget] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn list_budgets<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<BudgetResponseData>>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    state
+        .finance_service
+        .list_budgets(&principal, ledger_id.as_ref())
+        .await
+        .map_err(ApiError::from)
+        .map(|budgets| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(budgets.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Retrieve a [Budget].
+///
+/// # Responses
+///
+/// - 200 OK: the [Budget] is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Budget] does not exist or is not visible to the caller.
+pub async fn get_budget<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<BudgetResponseData>, ApiError> {
+    state
+        .finance_service
+        .get_budget(&principal, &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref budget| ApiSuccess::new(StatusCode::OK, budget.into()))
+}
+
+/// Change the amount, currency or period of a [Budget].
+///
+/// # Responses
+///
+/// - 200 OK: the updated [Budget] is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may not change budgets in the ledger.
+/// - 404 Not Found: the [Budget] does not exist or is not visible to the caller.
+/// - 422 Unprocessable entity: the amount is not positive, the currency is invalid, or the
+///   period is unknown or taken.
+pub async fn update_budget<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<UpdateBudgetHttpRequestBody>,
+) -> Result<ApiSuccess<BudgetResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(id)?;
+    state
+        .finance_service
+        .update_budget(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref budget| ApiSuccess::new(StatusCode::OK, budget.into()))
+}
+
+/// Delete a [Budget].
+///
+/// # Responses
+///
+/// - 204 No Content: the [Budget] was deleted.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may not delete budgets in the ledger.
+/// - 404 Not Found: the [Budget] does not exist or is not visible to the caller.
+pub async fn delete_budget<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<StatusCode, ApiError> {
+    state
+        .finance_service
+        .delete_budget(&principal, &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+/// Report how much of a [Budget] was spent in the period containing `on`, today by default, in
+/// the currency of the [Budget].
+///
+/// # Responses
+///
+/// - 200 OK: the spent and remaining amounts are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Budget] does not exist or is not visible to the caller.
+/// - 422 Unprocessable entity: an expense of the period cannot be converted to the currency of
+///   the [Budget] for want of an exchange rate.
+pub async fn budget_status<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<BudgetStatusQueryParams>,
+) -> Result<ApiSuccess<BudgetStatusResponseData>, ApiError> {
+    let on = query.on.unwrap_or_else(|| Utc::now().date_naive());
+    state
+        .finance_service
+        .budget_status(&principal, &id, on)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref status| ApiSuccess::new(StatusCode::OK, status.into()))
+}
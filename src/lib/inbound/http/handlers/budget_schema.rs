@@ -0,0 +1,72 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::account::Currency;
+use crate::domain::finance::models::budget::{
+    BudgetPeriod, CreateBudgetRequest, UpdateBudgetRequest,
+};
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateBudgetHttpRequestBody]
+/// The HTTP Request body for setting a [Budget] on a category
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateBudgetHttpRequestBody {
+    pub category_id: Uuid,
+    /// Limit per period in minor units.
+    pub amount: i64,
+    /// ISO 4217 code of the amount. Defaults to EUR, the reference currency.
+    pub currency: Option<String>,
+    /// Either `weekly` or `monthly`.
+    pub period: String,
+}
+
+impl CreateBudgetHttpRequestBody {
+    /// Converts the HTTP request body into a domain request set by `owner_id`.
+    pub fn try_into_domain(self, owner_id: Uuid) -> Result<CreateBudgetRequest, ApiError> {
+        let period = BudgetPeriod::from_str(&self.period)?;
+        let req = CreateBudgetRequest::new(self.category_id, owner_id, self.amount, period)?;
+        Ok(match self.currency {
+            Some(currency) => req.with_currency(Currency::new(&currency)?),
+            None => req,
+        })
+    }
+}
+
+///
+/// [UpdateBudgetHttpRequestBody]
+/// The HTTP Request body for changing a [Budget]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpdateBudgetHttpRequestBody {
+    pub amount: i64,
+    /// ISO 4217 code of the amount. Defaults to the current currency of the budget.
+    pub currency: Option<String>,
+    pub period: String,
+}
+
+impl UpdateBudgetHttpRequestBody {
+    /// Converts the HTTP request body into a domain request for the budget `id`.
+    pub fn try_into_domain(self, id: Uuid) -> Result<UpdateBudgetRequest, ApiError> {
+        let period = BudgetPeriod::from_str(&self.period)?;
+        let req = UpdateBudgetRequest::new(id, self.amount, period)?;
+        Ok(match self.currency {
+            Some(currency) => req.with_currency(Currency::new(&currency)?),
+            None => req,
+        })
+    }
+}
+
+///
+/// [BudgetStatusQueryParams]
+/// The query parameters selecting the period of a [BudgetStatus] report
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BudgetStatusQueryParams {
+    /// Any day of the period to report on. Defaults to today.
+    pub on: Option<NaiveDate>,
+}
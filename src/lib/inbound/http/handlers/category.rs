@@ -0,0 +1,89 @@
+use axum::extract::Path;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::category::Category;
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::category_schema::CreateCategoryHttpRequestBody;
+use super::expense::ListItemsResponseData;
+
+///
+/// `CategoryResponseData`
+/// The response body data field for [Category] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryResponseData {
+    id: String,
+    name: String,
+    ledger_id: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&Category> for CategoryResponseData {
+    fn from(category: &Category) -> Self {
+        Self {
+            id: category.id().to_string(),
+            name: category.name().to_string(),
+            ledger_id: category.ledger_id().map(Uuid::to_string),
+            created_at: *category.created_at(),
+        }
+    }
+}
+
+/// Create a personal [Category], or a shared one when nested under `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 201 Created: the [Category] was successfully created.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the name is empty or already taken.
+pub async fn create_category<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateCategoryHttpRequestBody>,
+) -> Result<ApiSuccess<CategoryResponseData>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    let domain_req = body.try_into_domain(*principal.user_id(), ledger_id)?;
+    state
+        .finance_service
+        .create_category(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref category| ApiSuccess::new(StatusCode::CREATED, category.into()))
+}
+
+/// List the caller's personal [Category]s, or a ledger's when nested under
+/// `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 200 OK: the [Category] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn list_categories<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<CategoryResponseData>>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    state
+        .finance_service
+        .list_categories(&principal, ledger_id.as_ref())
+        .await
+        .map_err(ApiError::from)
+        .map(|categories| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(categories.iter().map(Into::into).collect()),
+            )
+        })
+}
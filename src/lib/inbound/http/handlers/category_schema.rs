@@ -0,0 +1,29 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::category::{CategoryNameEmptyError, CreateCategoryRequest};
+
+///
+/// [CreateCategoryHttpRequestBody]
+/// The HTTP Request body for creating a [Category]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateCategoryHttpRequestBody {
+    pub name: String,
+}
+
+impl CreateCategoryHttpRequestBody {
+    /// Converts the HTTP request body into a domain request created by `owner_id`, shared in
+    /// `ledger_id` if given.
+    pub fn try_into_domain(
+        self,
+        owner_id: Uuid,
+        ledger_id: Option<Uuid>,
+    ) -> Result<CreateCategoryRequest, CategoryNameEmptyError> {
+        let req = CreateCategoryRequest::new(&self.name, owner_id)?;
+        Ok(match ledger_id {
+            Some(ledger_id) => req.with_ledger(ledger_id),
+            None => req,
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use axum::extract::Path;
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::duplicate::{DuplicateCluster, DuplicateSettings};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::duplicate_schema::{DuplicateSettingsHttpRequestBody, MergeDuplicatesHttpRequestBody};
+use super::expense::{ExpenseResponseData, ListItemsResponseData};
+
+///
+/// `DuplicateSettingsResponseData`
+/// The response body data field for [DuplicateSettings].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateSettingsResponseData {
+    mode: String,
+    date_window_days: u32,
+    min_similarity: u8,
+}
+
+impl From<&DuplicateSettings> for DuplicateSettingsResponseData {
+    fn from(settings: &DuplicateSettings) -> Self {
+        Self {
+            mode: settings.mode().to_string(),
+            date_window_days: settings.date_window_days(),
+            min_similarity: settings.min_similarity(),
+        }
+    }
+}
+
+///
+/// `DuplicateClusterResponseData`
+/// The response body data field for a [DuplicateCluster], its expenses in the order they were
+/// spent.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateClusterResponseData {
+    expenses: Vec<ExpenseResponseData>,
+}
+
+impl From<&DuplicateCluster> for DuplicateClusterResponseData {
+    fn from(cluster: &DuplicateCluster) -> Self {
+        Self {
+            expenses: cluster.expenses().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Retrieve how the caller wants duplicate expenses to be detected.
+///
+/// # Responses
+///
+/// - 200 OK: the [DuplicateSettings] are returned, the defaults if they were never changed.
+/// - 401 Unauthorized: the caller is anonymous.
+pub async fn get_duplicate_settings<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<DuplicateSettingsResponseData>, ApiError> {
+    state
+        .finance_service
+        .get_duplicate_settings(&principal)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref settings| ApiSuccess::new(StatusCode::OK, settings.into()))
+}
+
+/// Change how the caller wants duplicate expenses to be detected: `off`, `warn`, returning the
+/// suspected duplicates of a created expense, or `block`, refusing to create it.
+///
+/// # Responses
+///
+/// - 200 OK: the [DuplicateSettings] were saved.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the mode is unknown, or the window or similarity out of range.
+pub async fn update_duplicate_settings<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<DuplicateSettingsHttpRequestBody>,
+) -> Result<ApiSuccess<DuplicateSettingsResponseData>, ApiError> {
+    let settings = body.try_into_domain()?;
+    state
+        .finance_service
+        .update_duplicate_settings(&principal, &settings)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref settings| ApiSuccess::new(StatusCode::OK, settings.into()))
+}
+
+/// List the clusters of suspected duplicates among the caller's personal expenses or, when
+/// nested under `/ledgers/{ledger_id}`, among those of a shared ledger, for review.
+///
+/// # Responses
+///
+/// - 200 OK: the [DuplicateCluster] list is returned, most recent first.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn list_duplicates<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<DuplicateClusterResponseData>>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    state
+        .finance_service
+        .list_duplicates(&principal, ledger_id.as_ref())
+        .await
+        .map_err(ApiError::from)
+        .map(|clusters| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(clusters.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Merge duplicates into the expense kept, which gains their tags and, if it has none, their
+/// category, deleting them.
+///
+/// # Responses
+///
+/// - 200 OK: the merged expense is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may not change the expenses of the ledger.
+/// - 404 Not Found: an expense does not exist or is not visible to the caller.
+/// - 422 Unprocessable entity: nothing is to be merged, the expenses do not live in the same
+///   place, or a duplicate has attachments.
+pub async fn merge_duplicates<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<MergeDuplicatesHttpRequestBody>,
+) -> Result<ApiSuccess<ExpenseResponseData>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .finance_service
+        .merge_duplicates(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref expense| ApiSuccess::new(StatusCode::OK, expense.into()))
+}
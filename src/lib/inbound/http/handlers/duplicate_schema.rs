@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::duplicate::{
+    DuplicateMode, DuplicateSettings, MergeDuplicatesRequest,
+};
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [DuplicateSettingsHttpRequestBody]
+/// The HTTP Request body for changing how duplicate expenses are detected
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DuplicateSettingsHttpRequestBody {
+    /// One of `off`, `warn` or `block`.
+    pub mode: String,
+    /// How many days apart two expenses may be spent and still be duplicates.
+    pub date_window_days: u32,
+    /// How similar, in percent, the names of two duplicates must at least be.
+    pub min_similarity: u8,
+}
+
+impl DuplicateSettingsHttpRequestBody {
+    /// Converts the HTTP request body into domain settings.
+    pub fn try_into_domain(self) -> Result<DuplicateSettings, ApiError> {
+        Ok(DuplicateSettings::new(
+            DuplicateMode::from_str(&self.mode)?,
+            self.date_window_days,
+            self.min_similarity,
+        )?)
+    }
+}
+
+///
+/// [MergeDuplicatesHttpRequestBody]
+/// The HTTP Request body for merging duplicates into one expense
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MergeDuplicatesHttpRequestBody {
+    /// The expense kept, gaining the tags and category of the others.
+    pub keep_id: Uuid,
+    /// The expenses deleted.
+    pub duplicate_ids: Vec<Uuid>,
+}
+
+impl MergeDuplicatesHttpRequestBody {
+    /// Converts the HTTP request body into a domain request.
+    pub fn try_into_domain(self) -> Result<MergeDuplicatesRequest, ApiError> {
+        Ok(MergeDuplicatesRequest::new(
+            self.keep_id,
+            self.duplicate_ids,
+        )?)
+    }
+}
@@ -1,29 +1,59 @@
-use axum::extract::Query;
+use axum::body::Body;
+use axum::extract::{Path, Query};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
 use axum::{Json, extract::State, http::StatusCode};
+use chrono::{NaiveDate, Utc};
+use futures::TryStreamExt;
+use futures::stream::BoxStream;
 use serde::Serialize;
+use uuid::Uuid;
 
-use crate::domain::finance::ports::FinanceService;
+use crate::domain::auth::models::principal::Principal;
+use crate::domain::finance::models::account::Account;
+use crate::domain::finance::models::category::Category;
+use crate::domain::finance::models::expense::{CreatedExpense, ListExpensesRequest};
+use crate::domain::finance::models::quick::{QuickExpenseIssue, QuickExpenseOutcome};
+use crate::domain::finance::models::split::ExpenseSplit;
+use crate::domain::finance::models::tag::Tag;
+use crate::domain::finance::models::transaction::TransactionKind;
+use crate::domain::finance::ports::{ExpenseRepositoryError, ExpenseStream, FinanceService};
+use crate::inbound::export::journal::{Chart, Dialect, JournalWriter};
+use crate::inbound::export::qif::QifWriter;
+use crate::inbound::export::records::{encode_csv, encode_json, encode_ndjson};
+use crate::inbound::http::auth::Authenticated;
 use crate::inbound::http::server::AppState;
 use crate::{
     domain::finance::models::expense::Expense,
     inbound::http::{api_error::ApiError, api_success::ApiSuccess},
 };
 
-use super::expense_schema::{CreateExpenseHttpRequestBody, PaginationRequestQueryParams};
+use super::attachment::AttachmentResponseData;
+use super::expense_schema::{
+    CreateExpenseHttpRequestBody, ExportFormat, ExportQueryParams, PaginationRequestQueryParams,
+    QuickExpenseHttpRequestBody,
+};
 
 ///
 /// `CreateExpenseResponseData`
-/// The response body data field for successful [Expense] creation.
+/// The response body data field for successful [Expense] creation, listing the existing
+/// expenses it looks like a duplicate of.
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CreateExpenseResponseData {
     id: String,
+    suspected_duplicates: Vec<String>,
 }
 
-impl From<&Expense> for CreateExpenseResponseData {
-    fn from(expense: &Expense) -> Self {
+impl From<&CreatedExpense> for CreateExpenseResponseData {
+    fn from(created: &CreatedExpense) -> Self {
         Self {
-            id: expense.id().to_string(),
+            id: created.expense().id().to_string(),
+            suspected_duplicates: created
+                .suspected_duplicates()
+                .iter()
+                .map(Uuid::to_string)
+                .collect(),
         }
     }
 }
@@ -36,12 +66,66 @@ impl From<&Expense> for CreateExpenseResponseData {
 pub struct ExpenseResponseData {
     id: String,
     name: String,
+    amount: i64,
+    split: Option<SplitResponseData>,
+    category_id: Option<String>,
+    account_id: Option<String>,
+    spent_on: NaiveDate,
+    recurring_expense_id: Option<String>,
+    tags: Vec<String>,
+    counterparty: Option<String>,
+    reference: Option<String>,
+    attachments: Vec<AttachmentResponseData>,
 }
 impl From<&Expense> for ExpenseResponseData {
     fn from(expense: &Expense) -> Self {
         Self {
             id: expense.id().to_string(),
             name: expense.name().to_string(),
+            amount: expense.amount(),
+            split: expense.split().map(SplitResponseData::from),
+            category_id: expense.category_id().map(Uuid::to_string),
+            account_id: expense.account_id().map(Uuid::to_string),
+            spent_on: *expense.spent_on(),
+            recurring_expense_id: expense.recurring_expense_id().map(Uuid::to_string),
+            tags: expense.tags().iter().map(Tag::to_string).collect(),
+            counterparty: expense.counterparty().map(str::to_string),
+            reference: expense.reference().map(str::to_string),
+            attachments: expense.attachments().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+///
+/// `SplitResponseData`
+/// The response body data field describing how an [Expense] is split.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SplitResponseData {
+    paid_by: String,
+    method: String,
+    shares: Vec<ShareResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShareResponseData {
+    user_id: String,
+    amount: i64,
+}
+
+impl From<&ExpenseSplit> for SplitResponseData {
+    fn from(split: &ExpenseSplit) -> Self {
+        Self {
+            paid_by: split.paid_by().to_string(),
+            method: split.method().to_string(),
+            shares: split
+                .shares()
+                .iter()
+                .map(|share| ShareResponseData {
+                    user_id: share.user_id().to_string(),
+                    amount: share.amount(),
+                })
+                .collect(),
         }
     }
 }
@@ -61,44 +145,169 @@ impl<T> ListItemsResponseData<T> {
     }
 }
 
-/// Create a new [Expense].
+/// Create a new [Expense], either personal or, when nested under `/ledgers/{ledger_id}`, in a
+/// shared ledger.
 ///
 /// # Responses
 ///
-/// - 201 Created: the [Expense] was successfully created.
-/// - 422 Unprocessable entity: An [Expense] with the same name already exists.
+/// - 201 Created: the [Expense] was successfully created, along with the ids of the expenses it
+///   looks like a duplicate of when the caller is only warned about duplicates.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the [Expense] looks like a duplicate and the caller blocks
+///   duplicates, or the account is missing or unknown.
 pub async fn create_expense<FS: FinanceService>(
     State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
     Json(body): Json<CreateExpenseHttpRequestBody>,
 ) -> Result<ApiSuccess<CreateExpenseResponseData>, ApiError> {
-    let domain_req = body.try_into_domain()?;
+    let mut domain_req = body.try_into_domain(principal.user_id())?;
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
     state
         .finance_service
-        .create_expense(&domain_req)
+        .create_expense(Some(&principal), &domain_req)
         .await
         .map_err(ApiError::from)
         .map(|ref expense| ApiSuccess::new(StatusCode::CREATED, expense.into()))
 }
 
-/// List all [Expense].
+///
+/// `QuickExpenseResponseData`
+/// The response body data field for an [Expense] created from a one-line description.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickExpenseResponseData {
+    /// Whether the expense was created.
+    created: bool,
+    interpretation: QuickExpenseInterpretationData,
+    issues: Vec<QuickExpenseIssueData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expense: Option<ExpenseResponseData>,
+}
+
+/// How a one-line description was read, and what it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickExpenseInterpretationData {
+    name: Option<String>,
+    /// The amount as written, such as `4.50`.
+    amount: Option<String>,
+    /// The amount in minor units of the account's currency, once the account is resolved.
+    amount_minor: Option<i64>,
+    currency: Option<String>,
+    spent_on: NaiveDate,
+    category: Option<String>,
+    category_id: Option<String>,
+    account: Option<String>,
+    account_id: Option<String>,
+}
+
+/// Something that could not be read unambiguously.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickExpenseIssueData {
+    code: String,
+    message: String,
+    /// Whether the issue prevented the expense from being created.
+    blocking: bool,
+}
+
+impl From<&QuickExpenseIssue> for QuickExpenseIssueData {
+    fn from(issue: &QuickExpenseIssue) -> Self {
+        Self {
+            code: issue.code().to_string(),
+            message: issue.to_string(),
+            blocking: issue.is_blocking(),
+        }
+    }
+}
+
+impl From<&QuickExpenseOutcome> for QuickExpenseResponseData {
+    fn from(outcome: &QuickExpenseOutcome) -> Self {
+        let parsed = outcome.parsed();
+        Self {
+            created: outcome.expense().is_some(),
+            interpretation: QuickExpenseInterpretationData {
+                name: parsed.name().map(str::to_string),
+                amount: parsed.amount().map(ToString::to_string),
+                amount_minor: outcome.request().map(|req| req.amount()),
+                currency: outcome.currency().map(ToString::to_string),
+                spent_on: *parsed.spent_on(),
+                category: parsed.category().map(str::to_string),
+                category_id: outcome.category_id().map(ToString::to_string),
+                account: parsed.account().map(str::to_string),
+                account_id: outcome.account_id().map(ToString::to_string),
+            },
+            issues: outcome.issues().iter().map(Into::into).collect(),
+            expense: outcome.expense().map(Into::into),
+        }
+    }
+}
+
+/// Create a personal [Expense] from a one-line description, such as
+/// `coffee 4.50 EUR yesterday #food @cash`, returning how it was read.
+///
+/// `#word` names one of the caller's personal categories and `@word` one of their accounts,
+/// matched case-insensitively, by prefix if no name matches exactly. Without `@word`, the
+/// caller's only account is used. Dates may be relative, such as `yesterday`, `3 days ago` or
+/// `friday`, and default to today. With `dry_run`, or when an issue is blocking, nothing is
+/// created.
+///
+/// # Responses
+///
+/// - 200 OK: nothing was created; the interpretation and its issues are returned.
+/// - 201 Created: the [Expense] was created, and is returned with the interpretation.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the interpreted expense failed validation.
+pub async fn quick_add_expense<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<QuickExpenseHttpRequestBody>,
+) -> Result<ApiSuccess<QuickExpenseResponseData>, ApiError> {
+    let domain_req = body.into_domain(Utc::now().date_naive());
+    state
+        .finance_service
+        .quick_add_expense(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref outcome| {
+            let status = if outcome.expense().is_some() {
+                StatusCode::CREATED
+            } else {
+                StatusCode::OK
+            };
+            ApiSuccess::new(status, outcome.into())
+        })
+}
+
+/// List all personal [Expense], or those of a shared ledger when nested under
+/// `/ledgers/{ledger_id}`. Incomes and transfers are left out: see `/transactions`.
 ///
 /// # Responses
 ///
 /// - 200 OK: the [Expense] list is returned.
-/// - 404 Not Found: Page not found
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: Page not found, or the ledger does not exist or the caller is not a member.
 /// - 422 Unprocessable entity: Invalid pagination parameters.
 pub async fn list_expenses<FS>(
     State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
     Query(query): Query<PaginationRequestQueryParams>,
 ) -> Result<ApiSuccess<ListItemsResponseData<ExpenseResponseData>>, ApiError>
 where
     FS: FinanceService + Send + Sync + 'static,
 {
-    let domain_req = query.try_into_domain()?;
+    let mut domain_req = query.try_into_domain()?.with_kind(TransactionKind::Expense);
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
 
     state
         .finance_service
-        .list_expenses(&domain_req)
+        .list_expenses(&principal, &domain_req)
         .await
         .map_err(ApiError::from)
         .map(|expenses| {
@@ -111,89 +320,209 @@ where
         })
 }
 
+/// Export the caller's personal [Expense]s or, when nested under `/ledgers/{ledger_id}`, those
+/// of a ledger, with the same filters as [list_expenses] but without pagination.
+///
+/// Rows are streamed as they are read, so the body cannot turn into an error response once
+/// started: a failure midway truncates it.
+///
+/// # Responses
+///
+/// - 200 OK: the file is returned as an attachment.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the format is unknown.
+pub async fn export_expenses<FS>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<ExportQueryParams>,
+) -> Result<Response, ApiError>
+where
+    FS: FinanceService + Send + Sync + 'static,
+{
+    let format = query.try_into_format()?;
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    let mut domain_req = ListExpensesRequest::all();
+    // Journals and QIF files record incomes and transfers too, for their balances to reconcile.
+    if matches!(
+        format,
+        ExportFormat::Csv | ExportFormat::Json | ExportFormat::Ndjson
+    ) {
+        domain_req = domain_req.with_kind(TransactionKind::Expense);
+    }
+    if let Some(ledger_id) = ledger_id {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
+
+    let expenses = state
+        .finance_service
+        .export_expenses(&principal, &domain_req)
+        .await?;
+    let filename = format!(
+        "expenses-{}.{}",
+        Utc::now().date_naive(),
+        format.extension()
+    );
+    let chunks = match &format {
+        ExportFormat::Csv => encode_csv(expenses),
+        ExportFormat::Json => encode_json(expenses, |expense| ExpenseResponseData::from(expense)),
+        ExportFormat::Ndjson => {
+            encode_ndjson(expenses, |expense| ExpenseResponseData::from(expense))
+        }
+        ExportFormat::Ledger | ExportFormat::Beancount => {
+            let dialect = match format {
+                ExportFormat::Ledger => Dialect::Ledger,
+                _ => Dialect::Beancount,
+            };
+            let (accounts, categories) =
+                accounts_and_categories(&state, &principal, ledger_id.as_ref()).await?;
+            let chart = Chart::new(&accounts, &categories);
+            encode_journal(expenses, JournalWriter::new(dialect, chart))
+        }
+        ExportFormat::Qif { date_format } => {
+            let (accounts, categories) =
+                accounts_and_categories(&state, &principal, ledger_id.as_ref()).await?;
+            encode_qif(
+                expenses,
+                QifWriter::new(&accounts, &categories, date_format),
+            )
+        }
+    };
+    let chunks = chunks.inspect_err(|e| tracing::error!("Failed to export expenses: {:?}", e));
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// The caller's accounts and the categories of the export, named in the exported file.
+async fn accounts_and_categories<FS: FinanceService>(
+    state: &AppState<FS>,
+    principal: &Principal,
+    ledger_id: Option<&Uuid>,
+) -> Result<(Vec<Account>, Vec<Category>), ApiError> {
+    let accounts = state.finance_service.list_accounts(principal).await?;
+    let categories = state
+        .finance_service
+        .list_categories(principal, ledger_id)
+        .await?;
+    Ok((accounts, categories))
+}
+
+/// Encodes transactions as the entries of a journal, between the declarations of its accounts
+/// and whatever must follow them.
+fn encode_journal(
+    mut expenses: ExpenseStream,
+    mut journal: JournalWriter,
+) -> BoxStream<'static, Result<Vec<u8>, ExpenseRepositoryError>> {
+    Box::pin(async_stream::try_stream! {
+        yield journal.header().into_bytes();
+        while let Some(expense) = expenses.try_next().await? {
+            yield journal.entry(&expense).into_bytes();
+        }
+        yield journal.footer().into_bytes();
+    })
+}
+
+/// Encodes transactions as a QIF file, written once all are read as they are grouped by
+/// account.
+fn encode_qif(
+    mut expenses: ExpenseStream,
+    mut qif: QifWriter,
+) -> BoxStream<'static, Result<Vec<u8>, ExpenseRepositoryError>> {
+    Box::pin(async_stream::try_stream! {
+        while let Some(expense) = expenses.try_next().await? {
+            qif.push(&expense);
+        }
+        yield qif.finish().into_bytes();
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use std::mem;
     use std::sync::Arc;
 
-    use anyhow::anyhow;
+    use axum::body::to_bytes;
     use uuid::Uuid;
 
-    use crate::domain::finance::models::expense::{CreateExpenseError, ListExpensesRequest};
-    use crate::domain::finance::models::expense::{CreateExpenseRequest, Expense, ExpenseName};
-    use crate::domain::finance::ports::{ExpenseRepository, ExpenseRepositoryError};
+    use crate::domain::finance::models::account::{Account, AccountKind, AccountName, Currency};
+    use crate::domain::finance::models::expense::{Expense, ExpenseName};
+    use crate::domain::finance::models::ledger::{LedgerMember, LedgerRole};
     use crate::domain::finance::service::Service;
+    use crate::domain::finance::testing::MockExpenseRepository;
     use crate::outbound::email_client::EmailClient; // TODO: Use a mocked implementation once a
     // real email client is implemented.
     use crate::outbound::prometheus::Prometheus;
+    use crate::outbound::storage::local::LocalStorage;
+    use crate::outbound::thumbnail::Thumbnailer;
 
     use super::*;
 
-    #[derive(Clone)]
-    struct MockExpenseRepository {
-        create_expense_result: Arc<std::sync::Mutex<Result<Expense, CreateExpenseError>>>,
-        list_expenses_result: Arc<std::sync::Mutex<Result<Vec<Expense>, ExpenseRepositoryError>>>,
-    }
-    impl MockExpenseRepository {
-        fn new() -> Self {
-            Self {
-                create_expense_result: Arc::new(std::sync::Mutex::new(Ok(Expense::new(
-                    Uuid::new_v4(),
-                    ExpenseName::new("Mock Expense").unwrap(),
-                )))),
-                list_expenses_result: Arc::new(std::sync::Mutex::new(Ok(vec![]))),
-            }
-        }
+    /// Attachments are not exercised by these tests.
+    fn storage() -> LocalStorage {
+        LocalStorage::new(std::env::temp_dir())
     }
 
-    impl ExpenseRepository for MockExpenseRepository {
-        async fn create_expense(
-            &self,
-            _: &CreateExpenseRequest,
-        ) -> Result<Expense, CreateExpenseError> {
-            let mut guard = self.create_expense_result.lock();
-            let mut result = Err(CreateExpenseError::Unknown(anyhow!("substitute error")));
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
-        async fn list_expenses(
-            &self,
-            _: &ListExpensesRequest,
-        ) -> Result<Vec<Expense>, ExpenseRepositoryError> {
-            let mut guard = self.list_expenses_result.lock();
-            let mut result = Err(ExpenseRepositoryError::Unknown(anyhow!("substitute error")));
-            mem::swap(guard.as_deref_mut().unwrap(), &mut result);
-            result
-        }
+    fn account(owner_id: Uuid) -> Account {
+        Account::new(
+            Uuid::new_v4(),
+            owner_id,
+            AccountName::new("Checking").unwrap(),
+            AccountKind::Bank,
+            Currency::new("EUR").unwrap(),
+            0,
+            chrono::Utc::now(),
+        )
     }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_create_expense_success() {
         let expense_name = ExpenseName::new("Angus").unwrap();
         let expense_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let account = account(user_id);
         let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account.clone());
         let prometheus = Prometheus::new();
         let email_client = EmailClient::new();
-        repo.create_expense_result = Arc::new(std::sync::Mutex::new(Ok(Expense::new(
+        repo.create_expense_result = Arc::new(std::sync::Mutex::new(Some(Ok(Expense::new(
             expense_id,
             expense_name.clone(),
-        ))));
-        let service = Service::new(repo.clone(), prometheus, email_client);
+        )))));
+        let service = Service::new(
+            repo.clone(),
+            prometheus,
+            email_client,
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
 
         let state = axum::extract::State(AppState {
             finance_service: Arc::new(service),
         });
         let body = axum::extract::Json(CreateExpenseHttpRequestBody {
             name: expense_name.to_string(),
+            account_id: Some(*account.id()),
+            ..Default::default()
         });
         let expected = ApiSuccess::new(
             StatusCode::CREATED,
             CreateExpenseResponseData {
                 id: expense_id.to_string(),
+                suspected_duplicates: vec![],
             },
         );
 
-        let actual = create_expense(state, body).await;
+        let principal = Authenticated(Principal::new(user_id));
+        let actual = create_expense(state, None, principal, body).await;
         assert!(
             actual.is_ok(),
             "expected create_expense to succeed, but got {:?}",
@@ -208,12 +537,48 @@ mod tests {
         )
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_expense_requires_own_account() {
+        let mut repo = MockExpenseRepository::new();
+        repo.account = Some(account(Uuid::new_v4()));
+        let service = Service::new(
+            repo,
+            Prometheus::new(),
+            EmailClient::new(),
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
+
+        let state = axum::extract::State(AppState {
+            finance_service: Arc::new(service),
+        });
+        let principal = Authenticated(Principal::new(Uuid::new_v4()));
+        let body = axum::extract::Json(CreateExpenseHttpRequestBody {
+            name: "Groceries".to_string(),
+            account_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        });
+
+        let actual = create_expense(state, None, principal, body).await;
+        assert!(
+            matches!(actual, Err(ApiError::UnprocessableEntity(_))),
+            "expected create_expense to reject the account, but got {:?}",
+            actual
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_list_expenses_success() {
         let repo = MockExpenseRepository::new();
         let prometheus = Prometheus::new();
         let email_client = EmailClient::new();
-        let service = Service::new(repo.clone(), prometheus, email_client);
+        let service = Service::new(
+            repo.clone(),
+            prometheus,
+            email_client,
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
 
         let state = axum::extract::State(AppState {
             finance_service: Arc::new(service),
@@ -224,7 +589,8 @@ mod tests {
         });
         let expected = ApiSuccess::new(StatusCode::OK, ListItemsResponseData::new(vec![]));
 
-        let actual = list_expenses(state, query).await;
+        let principal = Authenticated(Principal::new(Uuid::new_v4()));
+        let actual = list_expenses(state, None, principal, query).await;
         assert!(
             actual.is_ok(),
             "expected list_expenses to succeed, but got {:?}",
@@ -238,4 +604,205 @@ mod tests {
             expected, actual
         )
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_ledger_expenses_not_member() {
+        let repo = MockExpenseRepository::new();
+        let service = Service::new(
+            repo,
+            Prometheus::new(),
+            EmailClient::new(),
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
+
+        let state = axum::extract::State(AppState {
+            finance_service: Arc::new(service),
+        });
+        let principal = Authenticated(Principal::new(Uuid::new_v4()));
+        let query = axum::extract::Query(PaginationRequestQueryParams {
+            page: Some(1),
+            size: Some(10),
+        });
+
+        let actual = list_expenses(state, Some(Path(Uuid::new_v4())), principal, query).await;
+        assert!(
+            matches!(actual, Err(ApiError::NotFoundError(_))),
+            "expected list_expenses to hide the ledger, but got {:?}",
+            actual
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_ledger_expense_forbidden_for_viewer() {
+        let ledger_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let mut repo = MockExpenseRepository::new();
+        repo.ledger_member = Some(LedgerMember::new(
+            ledger_id,
+            user_id,
+            LedgerRole::Viewer,
+            chrono::Utc::now(),
+        ));
+        let service = Service::new(
+            repo,
+            Prometheus::new(),
+            EmailClient::new(),
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
+
+        let state = axum::extract::State(AppState {
+            finance_service: Arc::new(service),
+        });
+        let body = axum::extract::Json(CreateExpenseHttpRequestBody {
+            name: "Groceries".to_string(),
+            ..Default::default()
+        });
+
+        let actual = create_expense(
+            state,
+            Some(Path(ledger_id)),
+            Authenticated(Principal::new(user_id)),
+            body,
+        )
+        .await;
+        assert!(
+            matches!(actual, Err(ApiError::PolicyDenied(_))),
+            "expected create_expense to be forbidden, but got {:?}",
+            actual
+        );
+    }
+
+    /// Export the expenses saved in `repo` in `format`, returning the response headers and body.
+    async fn export(
+        repo: MockExpenseRepository,
+        ledger_id: Option<Uuid>,
+        format: &str,
+    ) -> (header::HeaderMap, String) {
+        let service = Service::new(
+            repo,
+            Prometheus::new(),
+            EmailClient::new(),
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
+        let state = axum::extract::State(AppState {
+            finance_service: Arc::new(service),
+        });
+        let query = axum::extract::Query(ExportQueryParams {
+            format: Some(format.to_string()),
+            date_format: None,
+        });
+        let principal = Authenticated(Principal::new(Uuid::new_v4()));
+
+        let response = export_expenses(state, ledger_id.map(Path), principal, query)
+            .await
+            .unwrap();
+        let headers = response.headers().clone();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn saved(repo: &MockExpenseRepository, expense: Expense) {
+        repo.expenses.lock().unwrap().push(expense);
+    }
+
+    #[tokio::test]
+    async fn test_export_names_the_file() {
+        for (format, extension, content_type) in [
+            ("csv", "csv", "text/csv; charset=utf-8"),
+            ("json", "json", "application/json"),
+            ("ndjson", "ndjson", "application/x-ndjson"),
+        ] {
+            let (headers, _) = export(MockExpenseRepository::new(), None, format).await;
+
+            let disposition = headers[header::CONTENT_DISPOSITION].to_str().unwrap();
+            assert!(
+                disposition.starts_with("attachment; filename=\"expenses-")
+                    && disposition.ends_with(&format!(".{extension}\"")),
+                "unexpected Content-Disposition {:?}",
+                disposition
+            );
+            assert_eq!(headers[header::CONTENT_TYPE], content_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_leaves_out_incomes_transfers_and_other_ledgers() {
+        let ledger_id = Uuid::new_v4();
+        let repo = MockExpenseRepository::new();
+        let name = |name: &str| ExpenseName::new(name).unwrap();
+        saved(&repo, Expense::new(Uuid::new_v4(), name("Lunch")));
+        saved(
+            &repo,
+            Expense::new(Uuid::new_v4(), name("Salary")).with_kind(TransactionKind::Income),
+        );
+        saved(
+            &repo,
+            Expense::new(Uuid::new_v4(), name("Savings")).with_kind(TransactionKind::Transfer),
+        );
+        saved(
+            &repo,
+            Expense::new(Uuid::new_v4(), name("Groceries")).with_ledger(ledger_id),
+        );
+
+        let (_, json) = export(repo.clone(), None, "json").await;
+        let names: Vec<String> = serde_json::from_str::<Vec<serde_json::Value>>(&json)
+            .unwrap()
+            .iter()
+            .map(|expense| expense["name"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, ["Lunch"]);
+
+        let mut repo = repo;
+        repo.ledger_member = Some(LedgerMember::new(
+            ledger_id,
+            Uuid::new_v4(),
+            LedgerRole::Viewer,
+            chrono::Utc::now(),
+        ));
+        let (_, ndjson) = export(repo, Some(ledger_id), "ndjson").await;
+        assert_eq!(ndjson.lines().count(), 1);
+        assert!(ndjson.contains("\"Groceries\""));
+    }
+
+    #[tokio::test]
+    async fn test_list_expenses_leaves_out_incomes_and_transfers() {
+        let repo = MockExpenseRepository::new();
+        let name = |name: &str| ExpenseName::new(name).unwrap();
+        let lunch = Expense::new(Uuid::new_v4(), name("Lunch"));
+        saved(&repo, lunch.clone());
+        saved(
+            &repo,
+            Expense::new(Uuid::new_v4(), name("Salary")).with_kind(TransactionKind::Income),
+        );
+        saved(
+            &repo,
+            Expense::new(Uuid::new_v4(), name("Savings")).with_kind(TransactionKind::Transfer),
+        );
+        let service = Service::new(
+            repo,
+            Prometheus::new(),
+            EmailClient::new(),
+            storage(),
+            Thumbnailer::pictures_only(),
+        );
+        let state = axum::extract::State(AppState {
+            finance_service: Arc::new(service),
+        });
+        let query = axum::extract::Query(PaginationRequestQueryParams {
+            page: Some(1),
+            size: Some(10),
+        });
+        let principal = Authenticated(Principal::new(Uuid::new_v4()));
+
+        let actual = list_expenses(state, None, principal, query).await;
+
+        let expected = ApiSuccess::new(
+            StatusCode::OK,
+            ListItemsResponseData::new(vec![ExpenseResponseData::from(&lunch)]),
+        );
+        assert_eq!(actual.unwrap(), expected);
+    }
 }
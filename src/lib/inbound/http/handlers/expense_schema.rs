@@ -1,23 +1,145 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
 use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::domain::finance::models::expense::CreateExpenseRequest;
-use crate::domain::finance::models::expense::ExpenseNameEmptyError;
 use crate::domain::finance::models::expense::ListExpensesRequest;
 use crate::domain::finance::models::expense::PaginationError;
+use crate::domain::finance::models::quick::QuickExpenseRequest;
+use crate::domain::finance::models::split::{SplitMethod, SplitParticipant};
+use crate::domain::finance::models::tag::Tag;
+use crate::inbound::http::api_error::ApiError;
 
 ///
 /// [CreateExpenseHttpRequestBody]
 /// The HTTP Request body for creating an [Expense]
 ///
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
 pub struct CreateExpenseHttpRequestBody {
     pub name: String,
+    /// Total in minor units, e.g. cents.
+    pub amount: Option<i64>,
+    pub split: Option<SplitHttpRequestBody>,
+    pub category_id: Option<Uuid>,
+    /// The caller's account the money is paid from. Required.
+    pub account_id: Option<Uuid>,
+    /// Defaults to today.
+    pub spent_on: Option<NaiveDate>,
+    /// Free-form labels, compared case-insensitively.
+    pub tags: Option<Vec<String>>,
+}
+
+///
+/// [SplitHttpRequestBody]
+/// How the amount of an [Expense] is split between ledger members
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitHttpRequestBody {
+    /// One of `equal`, `shares`, `percentage` or `exact`.
+    pub method: String,
+    /// Defaults to the caller.
+    pub paid_by: Option<Uuid>,
+    pub participants: Vec<SplitParticipantHttpRequestBody>,
+}
+
+///
+/// [SplitParticipantHttpRequestBody]
+/// A participant of a split. Only the field matching the split method is read.
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitParticipantHttpRequestBody {
+    pub user_id: Uuid,
+    pub shares: Option<i64>,
+    pub percentage: Option<f64>,
+    /// Exact amount in minor units.
+    pub amount: Option<i64>,
 }
 
 impl CreateExpenseHttpRequestBody {
-    /// Converts the HTTP request body into a domain request.
-    pub fn try_into_domain(self) -> Result<CreateExpenseRequest, ExpenseNameEmptyError> {
-        CreateExpenseRequest::new(&self.name)
+    /// Converts the HTTP request body into a domain request. A split without an explicit payer is
+    /// paid by `caller`.
+    pub fn try_into_domain(self, caller: &Uuid) -> Result<CreateExpenseRequest, ApiError> {
+        let mut req =
+            CreateExpenseRequest::new(&self.name)?.with_amount(self.amount.unwrap_or(0))?;
+        if let Some(category_id) = self.category_id {
+            req = req.with_category(category_id);
+        }
+        if let Some(account_id) = self.account_id {
+            req = req.with_account(account_id);
+        }
+        if let Some(spent_on) = self.spent_on {
+            req = req.with_spent_on(spent_on);
+        }
+        if let Some(tags) = self.tags {
+            let tags = tags
+                .iter()
+                .map(|tag| Tag::new(tag))
+                .collect::<Result<Vec<_>, _>>()?;
+            req = req.with_tags(tags);
+        }
+        let Some(split) = self.split else {
+            return Ok(req);
+        };
+
+        let paid_by = split.paid_by.unwrap_or(*caller);
+        let method = SplitMethod::from_str(&split.method)?;
+        let participants = split
+            .participants
+            .iter()
+            .map(|p| p.try_into_domain(method))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(req.with_split(paid_by, method, &participants)?)
+    }
+}
+
+impl SplitParticipantHttpRequestBody {
+    /// Converts the participant into a domain participant, reading the field matching `method`.
+    fn try_into_domain(&self, method: SplitMethod) -> Result<SplitParticipant, ApiError> {
+        let missing = |field: &str| {
+            ApiError::UnprocessableEntity(format!(
+                "{} split requires {} for user {}",
+                method, field, self.user_id
+            ))
+        };
+        let weight = match method {
+            SplitMethod::Equal => 0,
+            SplitMethod::Shares => self.shares.ok_or_else(|| missing("shares"))?,
+            SplitMethod::Percentage => {
+                let percentage = self.percentage.ok_or_else(|| missing("percentage"))?;
+                (percentage * 100.0).round() as i64
+            }
+            SplitMethod::Exact => self.amount.ok_or_else(|| missing("amount"))?,
+        };
+        Ok(SplitParticipant::new(self.user_id, weight))
+    }
+}
+
+///
+/// [QuickExpenseHttpRequestBody]
+/// The HTTP Request body for creating an [Expense] from a one-line description
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct QuickExpenseHttpRequestBody {
+    /// Such as `coffee 4.50 EUR yesterday #food @cash`.
+    pub text: String,
+    /// The caller's current date, which relative dates are read from. Defaults to today in UTC.
+    pub today: Option<NaiveDate>,
+    /// Only interpret the text, without creating anything. Defaults to `false`.
+    pub dry_run: Option<bool>,
+}
+
+impl QuickExpenseHttpRequestBody {
+    /// Converts the HTTP request body into a domain request, relative to `today` by default.
+    pub fn into_domain(self, today: NaiveDate) -> QuickExpenseRequest {
+        let req = QuickExpenseRequest::new(&self.text, self.today.unwrap_or(today));
+        if self.dry_run.unwrap_or(false) {
+            req.with_dry_run()
+        } else {
+            req
+        }
     }
 }
 
@@ -39,3 +161,83 @@ impl PaginationRequestQueryParams {
         ListExpensesRequest::new(page, size)
     }
 }
+
+///
+/// [ExportQueryParams]
+/// The query parameters of an export of [Expense]s
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExportQueryParams {
+    /// One of `csv`, `json`, `ndjson`, `ledger` (also read by hledger), `beancount` or `qif`.
+    /// Defaults to `csv`.
+    pub format: Option<String>,
+    /// The strftime format of QIF dates. Defaults to `%m/%d/%Y`.
+    pub date_format: Option<String>,
+}
+
+/// The file formats [Expense]s can be exported to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// A single JSON array.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// A ledger-cli and hledger journal.
+    Ledger,
+    Beancount,
+    /// A QIF file, its dates written in the given strftime format.
+    Qif {
+        date_format: String,
+    },
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Ledger => "journal",
+            ExportFormat::Beancount => "beancount",
+            ExportFormat::Qif { .. } => "qif",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Ledger | ExportFormat::Beancount => "text/plain; charset=utf-8",
+            ExportFormat::Qif { .. } => "application/qif",
+        }
+    }
+}
+
+impl ExportQueryParams {
+    /// Reads the requested format.
+    pub fn try_into_format(self) -> Result<ExportFormat, ApiError> {
+        match self.format.as_deref().map(str::trim) {
+            None | Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") => Ok(ExportFormat::Json),
+            Some("ndjson") => Ok(ExportFormat::Ndjson),
+            Some("ledger" | "hledger") => Ok(ExportFormat::Ledger),
+            Some("beancount") => Ok(ExportFormat::Beancount),
+            Some("qif") => {
+                let date_format = self.date_format.unwrap_or("%m/%d/%Y".to_string());
+                // Dates are formatted as the file is written, which cannot fail midway.
+                if StrftimeItems::new(&date_format).any(|item| item == Item::Error) {
+                    return Err(ApiError::UnprocessableEntity(format!(
+                        "invalid date format {date_format}"
+                    )));
+                }
+                Ok(ExportFormat::Qif { date_format })
+            }
+            Some(other) => Err(ApiError::UnprocessableEntity(format!(
+                "unknown export format {other}, expected csv, json, ndjson, ledger, beancount or \
+                qif"
+            ))),
+        }
+    }
+}
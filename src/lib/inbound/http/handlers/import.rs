@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use axum::body::Bytes;
+use axum::extract::multipart::MultipartError;
+use axum::extract::{Multipart, Query};
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::domain::auth::models::principal::Principal;
+use crate::domain::finance::models::import::{
+    ImportReport, ImportRequest, ImportRowError, ImportedTransaction,
+};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+use crate::inbound::import::{camt, csv, mt940, ofx, qif};
+
+use super::import_schema::{
+    CsvMappingHttpRequestBody, ImportQueryParams, QifFormatHttpRequestBody,
+};
+
+/// The largest file accepted by an import, in bytes.
+pub const MAX_IMPORT_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+///
+/// `ImportReportResponseData`
+/// The response body data field for an [ImportReport].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportReportResponseData {
+    dry_run: bool,
+    rows: usize,
+    imported: usize,
+    /// Transactions left out as already imported.
+    skipped: usize,
+    errors: Vec<ImportRowErrorResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportRowErrorResponseData {
+    line: usize,
+    message: String,
+}
+
+impl From<&ImportRowError> for ImportRowErrorResponseData {
+    fn from(error: &ImportRowError) -> Self {
+        Self {
+            line: error.line(),
+            message: error.message().to_string(),
+        }
+    }
+}
+
+impl From<&ImportReport> for ImportReportResponseData {
+    fn from(report: &ImportReport) -> Self {
+        Self {
+            dry_run: report.is_dry_run(),
+            rows: report.rows(),
+            imported: report.imported(),
+            skipped: report.skipped(),
+            errors: report.errors().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Import the transactions of a CSV file into one of the caller's accounts.
+///
+/// The multipart body carries the file as `file` and, as `mapping`, a JSON object naming the
+/// columns to read and how dates and amounts are written. Rows that fail validation are left
+/// out and reported; the others are saved together, or not at all.
+///
+/// # Responses
+///
+/// - 200 OK: the dry run report is returned.
+/// - 201 Created: the valid rows were imported.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the body, the mapping or the file cannot be read, or the account
+///   does not exist.
+pub async fn import_csv<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(params): Query<ImportQueryParams>,
+    multipart: Multipart,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut fields = read_fields(multipart).await?;
+    let file = take_field(&mut fields, "file")?;
+    let mapping = take_field(&mut fields, "mapping")?;
+    let mapping: CsvMappingHttpRequestBody = serde_json::from_slice(&mapping)
+        .map_err(|e| ApiError::UnprocessableEntity(format!("invalid mapping: {e}")))?;
+
+    let rows = csv::parse_transactions(&file, &mapping.try_into_mapping()?)?;
+    import(&state, &principal, params, rows).await
+}
+
+/// Import the transactions of an OFX or QFX statement into one of the caller's accounts.
+///
+/// The multipart body carries the statement as `file`. Transactions whose `FITID` was already
+/// imported into the account are skipped, so overlapping statements can be imported safely.
+///
+/// # Responses
+///
+/// - 200 OK: the dry run report is returned.
+/// - 201 Created: the valid transactions were imported.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the body or the file cannot be read, or the account does not
+///   exist.
+pub async fn import_ofx<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(params): Query<ImportQueryParams>,
+    multipart: Multipart,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut fields = read_fields(multipart).await?;
+    let file = take_field(&mut fields, "file")?;
+
+    let rows = ofx::parse_transactions(&file)?;
+    import(&state, &principal, params, rows).await
+}
+
+/// Import the booked entries of a camt.053 statement into one of the caller's accounts.
+///
+/// The multipart body carries the statement as `file`. Entries are identified by the reference
+/// their bank gave them or, lacking one, by their date, amount, counterparty and reference, and
+/// those already imported into the account are skipped, so overlapping statements can be
+/// imported safely.
+///
+/// # Responses
+///
+/// - 200 OK: the dry run report is returned.
+/// - 201 Created: the valid entries were imported.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the body or the file cannot be read, or the account does not
+///   exist.
+pub async fn import_camt053<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(params): Query<ImportQueryParams>,
+    multipart: Multipart,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut fields = read_fields(multipart).await?;
+    let file = take_field(&mut fields, "file")?;
+
+    let rows = camt::parse_transactions(&file)?;
+    import(&state, &principal, params, rows).await
+}
+
+/// Import the statement lines of an MT940 statement into one of the caller's accounts.
+///
+/// The multipart body carries the statement as `file`. Statement lines are identified as in
+/// [import_camt053], and those already imported into the account are skipped.
+///
+/// # Responses
+///
+/// - 200 OK: the dry run report is returned.
+/// - 201 Created: the valid statement lines were imported.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the body or the file cannot be read, or the account does not
+///   exist.
+pub async fn import_mt940<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(params): Query<ImportQueryParams>,
+    multipart: Multipart,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut fields = read_fields(multipart).await?;
+    let file = take_field(&mut fields, "file")?;
+
+    let rows = mt940::parse_transactions(&file)?;
+    import(&state, &principal, params, rows).await
+}
+
+/// Import the transactions of the bank, cash and credit card sections of a QIF file into one
+/// of the caller's accounts.
+///
+/// The multipart body carries the file as `file` and, optionally as `format`, a JSON object
+/// telling how its dates and amounts are written, which QIF leaves to the application that
+/// wrote it. Transactions are identified by their date, amount, payee and memo, and those
+/// already imported into the account are skipped.
+///
+/// # Responses
+///
+/// - 200 OK: the dry run report is returned.
+/// - 201 Created: the valid transactions were imported.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the body, the format or the file cannot be read, or the account
+///   does not exist.
+pub async fn import_qif<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(params): Query<ImportQueryParams>,
+    multipart: Multipart,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut fields = read_fields(multipart).await?;
+    let file = take_field(&mut fields, "file")?;
+    let format = match fields.remove("format") {
+        Some(format) => serde_json::from_slice(&format)
+            .map_err(|e| ApiError::UnprocessableEntity(format!("invalid format: {e}")))?,
+        None => QifFormatHttpRequestBody::default(),
+    };
+
+    let rows = qif::parse_transactions(&file, &format.into_format())?;
+    import(&state, &principal, params, rows).await
+}
+
+/// Import the rows read from a file, as requested by the query parameters.
+async fn import<FS: FinanceService>(
+    state: &AppState<FS>,
+    principal: &Principal,
+    params: ImportQueryParams,
+    rows: Vec<Result<ImportedTransaction, ImportRowError>>,
+) -> Result<ApiSuccess<ImportReportResponseData>, ApiError> {
+    let mut domain_req = ImportRequest::new(params.account_id, rows);
+    if params.dry_run.unwrap_or(false) {
+        domain_req = domain_req.with_dry_run();
+    }
+    let status = if domain_req.is_dry_run() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    state
+        .finance_service
+        .import_transactions(principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref report| ApiSuccess::new(status, report.into()))
+}
+
+/// Reads the fields of a multipart body, keyed by name.
+async fn read_fields(mut multipart: Multipart) -> Result<HashMap<String, Bytes>, ApiError> {
+    let mut fields = HashMap::new();
+    while let Some(field) = multipart.next_field().await.map_err(invalid_body)? {
+        if let Some(name) = field.name().map(str::to_string) {
+            fields.insert(name, field.bytes().await.map_err(invalid_body)?);
+        }
+    }
+    Ok(fields)
+}
+
+fn take_field(fields: &mut HashMap<String, Bytes>, name: &str) -> Result<Bytes, ApiError> {
+    fields
+        .remove(name)
+        .ok_or_else(|| ApiError::UnprocessableEntity(format!("missing multipart field {name}")))
+}
+
+/// Reports a multipart body that cannot be read, such as one larger than the route allows.
+pub(super) fn invalid_body(e: MultipartError) -> ApiError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ApiError::PayloadTooLarge(e.body_text())
+    } else {
+        ApiError::UnprocessableEntity(e.body_text())
+    }
+}
@@ -0,0 +1,123 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::inbound::http::api_error::ApiError;
+use crate::inbound::import::csv::{Column, CsvMapping};
+use crate::inbound::import::qif::QifFormat;
+
+///
+/// [ImportQueryParams]
+/// The query parameters of an import
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ImportQueryParams {
+    /// The caller's account the transactions are imported into.
+    pub account_id: Uuid,
+    /// Whether to only report on the rows without saving them. Defaults to `false`.
+    pub dry_run: Option<bool>,
+}
+
+///
+/// [CsvMappingHttpRequestBody]
+/// The `mapping` part of a CSV import, describing where the fields of a transaction are found
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CsvMappingHttpRequestBody {
+    pub date: ColumnHttpRequestBody,
+    pub amount: ColumnHttpRequestBody,
+    pub name: ColumnHttpRequestBody,
+    /// The name of one of the caller's personal categories.
+    pub category: Option<ColumnHttpRequestBody>,
+    /// A strftime format such as `%d/%m/%Y`. Defaults to `%Y-%m-%d`.
+    pub date_format: Option<String>,
+    /// Defaults to `.`.
+    pub decimal_separator: Option<char>,
+    pub thousands_separator: Option<char>,
+    /// Defaults to `,`.
+    pub delimiter: Option<char>,
+    /// Whether the first line names the columns. Defaults to `true`.
+    pub has_header: Option<bool>,
+    /// The sign of expenses, `negative` or `positive`. Defaults to `negative`.
+    pub expense_sign: Option<String>,
+}
+
+///
+/// [ColumnHttpRequestBody]
+/// A column, by header name or by position starting at 0
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnHttpRequestBody {
+    Index(usize),
+    Name(String),
+}
+
+impl From<ColumnHttpRequestBody> for Column {
+    fn from(column: ColumnHttpRequestBody) -> Self {
+        match column {
+            ColumnHttpRequestBody::Index(index) => Column::Index(index),
+            ColumnHttpRequestBody::Name(name) => Column::Name(name),
+        }
+    }
+}
+
+impl CsvMappingHttpRequestBody {
+    /// Converts the mapping into the one read by the CSV parser.
+    pub fn try_into_mapping(self) -> Result<CsvMapping, ApiError> {
+        let mut mapping = CsvMapping::new(self.date.into(), self.amount.into(), self.name.into());
+        if let Some(category) = self.category {
+            mapping = mapping.with_category(category.into());
+        }
+        if let Some(date_format) = self.date_format {
+            mapping = mapping.with_date_format(&date_format);
+        }
+        if let Some(decimal_separator) = self.decimal_separator {
+            mapping = mapping.with_decimal_separator(decimal_separator);
+        }
+        if let Some(thousands_separator) = self.thousands_separator {
+            mapping = mapping.with_thousands_separator(thousands_separator);
+        }
+        if let Some(delimiter) = self.delimiter {
+            mapping = mapping.with_delimiter(delimiter)?;
+        }
+        if self.has_header == Some(false) {
+            mapping = mapping.without_header();
+        }
+        match self.expense_sign.as_deref().map(str::trim) {
+            None | Some("negative") => {}
+            Some("positive") => mapping = mapping.with_expenses_positive(),
+            Some(other) => {
+                return Err(ApiError::UnprocessableEntity(format!(
+                    "unknown expense sign {other}, expected negative or positive"
+                )));
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+///
+/// [QifFormatHttpRequestBody]
+/// The optional `format` part of a QIF import, describing how dates and amounts are written
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct QifFormatHttpRequestBody {
+    /// A strftime format such as `%d.%m.%Y`. Defaults to `%m/%d/%Y`.
+    pub date_format: Option<String>,
+    /// Defaults to `.`.
+    pub decimal_separator: Option<char>,
+}
+
+impl QifFormatHttpRequestBody {
+    /// Converts the format into the one read by the QIF parser.
+    pub fn into_format(self) -> QifFormat {
+        let mut format = QifFormat::new();
+        if let Some(date_format) = self.date_format {
+            format = format.with_date_format(&date_format);
+        }
+        if let Some(decimal_separator) = self.decimal_separator {
+            format = format.with_decimal_separator(decimal_separator);
+        }
+        format
+    }
+}
@@ -0,0 +1,220 @@
+use axum::extract::Path;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::ledger::{
+    AcceptInvitationRequest, Ledger, LedgerInvitation, LedgerMember,
+};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::expense::ListItemsResponseData;
+use super::ledger_schema::{CreateInvitationHttpRequestBody, CreateLedgerHttpRequestBody};
+
+///
+/// `LedgerResponseData`
+/// The response body data field for [Ledger] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LedgerResponseData {
+    id: String,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&Ledger> for LedgerResponseData {
+    fn from(ledger: &Ledger) -> Self {
+        Self {
+            id: ledger.id().to_string(),
+            name: ledger.name().to_string(),
+            created_at: *ledger.created_at(),
+        }
+    }
+}
+
+///
+/// `LedgerMemberResponseData`
+/// The response body data field for [LedgerMember] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LedgerMemberResponseData {
+    ledger_id: String,
+    user_id: String,
+    role: String,
+    joined_at: DateTime<Utc>,
+}
+
+impl From<&LedgerMember> for LedgerMemberResponseData {
+    fn from(member: &LedgerMember) -> Self {
+        Self {
+            ledger_id: member.ledger_id().to_string(),
+            user_id: member.user_id().to_string(),
+            role: member.role().to_string(),
+            joined_at: *member.joined_at(),
+        }
+    }
+}
+
+///
+/// `LedgerInvitationResponseData`
+/// The response body data field for a freshly issued [LedgerInvitation].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LedgerInvitationResponseData {
+    token: String,
+    ledger_id: String,
+    role: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<&LedgerInvitation> for LedgerInvitationResponseData {
+    fn from(invitation: &LedgerInvitation) -> Self {
+        Self {
+            token: invitation.token().to_string(),
+            ledger_id: invitation.ledger_id().to_string(),
+            role: invitation.role().to_string(),
+            expires_at: *invitation.expires_at(),
+        }
+    }
+}
+
+/// Create a new [Ledger] owned by the caller.
+///
+/// # Responses
+///
+/// - 201 Created: the [Ledger] was successfully created.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the ledger name is empty.
+pub async fn create_ledger<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateLedgerHttpRequestBody>,
+) -> Result<ApiSuccess<LedgerResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(*principal.user_id())?;
+    state
+        .finance_service
+        .create_ledger(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ledger| ApiSuccess::new(StatusCode::CREATED, ledger.into()))
+}
+
+/// List the [Ledger]s the caller is a member of.
+///
+/// # Responses
+///
+/// - 200 OK: the [Ledger] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+pub async fn list_ledgers<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<LedgerResponseData>>, ApiError> {
+    state
+        .finance_service
+        .list_ledgers(principal.user_id())
+        .await
+        .map_err(ApiError::from)
+        .map(|ledgers| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(ledgers.iter().map(LedgerResponseData::from).collect()),
+            )
+        })
+}
+
+/// Retrieve a [Ledger].
+///
+/// # Responses
+///
+/// - 200 OK: the [Ledger] is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Ledger] does not exist or the caller is not a member.
+pub async fn get_ledger<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<LedgerResponseData>, ApiError> {
+    state
+        .finance_service
+        .get_ledger(&principal, &ledger_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref ledger| ApiSuccess::new(StatusCode::OK, ledger.into()))
+}
+
+/// List the members of a [Ledger].
+///
+/// # Responses
+///
+/// - 200 OK: the [LedgerMember] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Ledger] does not exist or the caller is not a member.
+pub async fn list_ledger_members<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<LedgerMemberResponseData>>, ApiError> {
+    state
+        .finance_service
+        .list_ledger_members(&principal, &ledger_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|members| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(
+                    members.iter().map(LedgerMemberResponseData::from).collect(),
+                ),
+            )
+        })
+}
+
+/// Invite a new member to a [Ledger]. Only owners may invite.
+///
+/// # Responses
+///
+/// - 201 Created: the [LedgerInvitation] token is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller is not an owner of the [Ledger].
+/// - 404 Not Found: the [Ledger] does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the requested role is unknown or cannot be granted.
+pub async fn create_invitation<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateInvitationHttpRequestBody>,
+) -> Result<ApiSuccess<LedgerInvitationResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(ledger_id, *principal.user_id())?;
+    state
+        .finance_service
+        .create_invitation(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref invitation| ApiSuccess::new(StatusCode::CREATED, invitation.into()))
+}
+
+/// Accept a [LedgerInvitation], joining its [Ledger] with the invited role.
+///
+/// # Responses
+///
+/// - 201 Created: the caller's new [LedgerMember] is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the token is unknown or was already used.
+/// - 422 Unprocessable entity: the token expired or the caller is already a member.
+pub async fn accept_invitation<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(token): Path<String>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<LedgerMemberResponseData>, ApiError> {
+    let domain_req = AcceptInvitationRequest::new(&token, *principal.user_id());
+    state
+        .finance_service
+        .accept_invitation(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref member| ApiSuccess::new(StatusCode::CREATED, member.into()))
+}
@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::ledger::CreateInvitationRequest;
+use crate::domain::finance::models::ledger::CreateLedgerRequest;
+use crate::domain::finance::models::ledger::LedgerNameEmptyError;
+use crate::domain::finance::models::ledger::LedgerRole;
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateLedgerHttpRequestBody]
+/// The HTTP Request body for creating a [Ledger]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateLedgerHttpRequestBody {
+    pub name: String,
+}
+
+impl CreateLedgerHttpRequestBody {
+    /// Converts the HTTP request body into a domain request owned by `owner_id`.
+    pub fn try_into_domain(
+        self,
+        owner_id: Uuid,
+    ) -> Result<CreateLedgerRequest, LedgerNameEmptyError> {
+        CreateLedgerRequest::new(&self.name, owner_id)
+    }
+}
+
+///
+/// [CreateInvitationHttpRequestBody]
+/// The HTTP Request body for inviting a member to a [Ledger]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateInvitationHttpRequestBody {
+    pub role: String,
+}
+
+impl CreateInvitationHttpRequestBody {
+    /// Converts the HTTP request body into a domain request issued by `invited_by`.
+    pub fn try_into_domain(
+        self,
+        ledger_id: Uuid,
+        invited_by: Uuid,
+    ) -> Result<CreateInvitationRequest, ApiError> {
+        let role = LedgerRole::from_str(&self.role)?;
+        Ok(CreateInvitationRequest::new(ledger_id, role, invited_by)?)
+    }
+}
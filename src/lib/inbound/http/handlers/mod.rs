@@ -1,2 +1,28 @@
+pub mod account;
+pub mod account_schema;
+pub mod api_key;
+pub mod api_key_schema;
+pub mod attachment;
+pub mod balance;
+pub mod budget;
+pub mod budget_schema;
+pub mod category;
+pub mod category_schema;
+pub mod duplicate;
+pub mod duplicate_schema;
 pub mod expense;
 pub mod expense_schema;
+pub mod import;
+pub mod import_schema;
+pub mod ledger;
+pub mod ledger_schema;
+pub mod recurring;
+pub mod recurring_schema;
+pub mod report;
+pub mod report_schema;
+pub mod rule;
+pub mod rule_schema;
+pub mod settlement;
+pub mod settlement_schema;
+pub mod transaction;
+pub mod transaction_schema;
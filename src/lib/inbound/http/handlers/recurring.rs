@@ -0,0 +1,194 @@
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::recurring::{Occurrence, RecurringExpense};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::expense::ListItemsResponseData;
+use super::recurring_schema::{
+    CreateRecurringExpenseHttpRequestBody, UpcomingOccurrencesQueryParams,
+};
+
+/// How far ahead upcoming occurrences are listed when no end date is given, in days.
+const DEFAULT_UPCOMING_DAYS: u64 = 30;
+
+///
+/// `RecurringExpenseResponseData`
+/// The response body data field for [RecurringExpense] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecurringExpenseResponseData {
+    id: String,
+    name: String,
+    amount: i64,
+    ledger_id: Option<String>,
+    category_id: Option<String>,
+    account_id: Option<String>,
+    frequency: String,
+    interval: u32,
+    day_of_month: Option<u32>,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+    materialized_through: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<&RecurringExpense> for RecurringExpenseResponseData {
+    fn from(recurring: &RecurringExpense) -> Self {
+        let recurrence = recurring.recurrence();
+        Self {
+            id: recurring.id().to_string(),
+            name: recurring.name().to_string(),
+            amount: recurring.amount(),
+            ledger_id: recurring.ledger_id().map(Uuid::to_string),
+            category_id: recurring.category_id().map(Uuid::to_string),
+            account_id: recurring.account_id().map(Uuid::to_string),
+            frequency: recurrence.frequency().to_string(),
+            interval: recurrence.interval(),
+            day_of_month: recurrence.day_of_month(),
+            starts_on: *recurrence.starts_on(),
+            ends_on: recurrence.ends_on().copied(),
+            materialized_through: recurring.materialized_through().copied(),
+            created_at: *recurring.created_at(),
+        }
+    }
+}
+
+///
+/// `OccurrenceResponseData`
+/// The response body data field for an upcoming [Occurrence].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OccurrenceResponseData {
+    occurs_on: NaiveDate,
+    recurring_expense_id: String,
+    name: String,
+    amount: i64,
+}
+
+impl From<&Occurrence> for OccurrenceResponseData {
+    fn from(occurrence: &Occurrence) -> Self {
+        let recurring = occurrence.recurring_expense();
+        Self {
+            occurs_on: *occurrence.occurs_on(),
+            recurring_expense_id: recurring.id().to_string(),
+            name: recurring.name().to_string(),
+            amount: recurring.amount(),
+        }
+    }
+}
+
+/// Set up a personal [RecurringExpense], or a shared one when nested under
+/// `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 201 Created: the [RecurringExpense] was successfully set up.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the name is empty, the amount negative, the recurrence invalid, or
+///   the category or account unknown.
+pub async fn create_recurring_expense<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateRecurringExpenseHttpRequestBody>,
+) -> Result<ApiSuccess<RecurringExpenseResponseData>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    let domain_req = body.try_into_domain(*principal.user_id(), ledger_id)?;
+    state
+        .finance_service
+        .create_recurring_expense(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref recurring| ApiSuccess::new(StatusCode::CREATED, recurring.into()))
+}
+
+/// List the caller's personal [RecurringExpense]s, or a ledger's when nested under
+/// `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 200 OK: the [RecurringExpense] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+pub async fn list_recurring_expenses<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<RecurringExpenseResponseData>>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    state
+        .finance_service
+        .list_recurring_expenses(&principal, ledger_id.as_ref())
+        .await
+        .map_err(ApiError::from)
+        .map(|recurring| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(recurring.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Stop a [RecurringExpense]. Expenses it already created are kept.
+///
+/// # Responses
+///
+/// - 204 No Content: the [RecurringExpense] was deleted.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the [RecurringExpense] does not exist or is not visible to the caller.
+pub async fn delete_recurring_expense<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<StatusCode, ApiError> {
+    state
+        .finance_service
+        .delete_recurring_expense(&principal, &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+/// List the upcoming [Occurrence]s of the caller's personal [RecurringExpense]s, or a ledger's
+/// when nested under `/ledgers/{ledger_id}`, over the next 30 days by default.
+///
+/// # Responses
+///
+/// - 200 OK: the [Occurrence]s are returned in date order.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the range spans more than a year.
+pub async fn upcoming_occurrences<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<UpcomingOccurrencesQueryParams>,
+) -> Result<ApiSuccess<ListItemsResponseData<OccurrenceResponseData>>, ApiError> {
+    let ledger_id = ledger.map(|Path(ledger_id)| ledger_id);
+    let from = query.from.unwrap_or_else(|| Utc::now().date_naive());
+    let until = query
+        .until
+        .or_else(|| from.checked_add_days(Days::new(DEFAULT_UPCOMING_DAYS)))
+        .unwrap_or(from);
+    state
+        .finance_service
+        .upcoming_occurrences(&principal, ledger_id.as_ref(), from, until)
+        .await
+        .map_err(ApiError::from)
+        .map(|occurrences| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(occurrences.iter().map(Into::into).collect()),
+            )
+        })
+}
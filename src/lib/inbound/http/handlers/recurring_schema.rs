@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::recurring::{
+    CreateRecurringExpenseRequest, Frequency, Recurrence,
+};
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateRecurringExpenseHttpRequestBody]
+/// The HTTP Request body for setting up a [RecurringExpense]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateRecurringExpenseHttpRequestBody {
+    pub name: String,
+    /// Amount of each occurrence in minor units, e.g. cents.
+    pub amount: Option<i64>,
+    pub category_id: Option<Uuid>,
+    /// The account each occurrence is paid from. Required.
+    pub account_id: Option<Uuid>,
+    /// One of `daily`, `weekly`, `monthly` or `yearly`.
+    pub frequency: String,
+    /// Repeat every `interval` periods. Defaults to 1.
+    pub interval: Option<u32>,
+    /// For monthly recurrences, the day of the month. Defaults to that of `starts_on`.
+    pub day_of_month: Option<u32>,
+    pub starts_on: NaiveDate,
+    /// The last day an occurrence may fall on. Defaults to never ending.
+    pub ends_on: Option<NaiveDate>,
+}
+
+impl CreateRecurringExpenseHttpRequestBody {
+    /// Converts the HTTP request body into a domain request set up by `owner_id`, shared in
+    /// `ledger_id` if given.
+    pub fn try_into_domain(
+        self,
+        owner_id: Uuid,
+        ledger_id: Option<Uuid>,
+    ) -> Result<CreateRecurringExpenseRequest, ApiError> {
+        let frequency = Frequency::from_str(&self.frequency)?;
+        let mut recurrence =
+            Recurrence::new(frequency, self.starts_on).with_interval(self.interval.unwrap_or(1))?;
+        if let Some(day) = self.day_of_month {
+            recurrence = recurrence.with_day_of_month(day)?;
+        }
+        if let Some(ends_on) = self.ends_on {
+            recurrence = recurrence.with_ends_on(ends_on)?;
+        }
+
+        let mut req = CreateRecurringExpenseRequest::new(owner_id, &self.name, recurrence)?
+            .with_amount(self.amount.unwrap_or(0))?;
+        if let Some(ledger_id) = ledger_id {
+            req = req.with_ledger(ledger_id);
+        }
+        if let Some(category_id) = self.category_id {
+            req = req.with_category(category_id);
+        }
+        if let Some(account_id) = self.account_id {
+            req = req.with_account(account_id);
+        }
+        Ok(req)
+    }
+}
+
+///
+/// [UpcomingOccurrencesQueryParams]
+/// The query parameters bounding a listing of upcoming [Occurrence]s
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpcomingOccurrencesQueryParams {
+    /// Defaults to today.
+    pub from: Option<NaiveDate>,
+    /// Inclusive. Defaults to 30 days after `from`.
+    pub until: Option<NaiveDate>,
+}
@@ -0,0 +1,193 @@
+use axum::extract::{Path, Query};
+use axum::{extract::State, http::StatusCode};
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::report::{
+    Series, SeriesPoint, SummaryBucket, SummaryRequest, TimeseriesRequest,
+};
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::report_schema::{SummaryQueryParams, TimeseriesQueryParams};
+
+/// How many points a time series holds when no start date is given.
+const DEFAULT_SERIES_POINTS: u32 = 30;
+
+///
+/// `SummaryResponseData`
+/// The response body data field for a spending summary.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SummaryResponseData {
+    group_by: String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    buckets: Vec<SummaryBucketResponseData>,
+}
+
+impl SummaryResponseData {
+    fn new(req: &SummaryRequest, buckets: &[SummaryBucket]) -> Self {
+        Self {
+            group_by: req.grouping().to_string(),
+            from: req.from().copied(),
+            to: req.to().copied(),
+            buckets: buckets.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SummaryBucketResponseData {
+    /// `null` for uncategorized or untagged expenses.
+    key: Option<String>,
+    label: Option<String>,
+    currency: String,
+    total: i64,
+    count: i64,
+    average: i64,
+}
+
+impl From<&SummaryBucket> for SummaryBucketResponseData {
+    fn from(bucket: &SummaryBucket) -> Self {
+        Self {
+            key: bucket.key().map(str::to_string),
+            label: bucket.label().map(str::to_string),
+            currency: bucket.currency().to_string(),
+            total: bucket.total(),
+            count: bucket.count(),
+            average: bucket.average(),
+        }
+    }
+}
+
+///
+/// `TimeseriesResponseData`
+/// The response body data field for a spending time series.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeseriesResponseData {
+    interval: String,
+    /// The first day of the first point.
+    from: NaiveDate,
+    /// The first day of the last point.
+    to: NaiveDate,
+    cumulative: bool,
+    series: Vec<SeriesResponseData>,
+}
+
+impl TimeseriesResponseData {
+    fn new(req: &TimeseriesRequest, series: &[Series]) -> Self {
+        Self {
+            interval: req.interval().to_string(),
+            from: req.start(),
+            to: req.last_start(),
+            cumulative: req.is_cumulative(),
+            series: series.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeriesResponseData {
+    currency: String,
+    points: Vec<SeriesPointResponseData>,
+}
+
+impl From<&Series> for SeriesResponseData {
+    fn from(series: &Series) -> Self {
+        Self {
+            currency: series.currency().to_string(),
+            points: series.points().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeriesPointResponseData {
+    start: NaiveDate,
+    total: i64,
+    /// Only present when the previous period was asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_total: Option<i64>,
+}
+
+impl From<&SeriesPoint> for SeriesPointResponseData {
+    fn from(point: &SeriesPoint) -> Self {
+        Self {
+            start: *point.start(),
+            total: point.total(),
+            previous_total: point.previous_total(),
+        }
+    }
+}
+
+/// Summarize the caller's personal spending or, when nested under `/ledgers/{ledger_id}`, that
+/// of a shared ledger, per month, category or tag.
+///
+/// # Responses
+///
+/// - 200 OK: the buckets are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the grouping, range or currency is invalid, or an exchange rate
+///   needed for the conversion is missing.
+pub async fn spending_summary<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<SummaryQueryParams>,
+) -> Result<ApiSuccess<SummaryResponseData>, ApiError> {
+    let mut domain_req = query.try_into_domain()?;
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
+    state
+        .finance_service
+        .spending_summary(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref buckets| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                SummaryResponseData::new(&domain_req, buckets),
+            )
+        })
+}
+
+/// Compute the caller's personal spending or, when nested under `/ledgers/{ledger_id}`, that of
+/// a shared ledger, per day, week or month, with zeros where nothing was spent.
+///
+/// # Responses
+///
+/// - 200 OK: the series are returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the interval, range or currency is invalid, the series would be
+///   too long, or an exchange rate needed for the conversion is missing.
+pub async fn spending_timeseries<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<TimeseriesQueryParams>,
+) -> Result<ApiSuccess<TimeseriesResponseData>, ApiError> {
+    let today = Utc::now().date_naive();
+    let mut domain_req = query.try_into_domain(today, DEFAULT_SERIES_POINTS)?;
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
+    state
+        .finance_service
+        .spending_timeseries(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref series| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                TimeseriesResponseData::new(&domain_req, series),
+            )
+        })
+}
@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::domain::finance::models::account::Currency;
+use crate::domain::finance::models::report::{
+    SeriesInterval, SummaryGrouping, SummaryRequest, TimeseriesRequest,
+};
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [SummaryQueryParams]
+/// The query parameters of a spending summary
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SummaryQueryParams {
+    /// One of `month`, `category` or `tag`.
+    pub group_by: String,
+    /// Inclusive. Defaults to the first expense.
+    pub from: Option<NaiveDate>,
+    /// Inclusive. Defaults to the last expense.
+    pub to: Option<NaiveDate>,
+    /// ISO 4217 code to convert the amounts to. Defaults to the currency of each account.
+    pub currency: Option<String>,
+}
+
+impl SummaryQueryParams {
+    /// Converts the query parameters into a domain request.
+    pub fn try_into_domain(self) -> Result<SummaryRequest, ApiError> {
+        let mut req = SummaryRequest::new(SummaryGrouping::from_str(&self.group_by)?);
+        if let Some(from) = self.from {
+            req = req.with_from(from);
+        }
+        if let Some(to) = self.to {
+            req = req.with_to(to);
+        }
+        if let Some(currency) = self.currency {
+            req = req.with_currency(Currency::new(&currency)?);
+        }
+        Ok(req)
+    }
+}
+
+///
+/// [TimeseriesQueryParams]
+/// The query parameters of a spending time series
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TimeseriesQueryParams {
+    /// One of `day`, `week` or `month`. Defaults to `day`.
+    pub interval: Option<String>,
+    /// Widened to the start of its interval. Defaults to 30 intervals before `to`.
+    pub from: Option<NaiveDate>,
+    /// Widened to the end of its interval. Defaults to today.
+    pub to: Option<NaiveDate>,
+    /// Whether every point sums all the previous ones. Defaults to `false`.
+    pub cumulative: Option<bool>,
+    /// Whether to add the totals of the previous period of the same length. Defaults to `false`.
+    pub compare: Option<bool>,
+    /// ISO 4217 code to convert the amounts to. Defaults to one series per currency.
+    pub currency: Option<String>,
+}
+
+impl TimeseriesQueryParams {
+    /// Converts the query parameters into a domain request, ending on `today` unless told
+    /// otherwise and spanning `default_points` intervals unless a start is given.
+    pub fn try_into_domain(
+        self,
+        today: NaiveDate,
+        default_points: u32,
+    ) -> Result<TimeseriesRequest, ApiError> {
+        let interval = match self.interval {
+            Some(interval) => SeriesInterval::from_str(&interval)?,
+            None => SeriesInterval::Day,
+        };
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or_else(|| {
+            interval.back(interval.start_of(to), default_points.saturating_sub(1))
+        });
+        let mut req = TimeseriesRequest::new(interval, from, to);
+        if let Some(currency) = self.currency {
+            req = req.with_currency(Currency::new(&currency)?);
+        }
+        if self.cumulative.unwrap_or(false) {
+            req = req.with_cumulative();
+        }
+        if self.compare.unwrap_or(false) {
+            req = req.with_comparison();
+        }
+        Ok(req)
+    }
+}
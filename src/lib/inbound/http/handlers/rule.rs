@@ -0,0 +1,223 @@
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::rule::{
+    Classification, Rule, RuleApplicationReport, RuleChange, RuleConditions,
+};
+use crate::domain::finance::models::tag::Tag;
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::expense::ListItemsResponseData;
+use super::rule_schema::{ApplyRulesQueryParams, CreateRuleHttpRequestBody};
+
+///
+/// `RuleResponseData`
+/// The response body data field for [Rule] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleResponseData {
+    id: String,
+    name: String,
+    priority: i32,
+    conditions: RuleConditionsResponseData,
+    actions: RuleActionsResponseData,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleConditionsResponseData {
+    name_pattern: Option<String>,
+    min_amount: Option<i64>,
+    max_amount: Option<i64>,
+    account_id: Option<String>,
+    counterparty: Option<String>,
+    kind: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleActionsResponseData {
+    category_id: Option<String>,
+    tags: Vec<String>,
+    rename: Option<String>,
+}
+
+impl From<&RuleConditions> for RuleConditionsResponseData {
+    fn from(conditions: &RuleConditions) -> Self {
+        Self {
+            name_pattern: conditions.name_pattern().map(|p| p.as_str().to_string()),
+            min_amount: conditions.min_amount(),
+            max_amount: conditions.max_amount(),
+            account_id: conditions.account_id().map(Uuid::to_string),
+            counterparty: conditions.counterparty().map(str::to_string),
+            kind: conditions.kind().map(|kind| kind.to_string()),
+        }
+    }
+}
+
+impl From<&Rule> for RuleResponseData {
+    fn from(rule: &Rule) -> Self {
+        let actions = rule.actions();
+        Self {
+            id: rule.id().to_string(),
+            name: rule.name().to_string(),
+            priority: rule.priority(),
+            conditions: rule.conditions().into(),
+            actions: RuleActionsResponseData {
+                category_id: actions.category_id().map(Uuid::to_string),
+                tags: actions.tags().iter().map(Tag::to_string).collect(),
+                rename: actions.rename().map(ToString::to_string),
+            },
+            created_at: *rule.created_at(),
+        }
+    }
+}
+
+///
+/// `RuleApplicationResponseData`
+/// The response body data field for a [RuleApplicationReport].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleApplicationResponseData {
+    dry_run: bool,
+    examined: usize,
+    changed: usize,
+    changes: Vec<RuleChangeResponseData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleChangeResponseData {
+    expense_id: String,
+    rule_ids: Vec<String>,
+    before: ClassificationResponseData,
+    after: ClassificationResponseData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClassificationResponseData {
+    name: String,
+    category_id: Option<String>,
+    tags: Vec<String>,
+}
+
+impl From<&Classification> for ClassificationResponseData {
+    fn from(classification: &Classification) -> Self {
+        Self {
+            name: classification.name().to_string(),
+            category_id: classification.category_id().map(Uuid::to_string),
+            tags: classification.tags().iter().map(Tag::to_string).collect(),
+        }
+    }
+}
+
+impl From<&RuleChange> for RuleChangeResponseData {
+    fn from(change: &RuleChange) -> Self {
+        Self {
+            expense_id: change.expense_id().to_string(),
+            rule_ids: change.rule_ids().iter().map(Uuid::to_string).collect(),
+            before: change.before().into(),
+            after: change.after().into(),
+        }
+    }
+}
+
+impl From<&RuleApplicationReport> for RuleApplicationResponseData {
+    fn from(report: &RuleApplicationReport) -> Self {
+        Self {
+            dry_run: report.is_dry_run(),
+            examined: report.examined(),
+            changed: report.changes().len(),
+            changes: report.changes().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Set up a [Rule] filing the caller's personal expenses as they are created or imported.
+///
+/// # Responses
+///
+/// - 201 Created: the [Rule] was successfully created.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 422 Unprocessable entity: the rule has no condition or no action, its name pattern is not
+///   a valid regular expression, or its category or account is unknown.
+pub async fn create_rule<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateRuleHttpRequestBody>,
+) -> Result<ApiSuccess<RuleResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(*principal.user_id())?;
+    state
+        .finance_service
+        .create_rule(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref rule| ApiSuccess::new(StatusCode::CREATED, rule.into()))
+}
+
+/// List the caller's [Rule]s, in the order they are evaluated.
+///
+/// # Responses
+///
+/// - 200 OK: the [Rule] list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+pub async fn list_rules<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+) -> Result<ApiSuccess<ListItemsResponseData<RuleResponseData>>, ApiError> {
+    state
+        .finance_service
+        .list_rules(&principal)
+        .await
+        .map_err(ApiError::from)
+        .map(|rules| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(rules.iter().map(Into::into).collect()),
+            )
+        })
+}
+
+/// Delete a [Rule].
+///
+/// # Responses
+///
+/// - 204 No Content: the [Rule] was deleted.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the [Rule] does not exist or belongs to someone else.
+pub async fn delete_rule<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+) -> Result<StatusCode, ApiError> {
+    state
+        .finance_service
+        .delete_rule(&principal, &id)
+        .await
+        .map_err(ApiError::from)
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+/// Apply the caller's [Rule]s to their existing personal expenses, or preview the changes with
+/// `dry_run=true`.
+///
+/// # Responses
+///
+/// - 200 OK: the changed expenses are reported.
+/// - 401 Unauthorized: the caller is anonymous.
+pub async fn apply_rules<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<ApplyRulesQueryParams>,
+) -> Result<ApiSuccess<RuleApplicationResponseData>, ApiError> {
+    state
+        .finance_service
+        .apply_rules(&principal, query.dry_run.unwrap_or(false))
+        .await
+        .map_err(ApiError::from)
+        .map(|ref report| ApiSuccess::new(StatusCode::OK, report.into()))
+}
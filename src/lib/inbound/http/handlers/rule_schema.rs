@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::expense::ExpenseName;
+use crate::domain::finance::models::rule::{
+    CreateRuleRequest, NamePattern, RuleActions, RuleConditions, RuleName,
+};
+use crate::domain::finance::models::tag::Tag;
+use crate::domain::finance::models::transaction::TransactionKind;
+use crate::inbound::http::api_error::ApiError;
+
+///
+/// [CreateRuleHttpRequestBody]
+/// The HTTP Request body for setting up a [Rule]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateRuleHttpRequestBody {
+    pub name: String,
+    /// Rules with a lower priority are evaluated first. Defaults to `0`.
+    pub priority: Option<i32>,
+    pub conditions: RuleConditionsHttpRequestBody,
+    pub actions: RuleActionsHttpRequestBody,
+}
+
+///
+/// [RuleConditionsHttpRequestBody]
+/// What an expense must look like for a [Rule] to apply. At least one field is required.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct RuleConditionsHttpRequestBody {
+    /// A regular expression searched in the expense name, e.g. `(?i)^starbucks`.
+    pub name_pattern: Option<String>,
+    /// Smallest amount matched, inclusive, in minor units.
+    pub min_amount: Option<i64>,
+    /// Largest amount matched, inclusive, in minor units.
+    pub max_amount: Option<i64>,
+    pub account_id: Option<Uuid>,
+    /// Text the counterparty must contain, compared case-insensitively.
+    pub counterparty: Option<String>,
+    /// One of `expense`, `income` or `transfer`.
+    pub kind: Option<String>,
+}
+
+///
+/// [RuleActionsHttpRequestBody]
+/// What a [Rule] does to the expenses it matches. At least one field is required.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct RuleActionsHttpRequestBody {
+    /// One of the caller's personal categories, set on expenses without one.
+    pub category_id: Option<Uuid>,
+    pub tags: Option<Vec<String>>,
+    /// The new name of matching expenses.
+    pub rename: Option<String>,
+}
+
+impl CreateRuleHttpRequestBody {
+    /// Converts the HTTP request body into a domain request set up by `owner_id`.
+    pub fn try_into_domain(self, owner_id: Uuid) -> Result<CreateRuleRequest, ApiError> {
+        let conditions = self.conditions;
+        let mut domain_conditions = RuleConditions::new()
+            .with_amount_range(conditions.min_amount, conditions.max_amount)?;
+        if let Some(pattern) = conditions.name_pattern {
+            domain_conditions = domain_conditions.with_name_pattern(NamePattern::new(&pattern)?);
+        }
+        if let Some(account_id) = conditions.account_id {
+            domain_conditions = domain_conditions.with_account(account_id);
+        }
+        if let Some(counterparty) = conditions.counterparty {
+            domain_conditions = domain_conditions.with_counterparty(&counterparty);
+        }
+        if let Some(kind) = conditions.kind {
+            domain_conditions = domain_conditions.with_kind(TransactionKind::from_str(&kind)?);
+        }
+
+        let actions = self.actions;
+        let mut domain_actions = RuleActions::new();
+        if let Some(category_id) = actions.category_id {
+            domain_actions = domain_actions.with_category(category_id);
+        }
+        if let Some(tags) = actions.tags {
+            let tags = tags
+                .iter()
+                .map(|tag| Tag::new(tag))
+                .collect::<Result<Vec<_>, _>>()?;
+            domain_actions = domain_actions.with_tags(tags);
+        }
+        if let Some(rename) = actions.rename {
+            domain_actions = domain_actions.with_rename(ExpenseName::new(&rename)?);
+        }
+
+        Ok(CreateRuleRequest::new(
+            owner_id,
+            RuleName::new(&self.name)?,
+            self.priority.unwrap_or(0),
+            domain_conditions,
+            domain_actions,
+        )?)
+    }
+}
+
+///
+/// [ApplyRulesQueryParams]
+/// The query parameters of a re-application of the caller's rules
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ApplyRulesQueryParams {
+    /// Whether to only report on the changes without saving them. Defaults to `false`.
+    pub dry_run: Option<bool>,
+}
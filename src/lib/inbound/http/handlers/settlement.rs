@@ -0,0 +1,65 @@
+use axum::extract::Path;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::settlement::Settlement;
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::settlement_schema::RecordSettlementHttpRequestBody;
+
+///
+/// `SettlementResponseData`
+/// The response body data field for [Settlement] data.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SettlementResponseData {
+    id: String,
+    ledger_id: String,
+    from: String,
+    to: String,
+    amount: i64,
+    recorded_at: DateTime<Utc>,
+}
+
+impl From<&Settlement> for SettlementResponseData {
+    fn from(settlement: &Settlement) -> Self {
+        Self {
+            id: settlement.id().to_string(),
+            ledger_id: settlement.ledger_id().to_string(),
+            from: settlement.from().to_string(),
+            to: settlement.to().to_string(),
+            amount: settlement.amount(),
+            recorded_at: *settlement.recorded_at(),
+        }
+    }
+}
+
+/// Record a [Settlement] payment between two ledger members.
+///
+/// # Responses
+///
+/// - 201 Created: the [Settlement] was recorded and balances are updated.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the amount is not positive, both parties are the same member, or
+///   one of them does not belong to the ledger.
+pub async fn record_settlement<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    Path(ledger_id): Path<Uuid>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<RecordSettlementHttpRequestBody>,
+) -> Result<ApiSuccess<SettlementResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(ledger_id, *principal.user_id())?;
+    state
+        .finance_service
+        .record_settlement(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref settlement| ApiSuccess::new(StatusCode::CREATED, settlement.into()))
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::settlement::RecordSettlementRequest;
+use crate::domain::finance::models::settlement::SettlementError;
+
+///
+/// [RecordSettlementHttpRequestBody]
+/// The HTTP Request body for recording a [Settlement]
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RecordSettlementHttpRequestBody {
+    /// The member who paid. Defaults to the caller.
+    pub from: Option<Uuid>,
+    /// The member who was paid.
+    pub to: Uuid,
+    /// Amount in minor units, e.g. cents.
+    pub amount: i64,
+}
+
+impl RecordSettlementHttpRequestBody {
+    /// Converts the HTTP request body into a domain request recorded by `caller`.
+    pub fn try_into_domain(
+        self,
+        ledger_id: Uuid,
+        caller: Uuid,
+    ) -> Result<RecordSettlementRequest, SettlementError> {
+        RecordSettlementRequest::new(
+            ledger_id,
+            self.from.unwrap_or(caller),
+            self.to,
+            self.amount,
+            caller,
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::expense::Expense;
+use crate::domain::finance::ports::FinanceService;
+use crate::inbound::http::auth::Authenticated;
+use crate::inbound::http::server::AppState;
+use crate::inbound::http::{api_error::ApiError, api_success::ApiSuccess};
+
+use super::expense::{ExpenseResponseData, ListItemsResponseData};
+use super::transaction_schema::{CreateTransactionHttpRequestBody, ListTransactionsQueryParams};
+
+///
+/// `TransactionResponseData`
+/// The response body data field for an [Expense] of any kind.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionResponseData {
+    kind: String,
+    to_account_id: Option<String>,
+    #[serde(flatten)]
+    transaction: ExpenseResponseData,
+}
+
+impl From<&Expense> for TransactionResponseData {
+    fn from(expense: &Expense) -> Self {
+        Self {
+            kind: expense.kind().to_string(),
+            to_account_id: expense.to_account_id().map(Uuid::to_string),
+            transaction: expense.into(),
+        }
+    }
+}
+
+/// Record an expense, an income or a transfer, either personal or, when nested under
+/// `/ledgers/{ledger_id}`, in a shared ledger.
+///
+/// # Responses
+///
+/// - 201 Created: the transaction was successfully recorded.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 403 Forbidden: the caller may only view the ledger.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: the kind is unknown, a split was given for anything but an
+///   expense, or the accounts are missing, unknown or do not suit the kind.
+pub async fn create_transaction<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Json(body): Json<CreateTransactionHttpRequestBody>,
+) -> Result<ApiSuccess<TransactionResponseData>, ApiError> {
+    let mut domain_req = body.try_into_domain(principal.user_id())?;
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
+    state
+        .finance_service
+        .create_expense(Some(&principal), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|created| ApiSuccess::new(StatusCode::CREATED, created.expense().into()))
+}
+
+/// List personal transactions of every kind, or of the kind given as a filter, or those of a
+/// shared ledger when nested under `/ledgers/{ledger_id}`.
+///
+/// # Responses
+///
+/// - 200 OK: the transaction list is returned.
+/// - 401 Unauthorized: the caller is anonymous.
+/// - 404 Not Found: the ledger does not exist or the caller is not a member.
+/// - 422 Unprocessable entity: invalid pagination parameters or unknown kind.
+pub async fn list_transactions<FS: FinanceService>(
+    State(state): State<AppState<FS>>,
+    ledger: Option<Path<Uuid>>,
+    Authenticated(principal): Authenticated,
+    Query(query): Query<ListTransactionsQueryParams>,
+) -> Result<ApiSuccess<ListItemsResponseData<TransactionResponseData>>, ApiError> {
+    let mut domain_req = query.try_into_domain()?;
+    if let Some(Path(ledger_id)) = ledger {
+        domain_req = domain_req.with_ledger(ledger_id);
+    }
+    state
+        .finance_service
+        .list_expenses(&principal, &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|transactions| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListItemsResponseData::new(transactions.iter().map(Into::into).collect()),
+            )
+        })
+}
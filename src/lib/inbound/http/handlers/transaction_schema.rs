@@ -0,0 +1,64 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::finance::models::expense::{CreateExpenseRequest, ListExpensesRequest};
+use crate::domain::finance::models::transaction::TransactionKind;
+use crate::inbound::http::api_error::ApiError;
+
+use super::expense_schema::{CreateExpenseHttpRequestBody, PaginationRequestQueryParams};
+
+///
+/// [CreateTransactionHttpRequestBody]
+/// The HTTP Request body for recording an expense, an income or a transfer
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CreateTransactionHttpRequestBody {
+    /// One of `expense`, `income` or `transfer`.
+    pub kind: String,
+    /// The caller's account a transfer moves the money to. Required for transfers only.
+    pub to_account_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub transaction: CreateExpenseHttpRequestBody,
+}
+
+impl CreateTransactionHttpRequestBody {
+    /// Converts the HTTP request body into a domain request. A split without an explicit payer is
+    /// paid by `caller`.
+    pub fn try_into_domain(self, caller: &Uuid) -> Result<CreateExpenseRequest, ApiError> {
+        let kind = TransactionKind::from_str(&self.kind)?;
+        let req = self.transaction.try_into_domain(caller)?.with_kind(kind);
+        Ok(match self.to_account_id {
+            Some(to_account_id) => req.with_to_account(to_account_id),
+            None => req,
+        })
+    }
+}
+
+///
+/// [ListTransactionsQueryParams]
+/// The query parameters for listing transactions, optionally of a single kind
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ListTransactionsQueryParams {
+    pub page: Option<u32>,
+    pub size: Option<u32>,
+    /// One of `expense`, `income` or `transfer`. Defaults to every kind.
+    pub kind: Option<String>,
+}
+
+impl ListTransactionsQueryParams {
+    /// Converts the HTTP query parameters into a domain request.
+    pub fn try_into_domain(self) -> Result<ListExpensesRequest, ApiError> {
+        let pagination = PaginationRequestQueryParams {
+            page: self.page,
+            size: self.size,
+        };
+        let req = pagination.try_into_domain()?;
+        Ok(match self.kind {
+            Some(kind) => req.with_kind(TransactionKind::from_str(&kind)?),
+            None => req,
+        })
+    }
+}
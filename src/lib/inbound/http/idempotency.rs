@@ -0,0 +1,240 @@
+/*!
+    Module `idempotency` lets clients retry the requests creating resources without creating
+    them twice.
+
+    A client sends the same `Idempotency-Key` header, such as a UUID, with every attempt at one
+    logical request. The first attempt is processed and its successful response kept for
+    [IDEMPOTENCY_KEY_TTL]; retries get that response back, marked with `Idempotent-Replayed:
+    true`, without being processed again. A failed attempt frees its key to be retried.
+
+    Keys belong to the caller who sent them, so anonymous requests are processed as if they
+    carried none. A retry must repeat the request: only the boundary of a multipart upload, which
+    clients pick anew for every attempt, may change.
+
+    [IDEMPOTENCY_KEY_TTL]: crate::domain::finance::models::idempotency::IDEMPOTENCY_KEY_TTL
+*/
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use axum::body::{Body, to_bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::domain::auth::models::principal::Principal;
+use crate::domain::finance::models::idempotency::{
+    IdempotencyKey, RequestFingerprint, StoredResponse,
+};
+use crate::domain::finance::ports::FinanceService;
+
+use super::api_error::ApiError;
+use super::server::AppState;
+
+/// Header carrying the key identifying one logical request across retries.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header set on replayed responses.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// The largest request body read to be fingerprinted on routes keeping axum's default body
+/// limit.
+pub const DEFAULT_IDEMPOTENT_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+/// The state of the [idempotency] middleware of one route.
+pub(super) struct IdempotencyState<FS: FinanceService> {
+    finance_service: Arc<FS>,
+    /// The body limit of the route, up to which requests are fingerprinted.
+    max_body_size: usize,
+}
+
+impl<FS: FinanceService> IdempotencyState<FS> {
+    pub(super) fn new(state: &AppState<FS>, max_body_size: usize) -> Self {
+        Self {
+            finance_service: Arc::clone(&state.finance_service),
+            max_body_size,
+        }
+    }
+}
+
+/// Route middleware replaying the response to the first request made with an
+/// `Idempotency-Key`, instead of processing its retries.
+///
+/// # Responses
+///
+/// - The replayed response, if the same request was already answered with the same key.
+/// - 409 Conflict: the same request is still being processed with the same key.
+/// - 413 Payload Too Large: the body is too large to be fingerprinted.
+/// - 422 Unprocessable entity: the key is malformed, or was used for a different request.
+pub async fn idempotency<FS: FinanceService>(
+    State(state): State<IdempotencyState<FS>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(value) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(req).await);
+    };
+    let key = IdempotencyKey::new(value.to_str().unwrap_or_default())?;
+    let Some(principal) = req.extensions().get::<Principal>().cloned() else {
+        return Ok(next.run(req).await);
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, state.max_body_size).await.map_err(|_| {
+        ApiError::PayloadTooLarge(format!(
+            "request body exceeds {} bytes",
+            state.max_body_size
+        ))
+    })?;
+    let path = parts
+        .uri
+        .path_and_query()
+        .map_or(parts.uri.path(), |path| path.as_str());
+    let fingerprint = RequestFingerprint::new(
+        parts.method.as_str(),
+        path,
+        &without_boundary(&parts.headers, &body),
+    );
+    let finance_service = &state.finance_service;
+    if let Some(stored) = finance_service
+        .claim_idempotency_key(&principal, &key, &fingerprint)
+        .await?
+    {
+        return Ok(replay(stored));
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if !response.status().is_success() {
+        if let Err(e) = finance_service
+            .release_idempotency_key(&principal, &key)
+            .await
+        {
+            tracing::error!("Failed to release idempotency key {}: {:?}", key, e);
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(
+                "Failed to read the response to idempotency key {}: {}",
+                key,
+                e
+            );
+            return Err(ApiError::InternalServerError(e.to_string()));
+        }
+    };
+    let stored = StoredResponse::new(parts.status.as_u16(), body.to_vec());
+    // The request succeeded, so failing to keep its response must not fail it: a retry would
+    // then be answered 409 Conflict until the key expires, rather than processed twice.
+    if let Err(e) = finance_service
+        .complete_idempotent_request(&principal, &key, &stored)
+        .await
+    {
+        tracing::error!(
+            "Failed to save the response to idempotency key {}: {:?}",
+            key,
+            e
+        );
+    }
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// The placeholder replacing the boundary of a multipart body when it is fingerprinted.
+const BOUNDARY_PLACEHOLDER: &[u8] = b"boundary";
+
+/// `body`, with the delimiters of its parts replaced by [BOUNDARY_PLACEHOLDER] if it is
+/// multipart, so that retries sent with another boundary are recognised.
+fn without_boundary<'a>(headers: &HeaderMap, body: &'a [u8]) -> Cow<'a, [u8]> {
+    let Some(boundary) = multipart_boundary(headers) else {
+        return Cow::Borrowed(body);
+    };
+    let delimiter = [b"--", boundary.as_bytes()].concat();
+    let mut normalized = Vec::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest
+        .windows(delimiter.len())
+        .position(|window| window == delimiter.as_slice())
+    {
+        normalized.extend_from_slice(&rest[..start]);
+        normalized.extend_from_slice(BOUNDARY_PLACEHOLDER);
+        rest = &rest[start + delimiter.len()..];
+    }
+    normalized.extend_from_slice(rest);
+    Cow::Owned(normalized)
+}
+
+/// The boundary declared by a `multipart/*` `Content-Type` header.
+fn multipart_boundary(headers: &HeaderMap) -> Option<&str> {
+    let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().to_ascii_lowercase().starts_with("multipart/") {
+        return None;
+    }
+    params
+        .split(';')
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+        .filter(|boundary| !boundary.is_empty())
+}
+
+/// Rebuild a response kept by [idempotency]: every such response is an `ApiSuccess`, in JSON.
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status()).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body().to_vec()).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multipart(boundary: &str, content: &str) -> (HeaderMap, Vec<u8>) {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&format!("multipart/form-data; boundary=\"{boundary}\""))
+                .unwrap(),
+        );
+        let body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"\r\n\r\n{content}\r\n--{boundary}--\r\n"
+        );
+        (headers, body.into_bytes())
+    }
+
+    #[test]
+    fn test_multipart_boundary_is_left_out() {
+        let (headers, body) = multipart("abc", "receipt");
+        let (retry_headers, retry_body) = multipart("xyz123", "receipt");
+        let (other_headers, other_body) = multipart("abc", "invoice");
+
+        let normalized = without_boundary(&headers, &body);
+        assert_eq!(normalized, without_boundary(&retry_headers, &retry_body));
+        assert_ne!(normalized, without_boundary(&other_headers, &other_body));
+        assert!(!normalized.windows(3).any(|window| window == b"abc"));
+    }
+
+    #[test]
+    fn test_other_bodies_are_kept() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json; boundary=abc"),
+        );
+        let body = br#"{"name":"--abc"}"#;
+
+        assert_eq!(without_boundary(&headers, body), Cow::Borrowed(&body[..]));
+        assert_eq!(without_boundary(&HeaderMap::new(), body).as_ref(), body);
+    }
+}
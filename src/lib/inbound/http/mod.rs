@@ -1,7 +1,10 @@
 mod api_error;
 mod api_success;
+mod auth;
 mod handlers;
+mod idempotency;
 mod responses;
 mod server;
 
+pub use auth::TrustedProxySecret;
 pub use server::{HttpServer, HttpServerConfig};
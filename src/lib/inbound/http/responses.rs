@@ -21,7 +21,20 @@ impl ApiResponseBody<ApiErrorData> {
     pub fn new_error(status_code: StatusCode, message: String) -> Self {
         Self {
             status_code: status_code.as_u16(),
-            data: ApiErrorData { message },
+            data: ApiErrorData {
+                message,
+                denial: None,
+            },
+        }
+    }
+
+    pub fn new_denial(status_code: StatusCode, message: String, denial: DenialData) -> Self {
+        Self {
+            status_code: status_code.as_u16(),
+            data: ApiErrorData {
+                message,
+                denial: Some(denial),
+            },
         }
     }
 }
@@ -30,4 +43,21 @@ impl ApiResponseBody<ApiErrorData> {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ApiErrorData {
     pub message: String,
+    /// Set when the authorization policy refused the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub denial: Option<DenialData>,
+}
+
+/// What the authorization policy refused, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DenialData {
+    pub action: String,
+    pub resource: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_id: Option<String>,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
 }
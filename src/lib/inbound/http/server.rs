@@ -7,18 +7,52 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use axum::Router;
-use axum::routing::{get, post};
+use axum::extract::DefaultBodyLimit;
+use axum::handler::Handler;
+use axum::middleware;
+use axum::routing::{delete, get, post};
 use tokio::net;
 
+use crate::domain::auth::ports::AuthService;
 use crate::domain::finance::ports::FinanceService;
 use crate::inbound::http::handlers::expense::create_expense;
 
-use super::handlers::expense::list_expenses;
+use super::auth::{TrustedProxySecret, authenticate, require_write_scope};
+use super::handlers::account::{create_account, get_account, list_accounts};
+use super::handlers::api_key::{create_api_key, list_api_keys, revoke_api_key};
+use super::handlers::attachment::{MAX_ATTACHMENT_BODY_SIZE, add_attachment, get_attachment};
+use super::handlers::balance::{ledger_balances, settle_up};
+use super::handlers::budget::{
+    budget_status, create_budget, delete_budget, get_budget, list_budgets, update_budget,
+};
+use super::handlers::category::{create_category, list_categories};
+use super::handlers::duplicate::{
+    get_duplicate_settings, list_duplicates, merge_duplicates, update_duplicate_settings,
+};
+use super::handlers::expense::{export_expenses, list_expenses, quick_add_expense};
+use super::handlers::import::{
+    MAX_IMPORT_FILE_SIZE, import_camt053, import_csv, import_mt940, import_ofx, import_qif,
+};
+use super::handlers::ledger::{
+    accept_invitation, create_invitation, create_ledger, get_ledger, list_ledger_members,
+    list_ledgers,
+};
+use super::handlers::recurring::{
+    create_recurring_expense, delete_recurring_expense, list_recurring_expenses,
+    upcoming_occurrences,
+};
+use super::handlers::report::{spending_summary, spending_timeseries};
+use super::handlers::rule::{apply_rules, create_rule, delete_rule, list_rules};
+use super::handlers::settlement::record_settlement;
+use super::handlers::transaction::{create_transaction, list_transactions};
+use super::idempotency::{DEFAULT_IDEMPOTENT_BODY_SIZE, IdempotencyState, idempotency};
 
 /// Configuration for the HTTP server.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HttpServerConfig<'a> {
     pub port: &'a str,
+    /// The secret the upstream gateway sends along with the identity header, if there is one.
+    pub trusted_proxy_secret: Option<TrustedProxySecret>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +61,19 @@ pub(super) struct AppState<FS: FinanceService> {
     pub(super) finance_service: Arc<FS>,
 }
 
+#[derive(Debug, Clone)]
+/// The state shared between the API key management handlers.
+pub(super) struct ApiKeyState<AS: AuthService> {
+    pub(super) auth_service: Arc<AS>,
+}
+
+#[derive(Debug, Clone)]
+/// The state of the [authenticate] middleware.
+pub(super) struct AuthState<AS: AuthService> {
+    pub(super) auth_service: Arc<AS>,
+    pub(super) trusted_proxy_secret: Option<TrustedProxySecret>,
+}
+
 /// The application's HTTP server. The underlying HTTP package is opaque to module consumers.
 pub struct HttpServer {
     router: axum::Router,
@@ -35,8 +82,9 @@ pub struct HttpServer {
 
 impl HttpServer {
     /// Returns a new HTTP server bound to the port specified in `config`.
-    pub async fn new(
+    pub async fn new<AS: AuthService>(
         finance_service: impl FinanceService,
+        auth_service: AS,
         config: HttpServerConfig<'_>,
     ) -> anyhow::Result<Self> {
         let trace_layer = tower_http::trace::TraceLayer::new_for_http().make_span_with(
@@ -50,13 +98,22 @@ impl HttpServer {
         let state = AppState {
             finance_service: Arc::new(finance_service),
         };
+        let auth_service = Arc::new(auth_service);
         tracing::debug!("Initialized AppState");
 
         tracing::info!("Starting server with config: {:?}", config);
         let router = axum::Router::new()
-            .nest("/api", api_routes())
-            .layer(trace_layer)
-            .with_state(state);
+            .nest("/api", api_routes(state.clone()))
+            .with_state(state)
+            .merge(api_key_routes(Arc::clone(&auth_service)))
+            .layer(middleware::from_fn_with_state(
+                AuthState {
+                    auth_service,
+                    trusted_proxy_secret: config.trusted_proxy_secret.clone(),
+                },
+                authenticate::<AS>,
+            ))
+            .layer(trace_layer);
 
         let listener = net::TcpListener::bind(format!("0.0.0.0:{}", config.port))
             .await
@@ -75,8 +132,223 @@ impl HttpServer {
     }
 }
 
-fn api_routes<FS: FinanceService>() -> Router<AppState<FS>> {
+/// Routes under `/api` backed by the [FinanceService].
+///
+/// Routes that change state are layered with [require_write_scope], so read-only API keys can
+/// only reach the `GET` handlers. Routes creating resources, uploads and imports included, are
+/// also layered with [idempotency] up to their body limit, so that clients can retry them
+/// safely.
+fn api_routes<FS: FinanceService>(state: AppState<FS>) -> Router<AppState<FS>> {
+    let write = || middleware::from_fn(require_write_scope);
+    let idempotent_up_to = |max_body_size| {
+        middleware::from_fn_with_state(
+            IdempotencyState::new(&state, max_body_size),
+            idempotency::<FS>,
+        )
+    };
+    let idempotent = || idempotent_up_to(DEFAULT_IDEMPOTENT_BODY_SIZE);
+    Router::new()
+        .route(
+            "/expenses",
+            get(list_expenses::<FS>).post(create_expense::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/expenses/export", get(export_expenses::<FS>))
+        .route(
+            "/expenses/quick",
+            post(quick_add_expense::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/expenses/{id}/attachments",
+            post(
+                add_attachment::<FS>
+                    .layer(idempotent_up_to(MAX_ATTACHMENT_BODY_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BODY_SIZE)),
+            ),
+        )
+        .route(
+            "/expenses/{id}/attachments/{attachment_id}",
+            get(get_attachment::<FS>),
+        )
+        .route(
+            "/transactions",
+            get(list_transactions::<FS>)
+                .post(create_transaction::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/budgets",
+            get(list_budgets::<FS>).post(create_budget::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/budgets/{id}",
+            get(get_budget::<FS>)
+                .put(update_budget::<FS>.layer(write()))
+                .delete(delete_budget::<FS>.layer(write())),
+        )
+        .route("/budgets/{id}/status", get(budget_status::<FS>))
+        .route(
+            "/categories",
+            get(list_categories::<FS>)
+                .post(create_category::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/recurring-expenses",
+            get(list_recurring_expenses::<FS>).post(
+                create_recurring_expense::<FS>
+                    .layer(idempotent())
+                    .layer(write()),
+            ),
+        )
+        .route(
+            "/recurring-expenses/upcoming",
+            get(upcoming_occurrences::<FS>),
+        )
+        .route(
+            "/recurring-expenses/{id}",
+            delete(delete_recurring_expense::<FS>.layer(write())),
+        )
+        .route(
+            "/accounts",
+            get(list_accounts::<FS>).post(create_account::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/accounts/{id}", get(get_account::<FS>))
+        .route(
+            "/rules",
+            get(list_rules::<FS>).post(create_rule::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/rules/apply", post(apply_rules::<FS>.layer(write())))
+        .route("/rules/{id}", delete(delete_rule::<FS>.layer(write())))
+        .route("/duplicates", get(list_duplicates::<FS>))
+        .route(
+            "/duplicates/settings",
+            get(get_duplicate_settings::<FS>).put(update_duplicate_settings::<FS>.layer(write())),
+        )
+        .route(
+            "/duplicates/merge",
+            post(merge_duplicates::<FS>.layer(write())),
+        )
+        .route("/reports/summary", get(spending_summary::<FS>))
+        .route(
+            "/imports/csv",
+            post(
+                import_csv::<FS>
+                    .layer(idempotent_up_to(MAX_IMPORT_FILE_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+            ),
+        )
+        .route(
+            "/imports/ofx",
+            post(
+                import_ofx::<FS>
+                    .layer(idempotent_up_to(MAX_IMPORT_FILE_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+            ),
+        )
+        .route(
+            "/imports/camt053",
+            post(
+                import_camt053::<FS>
+                    .layer(idempotent_up_to(MAX_IMPORT_FILE_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+            ),
+        )
+        .route(
+            "/imports/mt940",
+            post(
+                import_mt940::<FS>
+                    .layer(idempotent_up_to(MAX_IMPORT_FILE_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+            ),
+        )
+        .route(
+            "/imports/qif",
+            post(
+                import_qif::<FS>
+                    .layer(idempotent_up_to(MAX_IMPORT_FILE_SIZE))
+                    .layer(write())
+                    .layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+            ),
+        )
+        .route("/reports/timeseries", get(spending_timeseries::<FS>))
+        .route(
+            "/ledgers",
+            get(list_ledgers::<FS>).post(create_ledger::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/ledgers/{ledger_id}", get(get_ledger::<FS>))
+        .route(
+            "/ledgers/{ledger_id}/members",
+            get(list_ledger_members::<FS>),
+        )
+        .route(
+            "/ledgers/{ledger_id}/invitations",
+            post(create_invitation::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/ledgers/{ledger_id}/balances", get(ledger_balances::<FS>))
+        .route("/ledgers/{ledger_id}/settle-up", get(settle_up::<FS>))
+        .route(
+            "/ledgers/{ledger_id}/settlements",
+            post(record_settlement::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/ledgers/{ledger_id}/expenses",
+            get(list_expenses::<FS>).post(create_expense::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/ledgers/{ledger_id}/expenses/export",
+            get(export_expenses::<FS>),
+        )
+        .route(
+            "/ledgers/{ledger_id}/duplicates",
+            get(list_duplicates::<FS>),
+        )
+        .route(
+            "/ledgers/{ledger_id}/transactions",
+            get(list_transactions::<FS>)
+                .post(create_transaction::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route("/ledgers/{ledger_id}/budgets", get(list_budgets::<FS>))
+        .route(
+            "/ledgers/{ledger_id}/categories",
+            get(list_categories::<FS>)
+                .post(create_category::<FS>.layer(idempotent()).layer(write())),
+        )
+        .route(
+            "/ledgers/{ledger_id}/recurring-expenses",
+            get(list_recurring_expenses::<FS>).post(
+                create_recurring_expense::<FS>
+                    .layer(idempotent())
+                    .layer(write()),
+            ),
+        )
+        .route(
+            "/ledgers/{ledger_id}/recurring-expenses/upcoming",
+            get(upcoming_occurrences::<FS>),
+        )
+        .route(
+            "/ledgers/{ledger_id}/reports/summary",
+            get(spending_summary::<FS>),
+        )
+        .route(
+            "/ledgers/{ledger_id}/reports/timeseries",
+            get(spending_timeseries::<FS>),
+        )
+        .route(
+            "/invitations/{token}/accept",
+            post(accept_invitation::<FS>.layer(write())),
+        )
+}
+
+/// Routes managing API keys, backed by the [AuthService].
+fn api_key_routes<AS: AuthService>(auth_service: Arc<AS>) -> Router {
     Router::new()
-        .route("/expenses", get(list_expenses::<FS>))
-        .route("/expenses", post(create_expense::<FS>))
+        .route(
+            "/api/api-keys",
+            get(list_api_keys::<AS>).post(create_api_key::<AS>),
+        )
+        .route("/api/api-keys/{id}", delete(revoke_api_key::<AS>))
+        .with_state(ApiKeyState { auth_service })
 }
@@ -0,0 +1,281 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::domain::finance::models::expense::ExpenseName;
+use crate::domain::finance::models::import::{ImportRowError, ImportedAmount, ImportedTransaction};
+
+/// A column of a CSV file, either by header name, compared case-insensitively, or by position
+/// starting at 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    Name(String),
+    Index(usize),
+}
+
+/// Where the fields of a transaction are found in a CSV file, and how they are written.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CsvMapping {
+    date: Column,
+    amount: Column,
+    name: Column,
+    category: Option<Column>,
+    date_format: String,
+    decimal_separator: char,
+    thousands_separator: Option<char>,
+    delimiter: u8,
+    has_header: bool,
+    expenses_positive: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum CsvImportError {
+    #[error("missing header line")]
+    MissingHeader,
+    #[error("no column named {name:?}")]
+    UnknownColumn { name: String },
+    #[error("column {name:?} cannot be found by name in a file without a header line")]
+    NamedColumnWithoutHeader { name: String },
+    #[error("invalid delimiter {0:?}, expected a single ASCII character")]
+    InvalidDelimiter(char),
+    #[error(transparent)]
+    Csv(#[from] ::csv::Error),
+}
+
+impl CsvMapping {
+    /// Reads comma-separated files with a header line, ISO dates, `.` as the decimal separator
+    /// and expenses as negative amounts.
+    pub fn new(date: Column, amount: Column, name: Column) -> Self {
+        Self {
+            date,
+            amount,
+            name,
+            category: None,
+            date_format: "%Y-%m-%d".to_string(),
+            decimal_separator: '.',
+            thousands_separator: None,
+            delimiter: b',',
+            has_header: true,
+            expenses_positive: false,
+        }
+    }
+
+    /// Reads the name of the caller's category to file each transaction under from `category`.
+    pub fn with_category(mut self, category: Column) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Sets the [chrono::format::strftime] format of dates, such as `%d/%m/%Y`.
+    pub fn with_date_format(mut self, date_format: &str) -> Self {
+        self.date_format = date_format.to_string();
+        self
+    }
+
+    pub fn with_decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// Ignores `thousands_separator` in amounts, such as the `.` of `1.234,56`.
+    pub fn with_thousands_separator(mut self, thousands_separator: char) -> Self {
+        self.thousands_separator = Some(thousands_separator);
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Result<Self, CsvImportError> {
+        self.delimiter = u8::try_from(delimiter)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or(CsvImportError::InvalidDelimiter(delimiter))?;
+        Ok(self)
+    }
+
+    /// Reads the first line as a transaction rather than a header.
+    pub fn without_header(mut self) -> Self {
+        self.has_header = false;
+        self
+    }
+
+    /// Reads positive amounts as expenses and negative ones as incomes, as in files listing
+    /// card spending.
+    pub fn with_expenses_positive(mut self) -> Self {
+        self.expenses_positive = true;
+        self
+    }
+}
+
+/// The positions of the mapped columns in the records of a file.
+struct Positions {
+    date: usize,
+    amount: usize,
+    name: usize,
+    category: Option<usize>,
+}
+
+/// Parse the transactions of a CSV file laid out as described by `mapping`.
+///
+/// # Errors
+///
+/// Fails as a whole when the header line is missing or lacks a mapped column. Rows that cannot be
+/// read are returned as [ImportRowError]s in their place.
+pub fn parse_transactions(
+    contents: &[u8],
+    mapping: &CsvMapping,
+) -> Result<Vec<Result<ImportedTransaction, ImportRowError>>, CsvImportError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(mapping.delimiter)
+        .has_headers(mapping.has_header)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(contents);
+    let headers = if mapping.has_header {
+        let headers = reader.headers()?.clone();
+        if headers.iter().all(str::is_empty) {
+            return Err(CsvImportError::MissingHeader);
+        }
+        Some(headers)
+    } else {
+        None
+    };
+    let position = |column: &Column| match (column, &headers) {
+        (Column::Index(index), _) => Ok(*index),
+        (Column::Name(name), Some(headers)) => headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| CsvImportError::UnknownColumn { name: name.clone() }),
+        (Column::Name(name), None) => {
+            Err(CsvImportError::NamedColumnWithoutHeader { name: name.clone() })
+        }
+    };
+    let positions = Positions {
+        date: position(&mapping.date)?,
+        amount: position(&mapping.amount)?,
+        name: position(&mapping.name)?,
+        category: mapping.category.as_ref().map(position).transpose()?,
+    };
+
+    let mut rows = Vec::new();
+    let mut record = ::csv::StringRecord::new();
+    loop {
+        let line = reader.position().line() as usize;
+        match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) if record.iter().all(str::is_empty) => continue,
+            Ok(true) => rows.push(parse_record(
+                record.position().map_or(line, |p| first_line(contents, p)),
+                &record,
+                &positions,
+                mapping,
+            )),
+            Err(e) => rows.push(Err(ImportRowError::new(line, e))),
+        }
+    }
+    Ok(rows)
+}
+
+/// The line a record starting at `position` is written on. The reader places records after
+/// the blank lines it skips before them.
+fn first_line(contents: &[u8], position: &::csv::Position) -> usize {
+    let blank_lines = contents[position.byte() as usize..]
+        .iter()
+        .take_while(|b| matches!(b, b'\n' | b'\r'))
+        .filter(|b| **b == b'\n')
+        .count();
+    position.line() as usize + blank_lines
+}
+
+fn parse_record(
+    line: usize,
+    record: &::csv::StringRecord,
+    positions: &Positions,
+    mapping: &CsvMapping,
+) -> Result<ImportedTransaction, ImportRowError> {
+    let field = |index: usize| {
+        record
+            .get(index)
+            .ok_or_else(|| ImportRowError::new(line, format!("missing column {}", index + 1)))
+    };
+    let raw_date = field(positions.date)?;
+    let spent_on = NaiveDate::parse_from_str(raw_date, &mapping.date_format).map_err(|_| {
+        ImportRowError::new(
+            line,
+            format!(
+                "invalid date {:?}, expected format {}",
+                raw_date, mapping.date_format
+            ),
+        )
+    })?;
+    let mut raw_amount = field(positions.amount)?.to_string();
+    if let Some(separator) = mapping.thousands_separator {
+        raw_amount.retain(|c| c != separator);
+    }
+    let mut amount = ImportedAmount::parse(&raw_amount, mapping.decimal_separator)
+        .map_err(|e| ImportRowError::new(line, e))?;
+    if mapping.expenses_positive {
+        amount = amount.negated();
+    }
+    let name =
+        ExpenseName::new(field(positions.name)?).map_err(|e| ImportRowError::new(line, e))?;
+
+    let transaction = ImportedTransaction::new(line, name, amount, spent_on);
+    match positions.category.map(field).transpose()? {
+        Some(category) if !category.is_empty() => Ok(transaction.with_category(category)),
+        _ => Ok(transaction),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> Column {
+        Column::Name(name.to_string())
+    }
+
+    #[test]
+    fn test_parse_transactions_with_header() {
+        let contents = "Date;Payee;Amount;Category\n\
+            18/10/2026;\"Bakery; rue Neuve\";-1.234,50;food\n\
+            \n\
+            19/10/2026;Salary;2.000,00;\n\
+            20/10/2026;;-3,00;\n\
+            2026-10-21;Bus;-2,10;\n";
+        let mapping = CsvMapping::new(column("date"), column("AMOUNT"), column("Payee"))
+            .with_category(column("Category"))
+            .with_date_format("%d/%m/%Y")
+            .with_decimal_separator(',')
+            .with_thousands_separator('.')
+            .with_delimiter(';')
+            .unwrap();
+
+        let rows = parse_transactions(contents.as_bytes(), &mapping).unwrap();
+
+        assert_eq!(rows.len(), 4);
+        let bakery = rows[0].as_ref().unwrap();
+        assert_eq!(bakery.line(), 2);
+        assert_eq!(bakery.name().to_string(), "Bakery; rue Neuve");
+        assert_eq!(bakery.amount().to_string(), "-1234.50");
+        assert_eq!(bakery.category(), Some("food"));
+        let salary = rows[1].as_ref().unwrap();
+        assert_eq!(salary.line(), 4);
+        assert!(!salary.amount().is_negative());
+        assert_eq!(salary.category(), None);
+        assert_eq!(rows[2].as_ref().unwrap_err().line(), 5);
+        assert_eq!(rows[3].as_ref().unwrap_err().line(), 6);
+    }
+
+    #[test]
+    fn test_parse_transactions_by_index() {
+        let mapping = CsvMapping::new(Column::Index(0), Column::Index(2), Column::Index(1))
+            .without_header()
+            .with_expenses_positive();
+
+        let rows = parse_transactions(b"2026-10-18,Coffee,2.50\n", &mapping).unwrap();
+
+        assert!(rows[0].as_ref().unwrap().amount().is_negative());
+        assert!(matches!(
+            parse_transactions(b"", &mapping.with_category(column("Category"))),
+            Err(CsvImportError::NamedColumnWithoutHeader { .. })
+        ));
+    }
+}
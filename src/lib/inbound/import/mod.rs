@@ -0,0 +1,55 @@
+/*!
+    Module `import` reads the transactions of files exported by banks and other finance tools
+    into the rows of an [ImportRequest](crate::domain::finance::models::import::ImportRequest).
+
+    Parsers only fail as a whole when a file cannot be read at all: a row that cannot be read is
+    reported as an [ImportRowError](crate::domain::finance::models::import::ImportRowError)
+    alongside the rows the domain rejects.
+*/
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::finance::models::import::ImportedTransaction;
+
+pub mod camt;
+pub mod csv;
+pub mod mt940;
+pub mod ofx;
+pub mod qif;
+
+/// Derives import identifiers for the statement entries a bank gave no reference to, from what
+/// the statement says of them. Identical entries are told apart by their rank in the statement,
+/// so that two coffees bought on the same day are both imported, and both skipped when an
+/// overlapping statement is imported.
+#[derive(Debug, Default)]
+pub struct FallbackImportIds {
+    ranks: HashMap<String, usize>,
+}
+
+impl FallbackImportIds {
+    /// The identifier of the next entry like `transaction`.
+    pub fn next(&mut self, transaction: &ImportedTransaction) -> String {
+        let key = format!(
+            "{}|{}|{}|{}",
+            transaction.spent_on(),
+            transaction.amount(),
+            transaction.counterparty().unwrap_or_default(),
+            transaction.reference().unwrap_or_default()
+        );
+        let rank = self.ranks.entry(key.clone()).or_default();
+        *rank += 1;
+        format!("{:x}", Sha256::digest(format!("{key}|{rank}")))
+    }
+}
+
+/// Reads a text file as UTF-8 or, failing that, as ISO 8859-1, in which older tools and many
+/// banks still write.
+pub fn decode_text(contents: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(contents) {
+        Ok(contents) => Cow::Borrowed(contents),
+        Err(_) => Cow::Owned(contents.iter().map(|&byte| char::from(byte)).collect()),
+    }
+}
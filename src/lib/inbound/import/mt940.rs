@@ -0,0 +1,349 @@
+/*!
+    Module `mt940` reads the statement lines of SWIFT MT940 customer statements, as provided by
+    European banks.
+
+    A statement is a list of fields, each starting on a line with its tag, such as `:61:`, and
+    going on over the lines that follow. Each `:61:` statement line may be followed by a `:86:`
+    field describing it, which banks write either in the structured form of the German banking
+    industry, with `?NN` subfields, or in the SWIFT form, with `/CODE/` words, or as plain text.
+*/
+
+use chrono::{Datelike, NaiveDate};
+use thiserror::Error;
+
+use crate::domain::finance::models::expense::ExpenseName;
+use crate::domain::finance::models::import::{ImportRowError, ImportedAmount, ImportedTransaction};
+
+use super::{FallbackImportIds, decode_text};
+
+/// The value of references a bank did not give.
+const NO_REFERENCE: &str = "NONREF";
+
+#[derive(Debug, Error)]
+pub enum Mt940Error {
+    #[error("not an MT940 statement, no :20: field found")]
+    NotMt940,
+}
+
+/// A field of a statement, with the line its tag is on.
+struct Field<'a> {
+    line: usize,
+    tag: &'a str,
+    value: String,
+}
+
+/// Parse the `:61:` statement lines of an MT940 file, in file order, along with the `:86:`
+/// field following each. Each is identified for deduplication by the reference its bank gave
+/// it, or else by its date, amount, counterparty and reference.
+///
+/// Files are read as UTF-8 or, failing that, as ISO 8859-1, which most banks still use.
+///
+/// # Errors
+///
+/// Fails as a whole when the file has no `:20:` field. Statement lines that cannot be read are
+/// returned as [ImportRowError]s in their place.
+pub fn parse_transactions(
+    contents: &[u8],
+) -> Result<Vec<Result<ImportedTransaction, ImportRowError>>, Mt940Error> {
+    let contents = decode_text(contents);
+    let fields = fields(&contents);
+    if !fields.iter().any(|field| field.tag == "20") {
+        return Err(Mt940Error::NotMt940);
+    }
+
+    let mut fallback_ids = FallbackImportIds::default();
+    let mut rows = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if field.tag != "61" {
+            continue;
+        }
+        let information = fields
+            .get(index + 1)
+            .filter(|next| next.tag == "86")
+            .map(|next| next.value.as_str());
+        rows.push(
+            parse_statement_line(field.line, &field.value, information).map(
+                |(transaction, bank_reference)| match bank_reference {
+                    Some(id) => transaction.with_import_id(&id),
+                    None => {
+                        let id = fallback_ids.next(&transaction);
+                        transaction.with_import_id(&id)
+                    }
+                },
+            ),
+        );
+    }
+    Ok(rows)
+}
+
+/// The fields of a statement, leaving out the SWIFT message blocks wrapping it, if any.
+fn fields(contents: &str) -> Vec<Field<'_>> {
+    let mut fields: Vec<Field> = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_end();
+        // Block headers such as `{1:F01BANKDEFFXXXX}{2:O940}{4:` open the message, and `-}`
+        // or `-` close it.
+        if line.starts_with('{') || line == "-}" || line == "-" {
+            continue;
+        }
+        match field_tag(line) {
+            Some(tag) => fields.push(Field {
+                line: index + 1,
+                tag,
+                value: line[tag.len() + 2..].to_string(),
+            }),
+            None => {
+                if let Some(field) = fields.last_mut() {
+                    field.value.push('\n');
+                    field.value.push_str(line);
+                }
+            }
+        }
+    }
+    fields
+}
+
+/// The tag of a line starting a field, such as `61` or `28C`.
+fn field_tag(line: &str) -> Option<&str> {
+    let tag = line.strip_prefix(':')?.split(':').next()?;
+    let (number, option) = tag.split_at_checked(2)?;
+    let valid = number.chars().all(|c| c.is_ascii_digit())
+        && option.len() <= 1
+        && option.chars().all(|c| c.is_ascii_uppercase())
+        && line.len() > tag.len() + 1;
+    valid.then_some(tag)
+}
+
+/// Reads a statement line, returning the transaction and the reference the bank gave it.
+///
+/// Its first line is written `YYMMDD[MMDD](C|D|RC|RD)[funds code]amount(type)(customer
+/// reference)[//bank reference]`, the optional `MMDD` being the booking date.
+fn parse_statement_line(
+    line: usize,
+    value: &str,
+    information: Option<&str>,
+) -> Result<(ImportedTransaction, Option<String>), ImportRowError> {
+    let invalid = |what: &str| ImportRowError::new(line, format!("invalid :61: {what}"));
+    let first_line = value.lines().next().unwrap_or_default().trim();
+
+    let value_date = first_line
+        .get(..6)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%y%m%d").ok())
+        .ok_or_else(|| invalid("value date"))?;
+    let mut rest = &first_line[6..];
+    let booking_date = match rest
+        .get(..4)
+        .filter(|s| s.chars().all(|c| c.is_ascii_digit()))
+    {
+        Some(entry_date) => {
+            rest = &rest[4..];
+            booking_date(value_date, entry_date).ok_or_else(|| invalid("entry date"))?
+        }
+        None => value_date,
+    };
+    // A reversed credit takes money out of the account, a reversed debit gives it back.
+    let (debit, after_mark) = if let Some(after) = rest.strip_prefix("RC") {
+        (true, after)
+    } else if let Some(after) = rest.strip_prefix("RD") {
+        (false, after)
+    } else if let Some(after) = rest.strip_prefix('C') {
+        (false, after)
+    } else if let Some(after) = rest.strip_prefix('D') {
+        (true, after)
+    } else {
+        return Err(invalid("debit/credit mark"));
+    };
+    // The funds code is the last letter of the currency code, which some banks include.
+    rest = after_mark
+        .strip_prefix(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(after_mark);
+    let amount_end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let amount = ImportedAmount::parse(&rest[..amount_end], ',')
+        .map_err(|e| ImportRowError::new(line, e))?;
+    let amount = if debit { amount.negated() } else { amount };
+    let references = rest[amount_end..].get(4..).unwrap_or_default();
+    let (customer_reference, bank_reference) =
+        references.split_once("//").unwrap_or((references, ""));
+    let bank_reference = Some(bank_reference.trim())
+        .filter(|reference| !reference.is_empty() && *reference != NO_REFERENCE)
+        .map(str::to_string);
+
+    let (counterparty, reference) = information.map(describe).unwrap_or_default();
+    let customer_reference = Some(customer_reference.trim())
+        .filter(|reference| !reference.is_empty() && *reference != NO_REFERENCE);
+    let name = counterparty
+        .as_deref()
+        .or(reference.as_deref())
+        .or(customer_reference)
+        .unwrap_or_default();
+    let name = ExpenseName::new(name).map_err(|e| ImportRowError::new(line, e))?;
+
+    let mut transaction = ImportedTransaction::new(line, name, amount, booking_date);
+    if let Some(counterparty) = counterparty {
+        transaction = transaction.with_counterparty(&counterparty);
+    }
+    if let Some(reference) = reference {
+        transaction = transaction.with_reference(&reference);
+    }
+    Ok((transaction, bank_reference))
+}
+
+/// The booking date written `MMDD` of a transaction valued on `value_date`, in the year making
+/// them closest, as a transaction valued in January may be booked in December.
+fn booking_date(value_date: NaiveDate, entry_date: &str) -> Option<NaiveDate> {
+    let month = entry_date[..2].parse().ok()?;
+    let day = entry_date[2..].parse().ok()?;
+    let year = match (value_date.month(), month) {
+        (1, 12) => value_date.year() - 1,
+        (12, 1) => value_date.year() + 1,
+        _ => value_date.year(),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// The counterparty and the reference described by a `:86:` field.
+fn describe(information: &str) -> (Option<String>, Option<String>) {
+    let structured = information
+        .get(..3)
+        .is_some_and(|code| code.chars().all(|c| c.is_ascii_digit()))
+        && information
+            .get(3..)
+            .is_some_and(|rest| rest.starts_with('?'));
+    if structured {
+        // Subfields wrap over lines anywhere, so line breaks are not part of their values.
+        let information = information.replace(['\r', '\n'], "");
+        let subfields = subfields(&information);
+        let joined = |codes: &[&str]| {
+            let value = subfields
+                .iter()
+                .filter(|(code, _)| codes.contains(code))
+                .map(|(_, value)| *value)
+                .collect::<String>();
+            non_blank(&value)
+        };
+        let remittance = joined(&["20", "21", "22", "23", "24", "25", "26", "27", "28", "29"]);
+        // SEPA remittance information is tagged, the text sent by the payer being `SVWZ+`.
+        let reference = remittance.map(|remittance| match remittance.split_once("SVWZ+") {
+            Some((_, text)) => text.trim().to_string(),
+            None => remittance,
+        });
+        (joined(&["32", "33"]), reference)
+    } else if information.starts_with('/') {
+        let information = information.replace(['\r', '\n'], "");
+        (
+            code_word(&information, "NAME"),
+            code_word(&information, "REMI"),
+        )
+    } else {
+        (None, non_blank(&information.replace(['\r', '\n'], " ")))
+    }
+}
+
+/// The `?NN` subfields of a structured `:86:` field, by code.
+fn subfields(information: &str) -> Vec<(&str, &str)> {
+    information
+        .split('?')
+        .skip(1)
+        .filter_map(|subfield| subfield.split_at_checked(2))
+        .collect()
+}
+
+/// The value following `/CODE/` in a SWIFT `:86:` field, up to the next code word.
+fn code_word(information: &str, code: &str) -> Option<String> {
+    let start = information.find(&format!("/{code}/"))? + code.len() + 2;
+    let value = &information[start..];
+    // Remittance information may itself be tagged as unstructured.
+    let value = value.strip_prefix("USTD//").unwrap_or(value);
+    let end = value
+        .match_indices('/')
+        .map(|(index, _)| index)
+        .find(|&index| is_code_word(&value[index + 1..]))
+        .unwrap_or(value.len());
+    non_blank(&value[..end])
+}
+
+/// Whether `rest` starts with an uppercase code word followed by a slash, such as `EREF/`.
+fn is_code_word(rest: &str) -> bool {
+    rest.find('/').is_some_and(|end| {
+        (2..=4).contains(&end) && rest[..end].chars().all(|c| c.is_ascii_uppercase())
+    })
+}
+
+fn non_blank(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_structured_statement() {
+        let contents = "{1:F01BANKDEFFXXXX0000000000}{2:O940}{4:\n\
+            :20:STARTUMS\n\
+            :25:10020030/1234567890\n\
+            :28C:00001/001\n\
+            :60F:C261017EUR1000,00\n\
+            :61:2610181018DR12,50NTRFNONREF//B1018-1\n\
+            :86:166?00SEPA-UEBERWEISUNG?20EREF+E2E-1?21SVWZ+Invoice 42?32Bak\n\
+            ery GmbH\n\
+            :61:2701021231C2000,NTRFNONREF\n\
+            :86:/NAME/ACME/REMI/USTD//Salary 12/2026/EREF/NOTPROVIDED\n\
+            :61:261020D3,20NMSCNONREF\n\
+            :62F:C261020EUR2984,30\n\
+            -}";
+
+        let rows = parse_transactions(contents.as_bytes()).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        let bakery = rows[0].as_ref().unwrap();
+        assert_eq!(bakery.line(), 6);
+        assert_eq!(bakery.name().to_string(), "Bakery GmbH");
+        assert_eq!(bakery.amount().to_string(), "-12.50");
+        assert_eq!(
+            *bakery.spent_on(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        );
+        assert_eq!(bakery.reference(), Some("Invoice 42"));
+        assert_eq!(bakery.import_id(), Some("B1018-1"));
+        let salary = rows[1].as_ref().unwrap();
+        assert_eq!(salary.amount().to_string(), "2000");
+        assert_eq!(
+            *salary.spent_on(),
+            NaiveDate::from_ymd_opt(2026, 12, 31).unwrap()
+        );
+        assert_eq!(salary.counterparty(), Some("ACME"));
+        assert_eq!(salary.reference(), Some("Salary 12/2026"));
+        assert_eq!(salary.import_id().map(str::len), Some(64));
+        let unnamed = rows[2].as_ref().unwrap_err();
+        assert_eq!(unnamed.line(), 11);
+    }
+
+    #[test]
+    fn test_reject_other_files() {
+        assert!(matches!(
+            parse_transactions(b"Date,Amount\n"),
+            Err(Mt940Error::NotMt940)
+        ));
+        let latin1 = b":20:1\n:61:261018C1,00NTRFNONREF\n:86:Caf\xe9\n";
+        let rows = parse_transactions(latin1).unwrap();
+        assert_eq!(rows[0].as_ref().unwrap().name().to_string(), "Caf\u{e9}");
+    }
+
+    #[test]
+    fn test_parse_non_ascii_information() {
+        let latin1 = b":20:1\n:61:261018D1,00NTRFNONREF\n:86:Fr\xfchst\xfcck GmbH\n";
+        let rows = parse_transactions(latin1).unwrap();
+        assert_eq!(
+            rows[0].as_ref().unwrap().name().to_string(),
+            "Fr\u{fc}hst\u{fc}ck GmbH"
+        );
+
+        let utf8 = ":20:1\n:61:261018D1,00NTRFNONREF\n:86:Zo\u{eb}\n";
+        let rows = parse_transactions(utf8.as_bytes()).unwrap();
+        assert_eq!(rows[0].as_ref().unwrap().name().to_string(), "Zo\u{eb}");
+    }
+}
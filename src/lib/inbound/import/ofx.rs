@@ -0,0 +1,175 @@
+/*!
+    Module `ofx` reads the statement transactions of OFX files, as exported by most banks,
+    including Quicken's QFX variant.
+
+    OFX 1.x is SGML, whose elements carrying a value have no end tag, while OFX 2.x is XML. Both
+    are read by the same scanner, which takes the value of an element to be the text up to the
+    next tag.
+*/
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::domain::finance::models::expense::ExpenseName;
+use crate::domain::finance::models::import::{ImportRowError, ImportedAmount, ImportedTransaction};
+
+const TRANSACTION_START: &str = "<STMTTRN>";
+const TRANSACTION_END: &str = "</STMTTRN>";
+
+#[derive(Debug, Error)]
+pub enum OfxError {
+    #[error("not an OFX file, no OFX element found")]
+    NotOfx,
+}
+
+/// Parse the `STMTTRN` records of an OFX file, in file order. Each is identified for
+/// deduplication by its `FITID`.
+///
+/// # Errors
+///
+/// Fails as a whole when the file has no `OFX` element. Records that cannot be read are returned
+/// as [ImportRowError]s in their place.
+pub fn parse_transactions(
+    contents: &[u8],
+) -> Result<Vec<Result<ImportedTransaction, ImportRowError>>, OfxError> {
+    let contents = String::from_utf8_lossy(contents);
+    // Tags are matched case-insensitively on an uppercase copy, whose offsets are the same.
+    let upper = contents.to_ascii_uppercase();
+    if !upper.contains("<OFX>") {
+        return Err(OfxError::NotOfx);
+    }
+
+    let mut rows = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = upper[offset..].find(TRANSACTION_START).map(|i| offset + i) {
+        let body_start = start + TRANSACTION_START.len();
+        let end = upper[body_start..]
+            .find(TRANSACTION_END)
+            .map_or(upper.len(), |i| body_start + i);
+        let line = contents[..start].matches('\n').count() + 1;
+        rows.push(parse_record(line, &contents[body_start..end]));
+        offset = end;
+    }
+    Ok(rows)
+}
+
+/// The value of the first element named `tag` in `record`, unescaped, if it is not empty.
+fn element(record: &str, tag: &str) -> Option<String> {
+    let mut rest = record;
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+        let close = after.find('>')?;
+        let name = &after[..close];
+        let text = &after[close + 1..];
+        let value = &text[..text.find('<').unwrap_or(text.len())];
+        if name.eq_ignore_ascii_case(tag) && !value.trim().is_empty() {
+            return Some(unescape(value.trim()));
+        }
+        rest = text;
+    }
+    None
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn parse_record(line: usize, record: &str) -> Result<ImportedTransaction, ImportRowError> {
+    let required = |tag: &str| {
+        element(record, tag).ok_or_else(|| ImportRowError::new(line, format!("missing {tag}")))
+    };
+    // Dates are written `YYYYMMDD`, optionally followed by a time and a time zone.
+    let posted = required("DTPOSTED")?;
+    let spent_on = posted
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| ImportRowError::new(line, format!("invalid DTPOSTED {posted:?}")))?;
+    // The decimal separator is a period, though some banks write a comma.
+    let raw_amount = required("TRNAMT")?;
+    let decimal_separator = if raw_amount.contains(',') && !raw_amount.contains('.') {
+        ','
+    } else {
+        '.'
+    };
+    let amount = ImportedAmount::parse(&raw_amount, decimal_separator)
+        .map_err(|e| ImportRowError::new(line, e))?;
+    let counterparty = element(record, "NAME");
+    let memo = element(record, "MEMO");
+    let name = counterparty
+        .as_ref()
+        .or(memo.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    let name = ExpenseName::new(&name).map_err(|e| ImportRowError::new(line, e))?;
+
+    let mut transaction = ImportedTransaction::new(line, name, amount, spent_on);
+    if let Some(counterparty) = counterparty {
+        transaction = transaction.with_counterparty(&counterparty);
+    }
+    if let Some(memo) = memo {
+        transaction = transaction.with_reference(&memo);
+    }
+    Ok(match element(record, "FITID") {
+        Some(fitid) => transaction.with_import_id(&fitid),
+        None => transaction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sgml_statement() {
+        let contents = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n\n<OFX>\n<BANKMSGSRSV1>\n\
+            <STMTTRNRS><STMTRS><CURDEF>EUR\n<BANKTRANLIST>\n\
+            <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20261018120000.000[-5:EST]\n\
+            <TRNAMT>-12.50\n<FITID>2026101801\n<NAME>Bakery &amp; Co\n</STMTTRN>\n\
+            <STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>2026-10-19\n<TRNAMT>100\n\
+            <FITID>2026101901\n<MEMO>Salary\n</STMTTRN>\n\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>\n";
+
+        let rows = parse_transactions(contents.as_bytes()).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        let bakery = rows[0].as_ref().unwrap();
+        assert_eq!(bakery.line(), 9);
+        assert_eq!(bakery.name().to_string(), "Bakery & Co");
+        assert_eq!(bakery.amount().to_string(), "-12.50");
+        assert_eq!(
+            *bakery.spent_on(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        );
+        assert_eq!(bakery.import_id(), Some("2026101801"));
+        let salary = rows[1].as_ref().unwrap_err();
+        assert_eq!(salary.line(), 16);
+        assert!(salary.message().contains("DTPOSTED"));
+    }
+
+    #[test]
+    fn test_parse_xml_statement() {
+        let contents = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN><TRNTYPE>POS</TRNTYPE><DTPOSTED>20261018</DTPOSTED><TRNAMT>-3,20</TRNAMT>
+<FITID>A1</FITID><PAYEE><NAME>Coffee</NAME></PAYEE></STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>"#;
+
+        let rows = parse_transactions(contents.as_bytes()).unwrap();
+
+        let coffee = rows[0].as_ref().unwrap();
+        assert_eq!(coffee.name().to_string(), "Coffee");
+        assert_eq!(coffee.amount().to_string(), "-3.20");
+        assert_eq!(coffee.import_id(), Some("A1"));
+        assert!(matches!(
+            parse_transactions(b"Date,Amount\n"),
+            Err(OfxError::NotOfx)
+        ));
+    }
+}
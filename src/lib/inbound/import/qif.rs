@@ -0,0 +1,260 @@
+/*!
+    Module `qif` reads the transactions of QIF files, as exported by older personal finance
+    applications such as Quicken or Microsoft Money.
+
+    A file is a list of sections, each opened by a `!Type:` header line, whose records are lists
+    of lines starting with a field code, such as `D` for the date, and end with a `^` line. Only
+    the records of bank, cash, credit card and other asset or liability accounts are read, those
+    of all such sections being imported into the same account.
+*/
+
+use chrono::{Datelike, NaiveDate};
+use thiserror::Error;
+
+use crate::domain::finance::models::expense::ExpenseName;
+use crate::domain::finance::models::import::{ImportRowError, ImportedAmount, ImportedTransaction};
+
+use super::{FallbackImportIds, decode_text};
+
+/// The `!Type:` sections holding the transactions of an account.
+const ACCOUNT_SECTIONS: [&str; 5] = ["bank", "cash", "ccard", "oth a", "oth l"];
+
+/// How the dates and amounts of a QIF file are written, which depends on the application and
+/// the locale that wrote it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifFormat {
+    date_format: String,
+    decimal_separator: char,
+}
+
+impl QifFormat {
+    /// Reads US dates such as `10/18/2026` or `10/18'26`, and `.` as the decimal separator.
+    pub fn new() -> Self {
+        Self {
+            date_format: "%m/%d/%Y".to_string(),
+            decimal_separator: '.',
+        }
+    }
+
+    /// Reads dates in the strftime `date_format`, such as `%d.%m.%Y`. Years written with two
+    /// digits are read as such even when the format asks for four.
+    pub fn with_date_format(mut self, date_format: &str) -> Self {
+        self.date_format = date_format.to_string();
+        self
+    }
+
+    /// Reads `decimal_separator` as the decimal point, the other of `.` and `,` being taken for
+    /// a thousands separator.
+    pub fn with_decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    pub fn date_format(&self) -> &str {
+        &self.date_format
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    fn parse_date(&self, raw: &str) -> Option<NaiveDate> {
+        // Quicken writes the years from 2000 after an apostrophe, and pads days with spaces.
+        let normalized = raw.trim().replace('\'', "/").replace(' ', "");
+        let date = NaiveDate::parse_from_str(&normalized, &self.date_format).ok();
+        match date {
+            Some(date) if date.year() < 100 && self.date_format.contains("%Y") => {
+                NaiveDate::parse_from_str(&normalized, &self.date_format.replace("%Y", "%y")).ok()
+            }
+            date => date,
+        }
+    }
+
+    fn parse_amount(&self, raw: &str) -> Result<ImportedAmount, String> {
+        let thousands_separator = if self.decimal_separator == ',' {
+            '.'
+        } else {
+            ','
+        };
+        ImportedAmount::parse(
+            &raw.replace(thousands_separator, ""),
+            self.decimal_separator,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+impl Default for QifFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum QifError {
+    #[error("not a QIF file, no !Type header found")]
+    NotQif,
+}
+
+/// Parse the records of the account sections of a QIF file, in file order. QIF having no
+/// transaction identifiers, each is identified for deduplication by its date, amount, payee and
+/// memo.
+///
+/// # Errors
+///
+/// Fails as a whole when the file has no `!Type:` header. Records that cannot be read are
+/// returned as [ImportRowError]s in their place.
+pub fn parse_transactions(
+    contents: &[u8],
+    format: &QifFormat,
+) -> Result<Vec<Result<ImportedTransaction, ImportRowError>>, QifError> {
+    let contents = decode_text(contents);
+    let mut has_type = false;
+    let mut in_account_section = false;
+    let mut record: Vec<(usize, &str)> = Vec::new();
+    let mut fallback_ids = FallbackImportIds::default();
+    let mut rows = Vec::new();
+
+    let mut finish_record = |record: &mut Vec<(usize, &str)>, in_account_section: bool| {
+        if in_account_section && !record.is_empty() {
+            rows.push(parse_record(record, format).map(|transaction| {
+                let id = fallback_ids.next(&transaction);
+                transaction.with_import_id(&id)
+            }));
+        }
+        record.clear();
+    };
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('!') {
+            finish_record(&mut record, in_account_section);
+            // Other headers, such as `!Account` or `!Option:AutoSwitch`, open sections of
+            // account or category lists, or set options.
+            in_account_section = match header.split_once(':') {
+                Some((key, section)) if key.eq_ignore_ascii_case("type") => {
+                    has_type = true;
+                    ACCOUNT_SECTIONS.contains(&section.trim().to_ascii_lowercase().as_str())
+                }
+                _ => false,
+            };
+        } else if line.starts_with('^') {
+            finish_record(&mut record, in_account_section);
+        } else if !line.trim().is_empty() {
+            record.push((index + 1, line));
+        }
+    }
+    finish_record(&mut record, in_account_section);
+
+    if has_type {
+        Ok(rows)
+    } else {
+        Err(QifError::NotQif)
+    }
+}
+
+/// Reads a record from its lines, ignoring the split lines `S`, `E` and `$` as the record holds
+/// the total.
+fn parse_record(
+    record: &[(usize, &str)],
+    format: &QifFormat,
+) -> Result<ImportedTransaction, ImportRowError> {
+    let line = record[0].0;
+    let field = |code: char| {
+        record
+            .iter()
+            .find_map(|(_, text)| text.strip_prefix(code))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let raw_date = field('D').ok_or_else(|| ImportRowError::new(line, "missing date"))?;
+    let spent_on = format.parse_date(raw_date).ok_or_else(|| {
+        ImportRowError::new(
+            line,
+            format!(
+                "invalid date {raw_date:?}, expected format {}",
+                format.date_format
+            ),
+        )
+    })?;
+    let raw_amount = field('T')
+        .or_else(|| field('U'))
+        .ok_or_else(|| ImportRowError::new(line, "missing amount"))?;
+    let amount = format
+        .parse_amount(raw_amount)
+        .map_err(|e| ImportRowError::new(line, e))?;
+    let payee = field('P');
+    let memo = field('M');
+    let name = ExpenseName::new(payee.or(memo).unwrap_or_default())
+        .map_err(|e| ImportRowError::new(line, e))?;
+
+    let mut transaction = ImportedTransaction::new(line, name, amount, spent_on);
+    if let Some(payee) = payee {
+        transaction = transaction.with_counterparty(payee);
+    }
+    if let Some(memo) = memo {
+        transaction = transaction.with_reference(memo);
+    }
+    // Categories are followed by a class after a slash, and transfers name an account between
+    // brackets instead.
+    let category = field('L')
+        .filter(|category| !category.starts_with('['))
+        .map(|category| category.split('/').next().unwrap_or_default());
+    Ok(match category {
+        Some(category) if !category.trim().is_empty() => transaction.with_category(category),
+        _ => transaction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_account_sections() {
+        let contents = "!Type:Cat\nNFood\nE\n^\n\
+            !Type:Bank\n\
+            D10/18'26\nT-1,234.50\nPBakery\nMInvoice 42\nLFood/Business\n^\n\
+            D10/19/2026\nT100.00\nPEmployer\nL[Savings]\n^\n\
+            D10/32/2026\nT-1.00\nPNowhere\n^\n";
+
+        let rows = parse_transactions(contents.as_bytes(), &QifFormat::new()).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        let bakery = rows[0].as_ref().unwrap();
+        assert_eq!(bakery.line(), 6);
+        assert_eq!(bakery.name().to_string(), "Bakery");
+        assert_eq!(bakery.amount().to_string(), "-1234.50");
+        assert_eq!(
+            *bakery.spent_on(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        );
+        assert_eq!(bakery.category(), Some("Food"));
+        assert_eq!(bakery.reference(), Some("Invoice 42"));
+        let salary = rows[1].as_ref().unwrap();
+        assert_eq!(salary.category(), None);
+        assert_ne!(salary.import_id(), bakery.import_id());
+        assert!(rows[2].as_ref().unwrap_err().message().contains("date"));
+    }
+
+    #[test]
+    fn test_parse_european_format() {
+        let contents = "!Type:CCard\nD18.10.26\nT-1.234,50\nPBakery\n^\n";
+        let format = QifFormat::new()
+            .with_date_format("%d.%m.%Y")
+            .with_decimal_separator(',');
+
+        let rows = parse_transactions(contents.as_bytes(), &format).unwrap();
+
+        let bakery = rows[0].as_ref().unwrap();
+        assert_eq!(bakery.amount().to_string(), "-1234.50");
+        assert_eq!(
+            *bakery.spent_on(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        );
+        assert!(matches!(
+            parse_transactions(b"Date,Amount\n", &format),
+            Err(QifError::NotQif)
+        ));
+    }
+}
@@ -1 +1,5 @@
+pub mod ecb;
+pub mod export;
 pub mod http;
+pub mod import;
+pub mod scheduler;
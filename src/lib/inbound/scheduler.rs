@@ -0,0 +1,167 @@
+/*!
+    Module `scheduler` drives the finance domain from background tasks rather than from HTTP
+    requests, turning recurring expenses into expenses as their occurrences fall due,
+    generating the thumbnails of new attachments, and forgetting expired idempotency keys.
+*/
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::domain::finance::ports::FinanceService;
+
+/// How many thumbnails a [ThumbnailScheduler] generates per batch.
+const THUMBNAIL_BATCH_SIZE: usize = 20;
+
+/// Configuration for the [RecurringExpenseScheduler], the [ThumbnailScheduler] and the
+/// [IdempotencyKeyScheduler].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedulerConfig {
+    /// How long to wait between two runs.
+    pub interval: Duration,
+}
+
+/// A job run in the background at a fixed interval by [spawn_every].
+trait PeriodicTask: Send + Sync + 'static {
+    fn run(&self) -> impl Future<Output = ()> + Send;
+}
+
+/// Run `task` in the background, right away and then every `interval`.
+///
+/// A run that outlasts `interval` delays the next one, rather than the missed ones being caught
+/// up with in a burst.
+fn spawn_every(interval: Duration, task: impl PeriodicTask) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            task.run().await;
+        }
+    })
+}
+
+/// Periodically materializes the due occurrences of every recurring expense.
+///
+/// Runs are idempotent, so several instances of the application may each run a scheduler.
+pub struct RecurringExpenseScheduler<FS: FinanceService> {
+    finance_service: FS,
+    config: SchedulerConfig,
+}
+
+impl<FS: FinanceService> RecurringExpenseScheduler<FS> {
+    pub fn new(finance_service: FS, config: SchedulerConfig) -> Self {
+        Self {
+            finance_service,
+            config,
+        }
+    }
+
+    /// Start running in the background, right away and then every configured interval.
+    pub fn spawn(self) -> JoinHandle<()> {
+        spawn_every(self.config.interval, self)
+    }
+}
+
+impl<FS: FinanceService> PeriodicTask for RecurringExpenseScheduler<FS> {
+    async fn run(&self) {
+        let today = Utc::now().date_naive();
+        match self
+            .finance_service
+            .materialize_recurring_expenses(today)
+            .await
+        {
+            Ok(0) => tracing::debug!("No recurring expense due"),
+            Ok(created) => tracing::info!("Created {} recurring expense occurrences", created),
+            Err(e) => tracing::error!("Failed to materialize recurring expenses: {:?}", e),
+        }
+    }
+}
+
+/// Periodically generates the thumbnails of the attachments uploaded since the last run.
+///
+/// Batches follow each other without waiting while attachments are pending, so that a burst of
+/// uploads is caught up with quickly.
+pub struct ThumbnailScheduler<FS: FinanceService> {
+    finance_service: FS,
+    config: SchedulerConfig,
+}
+
+impl<FS: FinanceService> ThumbnailScheduler<FS> {
+    pub fn new(finance_service: FS, config: SchedulerConfig) -> Self {
+        Self {
+            finance_service,
+            config,
+        }
+    }
+
+    /// Start running in the background, right away and then every configured interval.
+    pub fn spawn(self) -> JoinHandle<()> {
+        spawn_every(self.config.interval, self)
+    }
+
+    /// Generate one batch of thumbnails, returning how many were processed.
+    async fn run_batch(&self) -> usize {
+        match self
+            .finance_service
+            .generate_thumbnails(THUMBNAIL_BATCH_SIZE)
+            .await
+        {
+            Ok(0) => {
+                tracing::debug!("No thumbnail pending");
+                0
+            }
+            Ok(processed) => {
+                tracing::info!("Processed {} pending thumbnails", processed);
+                processed
+            }
+            Err(e) => {
+                tracing::error!("Failed to generate thumbnails: {:?}", e);
+                0
+            }
+        }
+    }
+}
+
+impl<FS: FinanceService> PeriodicTask for ThumbnailScheduler<FS> {
+    async fn run(&self) {
+        while self.run_batch().await == THUMBNAIL_BATCH_SIZE {}
+    }
+}
+
+/// Periodically deletes the idempotency keys whose responses are no longer replayed.
+pub struct IdempotencyKeyScheduler<FS: FinanceService> {
+    finance_service: FS,
+    config: SchedulerConfig,
+}
+
+impl<FS: FinanceService> IdempotencyKeyScheduler<FS> {
+    pub fn new(finance_service: FS, config: SchedulerConfig) -> Self {
+        Self {
+            finance_service,
+            config,
+        }
+    }
+
+    /// Start running in the background, right away and then every configured interval.
+    pub fn spawn(self) -> JoinHandle<()> {
+        spawn_every(self.config.interval, self)
+    }
+}
+
+impl<FS: FinanceService> PeriodicTask for IdempotencyKeyScheduler<FS> {
+    async fn run(&self) {
+        match self
+            .finance_service
+            .purge_idempotency_keys(Utc::now())
+            .await
+        {
+            Ok(0) => tracing::debug!("No idempotency key expired"),
+            Ok(purged) => tracing::info!("Purged {} expired idempotency keys", purged),
+            Err(e) => tracing::error!("Failed to purge idempotency keys: {:?}", e),
+        }
+    }
+}
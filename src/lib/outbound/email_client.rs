@@ -1,8 +1,9 @@
+use crate::domain::finance::models::budget::BudgetAlert;
 use crate::domain::finance::models::expense::Expense;
 use crate::domain::finance::ports::ExpenseNotifier;
 
 /// An unimplemented example of an adapter to [ExpenseNotifier].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct EmailClient;
 
 impl EmailClient {
@@ -13,4 +14,6 @@ impl EmailClient {
 
 impl ExpenseNotifier for EmailClient {
     async fn expense_created(&self, _: &Expense) {}
+
+    async fn budget_threshold_reached(&self, _: &BudgetAlert) {}
 }
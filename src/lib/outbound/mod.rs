@@ -1,3 +1,5 @@
 pub mod email_client;
 pub mod postgres;
 pub mod prometheus;
+pub mod storage;
+pub mod thumbnail;
@@ -1,11 +1,70 @@
 use anyhow::{Context, anyhow};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use sqlx::postgres::PgRow;
 use sqlx::{Executor, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use tracing::Level;
 use uuid::Uuid;
 
+use crate::domain::auth::models::api_key::{
+    ApiKey, ApiKeyError, ApiKeyName, ApiKeySecret, CreateApiKeyRequest,
+};
+use crate::domain::auth::models::principal::Scope;
+use crate::domain::auth::ports::ApiKeyRepository;
+use crate::domain::finance::models::account::{
+    Account, AccountError, AccountKind, AccountName, BalancePoint, CreateAccountRequest, Currency,
+};
+use crate::domain::finance::models::attachment::{
+    Attachment, AttachmentContentType, AttachmentError, ThumbnailStatus,
+};
+use crate::domain::finance::models::balance::Debt;
+use crate::domain::finance::models::budget::{
+    Budget, BudgetError, BudgetPeriod, BudgetThreshold, CreateBudgetRequest, UpdateBudgetRequest,
+};
+use crate::domain::finance::models::category::{
+    Category, CategoryError, CategoryName, CreateCategoryRequest,
+};
+use crate::domain::finance::models::duplicate::{
+    DuplicateError, DuplicateMerge, DuplicateMode, DuplicateSettings,
+};
+use crate::domain::finance::models::exchange::{
+    ExchangeRate, ExchangeRateError, MissingExchangeRate, REFERENCE_CURRENCY,
+};
 use crate::domain::finance::models::expense::ListExpensesRequest;
-use crate::domain::finance::ports::ExpenseRepositoryError;
+use crate::domain::finance::models::idempotency::{
+    ClaimIdempotencyKeyRequest, IdempotencyClaim, IdempotencyError, IdempotencyKey,
+    RequestFingerprint, StoredResponse,
+};
+use crate::domain::finance::models::import::ImportError;
+use crate::domain::finance::models::ledger::{
+    AcceptInvitationRequest, CreateInvitationRequest, CreateLedgerRequest, InvitationError, Ledger,
+    LedgerError, LedgerInvitation, LedgerMember, LedgerName, LedgerRole,
+};
+use crate::domain::finance::models::recurring::{
+    CreateRecurringExpenseRequest, Frequency, Recurrence, RecurrenceError, RecurringExpense,
+    RecurringExpenseError,
+};
+use crate::domain::finance::models::report::{
+    ReportError, ReportScope, Series, SeriesPoint, SummaryBucket, SummaryRequest, TimeseriesRequest,
+};
+use crate::domain::finance::models::rule::{
+    CreateRuleRequest, NamePattern, Rule, RuleActions, RuleChange, RuleConditions, RuleError,
+    RuleName,
+};
+use crate::domain::finance::models::settlement::{
+    RecordSettlementRequest, Settlement, SettlementError,
+};
+use crate::domain::finance::models::split::{ExpenseShare, ExpenseSplit, SplitMethod};
+use crate::domain::finance::models::tag::Tag;
+use crate::domain::finance::models::transaction::TransactionKind;
+use crate::domain::finance::ports::{
+    AccountRepository, AttachmentRepository, BudgetRepository, CategoryRepository,
+    DuplicateRepository, ExchangeRateRepository, ExpenseRepositoryError, ExpenseStream,
+    IdempotencyRepository, LedgerRepository, RecurringExpenseRepository, ReportRepository,
+    RuleRepository, SettlementRepository,
+};
 use crate::domain::finance::{
     models::expense::{CreateExpenseError, CreateExpenseRequest, Expense, ExpenseName},
     ports::ExpenseRepository,
@@ -42,7 +101,7 @@ impl Postgres {
     /// # Arguments
     ///
     /// * `tx` - The database transaction.
-    /// * `name` - The name of the expense.
+    /// * `req` - The request describing the expense.
     ///
     /// # Returns
     ///
@@ -50,13 +109,26 @@ impl Postgres {
     async fn save_expense(
         &self,
         tx: &mut Transaction<'_, sqlx::Postgres>,
-        name: &ExpenseName,
+        req: &CreateExpenseRequest,
     ) -> Result<Uuid, sqlx::Error> {
         let id = Uuid::new_v4();
         let span = tracing::span!(Level::DEBUG, "expense", expense_id = ?id);
         let _guard = span.enter();
         let id_as_string = id.to_string();
-        let name = name.to_string();
+        let name = req.name().to_string();
+        let ledger_id = req.ledger_id().map(Uuid::to_string);
+        let amount = req.amount();
+        let paid_by = req.split().map(|s| s.paid_by().to_string());
+        let split_method = req.split().map(|s| s.method().as_str());
+        let category_id = req.category_id().map(Uuid::to_string);
+        let spent_on = *req.spent_on();
+        let recurring_expense_id = req.recurring_expense_id().map(Uuid::to_string);
+        let kind = req.kind().as_str();
+        let account_id = req.account_id().map(Uuid::to_string);
+        let to_account_id = req.to_account_id().map(Uuid::to_string);
+        let import_id = req.import_id();
+        let counterparty = req.counterparty();
+        let reference = req.reference();
         tracing::event!(
             Level::DEBUG,
             "Saving expense with ID: {} and name: {}",
@@ -64,12 +136,61 @@ impl Postgres {
             name
         );
         let query = sqlx::query!(
-            "INSERT INTO expenses (id, name) VALUES ($1, $2)",
+            "INSERT INTO expenses (id, name, ledger_id, amount, paid_by, split_method, category_id, spent_on, recurring_expense_id, kind, account_id, to_account_id, import_id, counterparty, reference) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
             id_as_string,
             name,
+            ledger_id,
+            amount,
+            paid_by,
+            split_method,
+            category_id,
+            spent_on,
+            recurring_expense_id,
+            kind,
+            account_id,
+            to_account_id,
+            import_id,
+            counterparty,
+            reference,
         );
         tx.execute(query).await?;
 
+        if let Some(split) = req.split() {
+            let user_ids: Vec<String> = split
+                .shares()
+                .iter()
+                .map(|s| s.user_id().to_string())
+                .collect();
+            let amounts: Vec<i64> = split.shares().iter().map(ExpenseShare::amount).collect();
+            let positions: Vec<i32> = (0..split.shares().len() as i32).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO expense_shares (expense_id, user_id, amount, position)
+                SELECT $1, * FROM UNNEST($2::TEXT[], $3::BIGINT[], $4::INTEGER[])
+                "#,
+            )
+            .bind(&id_as_string)
+            .bind(user_ids)
+            .bind(amounts)
+            .bind(positions)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if !req.tags().is_empty() {
+            let tags: Vec<&str> = req.tags().iter().map(Tag::as_str).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO expense_tags (expense_id, tag)
+                SELECT $1, * FROM UNNEST($2::TEXT[])
+                "#,
+            )
+            .bind(&id_as_string)
+            .bind(tags)
+            .execute(&mut **tx)
+            .await?;
+        }
+
         tracing::event!(Level::DEBUG, "Expense Saved");
         Ok(id)
     }
@@ -77,44 +198,198 @@ impl Postgres {
     ///
     /// # Arguments
     ///
-    /// * `page` - page number for pagination
-    /// * `size` - number of expenses per page
+    /// * `owner_id` - the user whose accounts personal expenses are read from
+    /// * `ledger_id` - the ledger to read expenses from, or `None` for personal expenses
+    /// * `kind` - the kind of transactions to read, or `None` for every kind
     ///
     /// Returns the list of expenses
-    async fn read_expenses(&self) -> Result<Vec<Expense>, sqlx::Error> {
+    async fn read_expenses(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+        kind: Option<TransactionKind>,
+    ) -> Result<Vec<Expense>, sqlx::Error> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name
+            SELECT id, name, kind, ledger_id, amount, paid_by, split_method, category_id,
+                spent_on, recurring_expense_id, account_id, to_account_id, counterparty, reference
             FROM expenses
+            WHERE ledger_id IS NOT DISTINCT FROM $1 AND ($2::TEXT IS NULL OR kind = $2)
+                AND ($1 IS NOT NULL
+                    OR account_id IN (SELECT id FROM accounts WHERE owner_id = $3))
             ORDER BY name DESC
             "#,
         )
+        .bind(ledger_id.map(Uuid::to_string))
+        .bind(kind.map(|kind| kind.as_str()))
+        .bind(owner_id.to_string())
         .fetch_all(&self.pool)
         .await?;
+        let expenses = self.complete_expenses(rows).await?;
+
+        tracing::event!(
+            tracing::Level::DEBUG,
+            "Retrieved list of expenses: {} items",
+            expenses.len()
+        );
+        Ok(expenses)
+    }
+
+    /// Decodes expense rows, reading their split shares, tags and attachments alongside.
+    async fn complete_expenses(&self, rows: Vec<PgRow>) -> Result<Vec<Expense>, sqlx::Error> {
+        let ids: Vec<String> = rows
+            .iter()
+            .map(|row| row.try_get("id"))
+            .collect::<Result<_, _>>()?;
+        let mut shares = self.read_expense_shares(&ids).await?;
+        let mut tags = self.read_expense_tags(&ids).await?;
+        let mut attachments = self.read_expense_attachments(&ids).await?;
 
         let mut expenses = Vec::with_capacity(rows.len());
         for row in rows {
-            let id_str: String = row.try_get("id")?;
-            let name_str: String = row.try_get("name")?;
+            let id = decode_uuid(&row, "id")?;
+            expenses.push(decode_expense(
+                &row,
+                shares.remove(&id).unwrap_or_default(),
+                tags.remove(&id).unwrap_or_default(),
+                attachments.remove(&id).unwrap_or_default(),
+            )?);
+        }
+        Ok(expenses)
+    }
+
+    /// Fails with the first day, up to `through`, on which a transaction of `account`, or its
+    /// opening balance, cannot be converted to `currency`.
+    async fn ensure_exchange_rates(
+        &self,
+        account: &Account,
+        currency: &Currency,
+        through: NaiveDate,
+    ) -> Result<(), AccountError> {
+        if account.currency() == currency {
+            return Ok(());
+        }
+        let row = sqlx::query(
+            r#"
+            SELECT day
+            FROM (
+                SELECT $4::DATE AS day
+                UNION
+                SELECT spent_on
+                FROM expenses
+                WHERE (account_id = $1 OR to_account_id = $1) AND spent_on <= $5
+            ) days
+            WHERE convert_amount(1, $2, $3, day) IS NULL
+            ORDER BY day
+            LIMIT 1
+            "#,
+        )
+        .bind(account.id().to_string())
+        .bind(account.currency().as_str())
+        .bind(currency.as_str())
+        .bind(account.created_at().date_naive())
+        .bind(through)
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to check exchange rates of account {}", account.id()))?;
+        match row {
+            Some(row) => Err(MissingExchangeRate {
+                from: account.currency().clone(),
+                to: currency.clone(),
+                on: row.try_get("day").context("invalid exchange rate row")?,
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the split shares of the given expenses, keyed by expense id and kept in the order
+    /// the participants were given.
+    async fn read_expense_shares(
+        &self,
+        expense_ids: &[String],
+    ) -> Result<HashMap<Uuid, Vec<ExpenseShare>>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT expense_id, user_id, amount
+            FROM expense_shares
+            WHERE expense_id = ANY($1)
+            ORDER BY expense_id, position
+            "#,
+        )
+        .bind(expense_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut shares: HashMap<Uuid, Vec<ExpenseShare>> = HashMap::new();
+        for row in rows {
+            shares
+                .entry(decode_uuid(&row, "expense_id")?)
+                .or_default()
+                .push(ExpenseShare::new(
+                    decode_uuid(&row, "user_id")?,
+                    row.try_get("amount")?,
+                ));
+        }
+        Ok(shares)
+    }
 
-            let id = uuid::Uuid::parse_str(&id_str).map_err(|e| sqlx::Error::ColumnDecode {
-                index: "id".into(),
-                source: Box::new(e),
-            })?;
-            let name = ExpenseName::new(&name_str).map_err(|e| sqlx::Error::ColumnDecode {
-                index: "name".into(),
+    /// Reads the tags of the given expenses, keyed by expense id and sorted.
+    async fn read_expense_tags(
+        &self,
+        expense_ids: &[String],
+    ) -> Result<HashMap<Uuid, Vec<Tag>>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT expense_id, tag
+            FROM expense_tags
+            WHERE expense_id = ANY($1)
+            ORDER BY expense_id, tag
+            "#,
+        )
+        .bind(expense_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut tags: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+        for row in rows {
+            let tag: String = row.try_get("tag")?;
+            let tag = Tag::new(&tag).map_err(|e| sqlx::Error::ColumnDecode {
+                index: "tag".into(),
                 source: Box::new(e),
             })?;
-
-            expenses.push(Expense::new(id, name));
+            tags.entry(decode_uuid(&row, "expense_id")?)
+                .or_default()
+                .push(tag);
         }
+        Ok(tags)
+    }
 
-        tracing::event!(
-            tracing::Level::DEBUG,
-            "Retrieved list of expenses: {} items",
-            expenses.len()
-        );
-        Ok(expenses)
+    /// Reads the attachments of the given expenses, keyed by expense id in upload order.
+    async fn read_expense_attachments(
+        &self,
+        expense_ids: &[String],
+    ) -> Result<HashMap<Uuid, Vec<Attachment>>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, expense_id, file_name, content_type, size, created_at, thumbnail_status
+            FROM expense_attachments
+            WHERE expense_id = ANY($1)
+            ORDER BY expense_id, created_at, id
+            "#,
+        )
+        .bind(expense_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut attachments: HashMap<Uuid, Vec<Attachment>> = HashMap::new();
+        for row in rows {
+            attachments
+                .entry(decode_uuid(&row, "expense_id")?)
+                .or_default()
+                .push(decode_attachment(&row)?);
+        }
+        Ok(attachments)
     }
 }
 
@@ -147,10 +422,13 @@ impl ExpenseRepository for Postgres {
 
         tracing::debug!("Transaction started");
 
-        let expense_id = self.save_expense(&mut tx, req.name()).await.map_err(|e| {
-            if is_unique_constraint_violation(&e) {
-                CreateExpenseError::Duplicate {
-                    name: req.name().to_string(),
+        let expense_id = self.save_expense(&mut tx, req).await.map_err(|e| {
+            if let Some(recurring_expense_id) = req.recurring_expense_id()
+                && violated_constraint(&e) == Some(RECURRING_OCCURRENCE_CONSTRAINT)
+            {
+                CreateExpenseError::DuplicateOccurrence {
+                    recurring_expense_id: *recurring_expense_id,
+                    spent_on: *req.spent_on(),
                 }
             } else {
                 anyhow!(e)
@@ -165,29 +443,2479 @@ impl ExpenseRepository for Postgres {
             .unwrap_or_else(|e| panic!("failed to commit Postgres transaction: {}", e));
         tracing::debug!("Transaction committed");
 
-        Ok(Expense::new(expense_id, req.name().clone()))
+        Ok(saved_expense(expense_id, req))
+    }
+
+    async fn create_expenses(
+        &self,
+        reqs: &[CreateExpenseRequest],
+    ) -> Result<Vec<Expense>, ImportError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start Postgres transaction")?;
+        let mut expenses = Vec::with_capacity(reqs.len());
+        for req in reqs {
+            let expense_id = self
+                .save_expense(&mut tx, req)
+                .await
+                .with_context(|| format!("failed to save expense with name {:?}", req.name()))?;
+            expenses.push(saved_expense(expense_id, req));
+        }
+        tx.commit()
+            .await
+            .context("failed to commit Postgres transaction")?;
+        tracing::info!("{} expenses saved", expenses.len());
+        Ok(expenses)
+    }
+
+    async fn find_expense(&self, id: &Uuid) -> Result<Option<Expense>, ExpenseRepositoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, kind, ledger_id, amount, paid_by, split_method, category_id,
+                spent_on, recurring_expense_id, account_id, to_account_id, counterparty, reference
+            FROM expenses
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find expense {}", id))?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let ids = [id.to_string()];
+        let shares = self
+            .read_expense_shares(&ids)
+            .await
+            .context("failed to read expense shares")?;
+        let tags = self
+            .read_expense_tags(&ids)
+            .await
+            .context("failed to read expense tags")?;
+        let attachments = self
+            .read_expense_attachments(&ids)
+            .await
+            .context("failed to read expense attachments")?;
+        let expense = decode_expense(
+            &row,
+            shares.into_values().next().unwrap_or_default(),
+            tags.into_values().next().unwrap_or_default(),
+            attachments.into_values().next().unwrap_or_default(),
+        )
+        .context("invalid expense row")?;
+        Ok(Some(expense))
     }
 
     async fn list_expenses(
         &self,
-        _req: &ListExpensesRequest,
+        owner_id: &Uuid,
+        req: &ListExpensesRequest,
     ) -> Result<Vec<Expense>, ExpenseRepositoryError> {
-        self.read_expenses()
+        self.read_expenses(owner_id, req.ledger_id(), req.kind())
             .await
             .map_err(|_| ExpenseRepositoryError::Unknown(anyhow!("Error listing expenses")))
     }
-}
 
-const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "2067";
+    async fn find_import_ids(
+        &self,
+        account_id: &Uuid,
+        import_ids: &[&str],
+    ) -> Result<HashSet<String>, ImportError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT import_id
+            FROM expenses
+            WHERE account_id = $1 AND import_id = ANY($2)
+            "#,
+        )
+        .bind(account_id.to_string())
+        .bind(import_ids)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to find import ids of account {}", account_id))?;
+        rows.iter()
+            .map(|row| row.try_get("import_id"))
+            .collect::<Result<_, _>>()
+            .context("invalid import id row")
+            .map_err(ImportError::from)
+    }
 
-fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
-    if let sqlx::Error::Database(db_err) = err {
-        if let Some(code) = db_err.code() {
-            if code == UNIQUE_CONSTRAINT_VIOLATION_CODE {
-                return true;
+    fn stream_expenses(&self, owner_id: &Uuid, req: &ListExpensesRequest) -> ExpenseStream {
+        let pool = self.pool.clone();
+        let owner_id = owner_id.to_string();
+        let ledger_id = req.ledger_id().map(Uuid::to_string);
+        let kind = req.kind().map(|kind| kind.as_str());
+        Box::pin(async_stream::try_stream! {
+            // Tags, shares and attachments are aggregated per row so that every expense is
+            // complete as soon as it is read.
+            let mut rows = sqlx::query(
+                r#"
+                SELECT id, name, kind, ledger_id, amount, paid_by, split_method, category_id,
+                    spent_on, recurring_expense_id, account_id, to_account_id, counterparty,
+                    reference,
+                    ARRAY(
+                        SELECT tag FROM expense_tags t WHERE t.expense_id = e.id ORDER BY tag
+                    ) AS tags,
+                    ARRAY(
+                        SELECT user_id FROM expense_shares s
+                        WHERE s.expense_id = e.id ORDER BY position
+                    ) AS share_user_ids,
+                    ARRAY(
+                        SELECT amount FROM expense_shares s
+                        WHERE s.expense_id = e.id ORDER BY position
+                    ) AS share_amounts,
+                    ARRAY(
+                        SELECT id FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_ids,
+                    ARRAY(
+                        SELECT file_name FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_file_names,
+                    ARRAY(
+                        SELECT content_type FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_content_types,
+                    ARRAY(
+                        SELECT size FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_sizes,
+                    ARRAY(
+                        SELECT created_at FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_created_at,
+                    ARRAY(
+                        SELECT thumbnail_status FROM expense_attachments a
+                        WHERE a.expense_id = e.id ORDER BY created_at, id
+                    ) AS attachment_thumbnail_statuses
+                FROM expenses e
+                WHERE ledger_id IS NOT DISTINCT FROM $1 AND ($2::TEXT IS NULL OR kind = $2)
+                    AND ($1 IS NOT NULL
+                        OR account_id IN (SELECT id FROM accounts WHERE owner_id = $3))
+                ORDER BY spent_on, name, id
+                "#,
+            )
+            .bind(ledger_id)
+            .bind(kind)
+            .bind(owner_id)
+            .fetch(&pool);
+            while let Some(row) = rows.try_next().await.context("failed to read expenses")? {
+                yield decode_aggregated_expense(&row).context("invalid expense row")?;
             }
+        })
+    }
+
+    async fn list_ledger_debts(
+        &self,
+        ledger_id: &Uuid,
+    ) -> Result<Vec<Debt>, ExpenseRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT debtor, creditor, SUM(amount)::BIGINT AS amount
+            FROM (
+                SELECT s.user_id AS debtor, e.paid_by AS creditor, s.amount
+                FROM expense_shares s
+                JOIN expenses e ON e.id = s.expense_id
+                WHERE e.ledger_id = $1 AND e.kind = 'expense' AND s.user_id <> e.paid_by
+                UNION ALL
+                -- A settlement pays a debt back, which nets like a debt in the other direction.
+                SELECT to_user_id AS debtor, from_user_id AS creditor, amount
+                FROM settlements
+                WHERE ledger_id = $1
+            ) debts
+            GROUP BY debtor, creditor
+            "#,
+        )
+        .bind(ledger_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to sum debts of ledger {}", ledger_id))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(Debt::new(
+                    decode_uuid(row, "debtor")?,
+                    decode_uuid(row, "creditor")?,
+                    row.try_get("amount")?,
+                ))
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| anyhow!(e).context("invalid debt row").into())
+    }
+}
+
+/// Implementation of the `AttachmentRepository` trait for the `Postgres` struct.
+impl AttachmentRepository for Postgres {
+    async fn create_attachment(&self, attachment: &Attachment) -> Result<(), AttachmentError> {
+        sqlx::query(
+            r#"
+            INSERT INTO expense_attachments (id, expense_id, file_name, content_type, size,
+                created_at, thumbnail_status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(attachment.id().to_string())
+        .bind(attachment.expense_id().to_string())
+        .bind(attachment.file_name())
+        .bind(attachment.content_type().as_str())
+        .bind(attachment.size() as i64)
+        .bind(attachment.created_at())
+        .bind(attachment.thumbnail_status().as_str())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to save attachment {}", attachment.id()))?;
+        Ok(())
+    }
+
+    async fn find_attachment(&self, id: &Uuid) -> Result<Option<Attachment>, AttachmentError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, expense_id, file_name, content_type, size, created_at, thumbnail_status
+            FROM expense_attachments
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find attachment {}", id))?;
+        row.as_ref()
+            .map(decode_attachment)
+            .transpose()
+            .context("invalid attachment row")
+            .map_err(AttachmentError::from)
+    }
+
+    async fn list_pending_thumbnails(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Attachment>, AttachmentError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, expense_id, file_name, content_type, size, created_at, thumbnail_status
+            FROM expense_attachments
+            WHERE thumbnail_status = 'pending'
+            ORDER BY created_at, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list pending thumbnails")?;
+        rows.iter()
+            .map(decode_attachment)
+            .collect::<Result<Vec<_>, _>>()
+            .context("invalid attachment row")
+            .map_err(AttachmentError::from)
+    }
+
+    async fn set_thumbnail_status(
+        &self,
+        id: &Uuid,
+        status: ThumbnailStatus,
+    ) -> Result<(), AttachmentError> {
+        sqlx::query(
+            r#"
+            UPDATE expense_attachments
+            SET thumbnail_status = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(status.as_str())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to update thumbnail of attachment {}", id))?;
+        Ok(())
+    }
+}
+
+/// Implementation of the `LedgerRepository` trait for the `Postgres` struct.
+impl LedgerRepository for Postgres {
+    /// Creates a ledger and registers its creator as owner within a single transaction.
+    async fn create_ledger(&self, req: &CreateLedgerRequest) -> Result<Ledger, LedgerError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start Postgres transaction")?;
+
+        let id = Uuid::new_v4();
+        let row =
+            sqlx::query("INSERT INTO ledgers (id, name) VALUES ($1, $2) RETURNING created_at")
+                .bind(id.to_string())
+                .bind(req.name().to_string())
+                .fetch_one(&mut *tx)
+                .await
+                .with_context(|| format!("failed to save ledger with name {:?}", req.name()))?;
+        let created_at: DateTime<Utc> = row.try_get("created_at").context("invalid ledger row")?;
+
+        sqlx::query(
+            "INSERT INTO ledger_members (ledger_id, user_id, role, joined_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(id.to_string())
+        .bind(req.owner_id().to_string())
+        .bind(LedgerRole::Owner.as_str())
+        .bind(created_at)
+        .execute(&mut *tx)
+        .await
+        .context("failed to save ledger owner")?;
+
+        tx.commit()
+            .await
+            .context("failed to commit Postgres transaction")?;
+        tracing::info!("Ledger saved with ID: {}", id);
+
+        Ok(Ledger::new(id, req.name().clone(), created_at))
+    }
+
+    async fn find_ledger(&self, id: &Uuid) -> Result<Option<Ledger>, LedgerError> {
+        let row = sqlx::query("SELECT id, name, created_at FROM ledgers WHERE id = $1")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .with_context(|| format!("failed to read ledger {}", id))?;
+
+        Ok(row
+            .as_ref()
+            .map(decode_ledger)
+            .transpose()
+            .context("invalid ledger row")?)
+    }
+
+    async fn list_ledgers_for_user(&self, user_id: &Uuid) -> Result<Vec<Ledger>, LedgerError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT l.id, l.name, l.created_at
+            FROM ledgers l
+            JOIN ledger_members m ON m.ledger_id = l.id
+            WHERE m.user_id = $1
+            ORDER BY l.name
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to list ledgers for user {}", user_id))?;
+
+        Ok(rows
+            .iter()
+            .map(decode_ledger)
+            .collect::<Result<_, _>>()
+            .context("invalid ledger row")?)
+    }
+
+    async fn list_ledger_members(
+        &self,
+        ledger_id: &Uuid,
+    ) -> Result<Vec<LedgerMember>, LedgerError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT ledger_id, user_id, role, joined_at
+            FROM ledger_members
+            WHERE ledger_id = $1
+            ORDER BY joined_at
+            "#,
+        )
+        .bind(ledger_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to list members of ledger {}", ledger_id))?;
+
+        Ok(rows
+            .iter()
+            .map(decode_ledger_member)
+            .collect::<Result<_, _>>()
+            .context("invalid ledger member row")?)
+    }
+
+    async fn find_ledger_member(
+        &self,
+        ledger_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Result<Option<LedgerMember>, LedgerError> {
+        let row = sqlx::query(
+            r#"
+            SELECT ledger_id, user_id, role, joined_at
+            FROM ledger_members
+            WHERE ledger_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(ledger_id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to read member {} of ledger {}", user_id, ledger_id))?;
+
+        Ok(row
+            .as_ref()
+            .map(decode_ledger_member)
+            .transpose()
+            .context("invalid ledger member row")?)
+    }
+
+    async fn create_invitation(
+        &self,
+        req: &CreateInvitationRequest,
+    ) -> Result<LedgerInvitation, InvitationError> {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        sqlx::query(
+            r#"
+            INSERT INTO ledger_invitations (token, ledger_id, role, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&token)
+        .bind(req.ledger_id().to_string())
+        .bind(req.role().as_str())
+        .bind(req.invited_by().to_string())
+        .bind(req.expires_at())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to save invitation to ledger {}", req.ledger_id()))?;
+
+        Ok(LedgerInvitation::new(
+            token,
+            *req.ledger_id(),
+            req.role(),
+            *req.expires_at(),
+        ))
+    }
+
+    async fn accept_invitation(
+        &self,
+        req: &AcceptInvitationRequest,
+    ) -> Result<LedgerMember, InvitationError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start Postgres transaction")?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT ledger_id, role, expires_at
+            FROM ledger_invitations
+            WHERE token = $1 AND accepted_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(req.token())
+        .fetch_optional(&mut *tx)
+        .await
+        .context("failed to read invitation")?
+        .ok_or(InvitationError::NotFound)?;
+
+        let ledger_id = decode_uuid(&row, "ledger_id").context("invalid invitation row")?;
+        let role = decode_role(&row).context("invalid invitation row")?;
+        let expires_at: DateTime<Utc> = row
+            .try_get("expires_at")
+            .context("invalid invitation row")?;
+        if expires_at < Utc::now() {
+            return Err(InvitationError::Expired { expires_at });
         }
+
+        let joined = sqlx::query(
+            r#"
+            INSERT INTO ledger_members (ledger_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (ledger_id, user_id) DO NOTHING
+            RETURNING joined_at
+            "#,
+        )
+        .bind(ledger_id.to_string())
+        .bind(req.user_id().to_string())
+        .bind(role.as_str())
+        .fetch_optional(&mut *tx)
+        .await
+        .context("failed to save ledger member")?;
+        let Some(joined) = joined else {
+            return Err(InvitationError::AlreadyMember {
+                ledger_id,
+                user_id: *req.user_id(),
+            });
+        };
+        let joined_at: DateTime<Utc> = joined.try_get("joined_at").context("invalid member row")?;
+
+        sqlx::query(
+            "UPDATE ledger_invitations SET accepted_by = $2, accepted_at = now() WHERE token = $1",
+        )
+        .bind(req.token())
+        .bind(req.user_id().to_string())
+        .execute(&mut *tx)
+        .await
+        .context("failed to consume invitation")?;
+
+        tx.commit()
+            .await
+            .context("failed to commit Postgres transaction")?;
+        tracing::info!("User {} joined ledger {}", req.user_id(), ledger_id);
+
+        Ok(LedgerMember::new(
+            ledger_id,
+            *req.user_id(),
+            role,
+            joined_at,
+        ))
     }
+}
 
-    false
+/// Implementation of the `SettlementRepository` trait for the `Postgres` struct.
+impl SettlementRepository for Postgres {
+    async fn record_settlement(
+        &self,
+        req: &RecordSettlementRequest,
+    ) -> Result<Settlement, SettlementError> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO settlements (id, ledger_id, from_user_id, to_user_id, amount, recorded_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING recorded_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.ledger_id().to_string())
+        .bind(req.from().to_string())
+        .bind(req.to().to_string())
+        .bind(req.amount())
+        .bind(req.recorded_by().to_string())
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to save settlement in ledger {}", req.ledger_id()))?;
+        let recorded_at: DateTime<Utc> = row
+            .try_get("recorded_at")
+            .context("invalid settlement row")?;
+        tracing::info!("Settlement saved with ID: {}", id);
+
+        Ok(Settlement::new(
+            id,
+            *req.ledger_id(),
+            *req.from(),
+            *req.to(),
+            req.amount(),
+            recorded_at,
+        ))
+    }
+}
+
+impl CategoryRepository for Postgres {
+    async fn create_category(
+        &self,
+        req: &CreateCategoryRequest,
+    ) -> Result<Category, CategoryError> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO categories (id, name, ledger_id, owner_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.name().to_string())
+        .bind(req.ledger_id().map(Uuid::to_string))
+        .bind(req.owner_id().to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                CategoryError::Duplicate {
+                    name: req.name().clone(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!(
+                        "failed to save category with name {:?}",
+                        req.name()
+                    ))
+                    .into()
+            }
+        })?;
+        let created_at: DateTime<Utc> =
+            row.try_get("created_at").context("invalid category row")?;
+        tracing::info!("Category saved with ID: {}", id);
+
+        Ok(Category::new(
+            id,
+            req.name().clone(),
+            req.ledger_id().copied(),
+            *req.owner_id(),
+            created_at,
+        ))
+    }
+
+    async fn find_category(&self, id: &Uuid) -> Result<Option<Category>, CategoryError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, ledger_id, owner_id, created_at
+            FROM categories
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find category {}", id))?;
+        row.as_ref()
+            .map(decode_category)
+            .transpose()
+            .context("invalid category row")
+            .map_err(CategoryError::from)
+    }
+
+    async fn list_categories(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<Category>, CategoryError> {
+        let query = match ledger_id {
+            Some(ledger_id) => sqlx::query(
+                r#"
+                SELECT id, name, ledger_id, owner_id, created_at
+                FROM categories
+                WHERE ledger_id = $1
+                ORDER BY lower(name)
+                "#,
+            )
+            .bind(ledger_id.to_string()),
+            None => sqlx::query(
+                r#"
+                SELECT id, name, ledger_id, owner_id, created_at
+                FROM categories
+                WHERE ledger_id IS NULL AND owner_id = $1
+                ORDER BY lower(name)
+                "#,
+            )
+            .bind(owner_id.to_string()),
+        };
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to list categories")?;
+        rows.iter()
+            .map(decode_category)
+            .collect::<Result<_, _>>()
+            .context("invalid category row")
+            .map_err(CategoryError::from)
+    }
+}
+
+impl BudgetRepository for Postgres {
+    async fn create_budget(
+        &self,
+        req: &CreateBudgetRequest,
+        category: &Category,
+    ) -> Result<Budget, BudgetError> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO budgets (id, category_id, ledger_id, owner_id, amount, currency, period)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(category.id().to_string())
+        .bind(category.ledger_id().map(Uuid::to_string))
+        .bind(req.owner_id().to_string())
+        .bind(req.amount())
+        .bind(req.currency().as_str())
+        .bind(req.period().as_str())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                BudgetError::Duplicate {
+                    category_id: *category.id(),
+                    period: req.period(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!(
+                        "failed to save budget on category {}",
+                        category.id()
+                    ))
+                    .into()
+            }
+        })?;
+        let created_at: DateTime<Utc> = row.try_get("created_at").context("invalid budget row")?;
+        tracing::info!("Budget saved with ID: {}", id);
+
+        Ok(Budget::new(
+            id,
+            *category.id(),
+            category.ledger_id().copied(),
+            *req.owner_id(),
+            req.amount(),
+            req.period(),
+            created_at,
+        )
+        .with_currency(req.currency().clone()))
+    }
+
+    async fn find_budget(&self, id: &Uuid) -> Result<Option<Budget>, BudgetError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, category_id, ledger_id, owner_id, amount, currency, period, created_at
+            FROM budgets
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find budget {}", id))?;
+        row.as_ref()
+            .map(decode_budget)
+            .transpose()
+            .context("invalid budget row")
+            .map_err(BudgetError::from)
+    }
+
+    async fn list_budgets(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<Budget>, BudgetError> {
+        let query = match ledger_id {
+            Some(ledger_id) => sqlx::query(
+                r#"
+                SELECT id, category_id, ledger_id, owner_id, amount, currency, period, created_at
+                FROM budgets
+                WHERE ledger_id = $1
+                ORDER BY created_at, id
+                "#,
+            )
+            .bind(ledger_id.to_string()),
+            None => sqlx::query(
+                r#"
+                SELECT id, category_id, ledger_id, owner_id, amount, currency, period, created_at
+                FROM budgets
+                WHERE ledger_id IS NULL AND owner_id = $1
+                ORDER BY created_at, id
+                "#,
+            )
+            .bind(owner_id.to_string()),
+        };
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to list budgets")?;
+        rows.iter()
+            .map(decode_budget)
+            .collect::<Result<_, _>>()
+            .context("invalid budget row")
+            .map_err(BudgetError::from)
+    }
+
+    async fn update_budget(
+        &self,
+        budget: &Budget,
+        req: &UpdateBudgetRequest,
+    ) -> Result<Option<Budget>, BudgetError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE budgets
+            SET amount = $2, period = $3, currency = COALESCE($4, currency)
+            WHERE id = $1
+            RETURNING id, category_id, ledger_id, owner_id, amount, currency, period, created_at
+            "#,
+        )
+        .bind(req.id().to_string())
+        .bind(req.amount())
+        .bind(req.period().as_str())
+        .bind(req.currency().map(Currency::as_str))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_unique_constraint_violation(&e) {
+                BudgetError::Duplicate {
+                    category_id: *budget.category_id(),
+                    period: req.period(),
+                }
+            } else {
+                anyhow!(e)
+                    .context(format!("failed to update budget {}", req.id()))
+                    .into()
+            }
+        })?;
+        row.as_ref()
+            .map(decode_budget)
+            .transpose()
+            .context("invalid budget row")
+            .map_err(BudgetError::from)
+    }
+
+    async fn delete_budget(&self, id: &Uuid) -> Result<bool, BudgetError> {
+        let result = sqlx::query("DELETE FROM budgets WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to delete budget {}", id))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn sum_category_spending(
+        &self,
+        category_id: &Uuid,
+        currency: &Currency,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<i64, BudgetError> {
+        // Expenses recorded before accounts existed have no currency: they are taken to be in
+        // the reference currency.
+        let row = sqlx::query(
+            r#"
+            WITH converted AS (
+                SELECT e.spent_on, COALESCE(a.currency::TEXT, $5) AS from_currency,
+                    convert_amount(e.amount, COALESCE(a.currency::TEXT, $5), $4, e.spent_on)
+                        AS converted
+                FROM expenses e
+                LEFT JOIN accounts a ON a.id = e.account_id
+                WHERE e.category_id = $1 AND e.kind = 'expense'
+                    AND e.spent_on >= $2 AND e.spent_on < $3
+            )
+            SELECT COALESCE(SUM(converted), 0)::BIGINT AS spent,
+                MIN(spent_on) FILTER (WHERE converted IS NULL) AS missing_on,
+                (ARRAY_AGG(from_currency ORDER BY spent_on)
+                    FILTER (WHERE converted IS NULL))[1] AS missing_from
+            FROM converted
+            "#,
+        )
+        .bind(category_id.to_string())
+        .bind(from)
+        .bind(to)
+        .bind(currency.as_str())
+        .bind(REFERENCE_CURRENCY)
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to sum spending in category {}", category_id))?;
+
+        let missing_on: Option<NaiveDate> =
+            row.try_get("missing_on").context("invalid spending row")?;
+        if let Some(on) = missing_on {
+            return Err(MissingExchangeRate {
+                from: decode_currency(&row, "missing_from").context("invalid spending row")?,
+                to: currency.clone(),
+                on,
+            }
+            .into());
+        }
+        Ok(row.try_get("spent").context("invalid spending row")?)
+    }
+
+    async fn list_category_budgets(&self, category_id: &Uuid) -> Result<Vec<Budget>, BudgetError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, category_id, ledger_id, owner_id, amount, currency, period, created_at
+            FROM budgets
+            WHERE category_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(category_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to list budgets of category {}", category_id))?;
+        rows.iter()
+            .map(decode_budget)
+            .collect::<Result<_, _>>()
+            .context("invalid budget row")
+            .map_err(BudgetError::from)
+    }
+
+    async fn record_budget_alert(
+        &self,
+        budget_id: &Uuid,
+        period_start: NaiveDate,
+        threshold: BudgetThreshold,
+    ) -> Result<bool, BudgetError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO budget_alerts (budget_id, period_start, threshold)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(budget_id.to_string())
+        .bind(period_start)
+        .bind(threshold.percent())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to record alert for budget {}", budget_id))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl RecurringExpenseRepository for Postgres {
+    async fn create_recurring_expense(
+        &self,
+        req: &CreateRecurringExpenseRequest,
+    ) -> Result<RecurringExpense, RecurringExpenseError> {
+        let id = Uuid::new_v4();
+        let recurrence = req.recurrence();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO recurring_expenses (id, owner_id, ledger_id, name, amount, category_id,
+                account_id, frequency, interval_count, day_of_month, starts_on, ends_on)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.owner_id().to_string())
+        .bind(req.ledger_id().map(Uuid::to_string))
+        .bind(req.name().to_string())
+        .bind(req.amount())
+        .bind(req.category_id().map(Uuid::to_string))
+        .bind(req.account_id().map(Uuid::to_string))
+        .bind(recurrence.frequency().as_str())
+        .bind(recurrence.interval() as i32)
+        .bind(recurrence.day_of_month().map(|day| day as i16))
+        .bind(recurrence.starts_on())
+        .bind(recurrence.ends_on())
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to save recurring expense {:?}", req.name()))?;
+        let created_at: DateTime<Utc> = row
+            .try_get("created_at")
+            .context("invalid recurring expense row")?;
+        tracing::info!("Recurring expense saved with ID: {}", id);
+
+        let mut recurring = RecurringExpense::new(
+            id,
+            *req.owner_id(),
+            req.name().clone(),
+            req.amount(),
+            *recurrence,
+            created_at,
+        );
+        if let Some(ledger_id) = req.ledger_id() {
+            recurring = recurring.with_ledger(*ledger_id);
+        }
+        if let Some(category_id) = req.category_id() {
+            recurring = recurring.with_category(*category_id);
+        }
+        if let Some(account_id) = req.account_id() {
+            recurring = recurring.with_account(*account_id);
+        }
+        Ok(recurring)
+    }
+
+    async fn find_recurring_expense(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<RecurringExpense>, RecurringExpenseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, owner_id, ledger_id, name, amount, category_id, account_id, frequency,
+                interval_count, day_of_month, starts_on, ends_on, materialized_through, created_at
+            FROM recurring_expenses
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find recurring expense {}", id))?;
+        row.as_ref()
+            .map(decode_recurring_expense)
+            .transpose()
+            .context("invalid recurring expense row")
+            .map_err(RecurringExpenseError::from)
+    }
+
+    async fn list_recurring_expenses(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+    ) -> Result<Vec<RecurringExpense>, RecurringExpenseError> {
+        let query = match ledger_id {
+            Some(ledger_id) => sqlx::query(
+                r#"
+                SELECT id, owner_id, ledger_id, name, amount, category_id, account_id, frequency,
+                    interval_count, day_of_month, starts_on, ends_on, materialized_through, created_at
+                FROM recurring_expenses
+                WHERE ledger_id = $1
+                ORDER BY created_at, id
+                "#,
+            )
+            .bind(ledger_id.to_string()),
+            None => sqlx::query(
+                r#"
+                SELECT id, owner_id, ledger_id, name, amount, category_id, account_id, frequency,
+                    interval_count, day_of_month, starts_on, ends_on, materialized_through, created_at
+                FROM recurring_expenses
+                WHERE ledger_id IS NULL AND owner_id = $1
+                ORDER BY created_at, id
+                "#,
+            )
+            .bind(owner_id.to_string()),
+        };
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to list recurring expenses")?;
+        rows.iter()
+            .map(decode_recurring_expense)
+            .collect::<Result<_, _>>()
+            .context("invalid recurring expense row")
+            .map_err(RecurringExpenseError::from)
+    }
+
+    async fn delete_recurring_expense(&self, id: &Uuid) -> Result<bool, RecurringExpenseError> {
+        let result = sqlx::query("DELETE FROM recurring_expenses WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to delete recurring expense {}", id))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_due_recurring_expenses(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<RecurringExpense>, RecurringExpenseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, owner_id, ledger_id, name, amount, category_id, account_id, frequency,
+                interval_count, day_of_month, starts_on, ends_on, materialized_through, created_at
+            FROM recurring_expenses
+            WHERE starts_on <= $1
+                AND (materialized_through IS NULL OR materialized_through < $1)
+                AND (ends_on IS NULL OR materialized_through IS NULL
+                    OR materialized_through < ends_on)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list due recurring expenses")?;
+        rows.iter()
+            .map(decode_recurring_expense)
+            .collect::<Result<_, _>>()
+            .context("invalid recurring expense row")
+            .map_err(RecurringExpenseError::from)
+    }
+
+    async fn mark_recurring_expense_materialized(
+        &self,
+        id: &Uuid,
+        through: NaiveDate,
+    ) -> Result<(), RecurringExpenseError> {
+        sqlx::query(
+            r#"
+            UPDATE recurring_expenses
+            SET materialized_through = GREATEST(materialized_through, $2)
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(through)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to mark recurring expense {} materialized", id))?;
+        Ok(())
+    }
+}
+
+impl AccountRepository for Postgres {
+    async fn create_account(&self, req: &CreateAccountRequest) -> Result<Account, AccountError> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO accounts (id, owner_id, name, kind, currency, opening_balance)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.owner_id().to_string())
+        .bind(req.name().to_string())
+        .bind(req.kind().as_str())
+        .bind(req.currency().as_str())
+        .bind(req.opening_balance())
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to save account {:?}", req.name()))?;
+        let created_at: DateTime<Utc> = row.try_get("created_at").context("invalid account row")?;
+        tracing::info!("Account saved with ID: {}", id);
+
+        Ok(Account::new(
+            id,
+            *req.owner_id(),
+            req.name().clone(),
+            req.kind(),
+            req.currency().clone(),
+            req.opening_balance(),
+            created_at,
+        ))
+    }
+
+    async fn find_account(&self, id: &Uuid) -> Result<Option<Account>, AccountError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, owner_id, name, kind, currency, opening_balance, created_at
+            FROM accounts
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find account {}", id))?;
+        row.as_ref()
+            .map(decode_account)
+            .transpose()
+            .context("invalid account row")
+            .map_err(AccountError::from)
+    }
+
+    async fn list_accounts(&self, owner_id: &Uuid) -> Result<Vec<Account>, AccountError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, owner_id, name, kind, currency, opening_balance, created_at
+            FROM accounts
+            WHERE owner_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list accounts")?;
+        rows.iter()
+            .map(decode_account)
+            .collect::<Result<_, _>>()
+            .context("invalid account row")
+            .map_err(AccountError::from)
+    }
+
+    async fn account_balance(
+        &self,
+        account: &Account,
+        currency: &Currency,
+        as_of: NaiveDate,
+    ) -> Result<i64, AccountError> {
+        self.ensure_exchange_rates(account, currency, as_of).await?;
+        // Only transfers have a destination account, so every other transaction that credits
+        // the account is an income.
+        let row = sqlx::query(
+            r#"
+            SELECT (convert_amount($4, $2, $3, $5) + COALESCE(SUM(convert_amount(
+                CASE WHEN kind = 'income' OR to_account_id = $1 THEN amount ELSE -amount END,
+                $2, $3, spent_on
+            )), 0))::BIGINT AS balance
+            FROM expenses
+            WHERE (account_id = $1 OR to_account_id = $1) AND spent_on <= $6
+            "#,
+        )
+        .bind(account.id().to_string())
+        .bind(account.currency().as_str())
+        .bind(currency.as_str())
+        .bind(account.opening_balance())
+        .bind(account.created_at().date_naive())
+        .bind(as_of)
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to compute balance of account {}", account.id()))?;
+        Ok(row.try_get("balance").context("invalid balance row")?)
+    }
+
+    async fn account_balance_history(
+        &self,
+        account: &Account,
+        currency: &Currency,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<BalancePoint>, AccountError> {
+        self.ensure_exchange_rates(account, currency, until).await?;
+        let rows = sqlx::query(
+            r#"
+            WITH movements AS (
+                SELECT spent_on, convert_amount(
+                    CASE WHEN kind = 'income' OR to_account_id = $1 THEN amount ELSE -amount END,
+                    $2, $3, spent_on
+                ) AS delta
+                FROM expenses
+                WHERE account_id = $1 OR to_account_id = $1
+            ),
+            daily AS (
+                SELECT spent_on, SUM(delta) AS delta
+                FROM movements
+                WHERE spent_on BETWEEN $6 AND $7
+                GROUP BY spent_on
+            )
+            SELECT day::DATE AS day,
+                (convert_amount($4, $2, $3, $5)
+                    + (SELECT COALESCE(SUM(delta), 0) FROM movements WHERE spent_on < $6)
+                    + SUM(COALESCE(daily.delta, 0)) OVER (ORDER BY day)
+                )::BIGINT AS balance
+            FROM generate_series($6::DATE, $7::DATE, INTERVAL '1 day') AS day
+            LEFT JOIN daily ON daily.spent_on = day::DATE
+            ORDER BY day
+            "#,
+        )
+        .bind(account.id().to_string())
+        .bind(account.currency().as_str())
+        .bind(currency.as_str())
+        .bind(account.opening_balance())
+        .bind(account.created_at().date_naive())
+        .bind(from)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to compute history of account {}", account.id()))?;
+        rows.iter()
+            .map(|row| {
+                Ok(BalancePoint::new(
+                    row.try_get("day")?,
+                    row.try_get("balance")?,
+                ))
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .context("invalid balance row")
+            .map_err(AccountError::from)
+    }
+}
+
+impl DuplicateRepository for Postgres {
+    async fn find_duplicate_settings(
+        &self,
+        owner_id: &Uuid,
+    ) -> Result<Option<DuplicateSettings>, DuplicateError> {
+        let row = sqlx::query(
+            r#"
+            SELECT mode, date_window_days, min_similarity
+            FROM duplicate_settings
+            WHERE owner_id = $1
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to find duplicate settings")?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mode: String = row.try_get("mode").context("invalid settings row")?;
+        let date_window_days: i32 = row
+            .try_get("date_window_days")
+            .context("invalid settings row")?;
+        let min_similarity: i16 = row
+            .try_get("min_similarity")
+            .context("invalid settings row")?;
+        let settings = DuplicateSettings::new(
+            DuplicateMode::from_str(&mode).context("invalid duplicate mode")?,
+            u32::try_from(date_window_days).context("invalid date window")?,
+            u8::try_from(min_similarity).context("invalid similarity")?,
+        )
+        .map_err(|e| anyhow!("invalid duplicate settings: {}", e))?;
+        Ok(Some(settings))
+    }
+
+    async fn save_duplicate_settings(
+        &self,
+        owner_id: &Uuid,
+        settings: &DuplicateSettings,
+    ) -> Result<(), DuplicateError> {
+        sqlx::query(
+            r#"
+            INSERT INTO duplicate_settings (owner_id, mode, date_window_days, min_similarity)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (owner_id) DO UPDATE
+            SET mode = EXCLUDED.mode,
+                date_window_days = EXCLUDED.date_window_days,
+                min_similarity = EXCLUDED.min_similarity,
+                updated_at = now()
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .bind(settings.mode().as_str())
+        .bind(settings.date_window_days() as i32)
+        .bind(settings.min_similarity() as i16)
+        .execute(&self.pool)
+        .await
+        .context("failed to save duplicate settings")?;
+        Ok(())
+    }
+
+    async fn list_duplicate_candidates(
+        &self,
+        owner_id: &Uuid,
+        ledger_id: Option<&Uuid>,
+        from: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<Vec<Expense>, DuplicateError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, kind, ledger_id, amount, paid_by, split_method, category_id,
+                spent_on, recurring_expense_id, account_id, to_account_id, counterparty, reference
+            FROM expenses
+            WHERE CASE
+                    WHEN $2::TEXT IS NULL THEN ledger_id IS NULL
+                        AND account_id IN (SELECT id FROM accounts WHERE owner_id = $1)
+                    ELSE ledger_id = $2
+                END
+                AND ($3::DATE IS NULL OR spent_on >= $3)
+                AND ($4::DATE IS NULL OR spent_on <= $4)
+            ORDER BY spent_on, id
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .bind(ledger_id.map(Uuid::to_string))
+        .bind(from)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list expenses to look for duplicates in")?;
+        Ok(self
+            .complete_expenses(rows)
+            .await
+            .context("invalid expense row")?)
+    }
+
+    async fn merge_duplicates(&self, merge: &DuplicateMerge) -> Result<(), DuplicateError> {
+        let expense = merge.expense();
+        let expense_id = expense.id().to_string();
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+        sqlx::query("UPDATE expenses SET category_id = $2 WHERE id = $1")
+            .bind(&expense_id)
+            .bind(expense.category_id().map(Uuid::to_string))
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("failed to update expense {}", expense_id))?;
+        let tags: Vec<&str> = expense.tags().iter().map(Tag::as_str).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO expense_tags (expense_id, tag)
+            SELECT $1, * FROM UNNEST($2::TEXT[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&expense_id)
+        .bind(tags)
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("failed to tag expense {}", expense_id))?;
+        let removed_ids: Vec<String> = merge.removed_ids().iter().map(Uuid::to_string).collect();
+        sqlx::query("DELETE FROM expenses WHERE id = ANY($1)")
+            .bind(&removed_ids)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete duplicate expenses")?;
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+        tracing::info!(
+            "Merged {} duplicates into expense {}",
+            removed_ids.len(),
+            expense_id
+        );
+        Ok(())
+    }
+}
+
+impl IdempotencyRepository for Postgres {
+    async fn claim_idempotency_key(
+        &self,
+        req: &ClaimIdempotencyKeyRequest,
+    ) -> Result<IdempotencyClaim, IdempotencyError> {
+        let owner_id = req.owner_id().to_string();
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (owner_id, key, fingerprint, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (owner_id, key) DO UPDATE
+            SET fingerprint = EXCLUDED.fingerprint,
+                status = NULL,
+                body = NULL,
+                created_at = now(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= now()
+            RETURNING key
+            "#,
+        )
+        .bind(&owner_id)
+        .bind(req.key().as_str())
+        .bind(req.fingerprint().as_str())
+        .bind(req.expires_at())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to claim idempotency key {}", req.key()))?;
+        if claimed.is_some() {
+            return Ok(IdempotencyClaim::Acquired);
+        }
+
+        let row = sqlx::query(
+            r#"
+            SELECT fingerprint, status, body
+            FROM idempotency_keys
+            WHERE owner_id = $1 AND key = $2
+            "#,
+        )
+        .bind(&owner_id)
+        .bind(req.key().as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find idempotency key {}", req.key()))?;
+        // Released since the insert was refused: the original request failed a moment ago.
+        let Some(row) = row else {
+            return Ok(IdempotencyClaim::InProgress);
+        };
+        let fingerprint: String = row.try_get("fingerprint").context("invalid key row")?;
+        if RequestFingerprint::from_hex(fingerprint) != *req.fingerprint() {
+            return Ok(IdempotencyClaim::Mismatch);
+        }
+        let status: Option<i16> = row.try_get("status").context("invalid key row")?;
+        let body: Option<Vec<u8>> = row.try_get("body").context("invalid key row")?;
+        Ok(match (status, body) {
+            (Some(status), Some(body)) => IdempotencyClaim::Replay(StoredResponse::new(
+                u16::try_from(status).context("invalid response status")?,
+                body,
+            )),
+            _ => IdempotencyClaim::InProgress,
+        })
+    }
+
+    async fn save_idempotent_response(
+        &self,
+        owner_id: &Uuid,
+        key: &IdempotencyKey,
+        response: &StoredResponse,
+    ) -> Result<(), IdempotencyError> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status = $3, body = $4
+            WHERE owner_id = $1 AND key = $2
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .bind(key.as_str())
+        .bind(response.status() as i16)
+        .bind(response.body())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to save the response to idempotency key {}", key))?;
+        Ok(())
+    }
+
+    async fn release_idempotency_key(
+        &self,
+        owner_id: &Uuid,
+        key: &IdempotencyKey,
+    ) -> Result<(), IdempotencyError> {
+        sqlx::query(
+            "DELETE FROM idempotency_keys WHERE owner_id = $1 AND key = $2 AND status IS NULL",
+        )
+        .bind(owner_id.to_string())
+        .bind(key.as_str())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to release idempotency key {}", key))?;
+        Ok(())
+    }
+
+    async fn delete_expired_idempotency_keys(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<usize, IdempotencyError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete expired idempotency keys")?;
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+impl RuleRepository for Postgres {
+    async fn create_rule(&self, req: &CreateRuleRequest) -> Result<Rule, RuleError> {
+        let id = Uuid::new_v4();
+        let conditions = req.conditions();
+        let actions = req.actions();
+        let tags: Vec<&str> = actions.tags().iter().map(Tag::as_str).collect();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO rules (id, owner_id, name, priority, name_pattern, min_amount, max_amount,
+                account_id, counterparty, kind, category_id, tags, rename)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.owner_id().to_string())
+        .bind(req.name().to_string())
+        .bind(req.priority())
+        .bind(conditions.name_pattern().map(NamePattern::as_str))
+        .bind(conditions.min_amount())
+        .bind(conditions.max_amount())
+        .bind(conditions.account_id().map(Uuid::to_string))
+        .bind(conditions.counterparty())
+        .bind(conditions.kind().map(|kind| kind.as_str()))
+        .bind(actions.category_id().map(Uuid::to_string))
+        .bind(tags)
+        .bind(actions.rename().map(ToString::to_string))
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to save rule {}", req.name()))?;
+        let created_at: DateTime<Utc> = row.try_get("created_at").context("invalid rule row")?;
+        tracing::info!("Rule saved with ID: {}", id);
+
+        Ok(Rule::new(
+            id,
+            *req.owner_id(),
+            req.name().clone(),
+            req.priority(),
+            conditions.clone(),
+            actions.clone(),
+            created_at,
+        ))
+    }
+
+    async fn find_rule(&self, id: &Uuid) -> Result<Option<Rule>, RuleError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, owner_id, name, priority, name_pattern, min_amount, max_amount, account_id,
+                counterparty, kind, category_id, tags, rename, created_at
+            FROM rules
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to find rule {}", id))?;
+        row.as_ref()
+            .map(decode_rule)
+            .transpose()
+            .context("invalid rule row")
+            .map_err(RuleError::from)
+    }
+
+    async fn list_rules(&self, owner_id: &Uuid) -> Result<Vec<Rule>, RuleError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, owner_id, name, priority, name_pattern, min_amount, max_amount, account_id,
+                counterparty, kind, category_id, tags, rename, created_at
+            FROM rules
+            WHERE owner_id = $1
+            ORDER BY priority, created_at, id
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list rules")?;
+        rows.iter()
+            .map(decode_rule)
+            .collect::<Result<_, _>>()
+            .context("invalid rule row")
+            .map_err(RuleError::from)
+    }
+
+    async fn delete_rule(&self, id: &Uuid) -> Result<bool, RuleError> {
+        let result = sqlx::query("DELETE FROM rules WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to delete rule {}", id))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_rule_candidates(&self, owner_id: &Uuid) -> Result<Vec<Expense>, RuleError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, kind, ledger_id, amount, paid_by, split_method, category_id,
+                spent_on, recurring_expense_id, account_id, to_account_id, counterparty, reference
+            FROM expenses
+            WHERE ledger_id IS NULL
+                AND account_id IN (SELECT id FROM accounts WHERE owner_id = $1)
+            ORDER BY spent_on, id
+            "#,
+        )
+        .bind(owner_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list expenses to apply rules to")?;
+        Ok(self
+            .complete_expenses(rows)
+            .await
+            .context("invalid expense row")?)
+    }
+
+    async fn save_rule_changes(&self, changes: &[RuleChange]) -> Result<(), RuleError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start PostgreSQL transaction")?;
+        for change in changes {
+            let expense_id = change.expense_id().to_string();
+            let after = change.after();
+            sqlx::query("UPDATE expenses SET name = $2, category_id = $3 WHERE id = $1")
+                .bind(&expense_id)
+                .bind(after.name().to_string())
+                .bind(after.category_id().map(Uuid::to_string))
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("failed to update expense {}", expense_id))?;
+            let tags: Vec<&str> = after.tags().iter().map(Tag::as_str).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO expense_tags (expense_id, tag)
+                SELECT $1, * FROM UNNEST($2::TEXT[])
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(&expense_id)
+            .bind(tags)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("failed to tag expense {}", expense_id))?;
+        }
+        tx.commit()
+            .await
+            .context("failed to commit PostgreSQL transaction")?;
+        tracing::info!("Rules changed {} expenses", changes.len());
+        Ok(())
+    }
+}
+
+impl ExchangeRateRepository for Postgres {
+    async fn save_exchange_rates(
+        &self,
+        rates: &[ExchangeRate],
+    ) -> Result<usize, ExchangeRateError> {
+        let currencies: Vec<&str> = rates.iter().map(|r| r.currency().as_str()).collect();
+        let days: Vec<NaiveDate> = rates.iter().map(|r| *r.rate_on()).collect();
+        let values: Vec<String> = rates.iter().map(|r| r.rate().to_string()).collect();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO exchange_rates (currency, rate_on, rate)
+            SELECT currency, rate_on, rate::NUMERIC
+            FROM UNNEST($1::TEXT[], $2::DATE[], $3::TEXT[]) AS r (currency, rate_on, rate)
+            ON CONFLICT (currency, rate_on) DO UPDATE SET rate = EXCLUDED.rate
+            "#,
+        )
+        .bind(currencies)
+        .bind(days)
+        .bind(values)
+        .execute(&self.pool)
+        .await
+        .context("failed to save exchange rates")?;
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+impl ReportRepository for Postgres {
+    async fn spending_summary(
+        &self,
+        scope: &ReportScope,
+        req: &SummaryRequest,
+    ) -> Result<Vec<SummaryBucket>, ReportError> {
+        let (ledger_id, owner_id) = match scope {
+            ReportScope::Ledger(ledger_id) => (Some(ledger_id.to_string()), None),
+            ReportScope::Personal(owner_id) => (None, Some(owner_id.to_string())),
+        };
+        // Expenses recorded before accounts existed have no currency: they are taken to be in
+        // the reference currency.
+        let rows = sqlx::query(
+            r#"
+            WITH scoped AS (
+                SELECT e.id, e.spent_on, e.category_id, e.amount,
+                    COALESCE(a.currency::TEXT, $3) AS from_currency
+                FROM expenses e
+                LEFT JOIN accounts a ON a.id = e.account_id
+                WHERE e.kind = 'expense'
+                    AND CASE
+                        WHEN $1::TEXT IS NOT NULL THEN e.ledger_id = $1
+                        ELSE e.ledger_id IS NULL AND a.owner_id = $2
+                    END
+                    AND ($4::DATE IS NULL OR e.spent_on >= $4)
+                    AND ($5::DATE IS NULL OR e.spent_on <= $5)
+            ),
+            converted AS (
+                SELECT s.*, COALESCE($6, s.from_currency) AS currency,
+                    convert_amount(s.amount, s.from_currency, COALESCE($6, s.from_currency),
+                        s.spent_on) AS converted
+                FROM scoped s
+            )
+            SELECT *
+            FROM (
+                SELECT
+                    CASE $7
+                        WHEN 'month' THEN to_char(x.spent_on, 'YYYY-MM')
+                        WHEN 'category' THEN x.category_id
+                        ELSE t.tag
+                    END AS key,
+                    c.name AS label,
+                    x.currency,
+                    COALESCE(SUM(x.converted), 0)::BIGINT AS total,
+                    COUNT(*) AS count,
+                    COALESCE(ROUND(AVG(x.converted)), 0)::BIGINT AS average,
+                    MIN(x.spent_on) FILTER (WHERE x.converted IS NULL) AS missing_on,
+                    (ARRAY_AGG(x.from_currency ORDER BY x.spent_on)
+                        FILTER (WHERE x.converted IS NULL))[1] AS missing_from
+                FROM converted x
+                LEFT JOIN categories c ON $7 = 'category' AND c.id = x.category_id
+                LEFT JOIN expense_tags t ON $7 = 'tag' AND t.expense_id = x.id
+                GROUP BY 1, 2, 3
+            ) buckets
+            ORDER BY CASE WHEN $7 = 'month' THEN key END, total DESC, key NULLS LAST, currency
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(owner_id)
+        .bind(REFERENCE_CURRENCY)
+        .bind(req.from())
+        .bind(req.to())
+        .bind(req.currency().map(Currency::as_str))
+        .bind(req.grouping().as_str())
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to summarize spending")?;
+
+        let mut missing: Option<MissingExchangeRate> = None;
+        let mut buckets = Vec::with_capacity(rows.len());
+        for row in rows {
+            let currency = decode_currency(&row, "currency").context("invalid summary row")?;
+            let missing_on: Option<NaiveDate> =
+                row.try_get("missing_on").context("invalid summary row")?;
+            if let Some(on) = missing_on
+                && missing.as_ref().is_none_or(|m| on < m.on)
+            {
+                missing = Some(MissingExchangeRate {
+                    from: decode_currency(&row, "missing_from").context("invalid summary row")?,
+                    to: currency.clone(),
+                    on,
+                });
+            }
+            let mut bucket = SummaryBucket::new(
+                row.try_get("key").context("invalid summary row")?,
+                currency,
+                row.try_get("total").context("invalid summary row")?,
+                row.try_get("count").context("invalid summary row")?,
+                row.try_get("average").context("invalid summary row")?,
+            );
+            if let Some(label) = row.try_get("label").context("invalid summary row")? {
+                bucket = bucket.with_label(label);
+            }
+            buckets.push(bucket);
+        }
+        match missing {
+            Some(missing) => Err(missing.into()),
+            None => Ok(buckets),
+        }
+    }
+
+    async fn spending_timeseries(
+        &self,
+        scope: &ReportScope,
+        req: &TimeseriesRequest,
+    ) -> Result<Vec<Series>, ReportError> {
+        let (ledger_id, owner_id) = match scope {
+            ReportScope::Ledger(ledger_id) => (Some(ledger_id.to_string()), None),
+            ReportScope::Personal(owner_id) => (None, Some(owner_id.to_string())),
+        };
+        let points = req.points() as u32;
+        // Expenses of the previous period are read along, then moved forward by the length of
+        // the series onto the interval they compare with.
+        let read_from = if req.has_comparison() {
+            req.interval().back(req.start(), points)
+        } else {
+            req.start()
+        };
+        let rows = sqlx::query(
+            r#"
+            WITH buckets AS (
+                SELECT b::DATE AS start
+                FROM generate_series($4::TIMESTAMP, $5::TIMESTAMP, ('1 ' || $7)::INTERVAL) b
+            ),
+            scoped AS (
+                SELECT e.spent_on, e.amount, COALESCE(a.currency::TEXT, $3) AS from_currency
+                FROM expenses e
+                LEFT JOIN accounts a ON a.id = e.account_id
+                WHERE e.kind = 'expense'
+                    AND CASE
+                        WHEN $1::TEXT IS NOT NULL THEN e.ledger_id = $1
+                        ELSE e.ledger_id IS NULL AND a.owner_id = $2
+                    END
+                    AND e.spent_on >= $10::DATE
+                    AND e.spent_on < ($5::TIMESTAMP + ('1 ' || $7)::INTERVAL)::DATE
+            ),
+            converted AS (
+                SELECT s.spent_on, s.from_currency, COALESCE($6, s.from_currency) AS currency,
+                    convert_amount(s.amount, s.from_currency, COALESCE($6, s.from_currency),
+                        s.spent_on) AS converted,
+                    s.spent_on >= $4::DATE AS current,
+                    CASE
+                        WHEN s.spent_on >= $4::DATE THEN date_trunc($7, s.spent_on::TIMESTAMP)
+                        ELSE date_trunc($7, s.spent_on::TIMESTAMP)
+                            + $11::INTEGER * ('1 ' || $7)::INTERVAL
+                    END::DATE AS start
+                FROM scoped s
+            ),
+            currencies AS (
+                SELECT DISTINCT currency FROM converted
+                UNION
+                SELECT $6 WHERE $6::TEXT IS NOT NULL
+            ),
+            points AS (
+                SELECT g.currency, b.start,
+                    COALESCE(SUM(x.converted) FILTER (WHERE x.current), 0) AS total,
+                    COALESCE(SUM(x.converted) FILTER (WHERE NOT x.current), 0) AS previous_total,
+                    MIN(x.spent_on) FILTER (WHERE x.converted IS NULL) AS missing_on,
+                    (ARRAY_AGG(x.from_currency ORDER BY x.spent_on)
+                        FILTER (WHERE x.converted IS NULL))[1] AS missing_from
+                FROM currencies g
+                CROSS JOIN buckets b
+                LEFT JOIN converted x ON x.currency = g.currency AND x.start = b.start
+                GROUP BY g.currency, b.start
+            )
+            SELECT currency, start,
+                (CASE WHEN $8 THEN SUM(total) OVER w ELSE total END)::BIGINT AS total,
+                (CASE
+                    WHEN $9 AND $8 THEN SUM(previous_total) OVER w
+                    WHEN $9 THEN previous_total
+                END)::BIGINT AS previous_total,
+                missing_on,
+                missing_from
+            FROM points
+            WINDOW w AS (PARTITION BY currency ORDER BY start)
+            ORDER BY currency, start
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(owner_id)
+        .bind(REFERENCE_CURRENCY)
+        .bind(req.start())
+        .bind(req.last_start())
+        .bind(req.currency().map(Currency::as_str))
+        .bind(req.interval().as_str())
+        .bind(req.is_cumulative())
+        .bind(req.has_comparison())
+        .bind(read_from)
+        .bind(points as i32)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to compute spending time series")?;
+
+        let mut missing: Option<MissingExchangeRate> = None;
+        let mut series: Vec<(Currency, Vec<SeriesPoint>)> = Vec::new();
+        for row in rows {
+            let currency = decode_currency(&row, "currency").context("invalid series row")?;
+            let missing_on: Option<NaiveDate> =
+                row.try_get("missing_on").context("invalid series row")?;
+            if let Some(on) = missing_on
+                && missing.as_ref().is_none_or(|m| on < m.on)
+            {
+                missing = Some(MissingExchangeRate {
+                    from: decode_currency(&row, "missing_from").context("invalid series row")?,
+                    to: currency.clone(),
+                    on,
+                });
+            }
+            let mut point = SeriesPoint::new(
+                row.try_get("start").context("invalid series row")?,
+                row.try_get("total").context("invalid series row")?,
+            );
+            let previous_total: Option<i64> = row
+                .try_get("previous_total")
+                .context("invalid series row")?;
+            if let Some(previous_total) = previous_total {
+                point = point.with_previous_total(previous_total);
+            }
+            match series.last_mut() {
+                Some((last, points)) if *last == currency => points.push(point),
+                _ => series.push((currency, vec![point])),
+            }
+        }
+        match missing {
+            Some(missing) => Err(missing.into()),
+            None => Ok(series
+                .into_iter()
+                .map(|(currency, points)| Series::new(currency, points))
+                .collect()),
+        }
+    }
+}
+
+impl ApiKeyRepository for Postgres {
+    async fn create_api_key(
+        &self,
+        req: &CreateApiKeyRequest,
+        secret: &ApiKeySecret,
+    ) -> Result<ApiKey, ApiKeyError> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, user_id, name, prefix, key_hash, scope)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.user_id().to_string())
+        .bind(req.name().to_string())
+        .bind(secret.prefix())
+        .bind(secret.hash())
+        .bind(req.scope().as_str())
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to save api key for user {}", req.user_id()))?;
+        let created_at: DateTime<Utc> = row.try_get("created_at").context("invalid api key row")?;
+        tracing::info!("API key saved with ID: {}", id);
+
+        Ok(ApiKey::new(
+            id,
+            *req.user_id(),
+            req.name().clone(),
+            secret.prefix().to_string(),
+            req.scope(),
+            created_at,
+        ))
+    }
+
+    async fn find_api_key_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Option<(ApiKey, String)>, ApiKeyError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, name, prefix, key_hash, scope, created_at, last_used_at, revoked_at
+            FROM api_keys
+            WHERE prefix = $1
+            "#,
+        )
+        .bind(prefix)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to find api key")?;
+        row.map(|row| -> Result<(ApiKey, String), sqlx::Error> {
+            Ok((decode_api_key(&row)?, row.try_get("key_hash")?))
+        })
+        .transpose()
+        .context("invalid api key row")
+        .map_err(ApiKeyError::from)
+    }
+
+    async fn list_api_keys_for_user(&self, user_id: &Uuid) -> Result<Vec<ApiKey>, ApiKeyError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, name, prefix, scope, created_at, last_used_at, revoked_at
+            FROM api_keys
+            WHERE user_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("failed to list api keys for user {}", user_id))?;
+        rows.iter()
+            .map(decode_api_key)
+            .collect::<Result<_, _>>()
+            .context("invalid api key row")
+            .map_err(ApiKeyError::from)
+    }
+
+    async fn revoke_api_key(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+    ) -> Result<Option<ApiKey>, ApiKeyError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = COALESCE(revoked_at, now())
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, prefix, scope, created_at, last_used_at, revoked_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("failed to revoke api key {}", id))?;
+        row.as_ref()
+            .map(decode_api_key)
+            .transpose()
+            .context("invalid api key row")
+            .map_err(ApiKeyError::from)
+    }
+
+    async fn touch_api_key(&self, id: &Uuid, at: DateTime<Utc>) -> Result<(), ApiKeyError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = $1 WHERE id = $2")
+            .bind(at)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("failed to record use of api key {}", id))?;
+        Ok(())
+    }
+}
+
+fn decode_uuid(row: &PgRow, index: &str) -> Result<Uuid, sqlx::Error> {
+    let raw: String = row.try_get(index)?;
+    Uuid::parse_str(&raw).map_err(|e| sqlx::Error::ColumnDecode {
+        index: index.into(),
+        source: Box::new(e),
+    })
+}
+
+fn decode_optional_uuid(row: &PgRow, index: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    let raw: Option<String> = row.try_get(index)?;
+    raw.map(|raw| {
+        Uuid::parse_str(&raw).map_err(|e| sqlx::Error::ColumnDecode {
+            index: index.into(),
+            source: Box::new(e),
+        })
+    })
+    .transpose()
+}
+
+fn decode_currency(row: &PgRow, index: &str) -> Result<Currency, sqlx::Error> {
+    let currency: String = row.try_get(index)?;
+    Currency::new(&currency).map_err(|e| sqlx::Error::ColumnDecode {
+        index: index.into(),
+        source: Box::new(e),
+    })
+}
+
+fn decode_role(row: &PgRow) -> Result<LedgerRole, sqlx::Error> {
+    let raw: String = row.try_get("role")?;
+    LedgerRole::from_str(&raw).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "role".into(),
+        source: Box::new(e),
+    })
+}
+
+fn decode_ledger(row: &PgRow) -> Result<Ledger, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = LedgerName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    Ok(Ledger::new(
+        decode_uuid(row, "id")?,
+        name,
+        row.try_get("created_at")?,
+    ))
+}
+
+fn decode_ledger_member(row: &PgRow) -> Result<LedgerMember, sqlx::Error> {
+    Ok(LedgerMember::new(
+        decode_uuid(row, "ledger_id")?,
+        decode_uuid(row, "user_id")?,
+        decode_role(row)?,
+        row.try_get("joined_at")?,
+    ))
+}
+
+fn decode_category(row: &PgRow) -> Result<Category, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = CategoryName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    Ok(Category::new(
+        decode_uuid(row, "id")?,
+        name,
+        decode_optional_uuid(row, "ledger_id")?,
+        decode_uuid(row, "owner_id")?,
+        row.try_get("created_at")?,
+    ))
+}
+
+fn decode_budget(row: &PgRow) -> Result<Budget, sqlx::Error> {
+    let period: String = row.try_get("period")?;
+    let period = BudgetPeriod::from_str(&period).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "period".into(),
+        source: Box::new(e),
+    })?;
+    Ok(Budget::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "category_id")?,
+        decode_optional_uuid(row, "ledger_id")?,
+        decode_uuid(row, "owner_id")?,
+        row.try_get("amount")?,
+        period,
+        row.try_get("created_at")?,
+    )
+    .with_currency(decode_currency(row, "currency")?))
+}
+
+fn decode_rule(row: &PgRow) -> Result<Rule, sqlx::Error> {
+    let decode_error =
+        |index: &str, e: Box<dyn std::error::Error + Send + Sync>| sqlx::Error::ColumnDecode {
+            index: index.into(),
+            source: e,
+        };
+    let name: String = row.try_get("name")?;
+    let name = RuleName::new(&name).map_err(|e| decode_error("name", Box::new(e)))?;
+
+    let mut conditions = RuleConditions::new()
+        .with_amount_range(row.try_get("min_amount")?, row.try_get("max_amount")?)
+        .map_err(|e| decode_error("min_amount", Box::new(e)))?;
+    if let Some(pattern) = row.try_get::<Option<&str>, _>("name_pattern")? {
+        let pattern =
+            NamePattern::new(pattern).map_err(|e| decode_error("name_pattern", Box::new(e)))?;
+        conditions = conditions.with_name_pattern(pattern);
+    }
+    if let Some(account_id) = decode_optional_uuid(row, "account_id")? {
+        conditions = conditions.with_account(account_id);
+    }
+    if let Some(counterparty) = row.try_get::<Option<&str>, _>("counterparty")? {
+        conditions = conditions.with_counterparty(counterparty);
+    }
+    if let Some(kind) = row.try_get::<Option<&str>, _>("kind")? {
+        let kind =
+            TransactionKind::from_str(kind).map_err(|e| decode_error("kind", Box::new(e)))?;
+        conditions = conditions.with_kind(kind);
+    }
+
+    let tags = row
+        .try_get::<Vec<String>, _>("tags")?
+        .iter()
+        .map(|tag| Tag::new(tag))
+        .collect::<Result<_, _>>()
+        .map_err(|e| decode_error("tags", Box::new(e)))?;
+    let mut actions = RuleActions::new().with_tags(tags);
+    if let Some(category_id) = decode_optional_uuid(row, "category_id")? {
+        actions = actions.with_category(category_id);
+    }
+    if let Some(rename) = row.try_get::<Option<&str>, _>("rename")? {
+        let rename = ExpenseName::new(rename).map_err(|e| decode_error("rename", Box::new(e)))?;
+        actions = actions.with_rename(rename);
+    }
+
+    Ok(Rule::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "owner_id")?,
+        name,
+        row.try_get("priority")?,
+        conditions,
+        actions,
+        row.try_get("created_at")?,
+    ))
+}
+
+fn decode_recurring_expense(row: &PgRow) -> Result<RecurringExpense, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = ExpenseName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    let frequency: String = row.try_get("frequency")?;
+    let frequency = Frequency::from_str(&frequency).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "frequency".into(),
+        source: Box::new(e),
+    })?;
+    let interval: i32 = row.try_get("interval_count")?;
+    let day_of_month: Option<i16> = row.try_get("day_of_month")?;
+    let ends_on: Option<NaiveDate> = row.try_get("ends_on")?;
+    let invalid = |e: RecurrenceError| sqlx::Error::ColumnDecode {
+        index: "frequency".into(),
+        source: Box::new(e),
+    };
+    let mut recurrence = Recurrence::new(frequency, row.try_get("starts_on")?)
+        .with_interval(interval.try_into().unwrap_or(0))
+        .map_err(invalid)?;
+    if let Some(day) = day_of_month {
+        recurrence = recurrence
+            .with_day_of_month(day.try_into().unwrap_or(0))
+            .map_err(invalid)?;
+    }
+    if let Some(ends_on) = ends_on {
+        recurrence = recurrence.with_ends_on(ends_on).map_err(invalid)?;
+    }
+
+    let mut recurring = RecurringExpense::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "owner_id")?,
+        name,
+        row.try_get("amount")?,
+        recurrence,
+        row.try_get("created_at")?,
+    )
+    .with_materialized_through(row.try_get("materialized_through")?);
+    if let Some(ledger_id) = decode_optional_uuid(row, "ledger_id")? {
+        recurring = recurring.with_ledger(ledger_id);
+    }
+    if let Some(category_id) = decode_optional_uuid(row, "category_id")? {
+        recurring = recurring.with_category(category_id);
+    }
+    if let Some(account_id) = decode_optional_uuid(row, "account_id")? {
+        recurring = recurring.with_account(account_id);
+    }
+    Ok(recurring)
+}
+
+/// Decodes an expense row, completed with its split shares, if it is split, its tags and its
+/// attachments.
+fn decode_expense(
+    row: &PgRow,
+    shares: Vec<ExpenseShare>,
+    tags: Vec<Tag>,
+    attachments: Vec<Attachment>,
+) -> Result<Expense, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = ExpenseName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    let kind: String = row.try_get("kind")?;
+    let kind = TransactionKind::from_str(&kind).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "kind".into(),
+        source: Box::new(e),
+    })?;
+
+    let mut expense = Expense::new(decode_uuid(row, "id")?, name)
+        .with_kind(kind)
+        .with_amount(row.try_get("amount")?)
+        .with_spent_on(row.try_get("spent_on")?)
+        .with_tags(tags)
+        .with_attachments(attachments);
+    if let Some(ledger_id) = decode_optional_uuid(row, "ledger_id")? {
+        expense = expense.with_ledger(ledger_id);
+    }
+    if let Some(category_id) = decode_optional_uuid(row, "category_id")? {
+        expense = expense.with_category(category_id);
+    }
+    if let Some(recurring_expense_id) = decode_optional_uuid(row, "recurring_expense_id")? {
+        expense = expense.with_recurring_expense(recurring_expense_id);
+    }
+    if let Some(account_id) = decode_optional_uuid(row, "account_id")? {
+        expense = expense.with_account(account_id);
+    }
+    if let Some(to_account_id) = decode_optional_uuid(row, "to_account_id")? {
+        expense = expense.with_to_account(to_account_id);
+    }
+    if let Some(counterparty) = row.try_get::<Option<&str>, _>("counterparty")? {
+        expense = expense.with_counterparty(counterparty);
+    }
+    if let Some(reference) = row.try_get::<Option<&str>, _>("reference")? {
+        expense = expense.with_reference(reference);
+    }
+    let split_method: Option<String> = row.try_get("split_method")?;
+    if let (Some(paid_by), Some(method)) = (decode_optional_uuid(row, "paid_by")?, split_method) {
+        let method = SplitMethod::from_str(&method).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "split_method".into(),
+            source: Box::new(e),
+        })?;
+        expense = expense.with_split(ExpenseSplit::from_shares(paid_by, method, shares));
+    }
+    Ok(expense)
+}
+
+/// Decodes an expense row carrying its tags and split shares as arrays.
+fn decode_aggregated_expense(row: &PgRow) -> Result<Expense, sqlx::Error> {
+    let tags = row
+        .try_get::<Vec<String>, _>("tags")?
+        .iter()
+        .map(|tag| Tag::new(tag))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| sqlx::Error::ColumnDecode {
+            index: "tags".into(),
+            source: Box::new(e),
+        })?;
+    let user_ids: Vec<String> = row.try_get("share_user_ids")?;
+    let amounts: Vec<i64> = row.try_get("share_amounts")?;
+    let shares = user_ids
+        .iter()
+        .zip(amounts)
+        .map(|(user_id, amount)| {
+            Uuid::parse_str(user_id)
+                .map(|user_id| ExpenseShare::new(user_id, amount))
+                .map_err(|e| sqlx::Error::ColumnDecode {
+                    index: "share_user_ids".into(),
+                    source: Box::new(e),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let expense_id = decode_uuid(row, "id")?;
+    let ids: Vec<String> = row.try_get("attachment_ids")?;
+    let file_names: Vec<String> = row.try_get("attachment_file_names")?;
+    let content_types: Vec<String> = row.try_get("attachment_content_types")?;
+    let sizes: Vec<i64> = row.try_get("attachment_sizes")?;
+    let created_at: Vec<DateTime<Utc>> = row.try_get("attachment_created_at")?;
+    let thumbnail_statuses: Vec<String> = row.try_get("attachment_thumbnail_statuses")?;
+    let attachments = ids
+        .iter()
+        .zip(file_names)
+        .zip(content_types)
+        .zip(sizes)
+        .zip(created_at)
+        .zip(thumbnail_statuses)
+        .map(
+            |(((((id, file_name), content_type), size), created_at), thumbnail_status)| {
+                let id = Uuid::parse_str(id).map_err(|e| sqlx::Error::ColumnDecode {
+                    index: "attachment_ids".into(),
+                    source: Box::new(e),
+                })?;
+                let content_type = AttachmentContentType::from_str(&content_type).map_err(|e| {
+                    sqlx::Error::ColumnDecode {
+                        index: "attachment_content_types".into(),
+                        source: Box::new(e),
+                    }
+                })?;
+                let thumbnail_status =
+                    ThumbnailStatus::from_str(&thumbnail_status).map_err(|e| {
+                        sqlx::Error::ColumnDecode {
+                            index: "attachment_thumbnail_statuses".into(),
+                            source: Box::new(e),
+                        }
+                    })?;
+                Ok(Attachment::new(
+                    id,
+                    expense_id,
+                    &file_name,
+                    content_type,
+                    size as u64,
+                    created_at,
+                )
+                .with_thumbnail_status(thumbnail_status))
+            },
+        )
+        .collect::<Result<Vec<_>, sqlx::Error>>()?;
+    decode_expense(row, shares, tags, attachments)
+}
+
+fn decode_attachment(row: &PgRow) -> Result<Attachment, sqlx::Error> {
+    let content_type: String = row.try_get("content_type")?;
+    let content_type =
+        AttachmentContentType::from_str(&content_type).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "content_type".into(),
+            source: Box::new(e),
+        })?;
+    let size: i64 = row.try_get("size")?;
+    let thumbnail_status: String = row.try_get("thumbnail_status")?;
+    let thumbnail_status =
+        ThumbnailStatus::from_str(&thumbnail_status).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "thumbnail_status".into(),
+            source: Box::new(e),
+        })?;
+    Ok(Attachment::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "expense_id")?,
+        row.try_get("file_name")?,
+        content_type,
+        size as u64,
+        row.try_get("created_at")?,
+    )
+    .with_thumbnail_status(thumbnail_status))
+}
+
+fn decode_account(row: &PgRow) -> Result<Account, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = AccountName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    let kind: String = row.try_get("kind")?;
+    let kind = AccountKind::from_str(&kind).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "kind".into(),
+        source: Box::new(e),
+    })?;
+    Ok(Account::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "owner_id")?,
+        name,
+        kind,
+        decode_currency(row, "currency")?,
+        row.try_get("opening_balance")?,
+        row.try_get("created_at")?,
+    ))
+}
+
+fn decode_api_key(row: &PgRow) -> Result<ApiKey, sqlx::Error> {
+    let name: String = row.try_get("name")?;
+    let name = ApiKeyName::new(&name).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "name".into(),
+        source: Box::new(e),
+    })?;
+    let scope: String = row.try_get("scope")?;
+    let scope = Scope::from_str(&scope).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "scope".into(),
+        source: Box::new(e),
+    })?;
+    Ok(ApiKey::new(
+        decode_uuid(row, "id")?,
+        decode_uuid(row, "user_id")?,
+        name,
+        row.try_get("prefix")?,
+        scope,
+        row.try_get("created_at")?,
+    )
+    .with_last_used_at(row.try_get("last_used_at")?)
+    .with_revoked_at(row.try_get("revoked_at")?))
+}
+
+/// Postgres `unique_violation` SQLSTATE.
+const UNIQUE_CONSTRAINT_VIOLATION_CODE: &str = "23505";
+
+/// The unique index allowing a single expense per occurrence of a recurring expense.
+const RECURRING_OCCURRENCE_CONSTRAINT: &str = "expenses_recurring_occurrence_idx";
+
+fn is_unique_constraint_violation(err: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(db_err) = err
+        && let Some(code) = db_err.code()
+    {
+        return code == UNIQUE_CONSTRAINT_VIOLATION_CODE;
+    }
+
+    false
+}
+
+fn violated_constraint(err: &sqlx::Error) -> Option<&str> {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.constraint(),
+        _ => None,
+    }
+}
+
+/// The [Expense] saved as `expense_id` from `req`.
+fn saved_expense(expense_id: Uuid, req: &CreateExpenseRequest) -> Expense {
+    let mut expense = Expense::new(expense_id, req.name().clone())
+        .with_kind(req.kind())
+        .with_amount(req.amount())
+        .with_spent_on(*req.spent_on());
+    if let Some(ledger_id) = req.ledger_id() {
+        expense = expense.with_ledger(*ledger_id);
+    }
+    if let Some(category_id) = req.category_id() {
+        expense = expense.with_category(*category_id);
+    }
+    if let Some(recurring_expense_id) = req.recurring_expense_id() {
+        expense = expense.with_recurring_expense(*recurring_expense_id);
+    }
+    if let Some(account_id) = req.account_id() {
+        expense = expense.with_account(*account_id);
+    }
+    if let Some(to_account_id) = req.to_account_id() {
+        expense = expense.with_to_account(*to_account_id);
+    }
+    if let Some(split) = req.split() {
+        expense = expense.with_split(split.clone());
+    }
+    if let Some(counterparty) = req.counterparty() {
+        expense = expense.with_counterparty(counterparty);
+    }
+    if let Some(reference) = req.reference() {
+        expense = expense.with_reference(reference);
+    }
+    expense.with_tags(req.tags().to_vec())
 }
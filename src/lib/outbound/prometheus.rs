@@ -1,7 +1,7 @@
 use crate::domain::finance::ports::FinanceMetrics;
 
 /// An unimplemented example of an adapter to [FinanceMetrics].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Prometheus;
 
 impl Prometheus {
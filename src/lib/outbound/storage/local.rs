@@ -0,0 +1,86 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, anyhow};
+use uuid::Uuid;
+
+use crate::domain::finance::models::attachment::{AttachmentContentType, AttachmentError};
+use crate::domain::finance::ports::AttachmentStorage;
+
+/// An [AttachmentStorage] keeping each content as a file under a root directory, the key being
+/// its path relative to the root.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Stores files under `root`, which is created on first use.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The path of the file stored under `key`, refusing keys that would leave the root.
+    fn path(&self, key: &str) -> Result<PathBuf, AttachmentError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("invalid storage key {:?}", key).into());
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl AttachmentStorage for LocalStorage {
+    /// Writes the content to a temporary file first, then renames it, so that a partially
+    /// written file is never read.
+    async fn put(
+        &self,
+        key: &str,
+        _: AttachmentContentType,
+        content: &[u8],
+    ) -> Result<(), AttachmentError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("failed to create directory {}", parent.display()))?;
+        }
+        let partial = path.with_extension(format!("{}.partial", Uuid::new_v4().simple()));
+        tokio::fs::write(&partial, content)
+            .await
+            .with_context(|| format!("failed to write {}", partial.display()))?;
+        if let Err(e) = tokio::fs::rename(&partial, &path).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(anyhow!(e)
+                .context(format!("failed to move {} into place", path.display()))
+                .into());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AttachmentError> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!(e)
+                .context(format!("failed to read {}", path.display()))
+                .into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AttachmentError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(anyhow!(e)
+                .context(format!("failed to remove {}", path.display()))
+                .into()),
+        }
+    }
+}
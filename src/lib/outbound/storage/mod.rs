@@ -0,0 +1,48 @@
+/*!
+    Module `storage` keeps the content of attachments, either in a local directory or in an
+    S3-compatible object store such as MinIO, as configured at startup.
+*/
+
+pub mod local;
+pub mod s3;
+
+use crate::domain::finance::models::attachment::{AttachmentContentType, AttachmentError};
+use crate::domain::finance::ports::AttachmentStorage;
+
+use local::LocalStorage;
+use s3::S3Storage;
+
+/// The [AttachmentStorage] chosen by configuration.
+#[derive(Debug, Clone)]
+pub enum Storage {
+    Local(LocalStorage),
+    S3(S3Storage),
+}
+
+impl AttachmentStorage for Storage {
+    async fn put(
+        &self,
+        key: &str,
+        content_type: AttachmentContentType,
+        content: &[u8],
+    ) -> Result<(), AttachmentError> {
+        match self {
+            Storage::Local(storage) => storage.put(key, content_type, content).await,
+            Storage::S3(storage) => storage.put(key, content_type, content).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AttachmentError> {
+        match self {
+            Storage::Local(storage) => storage.get(key).await,
+            Storage::S3(storage) => storage.get(key).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AttachmentError> {
+        match self {
+            Storage::Local(storage) => storage.delete(key).await,
+            Storage::S3(storage) => storage.delete(key).await,
+        }
+    }
+}
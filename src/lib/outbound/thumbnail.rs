@@ -0,0 +1,189 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use anyhow::Context;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader, RgbImage};
+use pdfium_render::prelude::{PdfRenderConfig, Pdfium};
+
+use crate::domain::finance::models::attachment::{
+    AttachmentContentType, AttachmentError, THUMBNAIL_SIZE,
+};
+use crate::domain::finance::ports::ThumbnailRenderer;
+
+/// The quality thumbnails are encoded with, out of 100.
+const JPEG_QUALITY: u8 = 80;
+
+/// A [ThumbnailRenderer] resizing pictures, and rasterizing the first page of PDF documents
+/// through the system's Pdfium library.
+///
+/// Rendering is CPU-bound, so it runs on the blocking thread pool rather than on the runtime.
+#[derive(Clone)]
+pub struct Thumbnailer {
+    /// `None` if the Pdfium library could not be loaded, leaving PDF documents without
+    /// thumbnails.
+    pdfium: Option<Arc<Pdfium>>,
+}
+
+impl std::fmt::Debug for Thumbnailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Thumbnailer")
+            .field("pdfium", &self.pdfium.is_some())
+            .finish()
+    }
+}
+
+impl Thumbnailer {
+    /// Binds to the Pdfium library installed on the system, if any.
+    pub fn new() -> Self {
+        let pdfium = match Pdfium::bind_to_system_library() {
+            Ok(bindings) => Some(Arc::new(Pdfium::new(bindings))),
+            Err(e) => {
+                tracing::warn!("Pdfium is not available, PDF previews are disabled: {}", e);
+                None
+            }
+        };
+        Self { pdfium }
+    }
+
+    /// Resizes pictures only, leaving PDF documents without thumbnails.
+    pub fn pictures_only() -> Self {
+        Self { pdfium: None }
+    }
+
+    fn render_blocking(
+        &self,
+        content_type: AttachmentContentType,
+        content: &[u8],
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let image = match content_type {
+            AttachmentContentType::Jpeg => decode(content, ImageFormat::Jpeg)?,
+            AttachmentContentType::Png => decode(content, ImageFormat::Png)?,
+            AttachmentContentType::Webp => decode(content, ImageFormat::WebP)?,
+            AttachmentContentType::Pdf => match &self.pdfium {
+                Some(pdfium) => rasterize(pdfium, content)?,
+                None => return Ok(None),
+            },
+            AttachmentContentType::Gif | AttachmentContentType::Heic => return Ok(None),
+        };
+        let thumbnail = flatten(image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .encode_image(&thumbnail)
+            .context("failed to encode thumbnail")?;
+        Ok(Some(jpeg))
+    }
+}
+
+impl Default for Thumbnailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThumbnailRenderer for Thumbnailer {
+    /// Content that cannot be decoded gets no thumbnail, rather than failing: it would fail the
+    /// same way on every retry.
+    async fn render(
+        &self,
+        content_type: AttachmentContentType,
+        content: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, AttachmentError> {
+        let thumbnailer = self.clone();
+        let rendered = tokio::task::spawn_blocking(move || {
+            thumbnailer.render_blocking(content_type, &content)
+        })
+        .await
+        .context("thumbnail rendering panicked")?;
+        match rendered {
+            Ok(thumbnail) => Ok(thumbnail),
+            Err(e) => {
+                tracing::warn!("Failed to render {} thumbnail: {:#}", content_type, e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Decodes a picture, within the default memory limits of the decoders.
+fn decode(content: &[u8], format: ImageFormat) -> anyhow::Result<DynamicImage> {
+    ImageReader::with_format(Cursor::new(content), format)
+        .decode()
+        .with_context(|| format!("failed to decode {:?} picture", format))
+}
+
+/// Renders the first page of a PDF document, on white, at the size of a thumbnail.
+fn rasterize(pdfium: &Pdfium, content: &[u8]) -> anyhow::Result<DynamicImage> {
+    let document = pdfium
+        .load_pdf_from_byte_slice(content, None)
+        .context("failed to load PDF document")?;
+    let page = document
+        .pages()
+        .first()
+        .context("PDF document has no page")?;
+    let config = PdfRenderConfig::new()
+        .set_maximum_width(THUMBNAIL_SIZE as i32)
+        .set_maximum_height(THUMBNAIL_SIZE as i32);
+    let bitmap = page
+        .render_with_config(&config)
+        .context("failed to render PDF page")?;
+    Ok(bitmap.as_image())
+}
+
+/// Drops the transparency of a picture, as JPEG has none, laying it over a white background.
+fn flatten(image: DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.into_rgb8();
+    }
+    let rgba = image.into_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let over_white = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        image::Rgb([over_white(r), over_white(g), over_white(b)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageEncoder, Rgba, RgbaImage, codecs::png::PngEncoder};
+
+    #[tokio::test]
+    async fn test_picture_is_resized_to_jpeg() {
+        let picture = RgbaImage::from_pixel(800, 400, Rgba([255, 0, 0, 0]));
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(&picture, 800, 400, image::ExtendedColorType::Rgba8)
+            .unwrap();
+        let thumbnailer = Thumbnailer::pictures_only();
+
+        let thumbnail = thumbnailer
+            .render(AttachmentContentType::Png, png)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            AttachmentContentType::sniff(&thumbnail),
+            Some(AttachmentContentType::Jpeg)
+        );
+        let thumbnail = image::load_from_memory(&thumbnail).unwrap().into_rgb8();
+        assert_eq!(thumbnail.dimensions(), (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2));
+        // Fully transparent pixels come out white.
+        assert!(thumbnail.get_pixel(10, 10).0.iter().all(|c| *c > 240));
+
+        let unreadable = thumbnailer
+            .render(
+                AttachmentContentType::Png,
+                b"\x89PNG\r\n\x1A\ngarbage".to_vec(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unreadable, None);
+        let pdf = thumbnailer
+            .render(AttachmentContentType::Pdf, b"%PDF-1.4".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(pdf, None);
+    }
+}